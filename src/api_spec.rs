@@ -0,0 +1,443 @@
+//! Contract-level summary of OpenAPI and GraphQL schema changes.
+//!
+//! OpenAPI documents (JSON or YAML with a top-level `openapi` or `swagger`
+//! key) are flattened the same way as for the structural diff and grouped by
+//! operation (`GET /pets/{id}`), schema and schema property. GraphQL SDL is
+//! read line by line into types and their fields, one field per line;
+//! arguments may span lines. Path-level `parameters` in OpenAPI are not
+//! attributed to any operation.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::structural::{StructuredFormat, flatten};
+
+const HTTP_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "patch", "head", "options", "trace",
+];
+const GRAPHQL_KEYWORDS: &[&str] = &[
+    "type",
+    "interface",
+    "input",
+    "enum",
+    "union",
+    "scalar",
+    "schema",
+    "directive",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecChange {
+    pub kind: SpecChangeKind,
+    /// `GET /pets`, `schema Pet`, `field Pet.name`, `type Query` or `Query.users`
+    pub item: String,
+    /// What changed for `Changed` items, e.g. `parameters, response 404`
+    pub detail: Option<String>,
+}
+
+/// Summarize the contract changes between two versions of `path`. None when
+/// the file is not an API spec, or when a version does not parse.
+pub fn spec_summary(path: &Path, old: &str, new: &str) -> Option<Vec<SpecChange>> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    if ext == "graphql" || ext == "gql" {
+        return Some(graphql_changes(old, new));
+    }
+
+    let format = StructuredFormat::for_path(path)?;
+    let old = flatten(format, old).ok()?;
+    let new = flatten(format, new).ok()?;
+    let is_spec = |flat: &BTreeMap<String, String>| {
+        flat.contains_key("openapi") || flat.contains_key("swagger")
+    };
+    if !is_spec(&old) && !is_spec(&new) {
+        return None;
+    }
+    Some(openapi_changes(&old, &new))
+}
+
+type Items = BTreeMap<String, BTreeMap<String, String>>;
+
+fn openapi_changes(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<SpecChange> {
+    let old = openapi_items(old);
+    let new = openapi_items(new);
+    let mut changes = Vec::new();
+
+    for (item, old_keys) in &old {
+        match new.get(item) {
+            None => changes.push(added_or_removed(SpecChangeKind::Removed, item)),
+            Some(new_keys) if new_keys != old_keys => changes.push(SpecChange {
+                kind: SpecChangeKind::Changed,
+                item: item.clone(),
+                detail: Some(describe(item, old_keys, new_keys)),
+            }),
+            Some(_) => {}
+        }
+    }
+    for item in new.keys() {
+        if !old.contains_key(item) {
+            changes.push(added_or_removed(SpecChangeKind::Added, item));
+        }
+    }
+
+    // A schema that came or went already covers its properties
+    let whole_schemas: BTreeSet<String> = changes
+        .iter()
+        .filter(|c| c.kind != SpecChangeKind::Changed)
+        .filter_map(|c| c.item.strip_prefix("schema "))
+        .map(|name| format!("field {name}."))
+        .collect();
+    changes.retain(|c| {
+        !whole_schemas
+            .iter()
+            .any(|prefix| c.item.starts_with(prefix))
+    });
+    changes.sort_by(|a, b| a.item.cmp(&b.item));
+    changes
+}
+
+fn added_or_removed(kind: SpecChangeKind, item: &str) -> SpecChange {
+    SpecChange {
+        kind,
+        item: item.to_string(),
+        detail: None,
+    }
+}
+
+/// Group flattened keys by operation, schema and schema property
+fn openapi_items(flat: &BTreeMap<String, String>) -> Items {
+    let mut items = Items::new();
+    for (key, value) in flat {
+        if let Some(rest) = key.strip_prefix("paths.") {
+            let segments: Vec<&str> = rest.split('.').collect();
+            let Some(method) = (1..segments.len()).find(|&i| HTTP_METHODS.contains(&segments[i]))
+            else {
+                continue;
+            };
+            let item = format!(
+                "{} {}",
+                segments[method].to_ascii_uppercase(),
+                segments[..method].join(".")
+            );
+            let sub = segments[method + 1..].join(".");
+            items.entry(item).or_default().insert(sub, value.clone());
+            continue;
+        }
+
+        let Some(rest) = key
+            .strip_prefix("components.schemas.")
+            .or_else(|| key.strip_prefix("definitions."))
+        else {
+            continue;
+        };
+        let (name, sub) = rest.split_once('.').unwrap_or((rest, ""));
+        items
+            .entry(format!("schema {name}"))
+            .or_default()
+            .insert(sub.to_string(), value.clone());
+        if let Some(property) = sub.strip_prefix("properties.") {
+            let (field, field_sub) = property.split_once('.').unwrap_or((property, ""));
+            items
+                .entry(format!("field {name}.{field}"))
+                .or_default()
+                .insert(field_sub.to_string(), value.clone());
+        }
+    }
+    items
+}
+
+/// What differs between two versions of an item. A property with a single
+/// changed value shows it (`type: string → integer`); anything else lists
+/// the parts that changed.
+fn describe(item: &str, old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> String {
+    let differing: Vec<&String> = old
+        .keys()
+        .chain(new.keys())
+        .filter(|key| old.get(*key) != new.get(*key))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    if item.starts_with("field ")
+        && let [key] = differing.as_slice()
+        && let (Some(old_value), Some(new_value)) = (old.get(*key), new.get(*key))
+    {
+        return format!("{key}: {old_value} → {new_value}");
+    }
+
+    let mut aspects: Vec<String> = Vec::new();
+    for key in differing {
+        let part = aspect(key);
+        if !aspects.contains(&part) {
+            aspects.push(part);
+        }
+    }
+    aspects.join(", ")
+}
+
+/// The part of an operation or schema a flattened key belongs to
+fn aspect(key: &str) -> String {
+    let mut segments = key.split('.');
+    let first = segments.next().unwrap_or_default();
+    let first = first.split('[').next().unwrap_or(first);
+    match (first, segments.next()) {
+        ("responses", Some(status)) => format!("response {status}"),
+        ("properties", Some(field)) => {
+            format!("property {}", field.split('[').next().unwrap_or(field))
+        }
+        _ => first.to_string(),
+    }
+}
+
+fn graphql_changes(old: &str, new: &str) -> Vec<SpecChange> {
+    let old = graphql_items(old);
+    let new = graphql_items(new);
+    let mut changes = Vec::new();
+
+    for (item, old_sig) in &old {
+        match new.get(item) {
+            None => changes.push(added_or_removed(SpecChangeKind::Removed, item)),
+            Some(new_sig) if new_sig != old_sig => changes.push(SpecChange {
+                kind: SpecChangeKind::Changed,
+                item: item.clone(),
+                detail: Some(format!("{old_sig} → {new_sig}")),
+            }),
+            Some(_) => {}
+        }
+    }
+    for item in new.keys() {
+        if !old.contains_key(item) {
+            changes.push(added_or_removed(SpecChangeKind::Added, item));
+        }
+    }
+
+    // Fields of a type that came or went are covered by the type
+    let whole_types: BTreeSet<String> = changes
+        .iter()
+        .filter(|c| c.kind != SpecChangeKind::Changed)
+        .filter_map(|c| c.item.split_once(' ').map(|(_, name)| format!("{name}.")))
+        .collect();
+    changes.retain(|c| !whole_types.iter().any(|prefix| c.item.starts_with(prefix)));
+    changes.sort_by(|a, b| a.item.cmp(&b.item));
+    changes
+}
+
+/// Definitions (`type Query`) and their fields (`Query.users`), each mapped
+/// to its normalized signature
+fn graphql_items(text: &str) -> BTreeMap<String, String> {
+    let mut items = BTreeMap::new();
+    let mut current: Option<String> = None;
+    let mut pending = String::new();
+
+    for line in strip_graphql_descriptions(text).lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some(owner) = current.clone() else {
+            let header = line.strip_prefix("extend ").unwrap_or(line);
+            let mut words = header.split_whitespace();
+            let (Some(keyword), name) = (words.next(), words.next()) else {
+                continue;
+            };
+            if !GRAPHQL_KEYWORDS.contains(&keyword) {
+                continue;
+            }
+            let name = name
+                .unwrap_or_default()
+                .split(['{', '(', '='])
+                .next()
+                .unwrap_or_default();
+            let signature = header.split('{').next().unwrap_or(header);
+            let (item, owner) = if name.is_empty() {
+                (keyword.to_string(), keyword)
+            } else {
+                (format!("{keyword} {name}"), name)
+            };
+            items.insert(item, normalize(signature));
+            if header.contains('{') && !header.contains('}') {
+                current = Some(owner.to_string());
+            }
+            continue;
+        };
+
+        if line.starts_with('}') {
+            current = None;
+            pending.clear();
+            continue;
+        }
+        if !pending.is_empty() {
+            pending.push(' ');
+        }
+        pending.push_str(line);
+        if pending.matches('(').count() > pending.matches(')').count() {
+            continue;
+        }
+
+        let field = std::mem::take(&mut pending);
+        let name = field
+            .split(['(', ':', ' '])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        items.insert(format!("{owner}.{name}"), normalize(&field));
+    }
+    items
+}
+
+/// Drop `"""block"""` and single-line `"..."` descriptions
+fn strip_graphql_descriptions(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("\"\"\"") {
+        out.push_str(&rest[..start]);
+        match rest[start + 3..].find("\"\"\"") {
+            Some(end) => rest = &rest[start + 3 + end + 3..],
+            None => rest = "",
+        }
+    }
+    out.push_str(rest);
+    out.lines()
+        .filter(|line| {
+            let line = line.trim();
+            !(line.len() >= 2 && line.starts_with('"') && line.ends_with('"'))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Collapse whitespace so reformatting is not reported as a change
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .trim_end_matches(',')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(path: &str, old: &str, new: &str) -> Vec<(SpecChangeKind, String, Option<String>)> {
+        spec_summary(Path::new(path), old, new)
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.kind, c.item, c.detail))
+            .collect()
+    }
+
+    #[test]
+    fn should_ignore_plain_config_files() {
+        // given a YAML file without an openapi key
+        let result = spec_summary(Path::new("ci.yml"), "a: 1\n", "a: 2\n");
+
+        // then it is not summarized
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn should_group_openapi_changes_by_operation() {
+        // given an operation that gains a response and a new operation
+        let old = "openapi: 3.0.0\npaths:\n  /pets:\n    get:\n      responses:\n        '200':\n          description: ok\n";
+        let new = "openapi: 3.0.0\npaths:\n  /pets:\n    get:\n      responses:\n        '200':\n          description: ok\n        '404':\n          description: missing\n    post:\n      summary: add\n";
+
+        // when summarized
+        let changes = summary("api.yaml", old, new);
+
+        // then each operation is reported once
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    SpecChangeKind::Changed,
+                    "GET /pets".to_string(),
+                    Some("response 404".to_string())
+                ),
+                (SpecChangeKind::Added, "POST /pets".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_schema_property_changes_with_values() {
+        // given a schema whose property changes type and one removed schema
+        let old = r#"{"openapi": "3.1.0", "components": {"schemas": {
+            "Pet": {"properties": {"id": {"type": "string"}}},
+            "Owner": {"properties": {"name": {"type": "string"}}}}}}"#;
+        let new = r#"{"openapi": "3.1.0", "components": {"schemas": {
+            "Pet": {"properties": {"id": {"type": "integer"}}}}}}"#;
+
+        // when summarized
+        let changes = summary("openapi.json", old, new);
+
+        // then the removed schema hides its properties and the change shows values
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    SpecChangeKind::Changed,
+                    "field Pet.id".to_string(),
+                    Some("type: \"string\" → \"integer\"".to_string())
+                ),
+                (SpecChangeKind::Removed, "schema Owner".to_string(), None),
+                (
+                    SpecChangeKind::Changed,
+                    "schema Pet".to_string(),
+                    Some("property id".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_diff_graphql_types_and_fields() {
+        // given a type that loses a field and changes another, plus a new type
+        let old = "\"\"\"A pet\"\"\"\ntype Pet {\n  id: ID!\n  name: String # display\n  tag: String\n}\n";
+        let new = "type Pet {\n  id: ID!\n  name: String!\n}\n\nenum Color {\n  RED\n}\n";
+
+        // when summarized
+        let changes = summary("schema.graphql", old, new);
+
+        // then fields of the new type are not listed separately
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    SpecChangeKind::Changed,
+                    "Pet.name".to_string(),
+                    Some("name: String → name: String!".to_string())
+                ),
+                (SpecChangeKind::Removed, "Pet.tag".to_string(), None),
+                (SpecChangeKind::Added, "enum Color".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_join_multi_line_graphql_arguments() {
+        // given a field whose arguments span lines
+        let text = "type Query {\n  users(\n    first: Int\n  ): [User]\n}\n";
+
+        // when parsed
+        let items = graphql_items(text);
+
+        // then the field is one item
+        assert_eq!(
+            items.get("Query.users").map(String::as_str),
+            Some("users(first: Int): [User]")
+        );
+    }
+}
@@ -1,18 +1,56 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 
+use unicode_width::UnicodeWidthStr;
+
+use crate::api_spec::{SpecChange, spec_summary};
+use crate::config::{CompletionCriteria, Config};
 use crate::error::{Result, TuicrError};
+use crate::findings::{Finding, parse_findings};
+use crate::function_scope::{
+    enclosing_function, find_function, function_spans, has_grammar, reconstruct_old_file,
+};
+use crate::gitlab;
+use crate::hyperlink::{Hyperlink, LinkTargets};
+use crate::impact::{SymbolImpact, changed_signatures, find_references};
+use crate::indent_guard;
+use crate::input::chord::Chords;
+use crate::migration;
 use crate::model::{
-    Comment, CommentType, DiffFile, DiffLine, LineRange, LineSide, ReviewSession, SessionDiffSource,
+    ColumnRange, Comment, CommentType, ConflictLine, ConflictSide, ContentAnchor, DiffFile,
+    DiffLine, DiffSnapshot, FileReview, FileStatus, LineOrigin, LineRange, LineSide, Reply,
+    RequiredAction, ReviewSession, ReviewState, SessionDiffSource, Severity, tag_conflict_lines,
 };
-use crate::persistence::load_latest_session_for_context;
+use crate::moved::detect_moved_lines;
+use crate::output::generate_export_content;
+use crate::persistence::{
+    find_damaged_session, find_previous_session, load_latest_session_for_context, load_session,
+    salvage_session, save_backup_export, save_session, set_aside_damaged_session,
+};
+use crate::public_api::{ApiChange, public_api_changes};
+use crate::structural::{StructuralChange, StructuredFormat, structural_diff};
+use crate::test_mapping::{TestCoverage, alternate_files, test_coverage};
+use crate::text_edit::{KillRing, position_at_column, prev_grapheme_boundary, word_at};
 use crate::theme::Theme;
+use crate::todo::{TodoMarker, find_todos};
+use crate::trailer::{self, TrailerInfo};
 use crate::update::UpdateInfo;
 use crate::vcs::git::calculate_gap;
-use crate::vcs::{CommitInfo, VcsBackend, VcsInfo, detect_vcs};
+use crate::vcs::{
+    CommitInfo, CommitQuery, DiffSettings, FileStat, FileVersions, GitAttributes, GitBackend,
+    StdinBackend, TuicrIgnore, VcsBackend, VcsInfo, VcsType, WorktreeInfo, detect_vcs,
+    detect_vcs_at,
+};
 
 const VISIBLE_COMMIT_COUNT: usize = 10;
 const COMMIT_PAGE_SIZE: usize = 10;
+/// Lines of context added on each side when expanding a hunk from its header
+const HUNK_EXPAND_STEP: u32 = 10;
+/// Columns before a line's content in the unified diff, after the cursor
+/// indicator: line number and sign
+const UNIFIED_GUTTER_WIDTH: usize = 7;
+/// Body shown for `linguist-generated` and `-diff` files until expanded
+pub const GENERATED_PLACEHOLDER: &str = "(generated, press o to expand)";
 
 #[derive(Debug, Clone)]
 pub enum FileTreeItem {
@@ -25,6 +63,11 @@ pub enum FileTreeItem {
         file_idx: usize,
         depth: usize,
     },
+    /// Collapsible group holding all vendored files, listed after the tree
+    VendoredGroup {
+        count: usize,
+        expanded: bool,
+    },
 }
 
 /// Identifies a gap between hunks in a file (for context expansion)
@@ -67,6 +110,28 @@ pub enum AnnotatedLine {
     BinaryOrEmpty { file_idx: usize },
     /// Spacing between files
     Spacing,
+    /// Header of the "General" section after the last file
+    GeneralHeader,
+    /// A directory or session comment line, indexed into `ReviewSession::general_comments`
+    GeneralComment { entry_idx: usize },
+}
+
+/// Target of a comment that is not anchored to a file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GeneralTarget {
+    Session,
+    Directory(PathBuf),
+}
+
+/// Where a comment was being written, so a cancelled draft can be offered
+/// again the next time comment mode opens at the same spot
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DraftAnchor {
+    File(PathBuf),
+    Line(PathBuf, u32, LineSide),
+    General(GeneralTarget),
+    Edit(String),
+    Reply(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -76,26 +141,67 @@ pub enum InputMode {
     Command,
     Search,
     Help,
+    FunctionDiff,
+    StructuralDiff,
+    Migration,
+    PublicApi,
+    TestMap,
+    Todos,
+    StaleComments,
+    CommentList,
+    Attachments,
+    Impact,
+    /// Reviewing the export text before it is copied
+    ExportPreview,
     Confirm,
     CommitSelect,
+    /// Typing a `/` filter in the commit picker
+    CommitFilter,
+    WorktreeSelect,
     VisualSelect,
 }
 
+impl InputMode {
+    /// Modes where keys insert text, shown with a bar cursor
+    pub fn is_text_input(self) -> bool {
+        matches!(
+            self,
+            InputMode::Comment | InputMode::Command | InputMode::Search | InputMode::CommitFilter
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DiffSource {
     WorkingTree,
     CommitRange(Vec<String>),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConfirmAction {
     CopyAndQuit,
+    /// Write the suggested change with this comment id into its file
+    ApplySuggestion(String),
+    /// Pick up the open comments of an earlier review of the branch
+    SeedFromSession {
+        path: PathBuf,
+        summary: String,
+    },
+    /// Delete every comment on this file
+    ClearFileComments(PathBuf),
+    /// Recover what is readable from a saved review that no longer loads
+    RecoverDamagedSession {
+        path: PathBuf,
+        summary: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FocusedPanel {
     FileList,
     Diff,
+    /// Every comment of the review, under the file list
+    Comments,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -132,20 +238,66 @@ pub struct App {
     pub file_list_state: FileListState,
     pub diff_state: DiffState,
     pub help_state: HelpState,
+    pub function_diff: Option<FunctionDiffView>,
+    pub structural_diff: Option<StructuralDiffView>,
+    pub migration_view: Option<MigrationView>,
+    pub public_api: Option<PublicApiView>,
+    pub test_map: Option<TestMapView>,
+    pub todo_view: Option<TodoView>,
+    pub stale_view: Option<StaleCommentView>,
+    pub comment_list: Option<CommentListView>,
+    pub comments_pane: CommentsPaneState,
+    /// Comment types left out of the diff with `1`-`4` or `:show`
+    pub hidden_comment_types: HashSet<CommentType>,
+    /// Name recorded on new comments and replies (`reviewer_name` or git `user.name`)
+    pub reviewer: Option<String>,
+    /// Where file paths and commit SHAs link to; None without OSC 8 links
+    pub link_targets: Option<LinkTargets>,
+    /// Links drawn in the current frame, written out after it
+    pub hyperlinks: Vec<Hyperlink>,
+    /// Two-key chords and the prefix waiting for its second key
+    pub chords: Chords,
+    pub attachment_view: Option<AttachmentView>,
+    pub impact_view: Option<ImpactView>,
+    pub export_preview: Option<ExportPreview>,
+    /// Receives the result of a running `:impact` search
+    pub impact_search: Option<std::sync::mpsc::Receiver<Result<Vec<SymbolImpact>>>>,
     pub command_buffer: String,
     pub search_buffer: String,
     pub last_search_pattern: Option<String>,
     pub comment_buffer: String,
     pub comment_cursor: usize,
+    /// Text killed in the comment editor, for `Ctrl-Y`
+    pub kill_ring: KillRing,
+    /// Comment text abandoned with Esc, keyed by where it was being written
+    pub comment_drafts: HashMap<DraftAnchor, String>,
     pub comment_type: CommentType,
+    /// Severity of the comment being written, cycled with Shift-Tab
+    pub comment_severity: Option<Severity>,
+    /// Response the comment being written expects from the author
+    pub comment_required_action: Option<RequiredAction>,
+    /// The comment being written is over `max_chars` and the user was told;
+    /// saving again saves it anyway
+    pub comment_length_warned: bool,
     pub comment_is_file_level: bool,
     pub comment_line: Option<(u32, LineSide)>,
     pub editing_comment_id: Option<String>,
+    /// Set while writing a reply to the comment with this id
+    pub replying_to_comment_id: Option<String>,
+    /// Set while writing or editing a directory or session comment
+    pub comment_general_target: Option<GeneralTarget>,
+    /// Comments deleted with `dd`, most recent last
+    pub deleted_comments: Vec<DeletedComment>,
+    /// Comment copied with `Y`, for `p` to attach to other lines
+    pub yanked_comment: Option<Comment>,
 
     /// Visual selection anchor point (starting line, side)
     pub visual_anchor: Option<(u32, LineSide)>,
     /// Line range for range comments (used when creating comments from visual selection)
     pub comment_line_range: Option<(LineRange, LineSide)>,
+    /// Columns of the commented line the new comment points at, taken from
+    /// the word under the column cursor
+    pub comment_columns: Option<ColumnRange>,
 
     // Commit selection state
     pub commit_list: Vec<CommitInfo>,
@@ -155,18 +307,67 @@ pub struct App {
     /// Selected commit range as (start_idx, end_idx) inclusive, where start <= end.
     /// Indices refer to positions in commit_list (0 = newest/HEAD, higher = older).
     pub commit_selection_range: Option<(usize, usize)>,
+    /// Commit where a `v` range started; the selection follows the cursor
+    pub commit_anchor: Option<usize>,
+    /// Previews by commit id, loaded the first time a commit is highlighted
+    pub commit_previews: HashMap<String, CommitPreview>,
+    /// Per-file stats by commit id, filled in by a background worker
+    pub commit_stats: HashMap<String, Vec<FileStat>>,
+    /// Receives stats from the worker started by `poll_commit_stats`
+    pub commit_stats_rx: Option<std::sync::mpsc::Receiver<(String, Vec<FileStat>)>>,
     /// State describing how many commits are currently shown and how pagination behaves.
     pub visible_commit_count: usize,
     pub commit_page_size: usize,
     pub has_more_commit: bool,
+    /// Narrows the commit picker to commits matching every word, by summary,
+    /// author or SHA. While set, all loaded commits are searched.
+    pub commit_filter: String,
+    /// `author:`/`since:` terms of the filter the loaded commits were fetched with
+    pub commit_query: CommitQuery,
+
+    // Worktree picker state
+    pub worktree_list: Vec<WorktreeInfo>,
+    pub worktree_cursor: usize,
+    /// Mode to return to when the picker is dismissed
+    pub worktree_return_mode: InputMode,
 
     pub should_quit: bool,
     pub dirty: bool,
+    /// Reopened from a diff snapshot (`--snapshot`); the session is never written back
+    pub read_only: bool,
     pub quit_warned: bool,
     pub message: Option<Message>,
     pub pending_confirm: Option<ConfirmAction>,
     pub supports_keyboard_enhancement: bool,
     pub show_file_list: bool,
+    pub config: Config,
+    /// Settings passed to the backend for every diff it computes
+    pub diff_settings: DiffSettings,
+    /// Files marked `linguist-vendored` or matching a configured vendor glob
+    pub vendored_paths: HashSet<PathBuf>,
+    /// Files under a configured migration directory
+    pub migration_paths: HashSet<PathBuf>,
+    /// Files marked `linguist-generated` or `-diff` in `.gitattributes`
+    pub generated_paths: HashSet<PathBuf>,
+    /// Generated files the user expanded; the rest show a placeholder
+    pub expanded_generated: HashSet<PathBuf>,
+    /// Changed source files without a changed test (see `test_mapping`)
+    pub untested_paths: HashSet<PathBuf>,
+    /// Include files excluded by `.tuicrignore` (`:set showall`)
+    pub show_ignored: bool,
+    /// Files left out of the last load by `.tuicrignore`
+    pub ignored_file_count: usize,
+    /// Hunk header warnings keyed by (file_idx, hunk_idx): indentation changes in
+    /// whitespace-sensitive files and destructive statements in migrations
+    pub hunk_warnings: HashMap<(usize, usize), Vec<String>>,
+    /// Lines of each `(file_idx, hunk_idx)` that were moved rather than changed
+    pub moved_lines: HashMap<(usize, usize), Vec<bool>>,
+    /// Conflict roles per hunk line, keyed by file_idx, for conflicted files
+    pub conflict_lines: HashMap<usize, Vec<Vec<Option<ConflictLine>>>>,
+    /// Whether vendored files are expanded in the file list and diff view
+    pub show_vendored: bool,
+    /// J/K in the file list reorder files in the review plan
+    pub plan_mode: bool,
     pub file_list_area: Option<ratatui::layout::Rect>,
     pub diff_area: Option<ratatui::layout::Rect>,
     pub expanded_dirs: HashSet<String>,
@@ -180,6 +381,10 @@ pub struct App {
     pub output_to_stdout: bool,
     /// Pending output to print to stdout after TUI exits
     pub pending_stdout_output: Option<String>,
+    /// Clipboard text that exports replaced, most recent last
+    pub clipboard_history: Vec<String>,
+    /// The text tuicr itself last put on the clipboard
+    pub last_clipboard_copy: Option<String>,
     /// Calculated screen position for comment input cursor (col, row) for IME positioning.
     /// Set during render when in Comment mode, None otherwise.
     pub comment_cursor_screen_pos: Option<(u16, u16)>,
@@ -228,6 +433,9 @@ pub struct DiffState {
     /// Number of logical lines that fit in the viewport (set during render).
     /// When wrapping is enabled, this accounts for lines expanding to multiple visual rows.
     pub visible_line_count: usize,
+    /// Display column of the column cursor within the diff line, set by the
+    /// `w`/`b`/`e` motions; None until one is used (and after `Esc`)
+    pub cursor_col: Option<usize>,
 }
 
 impl Default for DiffState {
@@ -242,6 +450,7 @@ impl Default for DiffState {
             max_content_width: 0,
             wrap_lines: true,
             visible_line_count: 0,
+            cursor_col: None,
         }
     }
 }
@@ -253,6 +462,271 @@ pub struct HelpState {
     pub total_lines: usize, // Set during render
 }
 
+/// Old and new versions of one function, shown side by side in a popup
+#[derive(Debug, Default)]
+pub struct FunctionDiffView {
+    pub name: String,
+    /// 1-based line number of `old_lines[0]`
+    pub old_start: usize,
+    pub old_lines: Vec<String>,
+    /// 1-based line number of `new_lines[0]`
+    pub new_start: usize,
+    pub new_lines: Vec<String>,
+    pub scroll_offset: usize,
+    pub viewport_height: usize, // Set during render
+}
+
+impl FunctionDiffView {
+    pub fn total_lines(&self) -> usize {
+        self.old_lines.len().max(self.new_lines.len())
+    }
+}
+
+/// Key-level changes of a JSON or YAML file, shown in a popup, led by the
+/// contract summary for OpenAPI and GraphQL specs
+#[derive(Debug, Default)]
+pub struct StructuralDiffView {
+    pub path: PathBuf,
+    pub summary: Vec<SpecChange>,
+    pub changes: Vec<StructuralChange>,
+    pub scroll_offset: usize,
+    pub viewport_height: usize, // Set during render
+}
+
+impl StructuralDiffView {
+    /// Rows the popup renders: the summary under its heading, then the key
+    /// changes under theirs when there is a summary
+    pub fn line_count(&self) -> usize {
+        match (self.summary.len(), self.changes.len()) {
+            (0, changes) => changes,
+            (summary, 0) => summary + 1,
+            (summary, changes) => summary + changes + 3,
+        }
+    }
+}
+
+/// A migration shown next to the migration that reverses it
+#[derive(Debug, Default)]
+pub struct MigrationView {
+    pub up_path: PathBuf,
+    pub up_lines: Vec<String>,
+    pub down_path: PathBuf,
+    /// `None` when the down migration does not exist
+    pub down_lines: Option<Vec<String>>,
+    pub scroll_offset: usize,
+    pub viewport_height: usize, // Set during render
+}
+
+impl MigrationView {
+    pub fn total_lines(&self) -> usize {
+        self.up_lines
+            .len()
+            .max(self.down_lines.as_ref().map_or(0, Vec::len))
+    }
+}
+
+/// Public API changes of the Rust files in the diff, with a cursor for jumping to them
+#[derive(Debug, Default)]
+pub struct PublicApiView {
+    pub changes: Vec<ApiChange>,
+    pub cursor: usize,
+    pub scroll_offset: usize, // Kept in sync with the cursor during render
+}
+
+/// Changed source files and the changed tests mapped to them
+#[derive(Debug, Default)]
+pub struct TestMapView {
+    pub coverage: Vec<TestCoverage>,
+    pub cursor: usize,
+    pub scroll_offset: usize, // Kept in sync with the cursor during render
+}
+
+/// TODO, FIXME and HACK markers on added lines
+#[derive(Debug, Default)]
+pub struct TodoView {
+    pub todos: Vec<TodoMarker>,
+    pub cursor: usize,
+    pub scroll_offset: usize, // Kept in sync with the cursor during render
+}
+
+/// A line comment whose line is not in the current diff
+#[derive(Debug, Clone)]
+pub struct StaleComment {
+    pub path: PathBuf,
+    pub line: u32,
+    pub side: LineSide,
+    pub id: String,
+    pub content: String,
+    /// None when the whole file left the diff
+    pub file_idx: Option<usize>,
+}
+
+/// Comment types toggled together by the `1`-`4` keys and named in `:show`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentGroup {
+    Notes,
+    Suggestions,
+    Issues,
+    Praise,
+}
+
+impl CommentGroup {
+    pub const ALL: [CommentGroup; 4] = [
+        CommentGroup::Notes,
+        CommentGroup::Suggestions,
+        CommentGroup::Issues,
+        CommentGroup::Praise,
+    ];
+
+    pub fn types(self) -> &'static [CommentType] {
+        match self {
+            CommentGroup::Notes => &[CommentType::Note],
+            CommentGroup::Suggestions => &[CommentType::Suggestion, CommentType::CodeSuggestion],
+            CommentGroup::Issues => &[CommentType::Issue],
+            CommentGroup::Praise => &[CommentType::Praise],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CommentGroup::Notes => "notes",
+            CommentGroup::Suggestions => "suggestions",
+            CommentGroup::Issues => "issues",
+            CommentGroup::Praise => "praise",
+        }
+    }
+
+    /// `note`/`notes`, `suggestion`/`suggestions`, `issue`/`issues` or `praise`
+    pub fn parse(word: &str) -> Option<Self> {
+        let word = word.to_ascii_lowercase();
+        let singular = word.strip_suffix('s').unwrap_or(&word);
+        Self::ALL.into_iter().find(|group| {
+            let name = group.name();
+            name == word || name.strip_suffix('s').unwrap_or(name) == singular
+        })
+    }
+}
+
+/// One row of the comment list
+#[derive(Debug, Clone)]
+pub struct CommentListEntry {
+    pub id: String,
+    /// `src/app.rs:42`, `src/app.rs:~42` (deleted side), `src/app.rs`,
+    /// `src/` for a directory comment or `(review)` for a session comment
+    pub location: String,
+    pub comment_type: CommentType,
+    pub resolved: bool,
+    pub first_line: String,
+}
+
+/// Every comment of the session, shown in a popup
+#[derive(Debug, Default)]
+pub struct CommentListView {
+    pub entries: Vec<CommentListEntry>,
+    pub cursor: usize,
+    pub scroll_offset: usize, // Kept in sync with the cursor during render
+}
+
+/// Selection in the comments pane under the file list
+#[derive(Debug, Default)]
+pub struct CommentsPaneState {
+    pub cursor: usize,
+    pub scroll_offset: usize, // Kept in sync with the cursor during render
+}
+
+/// Line comments left without a line to render against after a reload
+#[derive(Debug, Default)]
+pub struct StaleCommentView {
+    pub comments: Vec<StaleComment>,
+    pub cursor: usize,
+    pub scroll_offset: usize, // Kept in sync with the cursor during render
+}
+
+/// How many replaced clipboard contents `:clip undo` can bring back
+const CLIPBOARD_HISTORY_LIMIT: usize = 10;
+
+/// How many deleted comments `u` can bring back
+const UNDO_DELETE_LIMIT: usize = 20;
+
+/// A comment removed with `dd`, kept so `u` can restore it
+#[derive(Debug)]
+pub struct DeletedComment {
+    slot: CommentSlot,
+    /// Position among the comments of the same slot
+    index: usize,
+    comment: Comment,
+}
+
+/// Where a deleted comment was anchored
+#[derive(Debug)]
+enum CommentSlot {
+    File(PathBuf),
+    Line(PathBuf, u32),
+    General(Option<PathBuf>),
+}
+
+/// What the commit picker shows beside the highlighted commit
+#[derive(Debug)]
+pub struct CommitPreview {
+    pub message: String,
+    pub files: Vec<FileStat>,
+}
+
+/// Files attached to the session with `:attach`
+#[derive(Debug, Default)]
+pub struct AttachmentView {
+    pub cursor: usize,
+    pub scroll_offset: usize, // Kept in sync with the cursor during render
+}
+
+/// Files outside the diff that reference functions whose signature changed
+#[derive(Debug, Default)]
+pub struct ImpactView {
+    pub impacts: Vec<SymbolImpact>,
+    pub scroll_offset: usize,
+    pub viewport_height: usize, // Set during render
+}
+
+impl ImpactView {
+    /// One line per function plus one per referencing file
+    pub fn total_lines(&self) -> usize {
+        self.impacts
+            .iter()
+            .map(|impact| 1 + impact.references.len())
+            .sum()
+    }
+}
+
+/// Diff lines shown around the anchor while writing a line comment
+const ANCHOR_PREVIEW_CONTEXT: u32 = 1;
+/// Most diff lines the anchor preview shows
+const ANCHOR_PREVIEW_MAX_LINES: usize = 5;
+
+/// A diff line shown at the top of the comment input, so the anchor can be
+/// checked without leaving the editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorPreviewLine {
+    pub lineno: u32,
+    pub origin: LineOrigin,
+    pub content: String,
+    /// Part of the commented line or range rather than context
+    pub anchored: bool,
+}
+
+/// The export text shown by `:clip --preview` before it is copied
+#[derive(Debug, Default)]
+pub struct ExportPreview {
+    pub content: String,
+    pub scroll_offset: usize,
+    pub viewport_height: usize, // Set during render
+}
+
+impl ExportPreview {
+    pub fn total_lines(&self) -> usize {
+        self.content.lines().count()
+    }
+}
+
 /// Represents a comment location for deletion
 enum CommentLocation {
     FileComment {
@@ -265,21 +739,132 @@ enum CommentLocation {
         side: LineSide,
         index: usize,
     },
+    General {
+        id: String,
+    },
 }
 
 impl App {
-    pub fn new(theme: Theme, output_to_stdout: bool) -> Result<Self> {
-        let vcs = detect_vcs()?;
+    /// Open the repository containing `repo_path` (`-C`), or the current directory
+    pub fn new(
+        repo_path: Option<&std::path::Path>,
+        theme: Theme,
+        config: Config,
+        output_to_stdout: bool,
+        pathspecs: Vec<String>,
+    ) -> Result<Self> {
+        let vcs = match repo_path {
+            Some(path) => detect_vcs_at(path)?,
+            None => detect_vcs()?,
+        };
+        Self::with_backend(vcs, theme, config, output_to_stdout, pathspecs)
+    }
+
+    /// Create an app that reviews a unified diff read from stdin instead of a repository.
+    /// Context lines are read from `repo_path` (`-C`), or the current directory.
+    pub fn from_stdin_diff(
+        diff_text: String,
+        repo_path: Option<&std::path::Path>,
+        theme: Theme,
+        config: Config,
+        output_to_stdout: bool,
+        pathspecs: Vec<String>,
+    ) -> Result<Self> {
+        let vcs = Box::new(match repo_path {
+            Some(path) => StdinBackend::from_diff_at(diff_text, path.to_path_buf()),
+            None => StdinBackend::from_diff(diff_text)?,
+        });
+        Self::with_backend(vcs, theme, config, output_to_stdout, pathspecs)
+    }
+
+    /// Reopen a saved session on the diff snapshot stored in it, after the
+    /// branch or working tree it was taken from has moved on
+    pub fn from_snapshot(
+        session_file: &PathBuf,
+        theme: Theme,
+        config: Config,
+        output_to_stdout: bool,
+    ) -> Result<Self> {
+        let session = load_session(session_file)?;
+        let Some(snapshot) = &session.snapshot else {
+            return Err(TuicrError::UnsupportedOperation(format!(
+                "{} holds no diff snapshot; set `snapshot_diff` before reviewing",
+                session_file.display()
+            )));
+        };
+        let mut app = Self::from_stdin_diff(
+            snapshot.diff_text()?,
+            Some(&session.repo_path),
+            theme,
+            config,
+            output_to_stdout,
+            session.pathspecs.clone(),
+        )?;
+        app.session = session;
+        app.read_only = true;
+        app.sort_files_by_directory(true);
+        app.expand_all_dirs();
+        app.rebuild_annotations();
+        Ok(app)
+    }
+
+    fn with_backend(
+        mut vcs: Box<dyn VcsBackend>,
+        theme: Theme,
+        config: Config,
+        output_to_stdout: bool,
+        pathspecs: Vec<String>,
+    ) -> Result<Self> {
+        let diff_settings = DiffSettings {
+            find_copies: config.find_copies,
+            expand_submodules: config.expand_submodules,
+            context_lines: config
+                .context_lines
+                .unwrap_or(DiffSettings::DEFAULT_CONTEXT_LINES),
+        };
+        vcs.set_diff_settings(diff_settings);
         let vcs_info = vcs.info().clone();
         let highlighter = theme.syntax_highlighter();
+        let reviewer = config
+            .reviewer_name
+            .clone()
+            .or_else(|| trailer::user_name(&vcs_info.root_path));
+        let link_targets = LinkTargets::detect(&vcs_info.root_path, config.hyperlinks);
+        let chords = Chords::new(&config.chords, config.chord_timeout_ms);
 
         // Try to get working tree diff first
-        let diff_result = vcs.get_working_tree_diff(highlighter);
+        let ignore = TuicrIgnore::load(&vcs_info.root_path);
+        let mut ignored_file_count = 0;
+        let diff_result = vcs
+            .get_working_tree_diff(highlighter)
+            .map(|files| {
+                let (files, hidden) = filter_ignored(filter_pathspecs(files, &pathspecs), &ignore);
+                ignored_file_count = hidden;
+                files
+            })
+            .and_then(|files| {
+                if files.is_empty() {
+                    Err(TuicrError::NoChanges)
+                } else {
+                    Ok(files)
+                }
+            });
 
         match diff_result {
             Ok(diff_files) => {
                 // We have unstaged changes - normal flow
-                let mut session = Self::load_or_create_session(&vcs_info);
+                // A piped diff has no stable identity, so it never resumes a saved session
+                let mut session = if vcs_info.vcs_type == VcsType::Stdin {
+                    ReviewSession::new(
+                        vcs_info.root_path.clone(),
+                        vcs_info.head_commit.clone(),
+                        None,
+                        SessionDiffSource::WorkingTree,
+                    )
+                } else {
+                    Self::load_or_create_session(&vcs_info)
+                };
+                session.pathspecs = pathspecs;
 
                 // Ensure all current diff files are in the session
                 for file in &diff_files {
@@ -300,32 +885,84 @@ impl App {
                     file_list_state: FileListState::default(),
                     diff_state: DiffState::default(),
                     help_state: HelpState::default(),
+                    function_diff: None,
+                    structural_diff: None,
+                    migration_view: None,
+                    public_api: None,
+                    test_map: None,
+                    todo_view: None,
+                    stale_view: None,
+                    comment_list: None,
+                    comments_pane: CommentsPaneState::default(),
+                    hidden_comment_types: HashSet::new(),
+                    reviewer,
+                    link_targets,
+                    hyperlinks: Vec::new(),
+                    chords,
+                    attachment_view: None,
+                    impact_view: None,
+                    export_preview: None,
+                    impact_search: None,
                     command_buffer: String::new(),
                     search_buffer: String::new(),
                     last_search_pattern: None,
                     comment_buffer: String::new(),
                     comment_cursor: 0,
+                    kill_ring: KillRing::default(),
+                    comment_drafts: HashMap::new(),
                     comment_type: CommentType::Note,
+                    comment_severity: None,
+                    comment_required_action: None,
+                    comment_length_warned: false,
                     comment_is_file_level: true,
                     comment_line: None,
                     editing_comment_id: None,
+                    replying_to_comment_id: None,
+                    comment_general_target: None,
+                    deleted_comments: Vec::new(),
+                    yanked_comment: None,
                     visual_anchor: None,
                     comment_line_range: None,
+                    comment_columns: None,
                     commit_list: Vec::new(),
                     commit_list_cursor: 0,
                     commit_list_scroll_offset: 0,
                     commit_list_viewport_height: 0,
                     commit_selection_range: None,
+                    commit_anchor: None,
+                    commit_previews: HashMap::new(),
+                    commit_stats: HashMap::new(),
+                    commit_stats_rx: None,
                     visible_commit_count: VISIBLE_COMMIT_COUNT,
                     commit_page_size: COMMIT_PAGE_SIZE,
                     has_more_commit: true,
+                    commit_filter: String::new(),
+                    commit_query: CommitQuery::default(),
+                    worktree_list: Vec::new(),
+                    worktree_cursor: 0,
+                    worktree_return_mode: InputMode::Normal,
                     should_quit: false,
                     dirty: false,
+                    read_only: false,
                     quit_warned: false,
                     message: None,
                     pending_confirm: None,
                     supports_keyboard_enhancement: false,
                     show_file_list: true,
+                    config,
+                    diff_settings,
+                    vendored_paths: HashSet::new(),
+                    migration_paths: HashSet::new(),
+                    generated_paths: HashSet::new(),
+                    expanded_generated: HashSet::new(),
+                    untested_paths: HashSet::new(),
+                    show_ignored: false,
+                    ignored_file_count,
+                    hunk_warnings: HashMap::new(),
+                    moved_lines: HashMap::new(),
+                    conflict_lines: HashMap::new(),
+                    show_vendored: false,
+                    plan_mode: false,
                     file_list_area: None,
                     diff_area: None,
                     expanded_dirs: HashSet::new(),
@@ -333,6 +970,8 @@ impl App {
                     expanded_content: HashMap::new(),
                     line_annotations: Vec::new(),
                     output_to_stdout,
+                    clipboard_history: Vec::new(),
+                    last_clipboard_copy: None,
                     pending_stdout_output: None,
                     comment_cursor_screen_pos: None,
                     update_info: None,
@@ -344,7 +983,8 @@ impl App {
             }
             Err(TuicrError::NoChanges) => {
                 // No unstaged changes - try to get recent commits
-                let commits = vcs.get_recent_commits(0, VISIBLE_COMMIT_COUNT)?;
+                let commits =
+                    vcs.get_recent_commits(0, VISIBLE_COMMIT_COUNT, &CommitQuery::default())?;
                 if commits.is_empty() {
                     return Err(TuicrError::NoChanges);
                 }
@@ -353,12 +993,13 @@ impl App {
                 let has_more_commit = commits.len() >= VISIBLE_COMMIT_COUNT;
                 let commit_count = commits.len();
 
-                let session = ReviewSession::new(
+                let mut session = ReviewSession::new(
                     vcs_info.root_path.clone(),
                     vcs_info.head_commit.clone(),
                     vcs_info.branch_name.clone(),
                     SessionDiffSource::WorkingTree,
                 );
+                session.pathspecs = pathspecs;
 
                 Ok(Self {
                     theme,
@@ -373,32 +1014,84 @@ impl App {
                     file_list_state: FileListState::default(),
                     diff_state: DiffState::default(),
                     help_state: HelpState::default(),
+                    function_diff: None,
+                    structural_diff: None,
+                    migration_view: None,
+                    public_api: None,
+                    test_map: None,
+                    todo_view: None,
+                    stale_view: None,
+                    comment_list: None,
+                    comments_pane: CommentsPaneState::default(),
+                    hidden_comment_types: HashSet::new(),
+                    reviewer,
+                    link_targets,
+                    hyperlinks: Vec::new(),
+                    chords,
+                    attachment_view: None,
+                    impact_view: None,
+                    export_preview: None,
+                    impact_search: None,
                     command_buffer: String::new(),
                     search_buffer: String::new(),
                     last_search_pattern: None,
                     comment_buffer: String::new(),
                     comment_cursor: 0,
+                    kill_ring: KillRing::default(),
+                    comment_drafts: HashMap::new(),
                     comment_type: CommentType::Note,
+                    comment_severity: None,
+                    comment_required_action: None,
+                    comment_length_warned: false,
                     comment_is_file_level: true,
                     comment_line: None,
                     editing_comment_id: None,
+                    replying_to_comment_id: None,
+                    comment_general_target: None,
+                    deleted_comments: Vec::new(),
+                    yanked_comment: None,
                     visual_anchor: None,
                     comment_line_range: None,
+                    comment_columns: None,
                     commit_list: commits,
                     commit_list_cursor: 0,
                     commit_list_scroll_offset: 0,
                     commit_list_viewport_height: 0,
                     commit_selection_range: None,
+                    commit_anchor: None,
+                    commit_previews: HashMap::new(),
+                    commit_stats: HashMap::new(),
+                    commit_stats_rx: None,
                     visible_commit_count: commit_count,
                     commit_page_size: COMMIT_PAGE_SIZE,
                     has_more_commit,
+                    commit_filter: String::new(),
+                    commit_query: CommitQuery::default(),
+                    worktree_list: Vec::new(),
+                    worktree_cursor: 0,
+                    worktree_return_mode: InputMode::Normal,
                     should_quit: false,
                     dirty: false,
+                    read_only: false,
                     quit_warned: false,
                     message: None,
                     pending_confirm: None,
                     supports_keyboard_enhancement: false,
                     show_file_list: true,
+                    config,
+                    diff_settings,
+                    vendored_paths: HashSet::new(),
+                    migration_paths: HashSet::new(),
+                    generated_paths: HashSet::new(),
+                    expanded_generated: HashSet::new(),
+                    untested_paths: HashSet::new(),
+                    show_ignored: false,
+                    ignored_file_count,
+                    hunk_warnings: HashMap::new(),
+                    moved_lines: HashMap::new(),
+                    conflict_lines: HashMap::new(),
+                    show_vendored: false,
+                    plan_mode: false,
                     file_list_area: None,
                     diff_area: None,
                     expanded_dirs: HashSet::new(),
@@ -406,6 +1099,8 @@ impl App {
                     expanded_content: HashMap::new(),
                     line_annotations: Vec::new(),
                     output_to_stdout,
+                    clipboard_history: Vec::new(),
+                    last_clipboard_copy: None,
                     pending_stdout_output: None,
                     comment_cursor_screen_pos: None,
                     update_info: None,
@@ -457,7 +1152,59 @@ impl App {
         session
     }
 
+    /// Whether the diff was piped in on stdin rather than read from a repository
+    pub fn is_stdin_diff(&self) -> bool {
+        self.vcs_info.vcs_type == VcsType::Stdin
+    }
+
+    /// Move line comments to where their code is in `new_files`, flagging
+    /// the ones whose code is gone. Comments saved without a fingerprint
+    /// get one from the diff being replaced first.
+    fn reanchor_comments(&mut self, new_files: &[DiffFile]) {
+        for (path, review) in &mut self.session.files {
+            let Some(new_file) = new_files.iter().find(|f| f.display_path() == path) else {
+                continue;
+            };
+            let old_file = self.diff_files.iter().find(|f| f.display_path() == path);
+            let mut lines: Vec<(u32, Comment)> = review
+                .line_comments
+                .drain()
+                .flat_map(|(line, comments)| comments.into_iter().map(move |c| (line, c)))
+                .collect();
+            lines.sort_by_key(|(line, _)| *line);
+
+            for (line, mut comment) in lines {
+                let side = comment.side.unwrap_or(LineSide::New);
+                let range = comment.line_range.unwrap_or(LineRange::single(line));
+                if comment.anchor.is_none() {
+                    comment.anchor = old_file
+                        .and_then(|file| ContentAnchor::capture(&side_lines(file, side), range));
+                }
+                let mut key = line;
+                if let Some(anchor) = comment.anchor {
+                    match anchor.locate(&side_lines(new_file, side), range) {
+                        Some(found) => {
+                            comment.anchor_lost = false;
+                            if comment.line_range.is_some() {
+                                comment.line_range = Some(found);
+                            }
+                            key = found.end;
+                        }
+                        None => comment.anchor_lost = true,
+                    }
+                }
+                review.add_line_comment(key, comment);
+            }
+        }
+    }
+
     pub fn reload_diff_files(&mut self) -> Result<usize> {
+        if self.is_stdin_diff() {
+            return Err(TuicrError::UnsupportedOperation(
+                "diff was read from stdin".into(),
+            ));
+        }
+
         let current_path = self.current_file_path().cloned();
         let prev_file_idx = self.diff_state.current_file_idx;
         let prev_cursor_line = self.diff_state.cursor_line;
@@ -473,13 +1220,23 @@ impl App {
         };
 
         let highlighter = self.theme.syntax_highlighter();
-        let diff_files = self.vcs.get_working_tree_diff(highlighter)?;
+        let diff_files = match &self.diff_source {
+            DiffSource::WorkingTree => self.vcs.get_working_tree_diff(highlighter)?,
+            DiffSource::CommitRange(commit_ids) => {
+                self.vcs.get_commit_range_diff(commit_ids, highlighter)?
+            }
+        };
+        let mut diff_files = self.filter_diff_files(diff_files);
+        if let DiffSource::CommitRange(commit_ids) = &self.diff_source {
+            self.prepend_commit_message(&mut diff_files, commit_ids);
+        }
 
         for file in &diff_files {
             let path = file.display_path().clone();
             self.session.add_file(path, file.status);
         }
 
+        self.reanchor_comments(&diff_files);
         self.diff_files = diff_files;
         self.clear_expanded_gaps();
 
@@ -531,6 +1288,56 @@ impl App {
         Ok(self.diff_files.len())
     }
 
+    /// Change the number of context lines around each hunk and regenerate the
+    /// diff. The previous setting is kept when the diff cannot be reloaded.
+    pub fn set_context_lines(&mut self, lines: u32) -> Result<()> {
+        let previous = self.diff_settings;
+        self.diff_settings.context_lines = lines;
+        self.vcs.set_diff_settings(self.diff_settings);
+        if let Err(e) = self.reload_diff_files() {
+            self.diff_settings = previous;
+            self.vcs.set_diff_settings(previous);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Put the messages of the reviewed commits in front of the files as a
+    /// reviewable entry. Backends without commit messages add nothing.
+    fn prepend_commit_message(&self, files: &mut Vec<DiffFile>, commit_ids: &[String]) {
+        let messages: Vec<(String, String)> = commit_ids
+            .iter()
+            .filter_map(|id| {
+                let message = self.vcs.get_commit_message(id).ok().flatten()?;
+                Some((id[..7.min(id.len())].to_string(), message))
+            })
+            .collect();
+        if !messages.is_empty() {
+            files.insert(0, DiffFile::commit_message(&messages));
+        }
+    }
+
+    /// Include or leave out files excluded by `.tuicrignore` and reload the diff
+    pub fn set_show_ignored(&mut self, show: bool) -> Result<()> {
+        self.show_ignored = show;
+        self.reload_diff_files()?;
+        Ok(())
+    }
+
+    /// Apply the session's pathspecs and, unless `show_ignored` is on,
+    /// `.tuicrignore` to freshly loaded files
+    fn filter_diff_files(&mut self, files: Vec<DiffFile>) -> Vec<DiffFile> {
+        let files = filter_pathspecs(files, &self.session.pathspecs);
+        if self.show_ignored {
+            self.ignored_file_count = 0;
+            return files;
+        }
+        let ignore = TuicrIgnore::load(&self.vcs_info.root_path);
+        let (files, hidden) = filter_ignored(files, &ignore);
+        self.ignored_file_count = hidden;
+        files
+    }
+
     pub fn current_file(&self) -> Option<&DiffFile> {
         self.diff_files.get(self.diff_state.current_file_idx)
     }
@@ -539,12 +1346,14 @@ impl App {
         self.current_file().map(|f| f.display_path())
     }
 
-    pub fn toggle_reviewed(&mut self) {
+    pub fn cycle_review_state(&mut self) {
         let file_idx = self.diff_state.current_file_idx;
-        self.toggle_reviewed_for_file_idx(file_idx, true);
+        self.cycle_review_state_for_file_idx(file_idx, true);
     }
 
-    pub fn toggle_reviewed_for_file_idx(&mut self, file_idx: usize, adjust_cursor: bool) {
+    /// Step a file to its next review state (unreviewed → in progress →
+    /// needs a second look → approved → unreviewed)
+    pub fn cycle_review_state_for_file_idx(&mut self, file_idx: usize, adjust_cursor: bool) {
         let Some(path) = self
             .diff_files
             .get(file_idx)
@@ -554,8 +1363,10 @@ impl App {
         };
 
         if let Some(review) = self.session.get_file_mut(&path) {
-            review.reviewed = !review.reviewed;
+            review.state = review.state.next();
+            let message = format!("{}: {}", path.display(), review.state.label());
             self.dirty = true;
+            self.set_message(message);
             self.rebuild_annotations();
 
             if adjust_cursor {
@@ -568,12 +1379,211 @@ impl App {
         }
     }
 
+    pub fn toggle_excluded(&mut self) {
+        let file_idx = self.diff_state.current_file_idx;
+        self.toggle_excluded_for_file_idx(file_idx);
+    }
+
+    /// Take a file out of the review scope, or bring it back. Excluded files
+    /// stay listed, dimmed, but are folded and left out of progress.
+    pub fn toggle_excluded_for_file_idx(&mut self, file_idx: usize) {
+        let Some(path) = self
+            .diff_files
+            .get(file_idx)
+            .map(|file| file.display_path().clone())
+        else {
+            return;
+        };
+        let Some(review) = self.session.get_file_mut(&path) else {
+            return;
+        };
+        review.excluded = !review.excluded;
+        let message = if review.excluded {
+            format!("{} excluded from the review (x to undo)", path.display())
+        } else {
+            format!("{} is back in the review", path.display())
+        };
+        self.dirty = true;
+        self.set_message(message);
+        self.rebuild_annotations();
+    }
+
+    /// Number of files counted toward review progress.
+    /// Excluded files never count; vendored files only count when
+    /// completion requires all files.
     pub fn file_count(&self) -> usize {
-        self.diff_files.len()
+        self.diff_files
+            .iter()
+            .filter(|file| self.counts_toward_progress(file))
+            .count()
     }
 
     pub fn reviewed_count(&self) -> usize {
-        self.session.reviewed_count()
+        self.state_count(ReviewState::Approved)
+    }
+
+    /// Files counted toward progress that are in `state`
+    pub fn state_count(&self, state: ReviewState) -> usize {
+        self.diff_files
+            .iter()
+            .filter(|file| {
+                self.counts_toward_progress(file)
+                    && self.session.file_state(file.display_path()) == state
+            })
+            .count()
+    }
+
+    fn counts_toward_progress(&self, file: &DiffFile) -> bool {
+        let path = file.display_path();
+        !self.session.is_file_excluded(path)
+            && (self.config.review_complete == CompletionCriteria::AllFiles
+                || !self.is_vendored(path))
+    }
+
+    /// Whether the review meets the configured completion criteria
+    pub fn is_review_complete(&self) -> bool {
+        match self.config.review_complete {
+            CompletionCriteria::BlockingResolved => self.session.blocking_count() == 0,
+            _ => self.reviewed_count() == self.file_count(),
+        }
+    }
+
+    /// Progress toward completion, e.g. `3/5 reviewed` or `2 blocking open`
+    pub fn progress_summary(&self) -> String {
+        match self.config.review_complete {
+            CompletionCriteria::BlockingResolved => {
+                format!("{} blocking open", self.session.blocking_count())
+            }
+            _ => {
+                let mut summary =
+                    format!("{}/{} reviewed", self.reviewed_count(), self.file_count());
+                for (state, label) in [
+                    (ReviewState::InProgress, "in progress"),
+                    (ReviewState::NeedsSecondLook, "second look"),
+                ] {
+                    let count = self.state_count(state);
+                    if count > 0 {
+                        summary.push_str(&format!(", {count} {label}"));
+                    }
+                }
+                let excluded = self
+                    .diff_files
+                    .iter()
+                    .filter(|file| self.session.is_file_excluded(file.display_path()))
+                    .count();
+                if excluded > 0 {
+                    summary.push_str(&format!(", {excluded} excluded"));
+                }
+                summary
+            }
+        }
+    }
+
+    /// First file that still keeps the review from being complete
+    pub fn first_incomplete_file(&self) -> Option<usize> {
+        self.diff_files.iter().position(|file| {
+            let path = file.display_path();
+            match self.config.review_complete {
+                CompletionCriteria::BlockingResolved => self
+                    .session
+                    .files
+                    .get(path)
+                    .is_some_and(|review| review.blocking_count() > 0),
+                _ => self.counts_toward_progress(file) && !self.session.is_file_reviewed(path),
+            }
+        })
+    }
+
+    pub fn is_vendored(&self, path: &PathBuf) -> bool {
+        self.vendored_paths.contains(path)
+    }
+
+    /// Whether a file renders as a header only: reviewed, excluded, or
+    /// vendored while the group is collapsed
+    pub fn is_file_folded(&self, path: &PathBuf) -> bool {
+        self.session.is_file_reviewed(path)
+            || self.session.is_file_excluded(path)
+            || (!self.show_vendored && self.is_vendored(path))
+    }
+
+    /// Expand or collapse the vendored group, keeping the cursor on the current file
+    pub fn toggle_vendored_group(&mut self) {
+        self.show_vendored = !self.show_vendored;
+        self.rebuild_annotations();
+        let file_idx = self.diff_state.current_file_idx;
+        let header_line = self.calculate_file_scroll_offset(file_idx);
+        self.diff_state.cursor_line = header_line;
+        self.ensure_cursor_visible();
+        self.ensure_valid_tree_selection();
+    }
+
+    /// Whether a generated file is still collapsed to its placeholder
+    pub fn is_generated_collapsed(&self, path: &PathBuf) -> bool {
+        self.generated_paths.contains(path) && !self.expanded_generated.contains(path)
+    }
+
+    /// Whether a file's body is a single placeholder line instead of hunks
+    pub fn shows_placeholder(&self, file: &DiffFile) -> bool {
+        file.is_binary || file.hunks.is_empty() || self.is_generated_collapsed(file.display_path())
+    }
+
+    /// Expand or collapse the generated file under the cursor.
+    /// Returns false when the current file is not generated.
+    pub fn toggle_generated_file(&mut self) -> bool {
+        let Some(path) = self.current_file_path().cloned() else {
+            return false;
+        };
+        if !self.generated_paths.contains(&path) {
+            return false;
+        }
+        if !self.expanded_generated.remove(&path) {
+            self.expanded_generated.insert(path);
+        }
+        self.rebuild_annotations();
+        let header_line = self.calculate_file_scroll_offset(self.diff_state.current_file_idx);
+        self.diff_state.cursor_line = header_line;
+        self.ensure_cursor_visible();
+        true
+    }
+
+    fn detect_generated_paths(&self, attributes: &GitAttributes) -> HashSet<PathBuf> {
+        self.diff_files
+            .iter()
+            .map(|file| file.display_path())
+            .filter(|path| {
+                attributes.is_set(path, "linguist-generated") || attributes.is_unset(path, "diff")
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn detect_vendored_paths(&self, attributes: &GitAttributes) -> HashSet<PathBuf> {
+        self.diff_files
+            .iter()
+            .map(|file| file.display_path())
+            .filter(|path| {
+                attributes.is_set(path, "linguist-vendored")
+                    || self
+                        .config
+                        .vendor_globs
+                        .iter()
+                        .any(|pattern| crate::glob::matches_path(pattern, path))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Write the session to disk, snapshotting the diff into it when
+    /// `snapshot_diff` is on. Returns None for a reopened snapshot, which is
+    /// never written back.
+    pub fn save_session(&mut self) -> Result<Option<PathBuf>> {
+        if self.read_only {
+            return Ok(None);
+        }
+        if self.config.snapshot_diff {
+            self.session.snapshot = Some(DiffSnapshot::capture(&self.diff_files)?);
+        }
+        save_session(&self.session).map(Some)
     }
 
     pub fn set_message(&mut self, msg: impl Into<String>) {
@@ -611,10 +1621,14 @@ impl App {
     }
 
     pub fn scroll_down(&mut self, lines: usize) {
+        let max_scroll = self.max_scroll_offset();
+        if self.config.free_scroll {
+            self.diff_state.scroll_offset = (self.diff_state.scroll_offset + lines).min(max_scroll);
+            return;
+        }
         // For half-page/page scrolling, move both cursor and scroll
         let total = self.total_lines();
         let max_line = total.saturating_sub(1);
-        let max_scroll = self.max_scroll_offset();
         self.diff_state.cursor_line = (self.diff_state.cursor_line + lines).min(max_line);
         self.diff_state.scroll_offset = (self.diff_state.scroll_offset + lines).min(max_scroll);
         self.ensure_cursor_visible();
@@ -622,6 +1636,10 @@ impl App {
     }
 
     pub fn scroll_up(&mut self, lines: usize) {
+        if self.config.free_scroll {
+            self.diff_state.scroll_offset = self.diff_state.scroll_offset.saturating_sub(lines);
+            return;
+        }
         // For half-page/page scrolling, move both cursor and scroll
         self.diff_state.cursor_line = self.diff_state.cursor_line.saturating_sub(lines);
         self.diff_state.scroll_offset = self.diff_state.scroll_offset.saturating_sub(lines);
@@ -648,6 +1666,52 @@ impl App {
             (self.diff_state.scroll_x.saturating_add(cols)).min(max_scroll_x);
     }
 
+    /// Move the column cursor along the current diff line with a word motion
+    /// over byte offsets, scrolling it into view when lines are not wrapped
+    pub fn move_column(&mut self, motion: fn(&str, usize) -> usize) {
+        let Some(content) = self.cursor_diff_line_content() else {
+            return;
+        };
+        let mut start = position_at_column(content, 0, self.diff_state.cursor_col.unwrap_or(0));
+        if start == content.len() {
+            start = prev_grapheme_boundary(content, start);
+        }
+        let column = content[..motion(content, start)].width();
+        self.diff_state.cursor_col = Some(column);
+
+        if self.diff_state.wrap_lines {
+            return;
+        }
+        let screen_column = UNIFIED_GUTTER_WIDTH + column;
+        let width = self.diff_state.viewport_width.saturating_sub(1).max(1);
+        if screen_column < self.diff_state.scroll_x {
+            self.diff_state.scroll_x = screen_column;
+        } else if screen_column >= self.diff_state.scroll_x + width {
+            self.diff_state.scroll_x = screen_column + 1 - width;
+        }
+    }
+
+    /// Text of the diff line under the cursor
+    fn cursor_diff_line_content(&self) -> Option<&str> {
+        let AnnotatedLine::DiffLine {
+            file_idx,
+            hunk_idx,
+            line_idx,
+            ..
+        } = self.line_annotations.get(self.diff_state.cursor_line)?
+        else {
+            return None;
+        };
+        let line = self
+            .diff_files
+            .get(*file_idx)?
+            .hunks
+            .get(*hunk_idx)?
+            .lines
+            .get(*line_idx)?;
+        Some(&line.content)
+    }
+
     pub fn toggle_diff_wrap(&mut self) {
         let enabled = !self.diff_state.wrap_lines;
         self.set_diff_wrap(enabled);
@@ -666,14 +1730,19 @@ impl App {
         self.set_message(format!("Diff wrapping: {status}"));
     }
 
-    fn ensure_cursor_visible(&mut self) {
+    /// Logical diff lines that fit on screen
+    fn visible_lines(&self) -> usize {
         // Use visible_line_count which is computed during render based on actual line widths.
         // Fall back to viewport_height if not yet set (before first render).
-        let visible_lines = if self.diff_state.visible_line_count > 0 {
+        if self.diff_state.visible_line_count > 0 {
             self.diff_state.visible_line_count
         } else {
             self.diff_state.viewport_height.max(1)
-        };
+        }
+    }
+
+    fn ensure_cursor_visible(&mut self) {
+        let visible_lines = self.visible_lines();
         let max_scroll = self.max_scroll_offset();
         if self.diff_state.cursor_line < self.diff_state.scroll_offset {
             self.diff_state.scroll_offset = self.diff_state.cursor_line;
@@ -827,13 +1896,27 @@ impl App {
             }
             AnnotatedLine::BinaryOrEmpty { file_idx } => {
                 let file = self.diff_files.get(*file_idx)?;
-                if file.is_binary {
+                if self.is_generated_collapsed(file.display_path()) {
+                    Some(GENERATED_PLACEHOLDER.to_string())
+                } else if let Some(special) = &file.special {
+                    Some(special.summary())
+                } else if let Some(detail) = &file.binary_detail {
+                    Some(detail.summary())
+                } else if file.is_binary {
                     Some("(binary file)".to_string())
+                } else if file.mode_change.is_some() {
+                    Some("(mode change only)".to_string())
                 } else {
                     Some("(no changes)".to_string())
                 }
             }
             AnnotatedLine::Spacing => None,
+            AnnotatedLine::GeneralHeader => Some("General".to_string()),
+            AnnotatedLine::GeneralComment { entry_idx } => self
+                .session
+                .general_comments()
+                .get(*entry_idx)
+                .map(|(_, comment)| comment.content.clone()),
         }
     }
 
@@ -851,6 +1934,37 @@ impl App {
         ))
     }
 
+    /// Switch page scrolling between moving the cursor along and only
+    /// moving the view
+    pub fn toggle_free_scroll(&mut self) {
+        self.config.free_scroll = !self.config.free_scroll;
+        if self.config.free_scroll {
+            self.set_message("Free scroll: Ctrl-d/u move the view only, zv brings the cursor");
+        } else {
+            self.ensure_cursor_visible();
+            self.set_message("Free scroll off: the cursor follows page scrolling");
+        }
+    }
+
+    /// Turn the plain-language notes on hunk headers on or off
+    pub fn toggle_guide(&mut self) {
+        self.config.guide = !self.config.guide;
+        if self.config.guide {
+            self.set_message("Guide on: hunk headers explain the diff and suggest keys");
+        } else {
+            self.set_message("Guide off");
+        }
+    }
+
+    /// Move the cursor to the middle of the view, for when free scrolling
+    /// has left it off screen
+    pub fn cursor_to_view(&mut self) {
+        let max_line = self.total_lines().saturating_sub(1);
+        self.diff_state.cursor_line =
+            (self.diff_state.scroll_offset + self.visible_lines() / 2).min(max_line);
+        self.update_current_file_from_cursor();
+    }
+
     pub fn center_cursor(&mut self) {
         let viewport = self.diff_state.viewport_height.max(1);
         let half_viewport = viewport / 2;
@@ -948,8 +2062,8 @@ impl App {
             // File header
             cumulative += 1;
 
-            // If file is reviewed, skip all content
-            if self.session.is_file_reviewed(path) {
+            // If file is folded, skip all content
+            if self.is_file_folded(path) {
                 continue;
             }
 
@@ -958,7 +2072,7 @@ impl App {
                 cumulative += review.file_comments.len();
             }
 
-            if file.is_binary || file.hunks.is_empty() {
+            if self.shows_placeholder(file) {
                 cumulative += 1; // "(binary file)" or "(no changes)"
             } else {
                 for hunk in &file.hunks {
@@ -987,8 +2101,8 @@ impl App {
 
             cumulative += 1; // File header
 
-            // If file is reviewed, skip all content
-            if self.session.is_file_reviewed(path) {
+            // If file is folded, skip all content
+            if self.is_file_folded(path) {
                 continue;
             }
 
@@ -996,7 +2110,7 @@ impl App {
                 cumulative += review.file_comments.len();
             }
 
-            if file.is_binary || file.hunks.is_empty() {
+            if self.shows_placeholder(file) {
                 cumulative += 1;
             } else {
                 for hunk in &file.hunks {
@@ -1038,8 +2152,8 @@ impl App {
     fn file_render_height(&self, file_idx: usize, file: &DiffFile) -> usize {
         let path = file.display_path();
 
-        // If reviewed, only show header (1 line total)
-        if self.session.is_file_reviewed(path) {
+        // If folded, only show header (1 line total)
+        if self.is_file_folded(path) {
             return 1;
         }
 
@@ -1054,7 +2168,7 @@ impl App {
             }
         }
 
-        if file.is_binary || file.hunks.is_empty() {
+        if self.shows_placeholder(file) {
             content_lines = 1;
         } else {
             let line_comments = self.session.files.get(path).map(|r| &r.line_comments);
@@ -1141,7 +2255,29 @@ impl App {
             .iter()
             .enumerate()
             .map(|(i, f)| self.file_render_height(i, f))
-            .sum()
+            .sum::<usize>()
+            + self.general_section_height()
+    }
+
+    /// Lines taken by the "General" section: its header plus directory and session comments
+    fn general_section_height(&self) -> usize {
+        if self.diff_files.is_empty() {
+            return 0;
+        }
+        1 + self
+            .session
+            .general_comments()
+            .iter()
+            .map(|(_, comment)| Self::comment_display_lines(comment))
+            .sum::<usize>()
+    }
+
+    /// Whether the cursor is in the "General" section after the last file
+    pub fn cursor_in_general_section(&self) -> bool {
+        matches!(
+            self.line_annotations.get(self.diff_state.cursor_line),
+            Some(AnnotatedLine::GeneralHeader | AnnotatedLine::GeneralComment { .. })
+        )
     }
 
     /// Calculate the maximum scroll offset.
@@ -1167,7 +2303,12 @@ impl App {
     /// Calculate the number of display lines a comment takes (header + content + footer)
     fn comment_display_lines(comment: &Comment) -> usize {
         let content_lines = comment.content.split('\n').count();
-        2 + content_lines // header + content lines + footer
+        let reply_lines: usize = comment
+            .replies
+            .iter()
+            .map(|reply| reply.content.split('\n').count())
+            .sum();
+        2 + content_lines + reply_lines // header + content lines + replies + footer
     }
 
     /// Returns the source line number and side at the current cursor position, if on a diff line
@@ -1188,9 +2329,31 @@ impl App {
         }
     }
 
+    /// Whether the cursor is on the summary line of a symlink or other special
+    /// entry, or on a line of an expanded submodule log
+    pub fn cursor_on_special_entry(&self) -> bool {
+        let file_idx = match self.line_annotations.get(self.diff_state.cursor_line) {
+            Some(AnnotatedLine::BinaryOrEmpty { file_idx }) => file_idx,
+            Some(AnnotatedLine::DiffLine { file_idx, .. }) => file_idx,
+            _ => return false,
+        };
+        self.diff_files
+            .get(*file_idx)
+            .is_some_and(|file| file.special.is_some())
+    }
+
     /// Find the comment at the current cursor position
     fn find_comment_at_cursor(&self) -> Option<CommentLocation> {
-        let target = self.diff_state.cursor_line;
+        self.find_comment_at(self.diff_state.cursor_line)
+    }
+
+    /// Whether the cursor is on a comment rather than a diff line
+    pub fn cursor_on_comment(&self) -> bool {
+        self.find_comment_at_cursor().is_some()
+    }
+
+    /// Comment shown on annotation row `target`, if any
+    fn find_comment_at(&self, target: usize) -> Option<CommentLocation> {
         match self.line_annotations.get(target) {
             Some(AnnotatedLine::FileComment {
                 file_idx,
@@ -1216,10 +2379,138 @@ impl App {
                     index: *comment_idx,
                 })
             }
+            Some(AnnotatedLine::GeneralComment { entry_idx }) => {
+                let (_, comment) = self.session.general_comments().get(*entry_idx).copied()?;
+                Some(CommentLocation::General {
+                    id: comment.id.clone(),
+                })
+            }
             _ => None,
         }
     }
 
+    /// Id of the comment at the current cursor position
+    fn comment_id_at_cursor(&self) -> Option<String> {
+        self.comment_id_at(self.diff_state.cursor_line)
+    }
+
+    fn comment_id_at(&self, target: usize) -> Option<String> {
+        match self.find_comment_at(target)? {
+            CommentLocation::FileComment { path, index } => {
+                let review = self.session.files.get(&path)?;
+                Some(review.file_comments.get(index)?.id.clone())
+            }
+            CommentLocation::LineComment {
+                path,
+                line,
+                side,
+                index,
+            } => {
+                let comments = self.session.files.get(&path)?.line_comments.get(&line)?;
+                comments
+                    .iter()
+                    .filter(|c| c.side.unwrap_or(LineSide::New) == side)
+                    .nth(index)
+                    .map(|c| c.id.clone())
+            }
+            CommentLocation::General { id } => Some(id),
+        }
+    }
+
+    /// Mark the comment at the cursor resolved, or reopen it.
+    /// Returns false when there is no comment at the cursor.
+    pub fn toggle_resolved_at_cursor(&mut self) -> bool {
+        let Some(comment) = self
+            .comment_id_at_cursor()
+            .and_then(|id| self.session.find_comment_mut(&id))
+        else {
+            return false;
+        };
+        comment.resolved = !comment.resolved;
+        let message = if comment.resolved {
+            "Comment resolved"
+        } else {
+            "Comment reopened"
+        };
+        self.dirty = true;
+        self.set_message(message);
+        true
+    }
+
+    /// Id of the suggested change at the cursor, if that is what the cursor is on
+    pub fn suggestion_at_cursor(&self) -> Option<String> {
+        let id = self.comment_id_at_cursor()?;
+        let comment = self.session.find_comment(&id)?;
+        (comment.comment_type == CommentType::CodeSuggestion).then_some(id)
+    }
+
+    /// Replace the commented lines in the file on disk with a suggested
+    /// change, mark the comment resolved and reload the diff. Only the
+    /// working tree is under review then: a commit's lines are not on disk.
+    pub fn apply_suggestion(&mut self, id: &str) -> Result<String> {
+        if self.is_stdin_diff() {
+            return Err(TuicrError::UnsupportedOperation(
+                "diff was read from stdin".into(),
+            ));
+        }
+        if self.diff_source != DiffSource::WorkingTree {
+            return Err(TuicrError::UnsupportedOperation(
+                "suggested changes can only be applied while reviewing the working tree".into(),
+            ));
+        }
+        let Some((path, range, comment)) = self.session.files.iter().find_map(|(path, review)| {
+            review.line_comments.iter().find_map(|(line, comments)| {
+                let comment = comments.iter().find(|c| c.id == id)?;
+                let range = comment.line_range.unwrap_or(LineRange::single(*line));
+                Some((path.clone(), range, comment.clone()))
+            })
+        }) else {
+            return Err(TuicrError::UnsupportedOperation(
+                "only suggested changes on diff lines can be applied".into(),
+            ));
+        };
+        if comment.side == Some(LineSide::Old) {
+            return Err(TuicrError::UnsupportedOperation(
+                "the suggestion is on deleted lines".into(),
+            ));
+        }
+
+        let full_path = self.vcs_info.root_path.join(&path);
+        let text = std::fs::read_to_string(&full_path)?;
+        let reviewed: Vec<&str> = self
+            .diff_files
+            .iter()
+            .find(|file| file.display_path() == &path)
+            .into_iter()
+            .flat_map(|file| file.hunks.iter().flat_map(|hunk| &hunk.lines))
+            .filter(|line| {
+                line.new_lineno
+                    .is_some_and(|lineno| (range.start..=range.end).contains(&lineno))
+            })
+            .map(|line| line.content.as_str())
+            .collect();
+        if !lines_unchanged(&text, range, &reviewed) {
+            return Err(TuicrError::UnsupportedOperation(format!(
+                "{} changed since the diff was loaded; reload with :e",
+                path.display()
+            )));
+        }
+        let Some(updated) = splice_lines(&text, range, &comment.content) else {
+            return Err(TuicrError::UnsupportedOperation(format!(
+                "{} is shorter than the suggestion's lines",
+                path.display()
+            )));
+        };
+        std::fs::write(&full_path, updated)?;
+
+        if let Some(comment) = self.session.find_comment_mut(id) {
+            comment.resolved = true;
+        }
+        self.dirty = true;
+        self.reload_diff_files()?;
+        Ok(format!("Applied suggested change to {}", path.display()))
+    }
+
     /// Delete the comment at the current cursor position, if any
     /// Returns true if a comment was deleted
     pub fn delete_comment_at_cursor(&mut self) -> bool {
@@ -1227,8 +2518,11 @@ impl App {
 
         match location {
             Some(CommentLocation::FileComment { path, index }) => {
-                if let Some(review) = self.session.get_file_mut(&path) {
-                    review.file_comments.remove(index);
+                if let Some(review) = self.session.get_file_mut(&path)
+                    && index < review.file_comments.len()
+                {
+                    let comment = review.file_comments.remove(index);
+                    self.remember_deleted_comment(CommentSlot::File(path), index, comment);
                     self.dirty = true;
                     self.set_message("Comment deleted");
                     self.rebuild_annotations();
@@ -1258,10 +2552,11 @@ impl App {
                         }
                     }
                     if let Some(idx) = actual_idx {
-                        comments.remove(idx);
+                        let comment = comments.remove(idx);
                         if comments.is_empty() {
                             review.line_comments.remove(&line);
                         }
+                        self.remember_deleted_comment(CommentSlot::Line(path, line), idx, comment);
                         self.dirty = true;
                         self.set_message(format!("Comment on line {line} deleted"));
                         self.rebuild_annotations();
@@ -1269,12 +2564,180 @@ impl App {
                     }
                 }
             }
+            Some(CommentLocation::General { id }) => {
+                if let Some((dir, index, comment)) = self.session.remove_general_comment(&id) {
+                    self.remember_deleted_comment(CommentSlot::General(dir), index, comment);
+                    self.dirty = true;
+                    self.set_message("Comment deleted");
+                    self.rebuild_annotations();
+                    return true;
+                }
+            }
             None => {}
         }
 
         false
     }
 
+    /// Delete a comment wherever it is, keeping it for `u` like `dd` does
+    pub fn delete_comment_by_id(&mut self, id: &str) -> bool {
+        let removed = self
+            .session
+            .remove_general_comment(id)
+            .map(|(dir, index, comment)| (CommentSlot::General(dir), index, comment))
+            .or_else(|| {
+                self.session.files.iter_mut().find_map(|(path, review)| {
+                    if let Some(index) = review.file_comments.iter().position(|c| c.id == id) {
+                        let comment = review.file_comments.remove(index);
+                        return Some((CommentSlot::File(path.clone()), index, comment));
+                    }
+                    let (line, index) =
+                        review.line_comments.iter().find_map(|(line, comments)| {
+                            Some((*line, comments.iter().position(|c| c.id == id)?))
+                        })?;
+                    let comments = review.line_comments.get_mut(&line)?;
+                    let comment = comments.remove(index);
+                    if comments.is_empty() {
+                        review.line_comments.remove(&line);
+                    }
+                    Some((CommentSlot::Line(path.clone(), line), index, comment))
+                })
+            });
+        let Some((slot, index, comment)) = removed else {
+            return false;
+        };
+        self.remember_deleted_comment(slot, index, comment);
+        self.dirty = true;
+        self.set_message("Comment deleted");
+        self.rebuild_annotations();
+        true
+    }
+
+    fn remember_deleted_comment(&mut self, slot: CommentSlot, index: usize, comment: Comment) {
+        if self.deleted_comments.len() == UNDO_DELETE_LIMIT {
+            self.deleted_comments.remove(0);
+        }
+        self.deleted_comments.push(DeletedComment {
+            slot,
+            index,
+            comment,
+        });
+    }
+
+    /// Put the most recently deleted comment back where it was.
+    /// Returns false when there is nothing to undo.
+    pub fn undo_delete_comment(&mut self) -> bool {
+        let Some(DeletedComment {
+            slot,
+            index,
+            comment,
+        }) = self.deleted_comments.pop()
+        else {
+            return false;
+        };
+
+        let message = match slot {
+            CommentSlot::File(path) => match self.session.get_file_mut(&path) {
+                Some(review) => {
+                    let comments = &mut review.file_comments;
+                    comments.insert(index.min(comments.len()), comment);
+                    "Comment restored".to_string()
+                }
+                None => format!("{} is no longer in the review", path.display()),
+            },
+            CommentSlot::Line(path, line) => match self.session.get_file_mut(&path) {
+                Some(review) => {
+                    let comments = review.line_comments.entry(line).or_default();
+                    comments.insert(index.min(comments.len()), comment);
+                    format!("Comment on line {line} restored")
+                }
+                None => format!("{} is no longer in the review", path.display()),
+            },
+            CommentSlot::General(dir) => {
+                self.session.insert_general_comment(dir, index, comment);
+                "Comment restored".to_string()
+            }
+        };
+        self.dirty = true;
+        self.set_message(message);
+        self.rebuild_annotations();
+        true
+    }
+
+    /// Copy the comment at the cursor, for `p` to attach to another line.
+    /// Returns false when there is no comment at the cursor.
+    pub fn yank_comment_at_cursor(&mut self) -> bool {
+        let comment = match self.find_comment_at_cursor() {
+            Some(CommentLocation::FileComment { path, index }) => self
+                .session
+                .files
+                .get(&path)
+                .and_then(|review| review.file_comments.get(index)),
+            Some(CommentLocation::LineComment {
+                path,
+                line,
+                side,
+                index,
+            }) => self
+                .session
+                .files
+                .get(&path)
+                .and_then(|review| review.line_comments.get(&line))
+                .and_then(|comments| {
+                    comments
+                        .iter()
+                        .filter(|c| c.side.unwrap_or(LineSide::New) == side)
+                        .nth(index)
+                }),
+            Some(CommentLocation::General { id }) => self
+                .session
+                .general_comments()
+                .into_iter()
+                .find_map(|(_, comment)| (comment.id == id).then_some(comment)),
+            None => None,
+        };
+        let Some(comment) = comment.cloned() else {
+            return false;
+        };
+        self.yanked_comment = Some(comment);
+        self.set_message("Comment copied: p attaches it to the line at the cursor");
+        true
+    }
+
+    /// Attach a copy of the yanked comment to the diff line at the cursor
+    pub fn paste_comment(&mut self) {
+        if self.yanked_comment.is_none() {
+            self.set_message("No comment copied (Y copies the one at the cursor)");
+            return;
+        }
+        let Some((line, side)) = self.get_line_at_cursor() else {
+            self.set_message("Move to a diff line to paste the comment");
+            return;
+        };
+        let Some(mut comment) = self
+            .yanked_comment
+            .as_ref()
+            .map(|yanked| yanked.duplicate(Some(side)))
+        else {
+            return;
+        };
+        comment.author = self.reviewer.clone();
+        comment.conflict_side = self.conflict_side_at(line, side);
+        comment.anchor = self.current_file().and_then(|file| {
+            ContentAnchor::capture(&side_lines(file, side), LineRange::single(line))
+        });
+        let Some(path) = self.current_file_path().cloned() else {
+            return;
+        };
+        let Some(review) = self.session.get_file_mut(&path) else {
+            return;
+        };
+        review.add_line_comment(line, comment);
+        self.dirty = true;
+        self.set_message(format!("Comment copied to line {line}"));
+        self.rebuild_annotations();
+    }
+
     pub fn clear_all_comments(&mut self) {
         let cleared = self.session.clear_comments();
         if cleared == 0 {
@@ -1287,6 +2750,25 @@ impl App {
         self.set_message(format!("Cleared {cleared} comments"));
     }
 
+    /// Delete the file and line comments of `path`, to start its feedback over
+    pub fn clear_file_comments(&mut self, path: &PathBuf) {
+        let cleared = self
+            .session
+            .get_file_mut(path)
+            .map_or(0, |review| review.clear_comments());
+        if cleared == 0 {
+            self.set_message(format!("No comments on {}", path.display()));
+            return;
+        }
+
+        self.dirty = true;
+        self.rebuild_annotations();
+        self.set_message(format!(
+            "Cleared {cleared} comment(s) on {}",
+            path.display()
+        ));
+    }
+
     /// Enter edit mode for the comment at the current cursor position
     /// Returns true if a comment was found and edit mode entered
     pub fn enter_edit_mode(&mut self) -> bool {
@@ -1301,6 +2783,8 @@ impl App {
                     self.comment_buffer = comment.content.clone();
                     self.comment_cursor = self.comment_buffer.len();
                     self.comment_type = comment.comment_type;
+                    self.comment_severity = comment.severity;
+                    self.comment_required_action = comment.action;
                     self.comment_is_file_level = true;
                     self.comment_line = None;
                     self.editing_comment_id = Some(comment.id.clone());
@@ -1326,6 +2810,8 @@ impl App {
                                 self.comment_buffer = comment.content.clone();
                                 self.comment_cursor = self.comment_buffer.len();
                                 self.comment_type = comment.comment_type;
+                                self.comment_severity = comment.severity;
+                                self.comment_required_action = comment.action;
                                 self.comment_is_file_level = false;
                                 self.comment_line = Some((line, side));
                                 self.editing_comment_id = Some(comment.id.clone());
@@ -1336,12 +2822,66 @@ impl App {
                     }
                 }
             }
+            Some(CommentLocation::General { id }) => {
+                let target =
+                    self.session
+                        .general_comments()
+                        .into_iter()
+                        .find_map(|(dir, comment)| {
+                            (comment.id == id).then(|| {
+                                let target = match dir {
+                                    Some(dir) => GeneralTarget::Directory(dir.clone()),
+                                    None => GeneralTarget::Session,
+                                };
+                                (
+                                    target,
+                                    comment.content.clone(),
+                                    comment.comment_type,
+                                    comment.severity,
+                                    comment.action,
+                                )
+                            })
+                        });
+                if let Some((target, content, comment_type, severity, required_action)) = target {
+                    self.enter_general_comment_mode(target);
+                    self.comment_buffer = content;
+                    self.comment_cursor = self.comment_buffer.len();
+                    self.comment_type = comment_type;
+                    self.comment_severity = severity;
+                    self.comment_required_action = required_action;
+                    self.editing_comment_id = Some(id);
+                    return true;
+                }
+            }
             None => {}
         }
 
         false
     }
 
+    /// Start a reply to the comment at the cursor. The input opens where a
+    /// new comment on the same anchor would. Returns false without a comment
+    pub fn enter_reply_mode(&mut self) -> bool {
+        if !self.enter_edit_mode() {
+            return false;
+        }
+        self.replying_to_comment_id = self.editing_comment_id.take();
+        self.comment_buffer.clear();
+        self.comment_cursor = 0;
+        true
+    }
+
+    /// Header verb for the comment input box
+    pub fn comment_input_action(&self) -> &'static str {
+        if self.replying_to_comment_id.is_some() {
+            "Reply"
+        } else if self.editing_comment_id.is_some() {
+            "Edit"
+        } else {
+            "Add"
+        }
+    }
+
     pub fn enter_command_mode(&mut self) {
         self.input_mode = InputMode::Command;
         self.command_buffer.clear();
@@ -1363,12 +2903,218 @@ impl App {
     }
 
     pub fn enter_comment_mode(&mut self, file_level: bool, line: Option<(u32, LineSide)>) {
+        self.comment_columns = if file_level || line.is_none() {
+            None
+        } else {
+            self.column_word_at_cursor()
+        };
         self.input_mode = InputMode::Comment;
         self.comment_buffer.clear();
         self.comment_cursor = 0;
         self.comment_type = CommentType::Note;
+        self.comment_severity = None;
+        self.comment_required_action = None;
         self.comment_is_file_level = file_level;
         self.comment_line = line;
+        self.comment_general_target = None;
+    }
+
+    /// Columns of the word under the column cursor, when one is shown
+    fn column_word_at_cursor(&self) -> Option<ColumnRange> {
+        let column = self.diff_state.cursor_col?;
+        let content = self.cursor_diff_line_content()?;
+        let word = word_at(content, position_at_column(content, 0, column));
+        if word.is_empty() || content[word.clone()].trim().is_empty() {
+            return None;
+        }
+        let start = content[..word.start].width() as u32 + 1;
+        let end = content[..word.end].width() as u32;
+        Some(ColumnRange { start, end })
+    }
+
+    /// Start a line comment that quotes the diff line under the cursor in a
+    /// fenced block, with the text cursor on the line below it.
+    /// Returns false when the cursor is not on a diff line.
+    pub fn enter_quote_comment_mode(&mut self) -> bool {
+        let Some(AnnotatedLine::DiffLine {
+            file_idx,
+            hunk_idx,
+            line_idx,
+            ..
+        }) = self.line_annotations.get(self.diff_state.cursor_line)
+        else {
+            return false;
+        };
+        let Some(file) = self.diff_files.get(*file_idx) else {
+            return false;
+        };
+        let Some(line) = file
+            .hunks
+            .get(*hunk_idx)
+            .and_then(|hunk| hunk.lines.get(*line_idx))
+        else {
+            return false;
+        };
+
+        let quote = fenced_quote(file.display_path(), &line.content);
+        let target = self.get_line_at_cursor();
+        self.enter_comment_mode(false, target);
+        self.comment_buffer = quote;
+        self.comment_cursor = self.comment_buffer.len();
+        true
+    }
+
+    /// Start a comment on a directory or on the whole review. The input is
+    /// shown in the "General" section, so the cursor moves there.
+    pub fn enter_general_comment_mode(&mut self, target: GeneralTarget) {
+        self.enter_comment_mode(false, None);
+        self.comment_general_target = Some(target);
+        self.diff_state.cursor_line = self.calculate_file_scroll_offset(self.diff_files.len());
+        self.ensure_cursor_visible();
+    }
+
+    /// Cut `start..end` out of the comment buffer into the kill ring,
+    /// leaving the cursor where the text was
+    pub fn kill_comment_text(&mut self, start: usize, end: usize) {
+        let killed: String = self.comment_buffer.drain(start..end).collect();
+        self.kill_ring.push(killed);
+        self.comment_cursor = start;
+    }
+
+    /// Insert the most recent kill at the cursor
+    pub fn yank_comment_text(&mut self) {
+        if let Some(text) = self.kill_ring.latest() {
+            self.comment_buffer.insert_str(self.comment_cursor, text);
+            self.comment_cursor += text.len();
+        }
+    }
+
+    /// The anchor the comment being written belongs to
+    fn comment_draft_anchor(&self) -> Option<DraftAnchor> {
+        if let Some(id) = &self.replying_to_comment_id {
+            return Some(DraftAnchor::Reply(id.clone()));
+        }
+        if let Some(id) = &self.editing_comment_id {
+            return Some(DraftAnchor::Edit(id.clone()));
+        }
+        if let Some(target) = &self.comment_general_target {
+            return Some(DraftAnchor::General(target.clone()));
+        }
+        let path = self.current_file_path()?.clone();
+        Some(if self.comment_is_file_level {
+            DraftAnchor::File(path)
+        } else if let Some((range, side)) = self.comment_line_range {
+            DraftAnchor::Line(path, range.end, side)
+        } else if let Some((line, side)) = self.comment_line {
+            DraftAnchor::Line(path, line, side)
+        } else {
+            DraftAnchor::File(path)
+        })
+    }
+
+    /// The commented diff line or range with a line of context either side,
+    /// empty unless a line comment is being written or edited
+    pub fn comment_anchor_preview(&self) -> Vec<AnchorPreviewLine> {
+        let mut lines = self.comment_anchor_lines(ANCHOR_PREVIEW_CONTEXT);
+        lines.truncate(ANCHOR_PREVIEW_MAX_LINES);
+        lines
+    }
+
+    /// Put the commented diff line or range at the top of the comment as a
+    /// fenced code block. Returns false when the comment is not on diff lines.
+    pub fn quote_anchor_in_comment(&mut self) -> bool {
+        let lines = self.comment_anchor_lines(0);
+        if lines.is_empty() {
+            return false;
+        }
+        let Some(file) = self.current_file() else {
+            return false;
+        };
+        let code: Vec<&str> = lines.iter().map(|line| line.content.as_str()).collect();
+        let quote = fenced_quote(file.display_path(), &code.join("\n"));
+        self.comment_buffer.insert_str(0, &quote);
+        self.comment_cursor += quote.len();
+        true
+    }
+
+    /// Diff lines of the comment's anchor with `context` lines either side
+    fn comment_anchor_lines(&self, context: u32) -> Vec<AnchorPreviewLine> {
+        if self.input_mode != InputMode::Comment
+            || self.comment_is_file_level
+            || self.comment_general_target.is_some()
+            || self.replying_to_comment_id.is_some()
+        {
+            return Vec::new();
+        }
+        let (range, side) = match (self.comment_line_range, self.comment_line) {
+            (Some((range, side)), _) => (range, side),
+            (None, Some((line, side))) => (LineRange::single(line), side),
+            (None, None) => return Vec::new(),
+        };
+        let Some(file) = self.current_file() else {
+            return Vec::new();
+        };
+        let first = range.start.saturating_sub(context);
+        let last = range.end + context;
+        file.hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .filter_map(|line| {
+                let lineno = match side {
+                    LineSide::Old => line.old_lineno,
+                    LineSide::New => line.new_lineno,
+                }?;
+                (first..=last).contains(&lineno).then(|| AnchorPreviewLine {
+                    lineno,
+                    origin: line.origin,
+                    content: line.content.clone(),
+                    anchored: (range.start..=range.end).contains(&lineno),
+                })
+            })
+            .collect()
+    }
+
+    /// Leave comment mode without saving, stashing any typed text as a draft
+    pub fn cancel_comment(&mut self) {
+        let text = self.comment_buffer.trim();
+        let unchanged_edit = self
+            .editing_comment_id
+            .as_deref()
+            .and_then(|id| self.session.find_comment(id))
+            .is_some_and(|c| c.content == text);
+        if !text.is_empty()
+            && !unchanged_edit
+            && let Some(anchor) = self.comment_draft_anchor()
+        {
+            self.comment_drafts
+                .insert(anchor, self.comment_buffer.clone());
+            self.set_message("Draft kept — Ctrl-R restores it here");
+        }
+        self.exit_comment_mode();
+    }
+
+    /// Point out a stashed draft when comment mode opens on its anchor
+    pub fn offer_comment_draft(&mut self) {
+        if let Some(anchor) = self.comment_draft_anchor()
+            && self.comment_drafts.contains_key(&anchor)
+        {
+            self.set_message("Unsaved draft here — Ctrl-R restores it");
+        }
+    }
+
+    /// Swap the stashed draft for this anchor into the comment buffer
+    pub fn restore_comment_draft(&mut self) {
+        let Some(anchor) = self.comment_draft_anchor() else {
+            return;
+        };
+        match self.comment_drafts.remove(&anchor) {
+            Some(draft) => {
+                self.comment_buffer = draft;
+                self.comment_cursor = self.comment_buffer.len();
+                self.set_message("Draft restored");
+            }
+            None => self.set_message("No draft here"),
+        }
     }
 
     pub fn exit_comment_mode(&mut self) {
@@ -1376,7 +3122,11 @@ impl App {
         self.comment_buffer.clear();
         self.comment_cursor = 0;
         self.editing_comment_id = None;
+        self.replying_to_comment_id = None;
         self.comment_line_range = None;
+        self.comment_general_target = None;
+        self.comment_columns = None;
+        self.comment_length_warned = false;
     }
 
     /// Enter visual selection mode, anchoring at the current cursor position
@@ -1410,6 +3160,13 @@ impl App {
         Some((range, anchor_side))
     }
 
+    /// The diff text of the visual selection, without the gutter, for the
+    /// clipboard
+    pub fn visual_selection_text(&self, prefixes: bool) -> Option<String> {
+        let (range, side) = self.get_visual_selection()?;
+        Some(selection_text(self.current_file()?, range, side, prefixes))
+    }
+
     /// Check if a given line is within the current visual selection
     pub fn is_line_in_visual_selection(&self, line: u32, side: LineSide) -> bool {
         if let Some((range, sel_side)) = self.get_visual_selection() {
@@ -1428,6 +3185,8 @@ impl App {
             self.comment_buffer.clear();
             self.comment_cursor = 0;
             self.comment_type = CommentType::Note;
+            self.comment_severity = None;
+            self.comment_required_action = None;
             self.comment_is_file_level = false;
             self.visual_anchor = None;
         } else {
@@ -1441,25 +3200,87 @@ impl App {
             self.set_message("Comment cannot be empty");
             return;
         }
+        let limits = self.config.comment_limits;
+        if limits.too_long(&self.comment_buffer) && !self.comment_length_warned {
+            self.comment_length_warned = true;
+            self.set_warning(format!(
+                "Comment is {} characters, over the limit of {}; save again to keep it anyway",
+                self.comment_buffer.chars().count(),
+                limits.max_chars.unwrap_or_default()
+            ));
+            return;
+        }
 
-        let content = self.comment_buffer.trim().to_string();
-
-        if let Some(path) = self.current_file_path().cloned()
-            && let Some(review) = self.session.get_file_mut(&path)
-        {
-            let message: String;
+        // Leading indentation is part of a suggested change
+        let content = if self.comment_type == CommentType::CodeSuggestion {
+            self.comment_buffer.trim_end().trim_start_matches('\n')
+        } else {
+            self.comment_buffer.trim()
+        }
+        .to_string();
+        if let Some(anchor) = self.comment_draft_anchor() {
+            self.comment_drafts.remove(&anchor);
+        }
 
-            // Check if we're editing an existing comment
-            if let Some(editing_id) = &self.editing_comment_id {
-                // Update existing comment
-                // Search in file comments
+        if let Some(parent_id) = self.replying_to_comment_id.clone() {
+            let message = match self.session.find_comment_mut(&parent_id) {
+                Some(parent) => {
+                    let mut reply = Reply::new(content);
+                    reply.author = self.reviewer.clone();
+                    parent.replies.push(reply);
+                    "Reply added"
+                }
+                None => "Error: Comment to reply to not found",
+            };
+            self.dirty = true;
+            self.set_message(message);
+            self.rebuild_annotations();
+            self.exit_comment_mode();
+            return;
+        }
+
+        if let Some(target) = self.comment_general_target.clone() {
+            self.save_general_comment(target, content);
+            self.exit_comment_mode();
+            return;
+        }
+
+        let conflict_side = match (self.comment_line_range, self.comment_line) {
+            (Some((range, side)), _) => self
+                .conflict_side_at(range.start, side)
+                .filter(|s| self.conflict_side_at(range.end, side) == Some(*s)),
+            (None, Some((line, side))) => self.conflict_side_at(line, side),
+            (None, None) => None,
+        };
+        let content_anchor = match (self.comment_line_range, self.comment_line) {
+            (Some((range, side)), _) => Some((range, side)),
+            (None, Some((line, side))) => Some((LineRange::single(line), side)),
+            (None, None) => None,
+        }
+        .and_then(|(range, side)| {
+            ContentAnchor::capture(&side_lines(self.current_file()?, side), range)
+        });
+
+        if let Some(path) = self.current_file_path().cloned()
+            && let Some(review) = self.session.get_file_mut(&path)
+        {
+            let message: String;
+
+            // Check if we're editing an existing comment
+            if let Some(editing_id) = &self.editing_comment_id {
+                // Update existing comment
+                // Search in file comments
                 if let Some(comment) = review
                     .file_comments
                     .iter_mut()
                     .find(|c| &c.id == editing_id)
                 {
-                    comment.content = content.clone();
-                    comment.comment_type = self.comment_type;
+                    comment.update(
+                        content.clone(),
+                        self.comment_type,
+                        self.comment_severity,
+                        self.comment_required_action,
+                    );
                     message = "Comment updated".to_string();
                 } else {
                     // If not found in file comments, search in line comments
@@ -1472,8 +3293,12 @@ impl App {
                     }
 
                     if let Some(comment) = found_comment {
-                        comment.content = content.clone();
-                        comment.comment_type = self.comment_type;
+                        comment.update(
+                            content.clone(),
+                            self.comment_type,
+                            self.comment_severity,
+                            self.comment_required_action,
+                        );
                         message = if let Some((line, _)) = self.comment_line {
                             format!("Comment on line {line} updated")
                         } else {
@@ -1486,13 +3311,21 @@ impl App {
             } else {
                 // Create new comment
                 if self.comment_is_file_level {
-                    let comment = Comment::new(content, self.comment_type, None);
+                    let mut comment = Comment::new(content, self.comment_type, None);
+                    comment.author = self.reviewer.clone();
+                    comment.severity = self.comment_severity;
+                    comment.action = self.comment_required_action;
                     review.add_file_comment(comment);
                     message = "File comment added".to_string();
                 } else if let Some((range, side)) = self.comment_line_range {
                     // Range comment from visual selection
-                    let comment =
+                    let mut comment =
                         Comment::new_with_range(content, self.comment_type, Some(side), range);
+                    comment.conflict_side = conflict_side;
+                    comment.anchor = content_anchor;
+                    comment.author = self.reviewer.clone();
+                    comment.severity = self.comment_severity;
+                    comment.action = self.comment_required_action;
                     // Store by end line of the range
                     review.add_line_comment(range.end, comment);
                     if range.is_single() {
@@ -1501,12 +3334,21 @@ impl App {
                         message = format!("Comment added to lines {}-{}", range.start, range.end);
                     }
                 } else if let Some((line, side)) = self.comment_line {
-                    let comment = Comment::new(content, self.comment_type, Some(side));
+                    let mut comment = Comment::new(content, self.comment_type, Some(side));
+                    comment.conflict_side = conflict_side;
+                    comment.anchor = content_anchor;
+                    comment.columns = self.comment_columns;
+                    comment.author = self.reviewer.clone();
+                    comment.severity = self.comment_severity;
+                    comment.action = self.comment_required_action;
                     review.add_line_comment(line, comment);
                     message = format!("Comment added to line {line}");
                 } else {
                     // Fallback to file comment if no line specified
-                    let comment = Comment::new(content, self.comment_type, None);
+                    let mut comment = Comment::new(content, self.comment_type, None);
+                    comment.author = self.reviewer.clone();
+                    comment.severity = self.comment_severity;
+                    comment.action = self.comment_required_action;
                     review.add_file_comment(comment);
                     message = "File comment added".to_string();
                 }
@@ -1520,13 +3362,70 @@ impl App {
         self.exit_comment_mode();
     }
 
+    fn save_general_comment(&mut self, target: GeneralTarget, content: String) {
+        let message = if let Some(editing_id) = &self.editing_comment_id {
+            match self.session.find_general_comment_mut(editing_id) {
+                Some(comment) => {
+                    comment.update(
+                        content,
+                        self.comment_type,
+                        self.comment_severity,
+                        self.comment_required_action,
+                    );
+                    "Comment updated".to_string()
+                }
+                None => "Error: Comment to edit not found".to_string(),
+            }
+        } else {
+            let mut comment = Comment::new(content, self.comment_type, None);
+            comment.author = self.reviewer.clone();
+            comment.severity = self.comment_severity;
+            comment.action = self.comment_required_action;
+            match target {
+                GeneralTarget::Directory(dir) => {
+                    let message = format!("Comment added to {}/", dir.display());
+                    self.session.add_general_comment(Some(dir), comment);
+                    message
+                }
+                GeneralTarget::Session => {
+                    self.session.add_general_comment(None, comment);
+                    "General comment added".to_string()
+                }
+            }
+        };
+
+        self.dirty = true;
+        self.set_message(message);
+        self.rebuild_annotations();
+    }
+
+    /// Next comment type. Suggested changes are only offered on diff lines;
+    /// switching to one fills an empty comment with the commented code.
     pub fn cycle_comment_type(&mut self) {
+        let anchor = self.comment_anchor_lines(0);
         self.comment_type = match self.comment_type {
             CommentType::Note => CommentType::Suggestion,
-            CommentType::Suggestion => CommentType::Issue,
+            CommentType::Suggestion if !anchor.is_empty() => CommentType::CodeSuggestion,
+            CommentType::Suggestion | CommentType::CodeSuggestion => CommentType::Issue,
             CommentType::Issue => CommentType::Praise,
             CommentType::Praise => CommentType::Note,
         };
+        if self.comment_type == CommentType::CodeSuggestion && self.comment_buffer.trim().is_empty()
+        {
+            let code: Vec<&str> = anchor.iter().map(|line| line.content.as_str()).collect();
+            self.comment_buffer = code.join("\n");
+            self.comment_cursor = self.comment_buffer.len();
+        }
+    }
+
+    /// Next severity for the comment being written, none included
+    pub fn cycle_comment_severity(&mut self) {
+        self.comment_severity = Severity::cycle(self.comment_severity);
+    }
+
+    /// Next required action for the comment being written, unset included
+    pub fn cycle_comment_required_action(&mut self) {
+        self.comment_required_action = RequiredAction::cycle(self.comment_required_action);
     }
 
     pub fn toggle_help(&mut self) {
@@ -1538,694 +3437,3742 @@ impl App {
         }
     }
 
-    pub fn help_scroll_down(&mut self, lines: usize) {
-        let max_offset = self
-            .help_state
-            .total_lines
-            .saturating_sub(self.help_state.viewport_height);
-        self.help_state.scroll_offset = (self.help_state.scroll_offset + lines).min(max_offset);
-    }
+    /// Open the function diff popup for the function around the cursor
+    pub fn open_function_diff(&mut self) -> Result<()> {
+        let Some(AnnotatedLine::DiffLine {
+            file_idx,
+            old_lineno,
+            new_lineno,
+            ..
+        }) = self
+            .line_annotations
+            .get(self.diff_state.cursor_line)
+            .cloned()
+        else {
+            self.set_warning("Move the cursor onto a diff line");
+            return Ok(());
+        };
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return Ok(());
+        };
+        if file.is_binary || file.special.is_some() || file.binary_detail.is_some() {
+            self.set_warning("No function diff for this file");
+            return Ok(());
+        }
 
-    pub fn help_scroll_up(&mut self, lines: usize) {
-        self.help_state.scroll_offset = self.help_state.scroll_offset.saturating_sub(lines);
-    }
+        let path = file.display_path().clone();
+        if !has_grammar(&path) {
+            self.set_warning("No function diff for this file type");
+            return Ok(());
+        }
 
-    pub fn help_scroll_to_top(&mut self) {
-        self.help_state.scroll_offset = 0;
-    }
+        let versions = self.file_versions(file)?;
+        let old_text = versions.old.unwrap_or_default();
+        let new_text = versions.new.unwrap_or_default();
+        let old_file: Vec<&str> = old_text.lines().collect();
+        let new_file: Vec<&str> = new_text.lines().collect();
+        let old_spans = function_spans(&path, &old_text).unwrap_or_default();
+        let new_spans = function_spans(&path, &new_text).unwrap_or_default();
+
+        // Locate the function on the side the cursor is on, then match it by name
+        let (old_span, new_span) = match (new_lineno, old_lineno) {
+            (Some(line), _) => {
+                let new_span = enclosing_function(&new_spans, (line as usize).saturating_sub(1));
+                let old_span =
+                    new_span.and_then(|span| find_function(&old_spans, &span.name, span.start));
+                (old_span, new_span)
+            }
+            (None, Some(line)) => {
+                let old_span = enclosing_function(&old_spans, (line as usize).saturating_sub(1));
+                let new_span =
+                    old_span.and_then(|span| find_function(&new_spans, &span.name, span.start));
+                (old_span, new_span)
+            }
+            (None, None) => (None, None),
+        };
+        let Some(name) = new_span.or(old_span).map(|s| s.name.clone()) else {
+            self.set_warning("Cursor is not inside a function");
+            return Ok(());
+        };
 
-    pub fn help_scroll_to_bottom(&mut self) {
-        let max_offset = self
-            .help_state
-            .total_lines
-            .saturating_sub(self.help_state.viewport_height);
-        self.help_state.scroll_offset = max_offset;
+        let body = |lines: &[&str], start: usize, end: usize| -> Vec<String> {
+            lines
+                .get(start..=end.min(lines.len().saturating_sub(1)))
+                .unwrap_or_default()
+                .iter()
+                .map(|line| line.to_string())
+                .collect()
+        };
+        let mut view = FunctionDiffView {
+            name,
+            ..FunctionDiffView::default()
+        };
+        if let Some(span) = old_span {
+            view.old_start = span.start + 1;
+            view.old_lines = body(&old_file, span.start, span.end);
+        }
+        if let Some(span) = new_span {
+            view.new_start = span.start + 1;
+            view.new_lines = body(&new_file, span.start, span.end);
+        }
+        self.function_diff = Some(view);
+        self.input_mode = InputMode::FunctionDiff;
+        Ok(())
     }
 
-    pub fn enter_confirm_mode(&mut self, action: ConfirmAction) {
-        self.input_mode = InputMode::Confirm;
-        self.pending_confirm = Some(action);
-    }
+    /// Open the structural diff popup for the current JSON, YAML or GraphQL file
+    pub fn open_structural_diff(&mut self) -> Result<()> {
+        let file_idx = self.diff_state.current_file_idx;
+        let Some(file) = self.diff_files.get(file_idx) else {
+            return Ok(());
+        };
+        let path = file.display_path().clone();
+        let has_text = !file.is_binary && file.special.is_none() && file.binary_detail.is_none();
+        let format = StructuredFormat::for_path(&path);
+        let is_graphql = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("graphql") || ext.eq_ignore_ascii_case("gql")
+            });
+        if format.is_none() && !is_graphql {
+            self.set_warning("Structural diff supports JSON, YAML and GraphQL files");
+            return Ok(());
+        }
+        if !has_text {
+            self.set_warning("No structural diff for this file");
+            return Ok(());
+        }
 
-    pub fn exit_confirm_mode(&mut self) {
-        self.input_mode = InputMode::Normal;
-        self.pending_confirm = None;
-    }
+        let versions = self.file_versions(&self.diff_files[file_idx])?;
+        let old_text = versions.old.unwrap_or_default();
+        let new_text = versions.new.unwrap_or_default();
 
-    pub fn enter_commit_select_mode(&mut self) -> Result<()> {
-        let commits = self.vcs.get_recent_commits(0, VISIBLE_COMMIT_COUNT)?;
-        if commits.is_empty() {
-            self.set_message("No commits found");
+        let summary = spec_summary(&path, &old_text, &new_text).unwrap_or_default();
+        let changes = match format.map(|format| structural_diff(format, &old_text, &new_text)) {
+            Some(Ok(changes)) => changes,
+            Some(Err(e)) => {
+                self.set_warning(format!("Could not parse {e}"));
+                return Ok(());
+            }
+            None => Vec::new(),
+        };
+        if summary.is_empty() && changes.is_empty() {
+            self.set_message("No key-level changes (formatting or ordering only)");
             return Ok(());
         }
-
-        // Check if there might be more commits
-        self.has_more_commit = commits.len() >= VISIBLE_COMMIT_COUNT;
-        self.commit_list = commits;
-        self.commit_list_cursor = 0;
-        self.commit_list_scroll_offset = 0;
-        self.commit_selection_range = None;
-        self.visible_commit_count = self.commit_list.len();
-        self.input_mode = InputMode::CommitSelect;
+        self.structural_diff = Some(StructuralDiffView {
+            path,
+            summary,
+            changes,
+            ..StructuralDiffView::default()
+        });
+        self.input_mode = InputMode::StructuralDiff;
         Ok(())
     }
 
-    pub fn exit_commit_select_mode(&mut self) -> Result<()> {
-        self.input_mode = InputMode::Normal;
-
-        // If we were viewing commits, try to go back to working tree
-        if matches!(self.diff_source, DiffSource::CommitRange(_)) {
-            let highlighter = self.theme.syntax_highlighter();
-            match self.vcs.get_working_tree_diff(highlighter) {
-                Ok(diff_files) => {
-                    self.diff_files = diff_files;
-                    self.diff_source = DiffSource::WorkingTree;
-
-                    // Update session for new files
-                    for file in &self.diff_files {
-                        let path = file.display_path().clone();
-                        self.session.add_file(path, file.status);
-                    }
+    /// Show the current migration next to its up or down companion
+    pub fn open_migration_view(&mut self) -> Result<()> {
+        let Some(file) = self.current_file() else {
+            return Ok(());
+        };
+        let path = file.display_path().clone();
+        if !self.migration_paths.contains(&path) {
+            self.set_warning("Not a migration (see migration_dirs in the config)");
+            return Ok(());
+        }
+        let Some((up_path, down_path)) = migration::migration_pair(&path) else {
+            self.set_warning("Migration name has no up/down counterpart");
+            return Ok(());
+        };
 
-                    self.sort_files_by_directory(true);
-                    self.expand_all_dirs();
-                }
-                Err(_) => {
-                    self.set_message("No working tree changes");
-                }
-            }
+        let read = |app: &Self, target: &PathBuf| -> Option<Vec<String>> {
+            let versions = match app.diff_files.iter().find(|f| f.display_path() == target) {
+                Some(file) => app.file_versions(file),
+                None => app.path_versions(target, target, None),
+            };
+            let text = versions.ok()?.new?;
+            Some(text.lines().map(String::from).collect())
+        };
+        let Some(up_lines) = read(self, &up_path) else {
+            self.set_warning(format!("Could not read {}", up_path.display()));
+            return Ok(());
+        };
+        let down_lines = read(self, &down_path);
+        if down_lines.is_none() {
+            self.set_warning(format!(
+                "No down migration: {} not found",
+                down_path.display()
+            ));
         }
 
+        self.migration_view = Some(MigrationView {
+            up_path,
+            up_lines,
+            down_path,
+            down_lines,
+            ..MigrationView::default()
+        });
+        self.input_mode = InputMode::Migration;
         Ok(())
     }
 
-    pub fn toggle_diff_view_mode(&mut self) {
-        self.diff_view_mode = match self.diff_view_mode {
-            DiffViewMode::Unified => DiffViewMode::SideBySide,
-            DiffViewMode::SideBySide => DiffViewMode::Unified,
-        };
-        let mode_name = match self.diff_view_mode {
-            DiffViewMode::Unified => "unified",
-            DiffViewMode::SideBySide => "side-by-side",
-        };
-        self.set_message(format!("Diff view mode: {mode_name}"));
+    /// Summarize added, removed and changed public items of Rust files
+    pub fn open_public_api(&mut self) {
+        let changes = public_api_changes(&self.diff_files);
+        if changes.is_empty() {
+            self.set_message("No public API changes in Rust files");
+            return;
+        }
+        self.public_api = Some(PublicApiView {
+            changes,
+            ..PublicApiView::default()
+        });
+        self.input_mode = InputMode::PublicApi;
     }
 
-    pub fn toggle_file_list(&mut self) {
-        self.show_file_list = !self.show_file_list;
-        let status = if self.show_file_list {
-            "visible"
-        } else {
-            "hidden"
-        };
-        self.set_message(format!("File list: {status}"));
+    pub fn close_public_api(&mut self) {
+        self.public_api = None;
+        self.input_mode = InputMode::Normal;
     }
 
-    // Commit selection methods
+    pub fn public_api_select_down(&mut self) {
+        if let Some(view) = &mut self.public_api
+            && view.cursor + 1 < view.changes.len()
+        {
+            view.cursor += 1;
+        }
+    }
 
-    pub fn commit_select_up(&mut self) {
-        if self.commit_list_cursor > 0 {
-            self.commit_list_cursor -= 1;
-            // Scroll up if cursor goes above visible area
-            if self.commit_list_cursor < self.commit_list_scroll_offset {
-                self.commit_list_scroll_offset = self.commit_list_cursor;
-            }
+    pub fn public_api_select_up(&mut self) {
+        if let Some(view) = &mut self.public_api {
+            view.cursor = view.cursor.saturating_sub(1);
         }
     }
 
-    pub fn commit_select_down(&mut self) {
-        let max_cursor = if self.can_show_more_commits() {
-            self.visible_commit_count
-        } else {
-            self.visible_commit_count.saturating_sub(1)
+    /// Close the summary and move the cursor to the selected declaration
+    pub fn jump_to_public_api_change(&mut self) {
+        let Some(change) = self
+            .public_api
+            .as_ref()
+            .and_then(|view| view.changes.get(view.cursor))
+            .cloned()
+        else {
+            return;
         };
+        self.close_public_api();
+        self.jump_to_file(change.file_idx);
+        if let Some(idx) = self.line_annotations.iter().position(|a| {
+            matches!(a, AnnotatedLine::DiffLine { file_idx, hunk_idx, line_idx, .. }
+                if *file_idx == change.file_idx && *hunk_idx == change.hunk_idx && *line_idx == change.line_idx)
+        }) {
+            self.diff_state.cursor_line = idx;
+            self.ensure_cursor_visible();
+        }
+    }
 
-        if self.commit_list_cursor < max_cursor {
-            self.commit_list_cursor += 1;
-            // Scroll down if cursor goes below visible area
-            if self.commit_list_viewport_height > 0
-                && self.commit_list_cursor
-                    >= self.commit_list_scroll_offset + self.commit_list_viewport_height
-            {
-                self.commit_list_scroll_offset =
-                    self.commit_list_cursor - self.commit_list_viewport_height + 1;
-            }
+    /// Jump between a changed source file and its changed test, in either direction
+    pub fn jump_to_alternate_file(&mut self) {
+        let current = self.diff_state.current_file_idx;
+        match alternate_files(&self.diff_files, current, &self.config.test_mappings).first() {
+            Some(&idx) => self.jump_to_file(idx),
+            None => self.set_message("No related source or test file in the diff"),
         }
     }
 
-    // Check if cursor is on the commit expand row
-    pub fn is_on_expand_row(&self) -> bool {
-        self.can_show_more_commits() && self.commit_list_cursor == self.visible_commit_count
+    /// List changed source files with the changed tests mapped to them
+    pub fn open_test_map(&mut self) {
+        let coverage = test_coverage(&self.diff_files, &self.config.test_mappings);
+        if coverage.is_empty() {
+            self.set_message("No changed source files");
+            return;
+        }
+        self.test_map = Some(TestMapView {
+            coverage,
+            ..TestMapView::default()
+        });
+        self.input_mode = InputMode::TestMap;
     }
 
-    pub fn can_show_more_commits(&self) -> bool {
-        self.visible_commit_count < self.commit_list.len() || self.has_more_commit
+    pub fn close_test_map(&mut self) {
+        self.test_map = None;
+        self.input_mode = InputMode::Normal;
     }
 
-    // Expand the commit list to show more commits
-    pub fn expand_commit(&mut self) -> Result<()> {
-        if self.visible_commit_count < self.commit_list.len() {
-            self.visible_commit_count =
-                (self.visible_commit_count + self.commit_page_size).min(self.commit_list.len());
-            return Ok(());
+    pub fn test_map_select_down(&mut self) {
+        if let Some(view) = &mut self.test_map
+            && view.cursor + 1 < view.coverage.len()
+        {
+            view.cursor += 1;
         }
+    }
 
-        if !self.has_more_commit {
-            self.set_message("No more commits");
-            return Ok(());
+    pub fn test_map_select_up(&mut self) {
+        if let Some(view) = &mut self.test_map {
+            view.cursor = view.cursor.saturating_sub(1);
         }
+    }
 
-        let offset = self.commit_list.len();
-        let limit = self.commit_page_size;
+    /// Close the panel and jump to the selected source file
+    pub fn jump_to_test_map_entry(&mut self) {
+        let Some(file_idx) = self
+            .test_map
+            .as_ref()
+            .and_then(|view| view.coverage.get(view.cursor))
+            .map(|coverage| coverage.file_idx)
+        else {
+            return;
+        };
+        self.close_test_map();
+        self.jump_to_file(file_idx);
+    }
+
+    /// List the TODO markers added by the diff
+    pub fn open_todos(&mut self) {
+        let todos = find_todos(&self.diff_files);
+        if todos.is_empty() {
+            self.set_message("No new TODO, FIXME or HACK markers");
+            return;
+        }
+        self.todo_view = Some(TodoView {
+            todos,
+            ..TodoView::default()
+        });
+        self.input_mode = InputMode::Todos;
+    }
 
-        let new_commits = self.vcs.get_recent_commits(offset, limit)?;
+    pub fn close_todos(&mut self) {
+        self.todo_view = None;
+        self.input_mode = InputMode::Normal;
+    }
 
-        if new_commits.is_empty() {
-            self.has_more_commit = false;
-            self.set_message("No more commits");
-            return Ok(());
+    pub fn todo_select_down(&mut self) {
+        if let Some(view) = &mut self.todo_view
+            && view.cursor + 1 < view.todos.len()
+        {
+            view.cursor += 1;
         }
+    }
 
-        if new_commits.len() < limit {
-            self.has_more_commit = false;
-            self.set_message("No more commits");
+    pub fn todo_select_up(&mut self) {
+        if let Some(view) = &mut self.todo_view {
+            view.cursor = view.cursor.saturating_sub(1);
         }
+    }
 
-        self.commit_list.extend(new_commits);
-        self.visible_commit_count = self.commit_list.len();
+    fn selected_todo(&self) -> Option<TodoMarker> {
+        self.todo_view
+            .as_ref()
+            .and_then(|view| view.todos.get(view.cursor))
+            .cloned()
+    }
 
-        Ok(())
+    /// List line comments whose line is no longer in the diff
+    pub fn open_stale_comments(&mut self) {
+        let comments = find_stale_comments(&self.session.files, &self.diff_files);
+        if comments.is_empty() {
+            self.set_message("No stale comments");
+            return;
+        }
+        self.stale_view = Some(StaleCommentView {
+            comments,
+            ..StaleCommentView::default()
+        });
+        self.input_mode = InputMode::StaleComments;
     }
 
-    pub fn toggle_commit_selection(&mut self) {
-        let cursor = self.commit_list_cursor;
-        if cursor >= self.commit_list.len() {
+    /// List every comment, in export order
+    pub fn open_comment_list(&mut self) {
+        let entries = comment_list_entries(&self.session);
+        if entries.is_empty() {
+            self.set_message("No comments yet");
             return;
         }
+        self.comment_list = Some(CommentListView {
+            entries,
+            ..CommentListView::default()
+        });
+        self.input_mode = InputMode::CommentList;
+    }
 
-        match self.commit_selection_range {
-            None => {
-                // No selection yet - select just this commit
-                self.commit_selection_range = Some((cursor, cursor));
-            }
-            Some((start, end)) => {
-                if cursor >= start && cursor <= end {
-                    // Cursor is within the range - shrink or deselect
-                    if start == end {
-                        // Only one commit selected, deselect all
-                        self.commit_selection_range = None;
-                    } else if cursor == start {
-                        // At start edge - shrink from start
-                        self.commit_selection_range = Some((start + 1, end));
-                    } else if cursor == end {
-                        // At end edge - shrink from end
-                        self.commit_selection_range = Some((start, end - 1));
-                    } else {
-                        // In the middle - shrink towards cursor (exclude everything after cursor)
-                        // This makes the cursor the new end of the range
-                        self.commit_selection_range = Some((start, cursor));
-                    }
-                } else {
-                    // Cursor is outside the range - extend to include it
-                    let new_start = start.min(cursor);
-                    let new_end = end.max(cursor);
-                    self.commit_selection_range = Some((new_start, new_end));
-                }
-            }
-        }
+    pub fn close_comment_list(&mut self) {
+        self.comment_list = None;
+        self.input_mode = InputMode::Normal;
     }
 
-    /// Check if a commit at the given index is selected
-    pub fn is_commit_selected(&self, index: usize) -> bool {
-        match self.commit_selection_range {
-            Some((start, end)) => index >= start && index <= end,
-            None => false,
+    pub fn comment_list_select_down(&mut self) {
+        if let Some(view) = &mut self.comment_list
+            && view.cursor + 1 < view.entries.len()
+        {
+            view.cursor += 1;
         }
     }
 
-    pub fn confirm_commit_selection(&mut self) -> Result<()> {
-        let Some((start, end)) = self.commit_selection_range else {
-            self.set_message("Select at least one commit");
-            return Ok(());
-        };
-
-        // Collect selected commit IDs (in order from oldest to newest, i.e., end to start)
-        // commit_list[0] is newest (HEAD), commit_list[end] is oldest selected
-        let selected_ids: Vec<String> = (start..=end)
-            .rev()
-            .filter_map(|i| self.commit_list.get(i).map(|c| c.id.clone()))
-            .collect();
-
-        if selected_ids.is_empty() {
-            self.set_message("Select at least one commit");
-            return Ok(());
+    pub fn comment_list_select_up(&mut self) {
+        if let Some(view) = &mut self.comment_list {
+            view.cursor = view.cursor.saturating_sub(1);
         }
+    }
 
-        // Get the diff for the selected commits
-        let highlighter = self.theme.syntax_highlighter();
-        let diff_files = self.vcs.get_commit_range_diff(&selected_ids, highlighter)?;
+    fn selected_comment_entry(&self) -> Option<CommentListEntry> {
+        self.comment_list
+            .as_ref()
+            .and_then(|view| view.entries.get(view.cursor))
+            .cloned()
+    }
 
-        if diff_files.is_empty() {
-            self.set_message("No changes in selected commits");
-            return Ok(());
+    /// Close the list and put the cursor on the selected comment. Returns
+    /// false when the comment is not shown in the diff (its file is not in
+    /// the diff, or its line is stale).
+    pub fn jump_to_comment_entry(&mut self) -> bool {
+        let Some(entry) = self.selected_comment_entry() else {
+            return false;
+        };
+        self.close_comment_list();
+        if !self.scroll_to_comment(&entry) {
+            return false;
         }
+        self.focused_panel = FocusedPanel::Diff;
+        true
+    }
 
-        // Update session with the newest commit as base
-        let newest_commit_id = selected_ids.last().unwrap().clone();
-        let loaded_session = load_latest_session_for_context(
-            &self.vcs_info.root_path,
-            self.vcs_info.branch_name.as_deref(),
-            &newest_commit_id,
-            SessionDiffSource::CommitRange,
-            Some(selected_ids.as_slice()),
-        )
-        .ok()
-        .and_then(|found| found.map(|(_path, session)| session));
-
-        let mut session = loaded_session.unwrap_or_else(|| {
-            let mut session = ReviewSession::new(
-                self.vcs_info.root_path.clone(),
-                newest_commit_id,
-                self.vcs_info.branch_name.clone(),
-                SessionDiffSource::CommitRange,
-            );
-            session.commit_range = Some(selected_ids.clone());
-            session
-        });
-
-        if session.commit_range.is_none() {
-            session.commit_range = Some(selected_ids.clone());
-            session.updated_at = chrono::Utc::now();
-        }
+    /// Put the diff cursor on a listed comment, warning when it isn't shown
+    fn scroll_to_comment(&mut self, entry: &CommentListEntry) -> bool {
+        let Some(idx) = (0..self.line_annotations.len())
+            .find(|&i| self.comment_id_at(i).is_some_and(|id| id == entry.id))
+        else {
+            self.set_warning(format!("{} is not shown in the diff", entry.location));
+            return false;
+        };
+        self.diff_state.cursor_line = idx;
+        self.update_current_file_from_cursor();
+        self.ensure_cursor_visible();
+        true
+    }
 
-        self.session = session;
+    /// Whether the comments pane shows under the file list: only while the
+    /// list is visible and there is something to show
+    pub fn has_comments_pane(&self) -> bool {
+        self.show_file_list && self.session.has_comments()
+    }
 
-        // Add files to session
-        for file in &diff_files {
-            let path = file.display_path().clone();
-            self.session.add_file(path, file.status);
-        }
+    /// Rows of the comments pane, in the same order as the comment list
+    pub fn comments_pane_entries(&self) -> Vec<CommentListEntry> {
+        comment_list_entries(&self.session)
+    }
 
-        // Update app state
-        self.diff_files = diff_files;
-        self.diff_source = DiffSource::CommitRange(selected_ids);
-        self.input_mode = InputMode::Normal;
+    fn selected_pane_entry(&self) -> Option<CommentListEntry> {
+        self.comments_pane_entries()
+            .into_iter()
+            .nth(self.comments_pane.cursor)
+    }
 
-        // Reset navigation state
-        self.diff_state = DiffState::default();
-        self.file_list_state = FileListState::default();
+    pub fn comments_pane_down(&mut self, n: usize) {
+        let last = self.comments_pane_entries().len().saturating_sub(1);
+        self.comments_pane.cursor = self.comments_pane.cursor.saturating_add(n).min(last);
+    }
 
-        self.sort_files_by_directory(true);
-        self.expand_all_dirs();
-        self.rebuild_annotations();
+    pub fn comments_pane_up(&mut self, n: usize) {
+        self.comments_pane.cursor = self.comments_pane.cursor.saturating_sub(n);
+    }
 
-        Ok(())
+    /// Scroll the diff to the selected comment, keeping the pane focused
+    pub fn jump_to_pane_comment(&mut self) {
+        if let Some(entry) = self.selected_pane_entry() {
+            self.scroll_to_comment(&entry);
+        }
     }
 
-    fn sort_files_by_directory(&mut self, reset_position: bool) {
-        use std::collections::BTreeMap;
-        use std::path::Path;
+    /// Jump to the selected comment and start editing it
+    pub fn edit_pane_comment(&mut self) {
+        if let Some(entry) = self.selected_pane_entry()
+            && self.scroll_to_comment(&entry)
+        {
+            self.focused_panel = FocusedPanel::Diff;
+            self.enter_edit_mode();
+        }
+    }
 
-        let current_path = if !reset_position {
-            self.current_file_path().cloned()
-        } else {
-            None
+    /// Resolve or reopen the selected comment
+    pub fn toggle_resolved_pane_comment(&mut self) {
+        let Some(entry) = self.selected_pane_entry() else {
+            return;
         };
+        if let Some(comment) = self.session.find_comment_mut(&entry.id) {
+            comment.resolved = !comment.resolved;
+            self.dirty = true;
+            self.rebuild_annotations();
+        }
+    }
 
-        let mut dir_map: BTreeMap<String, Vec<DiffFile>> = BTreeMap::new();
-
-        for file in self.diff_files.drain(..) {
-            let path = file.display_path();
-            let dir = if let Some(parent) = path.parent() {
-                if parent == Path::new("") {
-                    ".".to_string()
-                } else {
-                    parent.to_string_lossy().to_string()
-                }
-            } else {
-                ".".to_string()
-            };
-
-            dir_map.entry(dir).or_default().push(file);
+    /// Delete the selected comment; `u` brings it back as after `dd`
+    pub fn delete_pane_comment(&mut self) {
+        let Some(entry) = self.selected_pane_entry() else {
+            return;
+        };
+        if self.delete_comment_by_id(&entry.id) {
+            self.comments_pane_down(0);
+            if !self.has_comments_pane() {
+                self.focused_panel = FocusedPanel::Diff;
+            }
         }
+    }
 
-        for (_dir, files) in dir_map {
-            self.diff_files.extend(files);
+    /// Jump to the selected comment and start editing it
+    pub fn edit_comment_entry(&mut self) {
+        if self.jump_to_comment_entry() {
+            self.enter_edit_mode();
         }
+    }
 
-        if let Some(path) = current_path
-            && let Some(idx) = self
-                .diff_files
-                .iter()
-                .position(|f| f.display_path() == &path)
-        {
-            self.jump_to_file(idx);
+    /// Resolve or reopen the selected comment, keeping the list open
+    pub fn toggle_resolved_comment_entry(&mut self) {
+        let Some(entry) = self.selected_comment_entry() else {
+            return;
+        };
+        let Some(comment) = self.session.find_comment_mut(&entry.id) else {
             return;
+        };
+        comment.resolved = !comment.resolved;
+        let resolved = comment.resolved;
+        self.dirty = true;
+        if let Some(view) = &mut self.comment_list
+            && let Some(selected) = view.entries.get_mut(view.cursor)
+        {
+            selected.resolved = resolved;
         }
-
-        self.jump_to_file(0);
     }
 
-    pub fn expand_all_dirs(&mut self) {
-        use std::path::Path;
+    /// Whether the diff shows comments of this type
+    pub fn is_comment_shown(&self, comment: &Comment) -> bool {
+        !self.hidden_comment_types.contains(&comment.comment_type)
+    }
 
-        self.expanded_dirs.clear();
-        for file in &self.diff_files {
-            let path = file.display_path();
-            let mut current = path.parent();
-            while let Some(parent) = current {
-                if parent != Path::new("") {
-                    self.expanded_dirs
-                        .insert(parent.to_string_lossy().to_string());
-                }
-                current = parent.parent();
+    /// Hide the comments of `group` in the diff, or show them again
+    pub fn toggle_comment_group(&mut self, group: CommentGroup) {
+        let types = group.types();
+        let hide = types.iter().any(|t| !self.hidden_comment_types.contains(t));
+        for comment_type in types {
+            if hide {
+                self.hidden_comment_types.insert(*comment_type);
+            } else {
+                self.hidden_comment_types.remove(comment_type);
             }
         }
-        self.ensure_valid_tree_selection();
-    }
-
-    pub fn collapse_all_dirs(&mut self) {
-        self.expanded_dirs.clear();
-        self.ensure_valid_tree_selection();
+        self.refresh_comment_visibility();
+        let verb = if hide { "Hiding" } else { "Showing" };
+        self.set_message(format!("{verb} {}", group.name()));
     }
 
-    pub fn toggle_directory(&mut self, dir_path: &str) {
-        if self.expanded_dirs.contains(dir_path) {
-            self.expanded_dirs.remove(dir_path);
-            self.ensure_valid_tree_selection();
+    /// Show only the comments of `groups`, or every comment when empty
+    pub fn show_only_comment_groups(&mut self, groups: &[CommentGroup]) {
+        self.hidden_comment_types.clear();
+        if !groups.is_empty() {
+            let shown: Vec<CommentType> = groups.iter().flat_map(|g| g.types()).copied().collect();
+            self.hidden_comment_types.extend(
+                CommentGroup::ALL
+                    .iter()
+                    .flat_map(|g| g.types())
+                    .filter(|t| !shown.contains(t)),
+            );
+        }
+        self.refresh_comment_visibility();
+        if groups.is_empty() {
+            self.set_message("Showing all comments");
         } else {
-            self.expanded_dirs.insert(dir_path.to_string());
+            let names: Vec<&str> = groups.iter().map(|g| g.name()).collect();
+            self.set_message(format!("Showing only {}", names.join(", ")));
         }
     }
 
-    /// Check if a hunk gap has been expanded
-    pub fn is_gap_expanded(&self, gap_id: &GapId) -> bool {
-        self.expanded_gaps.contains(gap_id)
+    fn refresh_comment_visibility(&mut self) {
+        self.rebuild_annotations();
+        let last = self.line_annotations.len().saturating_sub(1);
+        self.diff_state.cursor_line = self.diff_state.cursor_line.min(last);
+        self.ensure_cursor_visible();
     }
 
-    /// Expand a gap to show hidden context lines
-    pub fn expand_gap(&mut self, gap_id: GapId) -> Result<()> {
-        if self.expanded_gaps.contains(&gap_id) {
-            return Ok(()); // Already expanded
+    /// Line comments whose line is not in the current diff
+    pub fn stale_comment_count(&self) -> usize {
+        find_stale_comments(&self.session.files, &self.diff_files).len()
+    }
+
+    pub fn close_stale_comments(&mut self) {
+        self.stale_view = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn stale_select_down(&mut self) {
+        if let Some(view) = &mut self.stale_view
+            && view.cursor + 1 < view.comments.len()
+        {
+            view.cursor += 1;
         }
+    }
 
-        let file = self.diff_files.get(gap_id.file_idx).ok_or_else(|| {
-            TuicrError::CorruptedSession(format!("Invalid file index: {}", gap_id.file_idx))
-        })?;
+    pub fn stale_select_up(&mut self) {
+        if let Some(view) = &mut self.stale_view {
+            view.cursor = view.cursor.saturating_sub(1);
+        }
+    }
 
-        let hunk = file.hunks.get(gap_id.hunk_idx).ok_or_else(|| {
-            TuicrError::CorruptedSession(format!("Invalid hunk index: {}", gap_id.hunk_idx))
-        })?;
+    fn selected_stale_comment(&self) -> Option<StaleComment> {
+        self.stale_view
+            .as_ref()
+            .and_then(|view| view.comments.get(view.cursor))
+            .cloned()
+    }
 
-        // Get previous hunk to calculate gap boundaries
-        let prev_hunk = if gap_id.hunk_idx > 0 {
-            file.hunks.get(gap_id.hunk_idx - 1)
-        } else {
-            None
+    /// Close the panel and jump to the selected comment's file
+    pub fn jump_to_stale_comment(&mut self) {
+        let Some(stale) = self.selected_stale_comment() else {
+            return;
         };
+        self.close_stale_comments();
+        match stale.file_idx {
+            Some(file_idx) => self.jump_to_file(file_idx),
+            None => self.set_warning(format!("{} is no longer in the diff", stale.path.display())),
+        }
+    }
 
-        // Calculate line range to fetch
-        let (start_line, end_line) = match prev_hunk {
-            None => (1, hunk.new_start.saturating_sub(1)),
-            Some(prev) => {
-                let prev_end = prev.new_start + prev.new_count;
-                (prev_end, hunk.new_start.saturating_sub(1))
-            }
+    /// Delete the selected stale comment; `u` brings it back
+    pub fn delete_stale_comment(&mut self) {
+        let Some(stale) = self.selected_stale_comment() else {
+            return;
+        };
+        let Some(review) = self.session.get_file_mut(&stale.path) else {
+            return;
+        };
+        let Some(comments) = review.line_comments.get_mut(&stale.line) else {
+            return;
+        };
+        let Some(idx) = comments.iter().position(|c| c.id == stale.id) else {
+            return;
         };
+        let comment = comments.remove(idx);
+        if comments.is_empty() {
+            review.line_comments.remove(&stale.line);
+        }
+        self.remember_deleted_comment(CommentSlot::Line(stale.path, stale.line), idx, comment);
+        self.dirty = true;
+        self.rebuild_annotations();
 
-        if start_line > end_line {
-            return Ok(()); // No gap to expand
+        if let Some(view) = &mut self.stale_view {
+            view.comments.remove(view.cursor);
+            if view.comments.is_empty() {
+                self.close_stale_comments();
+                self.set_message("No stale comments left");
+            } else {
+                view.cursor = view.cursor.min(view.comments.len() - 1);
+            }
         }
+    }
 
-        let file_path = file.display_path().clone();
-        let file_status = file.status;
+    /// Close the panel and move the cursor to the selected marker
+    pub fn jump_to_todo(&mut self) {
+        let Some(todo) = self.selected_todo() else {
+            return;
+        };
+        self.close_todos();
+        self.jump_to_file(todo.file_idx);
+        if let Some(idx) = self.line_annotations.iter().position(|a| {
+            matches!(a, AnnotatedLine::DiffLine { file_idx, hunk_idx, line_idx, .. }
+                if *file_idx == todo.file_idx && *hunk_idx == todo.hunk_idx && *line_idx == todo.line_idx)
+        }) {
+            self.diff_state.cursor_line = idx;
+            self.ensure_cursor_visible();
+        }
+    }
 
-        // Fetch the context lines
-        let lines = self
-            .vcs
-            .fetch_context_lines(&file_path, file_status, start_line, end_line)?;
-
-        self.expanded_content.insert(gap_id.clone(), lines);
-        self.expanded_gaps.insert(gap_id);
+    /// Leave a note on the selected marker's line, unless one quoting it exists
+    pub fn comment_on_todo(&mut self) {
+        let Some(todo) = self.selected_todo() else {
+            return;
+        };
+        let content = format!("New {}: {}", todo.marker, todo.text);
+        let Some(review) = self.session.get_file_mut(&todo.path) else {
+            return;
+        };
+        let exists = review
+            .line_comments
+            .get(&todo.line)
+            .is_some_and(|comments| comments.iter().any(|c| c.content == content));
+        if exists {
+            self.set_message(format!("Line {} already has this note", todo.line));
+            return;
+        }
+        let mut comment = Comment::new(content, CommentType::Note, Some(LineSide::New));
+        comment.author = self.reviewer.clone();
+        review.add_line_comment(todo.line, comment);
+        self.dirty = true;
         self.rebuild_annotations();
+        self.set_message(format!(
+            "Note added to {}:{}",
+            todo.path.display(),
+            todo.line
+        ));
+    }
 
-        Ok(())
+    /// Attach the findings of a static analysis report that fall on added
+    /// lines as comments, skipping ones already attached. Returns how many
+    /// were attached and how many fell outside the changed lines.
+    pub fn import_findings(&mut self, report: &std::path::Path) -> Result<(usize, usize)> {
+        let text = std::fs::read_to_string(report)?;
+        let findings = parse_findings(&text, &self.vcs_info.root_path)?;
+        let (mut attached, mut outside) = (0, 0);
+        for finding in findings {
+            let Some((path, line)) = changed_line(&self.diff_files, &finding) else {
+                outside += 1;
+                continue;
+            };
+            let Some(review) = self.session.get_file_mut(&path) else {
+                outside += 1;
+                continue;
+            };
+            let content = finding.comment_text();
+            let exists = review
+                .line_comments
+                .get(&line)
+                .is_some_and(|comments| comments.iter().any(|c| c.content == content));
+            if exists {
+                continue;
+            }
+            let comment_type = if finding.is_error {
+                CommentType::Issue
+            } else {
+                CommentType::Note
+            };
+            let mut comment = Comment::new(content, comment_type, Some(LineSide::New));
+            comment.source = Some(finding.tool.to_string());
+            review.add_line_comment(line, comment);
+            attached += 1;
+        }
+        if attached > 0 {
+            self.dirty = true;
+            self.rebuild_annotations();
+        }
+        Ok((attached, outside))
     }
 
-    /// Collapse an expanded gap
-    pub fn collapse_gap(&mut self, gap_id: GapId) {
-        self.expanded_gaps.remove(&gap_id);
-        self.expanded_content.remove(&gap_id);
-        self.rebuild_annotations();
+    /// Write the export to a timestamped file under the data directory when
+    /// `backup_exports` is on. Returns the file written, if any.
+    pub fn write_backup_export(&self) -> Result<Option<PathBuf>> {
+        if !self.config.backup_exports || !self.session.has_comments() {
+            return Ok(None);
+        }
+        let todos = find_todos(&self.diff_files);
+        let content =
+            generate_export_content(&self.session, &self.diff_source, &todos, &self.config)?;
+        save_backup_export(&self.session, &content).map(Some)
     }
 
-    /// Clear all expanded gaps (called when reloading diffs)
-    pub fn clear_expanded_gaps(&mut self) {
-        self.expanded_gaps.clear();
-        self.expanded_content.clear();
+    /// Attach a file to the session as review evidence. Relative paths are
+    /// resolved against the working directory.
+    pub fn attach(&mut self, path: &str) -> Result<PathBuf> {
+        let path = std::fs::canonicalize(path)?;
+        let root = std::fs::canonicalize(&self.vcs_info.root_path)
+            .unwrap_or_else(|_| self.vcs_info.root_path.clone());
+        let path = match path.strip_prefix(&root) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path,
+        };
+        if !self.session.attachments.contains(&path) {
+            self.session.attachments.push(path.clone());
+            self.dirty = true;
+        }
+        Ok(path)
     }
 
-    /// Rebuild the line annotations cache. Call this when:
-    /// - Diff files change (load/reload)
-    /// - Expansion state changes (expand/collapse gap)
-    /// - Comments are added/removed
-    pub fn rebuild_annotations(&mut self) {
-        self.line_annotations.clear();
+    pub fn open_attachments(&mut self) {
+        if self.session.attachments.is_empty() {
+            self.set_message("No attachments (add one with :attach <path>)");
+            return;
+        }
+        self.attachment_view = Some(AttachmentView::default());
+        self.input_mode = InputMode::Attachments;
+    }
 
-        for (file_idx, file) in self.diff_files.iter().enumerate() {
-            let path = file.display_path();
+    pub fn close_attachments(&mut self) {
+        self.attachment_view = None;
+        self.input_mode = InputMode::Normal;
+    }
 
-            // File header
-            self.line_annotations
-                .push(AnnotatedLine::FileHeader { file_idx });
+    pub fn attachment_select_down(&mut self) {
+        if let Some(view) = &mut self.attachment_view
+            && view.cursor + 1 < self.session.attachments.len()
+        {
+            view.cursor += 1;
+        }
+    }
 
-            // If reviewed, skip all content for this file
-            if self.session.is_file_reviewed(path) {
-                continue;
+    pub fn attachment_select_up(&mut self) {
+        if let Some(view) = &mut self.attachment_view {
+            view.cursor = view.cursor.saturating_sub(1);
+        }
+    }
+
+    /// Remove the selected attachment, closing the panel after the last one
+    pub fn remove_selected_attachment(&mut self) {
+        let Some(view) = &mut self.attachment_view else {
+            return;
+        };
+        if view.cursor >= self.session.attachments.len() {
+            return;
+        }
+        let path = self.session.attachments.remove(view.cursor);
+        view.cursor = view
+            .cursor
+            .min(self.session.attachments.len().saturating_sub(1));
+        self.dirty = true;
+        if self.session.attachments.is_empty() {
+            self.close_attachments();
+        }
+        self.set_message(format!("Removed {}", path.display()));
+    }
+
+    /// Search the repository in the background for callers of functions
+    /// whose signature changed; `poll_impact_search` picks up the result
+    pub fn start_impact_search(&mut self) {
+        if self.impact_search.is_some() {
+            self.set_message("Impact search already running");
+            return;
+        }
+        let signatures = changed_signatures(&self.diff_files);
+        if signatures.is_empty() {
+            self.set_message("No changed function signatures");
+            return;
+        }
+
+        let count = signatures.len();
+        let root = self.vcs_info.root_path.clone();
+        let changed: HashSet<PathBuf> = self
+            .diff_files
+            .iter()
+            .flat_map(|file| [file.old_path.clone(), file.new_path.clone()])
+            .flatten()
+            .collect();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(find_references(&root, signatures, &changed));
+        });
+        self.impact_search = Some(rx);
+        let plural = if count == 1 { "" } else { "s" };
+        self.set_message(format!(
+            "Searching for callers of {count} changed signature{plural}..."
+        ));
+    }
+
+    /// Show the result of a finished impact search, if any
+    pub fn poll_impact_search(&mut self) {
+        let Some(rx) = &self.impact_search else {
+            return;
+        };
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                Err(TuicrError::VcsCommand("impact search stopped".to_string()))
             }
+        };
+        self.impact_search = None;
 
-            // File comments
-            if let Some(review) = self.session.files.get(path) {
-                for (comment_idx, comment) in review.file_comments.iter().enumerate() {
-                    let comment_lines = Self::comment_display_lines(comment);
-                    for _ in 0..comment_lines {
-                        self.line_annotations.push(AnnotatedLine::FileComment {
-                            file_idx,
-                            comment_idx,
-                        });
-                    }
+        match result {
+            Ok(impacts) if impacts.is_empty() => {
+                self.set_message("No references to changed signatures outside the diff");
+            }
+            Ok(impacts) => {
+                let files: HashSet<&PathBuf> = impacts
+                    .iter()
+                    .flat_map(|impact| impact.references.iter().map(|(path, _)| path))
+                    .collect();
+                self.set_warning(format!(
+                    "{} unchanged files reference changed signatures",
+                    files.len()
+                ));
+                self.impact_view = Some(ImpactView {
+                    impacts,
+                    ..ImpactView::default()
+                });
+                if self.input_mode == InputMode::Normal {
+                    self.input_mode = InputMode::Impact;
                 }
             }
+            Err(e) => self.set_error(format!("Impact search failed: {e}")),
+        }
+    }
 
-            if file.is_binary || file.hunks.is_empty() {
-                self.line_annotations
-                    .push(AnnotatedLine::BinaryOrEmpty { file_idx });
-            } else {
-                // Get line comments for this file
-                let line_comments = self
-                    .session
-                    .files
-                    .get(path)
-                    .map(|r| &r.line_comments)
-                    .cloned()
-                    .unwrap_or_default();
+    pub fn close_impact_view(&mut self) {
+        self.impact_view = None;
+        self.input_mode = InputMode::Normal;
+    }
 
-                for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
-                    // Calculate gap before this hunk
-                    let prev_hunk = if hunk_idx > 0 {
-                        file.hunks.get(hunk_idx - 1)
-                    } else {
-                        None
-                    };
-                    let gap = calculate_gap(
-                        prev_hunk.map(|h| (&h.new_start, &h.new_count)),
-                        hunk.new_start,
-                    );
+    pub fn impact_view_scroll_down(&mut self, lines: usize) {
+        if let Some(view) = &mut self.impact_view {
+            let max_offset = view.total_lines().saturating_sub(view.viewport_height);
+            view.scroll_offset = (view.scroll_offset + lines).min(max_offset);
+        }
+    }
 
-                    let gap_id = GapId { file_idx, hunk_idx };
+    pub fn impact_view_scroll_up(&mut self, lines: usize) {
+        if let Some(view) = &mut self.impact_view {
+            view.scroll_offset = view.scroll_offset.saturating_sub(lines);
+        }
+    }
 
-                    if gap > 0 {
-                        if self.expanded_gaps.contains(&gap_id) {
-                            // Expanded content lines
-                            if let Some(content) = self.expanded_content.get(&gap_id) {
-                                for (content_idx, _) in content.iter().enumerate() {
-                                    self.line_annotations.push(AnnotatedLine::ExpandedContext {
-                                        gap_id: gap_id.clone(),
-                                        line_idx: content_idx,
-                                    });
-                                }
-                            }
-                        } else {
-                            // Expander line
-                            self.line_annotations.push(AnnotatedLine::Expander {
-                                gap_id: gap_id.clone(),
-                            });
-                        }
-                    }
+    /// Show the export text in a popup; accepting it copies it
+    pub fn open_export_preview(&mut self, content: String) {
+        self.export_preview = Some(ExportPreview {
+            content,
+            ..ExportPreview::default()
+        });
+        self.input_mode = InputMode::ExportPreview;
+    }
 
-                    // Hunk header
-                    self.line_annotations
-                        .push(AnnotatedLine::HunkHeader { file_idx, hunk_idx });
+    /// Close the preview, handing back its text
+    pub fn close_export_preview(&mut self) -> Option<String> {
+        self.input_mode = InputMode::Normal;
+        self.export_preview.take().map(|preview| preview.content)
+    }
 
-                    // Diff lines
-                    for (line_idx, diff_line) in hunk.lines.iter().enumerate() {
-                        self.line_annotations.push(AnnotatedLine::DiffLine {
-                            file_idx,
-                            hunk_idx,
-                            line_idx,
-                            old_lineno: diff_line.old_lineno,
-                            new_lineno: diff_line.new_lineno,
-                        });
+    pub fn export_preview_scroll_down(&mut self, lines: usize) {
+        if let Some(preview) = &mut self.export_preview {
+            let max_offset = preview
+                .total_lines()
+                .saturating_sub(preview.viewport_height);
+            preview.scroll_offset = (preview.scroll_offset + lines).min(max_offset);
+        }
+    }
 
-                        // Line comments on old side (deleted lines)
-                        if let Some(old_ln) = diff_line.old_lineno
-                            && let Some(comments) = line_comments.get(&old_ln)
-                        {
-                            for (idx, comment) in comments.iter().enumerate() {
-                                if comment.side == Some(LineSide::Old) {
-                                    let comment_lines = Self::comment_display_lines(comment);
-                                    for _ in 0..comment_lines {
-                                        self.line_annotations.push(AnnotatedLine::LineComment {
-                                            file_idx,
-                                            line: old_ln,
-                                            side: LineSide::Old,
-                                            comment_idx: idx,
-                                        });
-                                    }
-                                }
-                            }
-                        }
+    pub fn export_preview_scroll_up(&mut self, lines: usize) {
+        if let Some(preview) = &mut self.export_preview {
+            preview.scroll_offset = preview.scroll_offset.saturating_sub(lines);
+        }
+    }
 
-                        // Line comments on new side (added/context lines)
-                        if let Some(new_ln) = diff_line.new_lineno
-                            && let Some(comments) = line_comments.get(&new_ln)
-                        {
-                            for (idx, comment) in comments.iter().enumerate() {
-                                if comment.side != Some(LineSide::Old) {
-                                    let comment_lines = Self::comment_display_lines(comment);
-                                    for _ in 0..comment_lines {
-                                        self.line_annotations.push(AnnotatedLine::LineComment {
-                                            file_idx,
-                                            line: new_ln,
-                                            side: LineSide::New,
-                                            comment_idx: idx,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    pub fn close_migration_view(&mut self) {
+        self.migration_view = None;
+        self.input_mode = InputMode::Normal;
+    }
 
-            // Spacing line
-            self.line_annotations.push(AnnotatedLine::Spacing);
+    pub fn migration_view_scroll_down(&mut self, lines: usize) {
+        if let Some(view) = &mut self.migration_view {
+            let max_offset = view.total_lines().saturating_sub(view.viewport_height);
+            view.scroll_offset = (view.scroll_offset + lines).min(max_offset);
         }
     }
 
-    /// Check if cursor is on an expander line or expanded content and return GapId and whether expanded
-    /// Returns (GapId, is_expanded) - is_expanded is true if cursor is on expanded content
-    pub fn get_gap_at_cursor(&self) -> Option<(GapId, bool)> {
-        let target = self.diff_state.cursor_line;
-        match self.line_annotations.get(target) {
-            Some(AnnotatedLine::Expander { gap_id, .. }) => Some((gap_id.clone(), false)),
-            Some(AnnotatedLine::ExpandedContext { gap_id, .. }) => Some((gap_id.clone(), true)),
-            _ => None,
+    pub fn migration_view_scroll_up(&mut self, lines: usize) {
+        if let Some(view) = &mut self.migration_view {
+            view.scroll_offset = view.scroll_offset.saturating_sub(lines);
         }
     }
 
-    fn ensure_valid_tree_selection(&mut self) {
-        use std::path::Path;
+    pub fn close_structural_diff(&mut self) {
+        self.structural_diff = None;
+        self.input_mode = InputMode::Normal;
+    }
 
-        let visible_items = self.build_visible_items();
-        if visible_items.is_empty() {
-            self.file_list_state.select(0);
-            return;
+    pub fn structural_diff_scroll_down(&mut self, lines: usize) {
+        if let Some(view) = &mut self.structural_diff {
+            let max_offset = view.line_count().saturating_sub(view.viewport_height);
+            view.scroll_offset = (view.scroll_offset + lines).min(max_offset);
         }
+    }
 
-        let current_file_idx = self.diff_state.current_file_idx;
-        let file_visible = visible_items.iter().any(|item| {
-            matches!(item, FileTreeItem::File { file_idx, .. } if *file_idx == current_file_idx)
-        });
+    pub fn structural_diff_scroll_up(&mut self, lines: usize) {
+        if let Some(view) = &mut self.structural_diff {
+            view.scroll_offset = view.scroll_offset.saturating_sub(lines);
+        }
+    }
 
-        if file_visible {
-            if let Some(tree_idx) = self.file_idx_to_tree_idx(current_file_idx) {
-                self.file_list_state.select(tree_idx);
-            }
-        } else {
-            if let Some(file) = self.diff_files.get(current_file_idx) {
-                let file_path = file.display_path();
-                let mut current = file_path.parent();
-                while let Some(parent) = current {
-                    if parent != Path::new("") {
-                        let parent_str = parent.to_string_lossy().to_string();
-                        for (tree_idx, item) in visible_items.iter().enumerate() {
-                            if let FileTreeItem::Directory { path, .. } = item
-                                && *path == parent_str
-                            {
-                                self.file_list_state.select(tree_idx);
-                                return;
-                            }
-                        }
-                    }
-                    current = parent.parent();
-                }
+    pub fn close_function_diff(&mut self) {
+        self.function_diff = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn function_diff_scroll_down(&mut self, lines: usize) {
+        if let Some(view) = &mut self.function_diff {
+            let max_offset = view.total_lines().saturating_sub(view.viewport_height);
+            view.scroll_offset = (view.scroll_offset + lines).min(max_offset);
+        }
+    }
+
+    pub fn function_diff_scroll_up(&mut self, lines: usize) {
+        if let Some(view) = &mut self.function_diff {
+            view.scroll_offset = view.scroll_offset.saturating_sub(lines);
+        }
+    }
+
+    pub fn help_scroll_down(&mut self, lines: usize) {
+        let max_offset = self
+            .help_state
+            .total_lines
+            .saturating_sub(self.help_state.viewport_height);
+        self.help_state.scroll_offset = (self.help_state.scroll_offset + lines).min(max_offset);
+    }
+
+    pub fn help_scroll_up(&mut self, lines: usize) {
+        self.help_state.scroll_offset = self.help_state.scroll_offset.saturating_sub(lines);
+    }
+
+    pub fn help_scroll_to_top(&mut self) {
+        self.help_state.scroll_offset = 0;
+    }
+
+    pub fn help_scroll_to_bottom(&mut self) {
+        let max_offset = self
+            .help_state
+            .total_lines
+            .saturating_sub(self.help_state.viewport_height);
+        self.help_state.scroll_offset = max_offset;
+    }
+
+    /// Commit trailers for the merge commit: reviewer, review date and
+    /// commits, issue references from the branch and commit messages, and
+    /// the count of unresolved comments
+    pub fn review_trailer(&self) -> String {
+        let commit_ids = match &self.diff_source {
+            DiffSource::WorkingTree => &[][..],
+            DiffSource::CommitRange(ids) => &ids[..],
+        };
+        let messages: Vec<String> = commit_ids
+            .iter()
+            .filter_map(|id| self.vcs.get_commit_message(id).ok().flatten())
+            .collect();
+        let texts = self
+            .vcs_info
+            .branch_name
+            .iter()
+            .chain(&messages)
+            .map(String::as_str);
+        trailer::format_trailer(&TrailerInfo {
+            reviewer: trailer::reviewer(&self.vcs_info.root_path),
+            reviewed_at: chrono::Utc::now(),
+            commits: commit_ids
+                .iter()
+                .map(|id| id[..7.min(id.len())].to_string())
+                .collect(),
+            issue_refs: trailer::issue_refs(texts),
+            unresolved: self.session.unresolved_count(),
+        })
+    }
+
+    /// Record a copy to the clipboard, keeping the text it replaced unless
+    /// that text came from tuicr too
+    pub fn remember_clipboard_copy(&mut self, previous: Option<String>, copied: String) {
+        if let Some(previous) = previous
+            && !previous.is_empty()
+            && previous != copied
+            && self.last_clipboard_copy.as_ref() != Some(&previous)
+        {
+            if self.clipboard_history.len() == CLIPBOARD_HISTORY_LIMIT {
+                self.clipboard_history.remove(0);
             }
-            self.file_list_state.select(0);
+            self.clipboard_history.push(previous);
         }
+        self.last_clipboard_copy = Some(copied);
     }
 
-    pub fn build_visible_items(&self) -> Vec<FileTreeItem> {
-        use std::path::Path;
+    pub fn enter_confirm_mode(&mut self, action: ConfirmAction) {
+        self.input_mode = InputMode::Confirm;
+        self.pending_confirm = Some(action);
+    }
 
-        let mut items = Vec::new();
-        let mut seen_dirs: HashSet<String> = HashSet::new();
+    pub fn exit_confirm_mode(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.pending_confirm = None;
+    }
 
-        for (file_idx, file) in self.diff_files.iter().enumerate() {
-            let path = file.display_path();
+    /// When this review starts from scratch on a branch that was reviewed
+    /// before, offer to pick up where that review left off
+    pub fn offer_previous_session(&mut self) {
+        if self.input_mode != InputMode::Normal
+            || self.read_only
+            || self.is_stdin_diff()
+            || !self.session.is_untouched()
+        {
+            return;
+        }
+        let Some(branch) = self.vcs_info.branch_name.as_deref() else {
+            return;
+        };
+        let Ok(Some((path, previous))) =
+            find_previous_session(&self.vcs_info.root_path, branch, &self.session.id)
+        else {
+            return;
+        };
+        let summary = format!(
+            "{} open comment(s), last changed {}",
+            previous.unresolved_count(),
+            previous.updated_at.format("%Y-%m-%d")
+        );
+        self.enter_confirm_mode(ConfirmAction::SeedFromSession { path, summary });
+    }
 
-            let mut ancestors: Vec<String> = Vec::new();
-            let mut current = path.parent();
-            while let Some(parent) = current {
-                if parent != Path::new("") {
-                    ancestors.push(parent.to_string_lossy().to_string());
-                }
-                current = parent.parent();
+    /// Offer to recover a saved review of this repository that no longer
+    /// loads, instead of leaving it behind for a fresh one
+    pub fn offer_damaged_session(&mut self) {
+        if self.input_mode != InputMode::Normal
+            || self.read_only
+            || self.is_stdin_diff()
+            || !self.session.is_untouched()
+        {
+            return;
+        }
+        let Ok(Some((path, error))) =
+            find_damaged_session(&self.vcs_info.root_path, self.session.diff_source)
+        else {
+            return;
+        };
+        let reason = match error {
+            TuicrError::CorruptedSession(reason) => reason,
+            error => error.to_string(),
+        };
+        let salvaged = salvage_session(&path).ok();
+        let comments = salvaged.as_ref().map_or(0, |salvaged| {
+            salvaged
+                .files
+                .values()
+                .map(|review| review.comment_count())
+                .sum::<usize>()
+                + salvaged.general_comments().len()
+        });
+        if comments == 0 {
+            match set_aside_damaged_session(&path) {
+                Ok(kept) => self.set_warning(format!(
+                    "A saved review was damaged ({reason}) with nothing readable; kept as {}",
+                    kept.display()
+                )),
+                Err(e) => self.set_warning(format!(
+                    "A saved review is damaged ({reason}) and could not be set aside: {e}"
+                )),
             }
-            ancestors.reverse();
+            return;
+        }
+        let summary = format!("{reason}; {comments} comment(s) are readable");
+        self.enter_confirm_mode(ConfirmAction::RecoverDamagedSession { path, summary });
+    }
 
-            let mut visible = true;
-            for (depth, dir) in ancestors.iter().enumerate() {
-                if !seen_dirs.contains(dir) && visible {
-                    let expanded = self.expanded_dirs.contains(dir);
-                    items.push(FileTreeItem::Directory {
-                        path: dir.clone(),
-                        depth,
-                        expanded,
-                    });
-                    seen_dirs.insert(dir.clone());
-                }
+    /// Bring what is readable from the damaged session at `path` into this
+    /// review and set the file aside. Returns how many comments came back.
+    pub fn recover_damaged_session(&mut self, path: &std::path::Path) -> Result<usize> {
+        let salvaged = salvage_session(path)?;
+        let recovered = self.session.recover_from(&salvaged);
+        let diff_files = std::mem::take(&mut self.diff_files);
+        self.reanchor_comments(&diff_files);
+        self.diff_files = diff_files;
+        self.dirty = true;
+        self.rebuild_annotations();
+        set_aside_damaged_session(path)?;
+        Ok(recovered)
+    }
 
-                if !self.expanded_dirs.contains(dir) {
-                    visible = false;
+    /// Carry the open comments and file states of the earlier review saved
+    /// at `path` over into this one. Returns how many comments came along.
+    pub fn seed_from_session(&mut self, path: &PathBuf) -> Result<usize> {
+        let previous = load_session(path)?;
+        let carried = self.session.seed_from(&previous);
+        // Line comments follow their code to where it is in this diff
+        let diff_files = std::mem::take(&mut self.diff_files);
+        self.reanchor_comments(&diff_files);
+        self.diff_files = diff_files;
+        self.dirty = true;
+        self.rebuild_annotations();
+        Ok(carried)
+    }
+
+    pub fn enter_commit_select_mode(&mut self) -> Result<()> {
+        if self.is_stdin_diff() {
+            self.set_warning("Commit selection is not available for stdin diffs");
+            return Ok(());
+        }
+
+        let commits =
+            self.vcs
+                .get_recent_commits(0, VISIBLE_COMMIT_COUNT, &CommitQuery::default())?;
+        if commits.is_empty() {
+            self.set_message("No commits found");
+            return Ok(());
+        }
+
+        // Check if there might be more commits
+        self.has_more_commit = commits.len() >= VISIBLE_COMMIT_COUNT;
+        self.commit_list = commits;
+        self.commit_list_cursor = 0;
+        self.commit_list_scroll_offset = 0;
+        self.commit_selection_range = None;
+        self.commit_anchor = None;
+        self.visible_commit_count = self.commit_list.len();
+        self.commit_filter.clear();
+        self.commit_query = CommitQuery::default();
+        self.input_mode = InputMode::CommitSelect;
+        Ok(())
+    }
+
+    pub fn exit_commit_select_mode(&mut self) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+
+        // If we were viewing commits, try to go back to working tree
+        if matches!(self.diff_source, DiffSource::CommitRange(_)) {
+            let highlighter = self.theme.syntax_highlighter();
+            match self.vcs.get_working_tree_diff(highlighter) {
+                Ok(diff_files) => {
+                    self.diff_files = self.filter_diff_files(diff_files);
+                    self.diff_source = DiffSource::WorkingTree;
+
+                    // Update session for new files
+                    for file in &self.diff_files {
+                        let path = file.display_path().clone();
+                        self.session.add_file(path, file.status);
+                    }
+
+                    self.sort_files_by_directory(true);
+                    self.expand_all_dirs();
+                }
+                Err(_) => {
+                    self.set_message("No working tree changes");
                 }
             }
+        }
 
-            if visible {
-                items.push(FileTreeItem::File {
-                    file_idx,
-                    depth: ancestors.len(),
-                });
+        Ok(())
+    }
+
+    /// Open the worktree picker, or explain why there is nothing to pick
+    pub fn enter_worktree_select_mode(&mut self) -> Result<()> {
+        let worktrees = self.vcs.list_worktrees()?;
+        if worktrees.len() < 2 {
+            self.set_message("No other worktrees");
+            return Ok(());
+        }
+
+        self.worktree_cursor = worktrees.iter().position(|w| w.is_current).unwrap_or(0);
+        self.worktree_list = worktrees;
+        self.worktree_return_mode = self.input_mode;
+        self.input_mode = InputMode::WorktreeSelect;
+        Ok(())
+    }
+
+    pub fn exit_worktree_select_mode(&mut self) {
+        self.input_mode = self.worktree_return_mode;
+    }
+
+    pub fn worktree_select_up(&mut self) {
+        self.worktree_cursor = self.worktree_cursor.saturating_sub(1);
+    }
+
+    pub fn worktree_select_down(&mut self) {
+        if self.worktree_cursor + 1 < self.worktree_list.len() {
+            self.worktree_cursor += 1;
+        }
+    }
+
+    pub fn confirm_worktree_selection(&mut self) -> Result<()> {
+        let Some(worktree) = self.worktree_list.get(self.worktree_cursor).cloned() else {
+            return Ok(());
+        };
+        if worktree.is_current {
+            self.exit_worktree_select_mode();
+            return Ok(());
+        }
+        if self.dirty {
+            self.exit_worktree_select_mode();
+            self.set_warning("Unsaved review: :w before switching worktrees");
+            return Ok(());
+        }
+        self.switch_worktree(&worktree.path)
+    }
+
+    /// Review the working tree of another checkout of the same repository.
+    /// Nothing changes unless the new worktree can be opened and diffed.
+    fn switch_worktree(&mut self, path: &std::path::Path) -> Result<()> {
+        let mut vcs: Box<dyn VcsBackend> = Box::new(GitBackend::discover_at(path)?);
+        vcs.set_diff_settings(self.diff_settings);
+        let highlighter = self.theme.syntax_highlighter();
+        let diff_files = match vcs.get_working_tree_diff(highlighter) {
+            Ok(diff_files) => diff_files,
+            Err(TuicrError::NoChanges) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        self.vcs_info = vcs.info().clone();
+        self.vcs = vcs;
+        let pathspecs = std::mem::take(&mut self.session.pathspecs);
+        self.session = Self::load_or_create_session(&self.vcs_info);
+        self.session.pathspecs = pathspecs;
+        let diff_files = self.filter_diff_files(diff_files);
+        for file in &diff_files {
+            let path = file.display_path().clone();
+            self.session.add_file(path, file.status);
+        }
+        self.diff_files = diff_files;
+        self.diff_source = DiffSource::WorkingTree;
+        self.dirty = false;
+        self.quit_warned = false;
+        self.visual_anchor = None;
+        self.function_diff = None;
+        self.structural_diff = None;
+        self.migration_view = None;
+        self.public_api = None;
+        self.test_map = None;
+        self.todo_view = None;
+        self.stale_view = None;
+        self.comment_list = None;
+        self.attachment_view = None;
+        self.impact_view = None;
+        self.impact_search = None;
+        self.input_mode = InputMode::Normal;
+
+        self.clear_expanded_gaps();
+        self.sort_files_by_directory(true);
+        self.expand_all_dirs();
+        self.rebuild_annotations();
+
+        self.set_message(format!("Reviewing {}", self.vcs_info.root_path.display()));
+        if self.diff_files.is_empty() {
+            // Same fallback as startup: pick commits when nothing is uncommitted
+            self.enter_commit_select_mode()?;
+        }
+        Ok(())
+    }
+
+    pub fn toggle_diff_view_mode(&mut self) {
+        self.diff_view_mode = match self.diff_view_mode {
+            DiffViewMode::Unified => DiffViewMode::SideBySide,
+            DiffViewMode::SideBySide => DiffViewMode::Unified,
+        };
+        let mode_name = match self.diff_view_mode {
+            DiffViewMode::Unified => "unified",
+            DiffViewMode::SideBySide => "side-by-side",
+        };
+        self.set_message(format!("Diff view mode: {mode_name}"));
+    }
+
+    pub fn toggle_file_list(&mut self) {
+        self.show_file_list = !self.show_file_list;
+        let status = if self.show_file_list {
+            "visible"
+        } else {
+            "hidden"
+        };
+        self.set_message(format!("File list: {status}"));
+    }
+
+    // Commit selection methods
+
+    pub fn commit_select_up(&mut self) {
+        if self.commit_list_cursor > 0 {
+            self.commit_list_cursor -= 1;
+            // Scroll up if cursor goes above visible area
+            if self.commit_list_cursor < self.commit_list_scroll_offset {
+                self.commit_list_scroll_offset = self.commit_list_cursor;
             }
         }
+        self.extend_commit_anchor();
+    }
 
-        items
+    pub fn commit_select_down(&mut self) {
+        let row_count = self.commit_rows().len();
+        let max_cursor = if self.can_show_more_commits() {
+            row_count
+        } else {
+            row_count.saturating_sub(1)
+        };
+
+        if self.commit_list_cursor < max_cursor {
+            self.commit_list_cursor += 1;
+            // Scroll down if cursor goes below visible area
+            if self.commit_list_viewport_height > 0
+                && self.commit_list_cursor
+                    >= self.commit_list_scroll_offset + self.commit_list_viewport_height
+            {
+                self.commit_list_scroll_offset =
+                    self.commit_list_cursor - self.commit_list_viewport_height + 1;
+            }
+        }
+        self.extend_commit_anchor();
     }
 
-    pub fn get_selected_tree_item(&self) -> Option<FileTreeItem> {
-        let visible_items = self.build_visible_items();
-        let selected_idx = self.file_list_state.selected();
-        visible_items.get(selected_idx).cloned()
+    /// Start a range at the commit under the cursor (`v`), so moving the
+    /// cursor selects everything from there to the cursor. Pressing `v`
+    /// again drops the anchor and keeps the selection.
+    pub fn toggle_commit_anchor(&mut self) {
+        if self.commit_anchor.take().is_some() {
+            return;
+        }
+        let Some(&index) = self.commit_rows().get(self.commit_list_cursor) else {
+            return;
+        };
+        self.commit_anchor = Some(index);
+        self.commit_selection_range = Some((index, index));
+    }
+
+    /// Load the preview of the commit under the cursor if needed, and
+    /// return its index in `commit_list`
+    pub fn load_cursor_commit_preview(&mut self) -> Option<usize> {
+        let index = *self.commit_rows().get(self.commit_list_cursor)?;
+        let commit = &self.commit_list[index];
+        if !self.commit_previews.contains_key(&commit.id) {
+            let message = match self.vcs.get_commit_message(&commit.id) {
+                Ok(Some(message)) => message,
+                Ok(None) => commit.summary.clone(),
+                Err(e) => format!("{}\n\n(Failed to load message: {e})", commit.summary),
+            };
+            let files = match self.commit_stats.get(&commit.id) {
+                Some(files) => files.clone(),
+                None => self.vcs.get_commit_stats(&commit.id).unwrap_or_default(),
+            };
+            self.commit_previews
+                .insert(commit.id.clone(), CommitPreview { message, files });
+        }
+        Some(index)
+    }
+
+    /// Store stats sent by the background worker, and start a new worker
+    /// for picker commits that don't have stats yet
+    pub fn poll_commit_stats(&mut self) {
+        if let Some(rx) = &self.commit_stats_rx {
+            loop {
+                match rx.try_recv() {
+                    Ok((id, files)) => {
+                        self.commit_stats.insert(id, files);
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => return,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => break,
+                }
+            }
+            self.commit_stats_rx = None;
+        }
+
+        if !matches!(
+            self.input_mode,
+            InputMode::CommitSelect | InputMode::CommitFilter
+        ) {
+            return;
+        }
+        let ids: Vec<String> = self
+            .commit_list
+            .iter()
+            .filter(|commit| !self.commit_stats.contains_key(&commit.id))
+            .map(|commit| commit.id.clone())
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        // The worker opens its own backend so the UI never waits on a diff.
+        // Every id gets an answer, empty on failure, so none is retried
+        let root = self.vcs_info.root_path.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let backend = crate::vcs::detect_vcs_at(&root).ok();
+            for id in ids {
+                let files = backend
+                    .as_ref()
+                    .and_then(|backend| backend.get_commit_stats(&id).ok())
+                    .unwrap_or_default();
+                if tx.send((id, files)).is_err() {
+                    return;
+                }
+            }
+        });
+        self.commit_stats_rx = Some(rx);
+    }
+
+    /// Drop the anchor and the range it selected
+    pub fn cancel_commit_anchor(&mut self) {
+        self.commit_anchor = None;
+        self.commit_selection_range = None;
+    }
+
+    fn extend_commit_anchor(&mut self) {
+        let Some(anchor) = self.commit_anchor else {
+            return;
+        };
+        // The expand row has no commit; the range stays where it was
+        if let Some(&index) = self.commit_rows().get(self.commit_list_cursor) {
+            self.commit_selection_range = Some((anchor.min(index), anchor.max(index)));
+        }
+    }
+
+    /// Indices into `commit_list` of the rows shown in the picker, in order
+    pub fn commit_rows(&self) -> Vec<usize> {
+        if self.commit_filter.trim().is_empty() {
+            return (0..self.visible_commit_count.min(self.commit_list.len())).collect();
+        }
+        self.commit_list
+            .iter()
+            .enumerate()
+            .filter(|(_, commit)| commit_matches_filter(commit, &self.commit_filter))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // Check if cursor is on the commit expand row
+    pub fn is_on_expand_row(&self) -> bool {
+        self.can_show_more_commits() && self.commit_list_cursor == self.commit_rows().len()
+    }
+
+    pub fn can_show_more_commits(&self) -> bool {
+        let filtering = !self.commit_filter.trim().is_empty();
+        (!filtering && self.visible_commit_count < self.commit_list.len()) || self.has_more_commit
+    }
+
+    pub fn enter_commit_filter_mode(&mut self) {
+        self.input_mode = InputMode::CommitFilter;
+    }
+
+    /// Leave the filter prompt; `keep` leaves the list narrowed. Changed
+    /// `author:`/`since:` terms refetch the commits from the VCS.
+    pub fn exit_commit_filter_mode(&mut self, keep: bool) -> Result<()> {
+        self.input_mode = InputMode::CommitSelect;
+        if !keep {
+            return self.clear_commit_filter();
+        }
+        match parse_commit_query(&self.commit_filter) {
+            Ok(query) if query != self.commit_query => self.load_filtered_commits(query),
+            Ok(_) => Ok(()),
+            Err(message) => {
+                self.set_warning(message);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn clear_commit_filter(&mut self) -> Result<()> {
+        self.set_commit_filter(String::new());
+        if self.commit_query == CommitQuery::default() {
+            return Ok(());
+        }
+        self.load_filtered_commits(CommitQuery::default())
+    }
+
+    fn load_filtered_commits(&mut self, query: CommitQuery) -> Result<()> {
+        let commits = self
+            .vcs
+            .get_recent_commits(0, VISIBLE_COMMIT_COUNT, &query)?;
+        if commits.is_empty() {
+            self.set_message("No matching commits");
+        }
+        self.has_more_commit = commits.len() >= VISIBLE_COMMIT_COUNT;
+        self.commit_list = commits;
+        self.commit_list_cursor = 0;
+        self.commit_list_scroll_offset = 0;
+        self.commit_selection_range = None;
+        self.commit_anchor = None;
+        self.visible_commit_count = self.commit_list.len();
+        self.commit_query = query;
+        Ok(())
+    }
+
+    pub fn set_commit_filter(&mut self, filter: String) {
+        self.commit_filter = filter;
+        self.commit_list_cursor = 0;
+        self.commit_list_scroll_offset = 0;
+    }
+
+    // Expand the commit list to show more commits
+    pub fn expand_commit(&mut self) -> Result<()> {
+        let filtering = !self.commit_filter.trim().is_empty();
+        if !filtering && self.visible_commit_count < self.commit_list.len() {
+            self.visible_commit_count =
+                (self.visible_commit_count + self.commit_page_size).min(self.commit_list.len());
+            return Ok(());
+        }
+
+        if !self.has_more_commit {
+            self.set_message("No more commits");
+            return Ok(());
+        }
+
+        let offset = self.commit_list.len();
+        let limit = self.commit_page_size;
+
+        let new_commits = self
+            .vcs
+            .get_recent_commits(offset, limit, &self.commit_query)?;
+
+        if new_commits.is_empty() {
+            self.has_more_commit = false;
+            self.set_message("No more commits");
+            return Ok(());
+        }
+
+        if new_commits.len() < limit {
+            self.has_more_commit = false;
+            self.set_message("No more commits");
+        }
+
+        self.commit_list.extend(new_commits);
+        self.visible_commit_count = self.commit_list.len();
+
+        Ok(())
+    }
+
+    pub fn toggle_commit_selection(&mut self) {
+        // Ranges span commit_list, so a filtered selection keeps the
+        // commits in between that the filter hides
+        let Some(&cursor) = self.commit_rows().get(self.commit_list_cursor) else {
+            return;
+        };
+        self.commit_anchor = None;
+
+        match self.commit_selection_range {
+            None => {
+                // No selection yet - select just this commit
+                self.commit_selection_range = Some((cursor, cursor));
+            }
+            Some((start, end)) => {
+                if cursor >= start && cursor <= end {
+                    // Cursor is within the range - shrink or deselect
+                    if start == end {
+                        // Only one commit selected, deselect all
+                        self.commit_selection_range = None;
+                    } else if cursor == start {
+                        // At start edge - shrink from start
+                        self.commit_selection_range = Some((start + 1, end));
+                    } else if cursor == end {
+                        // At end edge - shrink from end
+                        self.commit_selection_range = Some((start, end - 1));
+                    } else {
+                        // In the middle - shrink towards cursor (exclude everything after cursor)
+                        // This makes the cursor the new end of the range
+                        self.commit_selection_range = Some((start, cursor));
+                    }
+                } else {
+                    // Cursor is outside the range - extend to include it
+                    let new_start = start.min(cursor);
+                    let new_end = end.max(cursor);
+                    self.commit_selection_range = Some((new_start, new_end));
+                }
+            }
+        }
+    }
+
+    /// Check if a commit at the given index is selected
+    pub fn is_commit_selected(&self, index: usize) -> bool {
+        match self.commit_selection_range {
+            Some((start, end)) => index >= start && index <= end,
+            None => false,
+        }
+    }
+
+    pub fn confirm_commit_selection(&mut self) -> Result<()> {
+        let Some((start, end)) = self.commit_selection_range else {
+            self.set_message("Select at least one commit");
+            return Ok(());
+        };
+
+        // Collect selected commit IDs (in order from oldest to newest, i.e., end to start)
+        // commit_list[0] is newest (HEAD), commit_list[end] is oldest selected
+        let selected_ids: Vec<String> = (start..=end)
+            .rev()
+            .filter_map(|i| self.commit_list.get(i).map(|c| c.id.clone()))
+            .collect();
+
+        if selected_ids.is_empty() {
+            self.set_message("Select at least one commit");
+            return Ok(());
+        }
+
+        self.load_commit_range(selected_ids)
+    }
+
+    /// Review the commits of the current branch since it forked from `base`,
+    /// or the default base branch, detected from `origin/HEAD` or
+    /// main/master, when None. Returns how many commits are under review,
+    /// 0 when the branch has none ahead of the base.
+    pub fn review_against_base(&mut self, base: Option<&str>) -> Result<usize> {
+        let (base, commit_ids) = self.vcs.get_commits_since_base(base)?;
+        if commit_ids.is_empty() {
+            self.set_message(format!("No commits ahead of {base}"));
+            return Ok(0);
+        }
+        let count = commit_ids.len();
+        let source = DiffSource::CommitRange(commit_ids.clone());
+        self.load_commit_range(commit_ids)?;
+        if self.diff_source == source {
+            self.set_message(format!("Reviewing commits since {base}"));
+        }
+        Ok(count)
+    }
+
+    /// Review a GitLab merge request of the `origin` remote as a commit range
+    pub fn review_merge_request(&mut self, iid: u64) -> Result<()> {
+        if self.vcs_info.vcs_type != VcsType::Git {
+            return Err(TuicrError::UnsupportedOperation(
+                "Merge requests need a git repository".to_string(),
+            ));
+        }
+        let mr = gitlab::fetch_merge_request(&self.vcs_info.root_path, &self.config.gitlab, iid)?;
+        let commit_ids = gitlab::merge_request_commits(&self.vcs_info.root_path, &mr)?;
+        if commit_ids.is_empty() {
+            self.set_message(format!("!{iid} has no commits"));
+            return Ok(());
+        }
+        let source = DiffSource::CommitRange(commit_ids.clone());
+        self.load_commit_range(commit_ids)?;
+        if self.diff_source == source {
+            self.set_message(format!("Reviewing !{iid}: {}", mr.title));
+        }
+        Ok(())
+    }
+
+    /// Load the diff of `selected_ids` (oldest first) with its saved session
+    fn load_commit_range(&mut self, selected_ids: Vec<String>) -> Result<()> {
+        // Get the diff for the selected commits
+        let highlighter = self.theme.syntax_highlighter();
+        let diff_files = self.vcs.get_commit_range_diff(&selected_ids, highlighter)?;
+        let mut diff_files = self.filter_diff_files(diff_files);
+
+        if diff_files.is_empty() {
+            self.set_message("No changes in selected commits");
+            return Ok(());
+        }
+        self.prepend_commit_message(&mut diff_files, &selected_ids);
+
+        // Update session with the newest commit as base
+        let newest_commit_id = selected_ids.last().unwrap().clone();
+        let loaded_session = load_latest_session_for_context(
+            &self.vcs_info.root_path,
+            self.vcs_info.branch_name.as_deref(),
+            &newest_commit_id,
+            SessionDiffSource::CommitRange,
+            Some(selected_ids.as_slice()),
+        )
+        .ok()
+        .and_then(|found| found.map(|(_path, session)| session));
+
+        let mut session = loaded_session.unwrap_or_else(|| {
+            let mut session = ReviewSession::new(
+                self.vcs_info.root_path.clone(),
+                newest_commit_id,
+                self.vcs_info.branch_name.clone(),
+                SessionDiffSource::CommitRange,
+            );
+            session.commit_range = Some(selected_ids.clone());
+            session
+        });
+
+        if session.commit_range.is_none() {
+            session.commit_range = Some(selected_ids.clone());
+            session.updated_at = chrono::Utc::now();
+        }
+        session.pathspecs = std::mem::take(&mut self.session.pathspecs);
+
+        self.session = session;
+
+        // Add files to session
+        for file in &diff_files {
+            let path = file.display_path().clone();
+            self.session.add_file(path, file.status);
+        }
+
+        // Update app state
+        self.diff_files = diff_files;
+        self.diff_source = DiffSource::CommitRange(selected_ids);
+        self.input_mode = InputMode::Normal;
+
+        // Reset navigation state
+        self.diff_state = DiffState::default();
+        self.file_list_state = FileListState::default();
+
+        self.sort_files_by_directory(true);
+        self.expand_all_dirs();
+        self.rebuild_annotations();
+        self.offer_previous_session();
+
+        Ok(())
+    }
+
+    fn sort_files_by_directory(&mut self, reset_position: bool) {
+        use std::collections::BTreeMap;
+        use std::path::Path;
+
+        let current_path = if !reset_position {
+            self.current_file_path().cloned()
+        } else {
+            None
+        };
+
+        // A single-file diff gets the whole width until Tab brings the list back
+        if reset_position && !self.config.keep_file_list {
+            let file_count = self
+                .diff_files
+                .iter()
+                .filter(|file| !file.is_commit_message())
+                .count();
+            self.show_file_list = file_count != 1;
+        }
+
+        let attributes = GitAttributes::load(&self.vcs_info.root_path);
+        self.vendored_paths = self.detect_vendored_paths(&attributes);
+        self.generated_paths = self.detect_generated_paths(&attributes);
+        self.migration_paths = self
+            .diff_files
+            .iter()
+            .map(|file| file.display_path())
+            .filter(|path| migration::is_migration(path, &self.config.migration_dirs))
+            .cloned()
+            .collect();
+        self.untested_paths = test_coverage(&self.diff_files, &self.config.test_mappings)
+            .into_iter()
+            .filter(|coverage| coverage.tests.is_empty())
+            .map(|coverage| coverage.source)
+            .collect();
+
+        let mut dir_map: BTreeMap<String, Vec<DiffFile>> = BTreeMap::new();
+        let mut vendored: Vec<DiffFile> = Vec::new();
+        let mut commit_message: Option<DiffFile> = None;
+
+        for file in self.diff_files.drain(..) {
+            if file.is_commit_message() {
+                commit_message = Some(file);
+                continue;
+            }
+            if self.vendored_paths.contains(file.display_path()) {
+                vendored.push(file);
+                continue;
+            }
+
+            let path = file.display_path();
+            let dir = if let Some(parent) = path.parent() {
+                if parent == Path::new("") {
+                    ".".to_string()
+                } else {
+                    parent.to_string_lossy().to_string()
+                }
+            } else {
+                ".".to_string()
+            };
+
+            dir_map.entry(dir).or_default().push(file);
+        }
+
+        for (_dir, files) in dir_map {
+            self.diff_files.extend(files);
+        }
+        self.apply_review_plan();
+        // The commit message always comes first, plan or not
+        if let Some(file) = commit_message {
+            self.diff_files.insert(0, file);
+        }
+
+        // Vendored files live in their own group after the tree
+        vendored.sort_by(|a, b| a.display_path().cmp(b.display_path()));
+        self.diff_files.extend(vendored);
+        self.refresh_hunk_warnings();
+        self.refresh_conflict_lines();
+        self.moved_lines = detect_moved_lines(&self.diff_files);
+
+        if let Some(path) = current_path
+            && let Some(idx) = self
+                .diff_files
+                .iter()
+                .position(|f| f.display_path() == &path)
+        {
+            self.jump_to_file(idx);
+            return;
+        }
+
+        self.jump_to_file(0);
+    }
+
+    /// Recompute hunk header warnings; indices change whenever files are reordered
+    fn refresh_hunk_warnings(&mut self) {
+        self.hunk_warnings.clear();
+        for (file_idx, file) in self.diff_files.iter().enumerate() {
+            let path = file.display_path();
+            let indent_sensitive = indent_guard::is_indent_sensitive(path);
+            let is_migration = self.migration_paths.contains(path);
+            if !indent_sensitive && !is_migration {
+                continue;
+            }
+            for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+                let mut warnings = Vec::new();
+                if indent_sensitive {
+                    warnings.extend(indent_guard::hunk_warnings(hunk));
+                }
+                if is_migration {
+                    warnings.extend(migration::destructive_warnings(hunk));
+                }
+                if !warnings.is_empty() {
+                    self.hunk_warnings.insert((file_idx, hunk_idx), warnings);
+                }
+            }
+        }
+    }
+
+    /// Whether a deleted or added line belongs to a block moved elsewhere in the diff
+    pub fn is_moved_line(&self, file_idx: usize, hunk_idx: usize, line_idx: usize) -> bool {
+        self.moved_lines
+            .get(&(file_idx, hunk_idx))
+            .and_then(|flags| flags.get(line_idx))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Whether any hunk of the file has a header warning
+    pub fn file_has_warnings(&self, file_idx: usize) -> bool {
+        self.hunk_warnings.keys().any(|(idx, _)| *idx == file_idx)
+    }
+
+    fn refresh_conflict_lines(&mut self) {
+        self.conflict_lines = self
+            .diff_files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.status == FileStatus::Conflicted)
+            .map(|(file_idx, file)| (file_idx, tag_conflict_lines(&file.hunks)))
+            .collect();
+    }
+
+    /// Number of files with unresolved merge conflicts
+    pub fn conflicted_file_count(&self) -> usize {
+        self.diff_files
+            .iter()
+            .filter(|file| file.status == FileStatus::Conflicted)
+            .count()
+    }
+
+    /// Conflict section of a new-side line in the current file, if any
+    fn conflict_side_at(&self, line: u32, side: LineSide) -> Option<ConflictSide> {
+        if side != LineSide::New {
+            return None;
+        }
+        let file_idx = self.diff_state.current_file_idx;
+        let tags = self.conflict_lines.get(&file_idx)?;
+        let file = self.diff_files.get(file_idx)?;
+        file.hunks
+            .iter()
+            .zip(tags)
+            .flat_map(|(hunk, hunk_tags)| hunk.lines.iter().zip(hunk_tags))
+            .find(|(diff_line, _)| {
+                diff_line.origin != LineOrigin::Deletion && diff_line.new_lineno == Some(line)
+            })
+            .and_then(|(_, tag)| match tag {
+                Some(ConflictLine::Section(side)) => Some(*side),
+                _ => None,
+            })
+    }
+
+    /// Reorder non-vendored files to follow the session's review plan.
+    /// Files missing from the plan keep their tree order after the planned ones.
+    fn apply_review_plan(&mut self) {
+        let plan = &self.session.review_plan;
+        if plan.is_empty() {
+            return;
+        }
+        self.diff_files.sort_by_key(|file| {
+            plan.iter()
+                .position(|path| path == file.display_path())
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    pub fn has_review_plan(&self) -> bool {
+        !self.session.review_plan.is_empty()
+    }
+
+    /// Current order of non-vendored files, as stored in the review plan
+    fn planned_paths(&self) -> Vec<PathBuf> {
+        self.diff_files
+            .iter()
+            .map(|file| file.display_path())
+            .filter(|path| !self.is_vendored(path))
+            .cloned()
+            .collect()
+    }
+
+    /// Enter or leave plan mode. Entering seeds the plan from the current order.
+    pub fn toggle_plan_mode(&mut self) {
+        self.plan_mode = !self.plan_mode;
+        if !self.plan_mode {
+            self.set_message("Plan mode off");
+            return;
+        }
+
+        if !self.has_review_plan() {
+            self.session.review_plan = self.planned_paths();
+            self.dirty = true;
+            self.ensure_valid_tree_selection();
+        }
+        self.focused_panel = FocusedPanel::FileList;
+        self.set_message("Plan mode: J/K to move the selected file, :plan to finish");
+    }
+
+    /// Move the file selected in the file list one step later or earlier in the plan
+    pub fn move_file_in_plan(&mut self, down: bool) {
+        let Some(FileTreeItem::File { file_idx, .. }) = self.get_selected_tree_item() else {
+            return;
+        };
+        let target = if down {
+            file_idx + 1
+        } else if let Some(target) = file_idx.checked_sub(1) {
+            target
+        } else {
+            return;
+        };
+        let Some(other) = self.diff_files.get(target) else {
+            return;
+        };
+        if self.is_vendored(other.display_path())
+            || self.is_vendored(self.diff_files[file_idx].display_path())
+        {
+            return;
+        }
+
+        self.diff_files.swap(file_idx, target);
+        self.session.review_plan = self.planned_paths();
+        self.dirty = true;
+        // Expanded gaps are keyed by file index
+        self.clear_expanded_gaps();
+        self.rebuild_annotations();
+        self.jump_to_file(target);
+    }
+
+    /// Drop the review plan and return to the directory tree order
+    pub fn clear_review_plan(&mut self) {
+        self.plan_mode = false;
+        if !self.has_review_plan() {
+            return;
+        }
+        self.session.review_plan.clear();
+        self.dirty = true;
+        self.clear_expanded_gaps();
+        self.sort_files_by_directory(false);
+        self.expand_all_dirs();
+        self.rebuild_annotations();
+        self.set_message("Review plan cleared");
+    }
+
+    pub fn expand_all_dirs(&mut self) {
+        use std::path::Path;
+
+        self.expanded_dirs.clear();
+        for file in &self.diff_files {
+            let path = file.display_path();
+            let mut current = path.parent();
+            while let Some(parent) = current {
+                if parent != Path::new("") {
+                    self.expanded_dirs
+                        .insert(parent.to_string_lossy().to_string());
+                }
+                current = parent.parent();
+            }
+        }
+        self.ensure_valid_tree_selection();
+    }
+
+    pub fn collapse_all_dirs(&mut self) {
+        self.expanded_dirs.clear();
+        self.ensure_valid_tree_selection();
+    }
+
+    pub fn toggle_directory(&mut self, dir_path: &str) {
+        if self.expanded_dirs.contains(dir_path) {
+            self.expanded_dirs.remove(dir_path);
+            self.ensure_valid_tree_selection();
+        } else {
+            self.expanded_dirs.insert(dir_path.to_string());
+        }
+    }
+
+    /// Check if a hunk gap has been expanded
+    pub fn is_gap_expanded(&self, gap_id: &GapId) -> bool {
+        self.expanded_gaps.contains(gap_id)
+    }
+
+    /// Expand a gap to show hidden context lines
+    pub fn expand_gap(&mut self, gap_id: GapId) -> Result<()> {
+        if self.expanded_gaps.contains(&gap_id) {
+            return Ok(()); // Already expanded
+        }
+
+        let file = self.diff_files.get(gap_id.file_idx).ok_or_else(|| {
+            TuicrError::CorruptedSession(format!("Invalid file index: {}", gap_id.file_idx))
+        })?;
+
+        let hunk = file.hunks.get(gap_id.hunk_idx).ok_or_else(|| {
+            TuicrError::CorruptedSession(format!("Invalid hunk index: {}", gap_id.hunk_idx))
+        })?;
+
+        // Get previous hunk to calculate gap boundaries
+        let prev_hunk = if gap_id.hunk_idx > 0 {
+            file.hunks.get(gap_id.hunk_idx - 1)
+        } else {
+            None
+        };
+
+        // Calculate line range to fetch
+        let (start_line, end_line) = match prev_hunk {
+            None => (1, hunk.new_start.saturating_sub(1)),
+            Some(prev) => {
+                let prev_end = prev.new_start + prev.new_count;
+                (prev_end, hunk.new_start.saturating_sub(1))
+            }
+        };
+
+        if start_line > end_line {
+            return Ok(()); // No gap to expand
+        }
+
+        let file_path = file.display_path().clone();
+        let file_status = file.status;
+
+        // Fetch the context lines
+        let lines = self
+            .vcs
+            .fetch_context_lines(&file_path, file_status, start_line, end_line)?;
+
+        self.expanded_content.insert(gap_id.clone(), lines);
+        self.expanded_gaps.insert(gap_id);
+        self.rebuild_annotations();
+
+        Ok(())
+    }
+
+    /// Get the hunk whose header is under the cursor
+    pub fn hunk_header_at_cursor(&self) -> Option<(usize, usize)> {
+        match self.line_annotations.get(self.diff_state.cursor_line) {
+            Some(AnnotatedLine::HunkHeader { file_idx, hunk_idx }) => Some((*file_idx, *hunk_idx)),
+            _ => None,
+        }
+    }
+
+    /// Grow a hunk by up to `HUNK_EXPAND_STEP` lines of context above and below,
+    /// never reaching into a neighbouring hunk
+    pub fn expand_hunk(&mut self, file_idx: usize, hunk_idx: usize) -> Result<()> {
+        let file = self.diff_files.get(file_idx).ok_or_else(|| {
+            TuicrError::CorruptedSession(format!("Invalid file index: {file_idx}"))
+        })?;
+        if file.status == FileStatus::Deleted || file.special.is_some() {
+            self.set_message("No more context to show");
+            return Ok(());
+        }
+        let hunk = file.hunks.get(hunk_idx).ok_or_else(|| {
+            TuicrError::CorruptedSession(format!("Invalid hunk index: {hunk_idx}"))
+        })?;
+
+        let floor = match hunk_idx.checked_sub(1).and_then(|i| file.hunks.get(i)) {
+            Some(prev) => prev.new_start + prev.new_count,
+            None => 1,
+        };
+        let above_start = hunk.new_start.saturating_sub(HUNK_EXPAND_STEP).max(floor);
+        let above_end = hunk.new_start.saturating_sub(1);
+
+        let hunk_end = hunk.new_start + hunk.new_count;
+        let below_end = match file.hunks.get(hunk_idx + 1) {
+            Some(next) => (hunk_end + HUNK_EXPAND_STEP - 1).min(next.new_start.saturating_sub(1)),
+            None => hunk_end + HUNK_EXPAND_STEP - 1,
+        };
+
+        // Read the reviewed revision, which is not the working tree for a commit range
+        let new_text = self.file_versions(file)?.new.unwrap_or_default();
+        let new_lines: Vec<&str> = new_text.lines().collect();
+        let mut above = context_lines(&new_lines, above_start, above_end);
+        let mut below = context_lines(&new_lines, hunk_end, below_end);
+
+        if above.is_empty() && below.is_empty() {
+            self.set_message("No more context to show");
+            return Ok(());
+        }
+
+        let hunk = &mut self.diff_files[file_idx].hunks[hunk_idx];
+        // Context lines are read from the new side, so shift them onto the old side
+        let above_offset = i64::from(hunk.old_start) - i64::from(hunk.new_start);
+        let below_offset = i64::from(hunk.old_start + hunk.old_count) - i64::from(hunk_end);
+        for line in &mut above {
+            line.old_lineno = line
+                .new_lineno
+                .map(|n| (i64::from(n) + above_offset) as u32);
+        }
+        for line in &mut below {
+            line.old_lineno = line
+                .new_lineno
+                .map(|n| (i64::from(n) + below_offset) as u32);
+        }
+
+        let added_above = above.len() as u32;
+        let added_below = below.len() as u32;
+        hunk.lines.splice(0..0, above);
+        hunk.lines.extend(below);
+        hunk.old_start = hunk.old_start.saturating_sub(added_above);
+        hunk.new_start = hunk.new_start.saturating_sub(added_above);
+        hunk.old_count += added_above + added_below;
+        hunk.new_count += added_above + added_below;
+        hunk.header = rewrite_hunk_header(
+            &hunk.header,
+            hunk.old_start,
+            hunk.old_count,
+            hunk.new_start,
+            hunk.new_count,
+        );
+
+        // Gap expansions and moved-line flags were computed against the old hunk bounds
+        self.moved_lines = detect_moved_lines(&self.diff_files);
+        self.expanded_gaps.retain(|gap| gap.file_idx != file_idx);
+        self.expanded_content
+            .retain(|gap, _| gap.file_idx != file_idx);
+        self.rebuild_annotations();
+
+        if let Some(idx) = self.line_annotations.iter().position(|a| {
+            matches!(a, AnnotatedLine::HunkHeader { file_idx: f, hunk_idx: h } if *f == file_idx && *h == hunk_idx)
+        }) {
+            self.diff_state.cursor_line = idx;
+            self.ensure_cursor_visible();
+        }
+
+        Ok(())
+    }
+
+    /// Both versions of a diff file as of the current diff source. Backends
+    /// that cannot read revisions fall back to the working tree file, with
+    /// the old side rebuilt from the hunks.
+    fn file_versions(&self, file: &DiffFile) -> Result<FileVersions> {
+        let display_path = file.display_path();
+        let old_path = file.old_path.as_ref().unwrap_or(display_path);
+        let new_path = file.new_path.as_ref().unwrap_or(display_path);
+        self.path_versions(old_path, new_path, Some(file))
+    }
+
+    /// Both versions of a path as of the current diff source; `file` is its
+    /// entry in the diff, None for a file the diff does not touch
+    fn path_versions(
+        &self,
+        old_path: &std::path::Path,
+        new_path: &std::path::Path,
+        file: Option<&DiffFile>,
+    ) -> Result<FileVersions> {
+        let commit_ids = match &self.diff_source {
+            DiffSource::WorkingTree => None,
+            DiffSource::CommitRange(ids) => Some(ids.as_slice()),
+        };
+        match self.vcs.get_file_versions(old_path, new_path, commit_ids) {
+            Err(TuicrError::UnsupportedOperation(_)) if commit_ids.is_none() => {
+                let new = std::fs::read_to_string(self.vcs_info.root_path.join(new_path)).ok();
+                let old = match (file, &new) {
+                    (_, None) => None,
+                    (None, Some(text)) => Some(text.clone()),
+                    (Some(file), _) if file.status == FileStatus::Added => None,
+                    (Some(file), Some(text)) => {
+                        let lines: Vec<String> = text.lines().map(String::from).collect();
+                        Some(reconstruct_old_file(&lines, &file.hunks).join("\n"))
+                    }
+                };
+                Ok(FileVersions { old, new })
+            }
+            result => result,
+        }
+    }
+
+    /// Collapse an expanded gap
+    pub fn collapse_gap(&mut self, gap_id: GapId) {
+        self.expanded_gaps.remove(&gap_id);
+        self.expanded_content.remove(&gap_id);
+        self.rebuild_annotations();
+    }
+
+    /// Clear all expanded gaps (called when reloading diffs)
+    pub fn clear_expanded_gaps(&mut self) {
+        self.expanded_gaps.clear();
+        self.expanded_content.clear();
+    }
+
+    /// Rebuild the line annotations cache. Call this when:
+    /// - Diff files change (load/reload)
+    /// - Expansion state changes (expand/collapse gap)
+    /// - Comments are added/removed
+    pub fn rebuild_annotations(&mut self) {
+        self.line_annotations.clear();
+
+        for (file_idx, file) in self.diff_files.iter().enumerate() {
+            let path = file.display_path();
+
+            // File header
+            self.line_annotations
+                .push(AnnotatedLine::FileHeader { file_idx });
+
+            // If folded, skip all content for this file
+            if self.is_file_folded(path) {
+                continue;
+            }
+
+            // File comments
+            if let Some(review) = self.session.files.get(path) {
+                for (comment_idx, comment) in review.file_comments.iter().enumerate() {
+                    if self.hidden_comment_types.contains(&comment.comment_type) {
+                        continue;
+                    }
+                    let comment_lines = Self::comment_display_lines(comment);
+                    for _ in 0..comment_lines {
+                        self.line_annotations.push(AnnotatedLine::FileComment {
+                            file_idx,
+                            comment_idx,
+                        });
+                    }
+                }
+            }
+
+            if self.shows_placeholder(file) {
+                self.line_annotations
+                    .push(AnnotatedLine::BinaryOrEmpty { file_idx });
+            } else {
+                // Get line comments for this file
+                let line_comments = self
+                    .session
+                    .files
+                    .get(path)
+                    .map(|r| &r.line_comments)
+                    .cloned()
+                    .unwrap_or_default();
+
+                for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+                    // Calculate gap before this hunk
+                    let prev_hunk = if hunk_idx > 0 {
+                        file.hunks.get(hunk_idx - 1)
+                    } else {
+                        None
+                    };
+                    let gap = calculate_gap(
+                        prev_hunk.map(|h| (&h.new_start, &h.new_count)),
+                        hunk.new_start,
+                    );
+
+                    let gap_id = GapId { file_idx, hunk_idx };
+
+                    if gap > 0 {
+                        if self.expanded_gaps.contains(&gap_id) {
+                            // Expanded content lines
+                            if let Some(content) = self.expanded_content.get(&gap_id) {
+                                for (content_idx, _) in content.iter().enumerate() {
+                                    self.line_annotations.push(AnnotatedLine::ExpandedContext {
+                                        gap_id: gap_id.clone(),
+                                        line_idx: content_idx,
+                                    });
+                                }
+                            }
+                        } else {
+                            // Expander line
+                            self.line_annotations.push(AnnotatedLine::Expander {
+                                gap_id: gap_id.clone(),
+                            });
+                        }
+                    }
+
+                    // Hunk header
+                    self.line_annotations
+                        .push(AnnotatedLine::HunkHeader { file_idx, hunk_idx });
+
+                    // Diff lines
+                    for (line_idx, diff_line) in hunk.lines.iter().enumerate() {
+                        self.line_annotations.push(AnnotatedLine::DiffLine {
+                            file_idx,
+                            hunk_idx,
+                            line_idx,
+                            old_lineno: diff_line.old_lineno,
+                            new_lineno: diff_line.new_lineno,
+                        });
+
+                        // Line comments on old side (deleted lines)
+                        if let Some(old_ln) = diff_line.old_lineno
+                            && let Some(comments) = line_comments.get(&old_ln)
+                        {
+                            for (idx, comment) in comments.iter().enumerate() {
+                                if comment.side == Some(LineSide::Old)
+                                    && !self.hidden_comment_types.contains(&comment.comment_type)
+                                {
+                                    let comment_lines = Self::comment_display_lines(comment);
+                                    for _ in 0..comment_lines {
+                                        self.line_annotations.push(AnnotatedLine::LineComment {
+                                            file_idx,
+                                            line: old_ln,
+                                            side: LineSide::Old,
+                                            comment_idx: idx,
+                                        });
+                                    }
+                                }
+                            }
+                        }
+
+                        // Line comments on new side (added/context lines)
+                        if let Some(new_ln) = diff_line.new_lineno
+                            && let Some(comments) = line_comments.get(&new_ln)
+                        {
+                            for (idx, comment) in comments.iter().enumerate() {
+                                if comment.side != Some(LineSide::Old)
+                                    && !self.hidden_comment_types.contains(&comment.comment_type)
+                                {
+                                    let comment_lines = Self::comment_display_lines(comment);
+                                    for _ in 0..comment_lines {
+                                        self.line_annotations.push(AnnotatedLine::LineComment {
+                                            file_idx,
+                                            line: new_ln,
+                                            side: LineSide::New,
+                                            comment_idx: idx,
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Spacing line
+            self.line_annotations.push(AnnotatedLine::Spacing);
+        }
+
+        if !self.diff_files.is_empty() {
+            self.line_annotations.push(AnnotatedLine::GeneralHeader);
+            for (entry_idx, (_, comment)) in self.session.general_comments().iter().enumerate() {
+                if self.hidden_comment_types.contains(&comment.comment_type) {
+                    continue;
+                }
+                for _ in 0..Self::comment_display_lines(comment) {
+                    self.line_annotations
+                        .push(AnnotatedLine::GeneralComment { entry_idx });
+                }
+            }
+        }
+    }
+
+    /// Check if cursor is on an expander line or expanded content and return GapId and whether expanded
+    /// Returns (GapId, is_expanded) - is_expanded is true if cursor is on expanded content
+    pub fn get_gap_at_cursor(&self) -> Option<(GapId, bool)> {
+        let target = self.diff_state.cursor_line;
+        match self.line_annotations.get(target) {
+            Some(AnnotatedLine::Expander { gap_id, .. }) => Some((gap_id.clone(), false)),
+            Some(AnnotatedLine::ExpandedContext { gap_id, .. }) => Some((gap_id.clone(), true)),
+            _ => None,
+        }
+    }
+
+    fn ensure_valid_tree_selection(&mut self) {
+        use std::path::Path;
+
+        let visible_items = self.build_visible_items();
+        if visible_items.is_empty() {
+            self.file_list_state.select(0);
+            return;
+        }
+
+        let current_file_idx = self.diff_state.current_file_idx;
+        let file_visible = visible_items.iter().any(|item| {
+            matches!(item, FileTreeItem::File { file_idx, .. } if *file_idx == current_file_idx)
+        });
+
+        if file_visible {
+            if let Some(tree_idx) = self.file_idx_to_tree_idx(current_file_idx) {
+                self.file_list_state.select(tree_idx);
+            }
+        } else {
+            if let Some(file) = self.diff_files.get(current_file_idx) {
+                let file_path = file.display_path();
+                if self.is_vendored(file_path)
+                    && let Some(group_idx) = visible_items
+                        .iter()
+                        .position(|item| matches!(item, FileTreeItem::VendoredGroup { .. }))
+                {
+                    self.file_list_state.select(group_idx);
+                    return;
+                }
+
+                let mut current = file_path.parent();
+                while let Some(parent) = current {
+                    if parent != Path::new("") {
+                        let parent_str = parent.to_string_lossy().to_string();
+                        for (tree_idx, item) in visible_items.iter().enumerate() {
+                            if let FileTreeItem::Directory { path, .. } = item
+                                && *path == parent_str
+                            {
+                                self.file_list_state.select(tree_idx);
+                                return;
+                            }
+                        }
+                    }
+                    current = parent.parent();
+                }
+            }
+            self.file_list_state.select(0);
+        }
+    }
+
+    pub fn build_visible_items(&self) -> Vec<FileTreeItem> {
+        use std::path::Path;
+
+        let mut items = Vec::new();
+        let mut seen_dirs: HashSet<String> = HashSet::new();
+        let mut vendored_files: Vec<usize> = Vec::new();
+
+        for (file_idx, file) in self.diff_files.iter().enumerate() {
+            let path = file.display_path();
+
+            if self.is_vendored(path) {
+                vendored_files.push(file_idx);
+                continue;
+            }
+
+            // A review plan replaces the tree with a flat, ordered list
+            if self.has_review_plan() {
+                items.push(FileTreeItem::File { file_idx, depth: 0 });
+                continue;
+            }
+
+            let mut ancestors: Vec<String> = Vec::new();
+            let mut current = path.parent();
+            while let Some(parent) = current {
+                if parent != Path::new("") {
+                    ancestors.push(parent.to_string_lossy().to_string());
+                }
+                current = parent.parent();
+            }
+            ancestors.reverse();
+
+            let mut visible = true;
+            for (depth, dir) in ancestors.iter().enumerate() {
+                if !seen_dirs.contains(dir) && visible {
+                    let expanded = self.expanded_dirs.contains(dir);
+                    items.push(FileTreeItem::Directory {
+                        path: dir.clone(),
+                        depth,
+                        expanded,
+                    });
+                    seen_dirs.insert(dir.clone());
+                }
+
+                if !self.expanded_dirs.contains(dir) {
+                    visible = false;
+                }
+            }
+
+            if visible {
+                items.push(FileTreeItem::File {
+                    file_idx,
+                    depth: ancestors.len(),
+                });
+            }
+        }
+
+        if !vendored_files.is_empty() {
+            items.push(FileTreeItem::VendoredGroup {
+                count: vendored_files.len(),
+                expanded: self.show_vendored,
+            });
+            if self.show_vendored {
+                items.extend(
+                    vendored_files
+                        .into_iter()
+                        .map(|file_idx| FileTreeItem::File { file_idx, depth: 1 }),
+                );
+            }
+        }
+
+        items
+    }
+
+    pub fn get_selected_tree_item(&self) -> Option<FileTreeItem> {
+        let visible_items = self.build_visible_items();
+        let selected_idx = self.file_list_state.selected();
+        visible_items.get(selected_idx).cloned()
+    }
+}
+
+/// Keep the files selected by any of the pathspecs; no pathspecs keeps everything
+fn filter_pathspecs(files: Vec<DiffFile>, pathspecs: &[String]) -> Vec<DiffFile> {
+    if pathspecs.is_empty() {
+        return files;
+    }
+    files
+        .into_iter()
+        .filter(|file| {
+            pathspecs
+                .iter()
+                .any(|spec| crate::glob::matches_pathspec(spec, file.display_path()))
+        })
+        .collect()
+}
+
+/// Drop files excluded by `.tuicrignore`, returning the rest and how many were dropped
+fn filter_ignored(files: Vec<DiffFile>, ignore: &TuicrIgnore) -> (Vec<DiffFile>, usize) {
+    if ignore.is_empty() {
+        return (files, 0);
+    }
+    let total = files.len();
+    let kept: Vec<DiffFile> = files
+        .into_iter()
+        .filter(|file| !ignore.is_ignored(file.display_path()))
+        .collect();
+    let hidden = total - kept.len();
+    (kept, hidden)
+}
+
+/// Whether every whitespace-separated word of `filter` appears, ignoring
+/// case, in the commit's summary, author or SHA. `author:`/`since:` terms
+/// were applied when fetching and are skipped here.
+fn commit_matches_filter(commit: &CommitInfo, filter: &str) -> bool {
+    let haystack = format!("{} {} {}", commit.summary, commit.author, commit.id).to_lowercase();
+    filter
+        .to_lowercase()
+        .split_whitespace()
+        .filter(|word| !word.starts_with("author:") && !word.starts_with("since:"))
+        .all(|word| haystack.contains(word))
+}
+
+/// The `author:name` and `since:YYYY-MM-DD` terms of a commit picker filter
+fn parse_commit_query(filter: &str) -> std::result::Result<CommitQuery, String> {
+    let mut query = CommitQuery::default();
+    for word in filter.split_whitespace() {
+        if let Some(author) = word.strip_prefix("author:") {
+            query.author = (!author.is_empty()).then(|| author.to_string());
+        } else if let Some(since) = word.strip_prefix("since:") {
+            let day = chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d")
+                .map_err(|_| format!("since: expects a date like 2024-01-01, not '{since}'"))?;
+            query.since = Some(day);
+        }
+    }
+    Ok(query)
+}
+
+/// Line number to content for one side of a file's diff
+/// The text of the lines in `range` on `side`, one per line, with their
+/// `+`/`-`/space prefix when `prefixes` is set
+fn selection_text(file: &DiffFile, range: LineRange, side: LineSide, prefixes: bool) -> String {
+    file.hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .filter(|line| {
+            let lineno = match side {
+                LineSide::Old => line.old_lineno,
+                LineSide::New => line.new_lineno,
+            };
+            lineno.is_some_and(|lineno| range.contains(lineno))
+        })
+        .map(|line| {
+            let prefix = match (prefixes, line.origin) {
+                (false, _) => "",
+                (true, LineOrigin::Addition) => "+",
+                (true, LineOrigin::Deletion) => "-",
+                (true, LineOrigin::Context) => " ",
+            };
+            format!("{prefix}{}\n", line.content)
+        })
+        .collect()
+}
+
+fn side_lines(file: &DiffFile, side: LineSide) -> BTreeMap<u32, &str> {
+    file.hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .filter_map(|line| {
+            let lineno = match side {
+                LineSide::Old => line.old_lineno,
+                LineSide::New => line.new_lineno,
+            }?;
+            Some((lineno, line.content.as_str()))
+        })
+        .collect()
+}
+
+/// Comment list rows: general comments, then each file's comments with
+/// file-level ones before line comments in line order
+fn comment_list_entries(session: &ReviewSession) -> Vec<CommentListEntry> {
+    let entry = |location: String, comment: &Comment| CommentListEntry {
+        id: comment.id.clone(),
+        location,
+        comment_type: comment.comment_type,
+        resolved: comment.resolved,
+        first_line: comment
+            .content
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    let mut entries: Vec<CommentListEntry> = session
+        .general_comments()
+        .into_iter()
+        .map(|(dir, comment)| {
+            let location = dir.map_or_else(
+                || "(review)".to_string(),
+                |dir| format!("{}/", dir.display()),
+            );
+            entry(location, comment)
+        })
+        .collect();
+
+    let mut files: Vec<_> = session.files.iter().collect();
+    files.sort_by_key(|(path, _)| *path);
+    for (path, review) in files {
+        for comment in &review.file_comments {
+            entries.push(entry(path.display().to_string(), comment));
+        }
+        let mut lines: Vec<_> = review.line_comments.iter().collect();
+        lines.sort_by_key(|(line, _)| **line);
+        for (line, comments) in lines {
+            for comment in comments {
+                let side = if comment.side == Some(LineSide::Old) {
+                    "~"
+                } else {
+                    ""
+                };
+                entries.push(entry(format!("{}:{side}{line}", path.display()), comment));
+            }
+        }
+    }
+    entries
+}
+
+/// The first added line a finding covers, with the path of its diff file.
+/// Report paths may be relative to a subdirectory (a cargo workspace member)
+/// or carry a prefix, so paths match when one ends with the other.
+fn changed_line(diff_files: &[DiffFile], finding: &Finding) -> Option<(PathBuf, u32)> {
+    let file = diff_files.iter().find(|file| {
+        let path = file.display_path();
+        path.ends_with(&finding.path) || finding.path.ends_with(path)
+    })?;
+    let span = finding.line..=finding.end_line.max(finding.line);
+    file.hunks
+        .iter()
+        .flat_map(|hunk| &hunk.lines)
+        .filter(|line| line.origin == LineOrigin::Addition)
+        .filter_map(|line| line.new_lineno)
+        .find(|lineno| span.contains(lineno))
+        .map(|lineno| (file.display_path().clone(), lineno))
+}
+
+/// Line comments whose line, on the side they were left on, is not part of
+/// any hunk of `diff_files`, sorted by path and line
+fn find_stale_comments(
+    files: &HashMap<PathBuf, FileReview>,
+    diff_files: &[DiffFile],
+) -> Vec<StaleComment> {
+    let mut stale = Vec::new();
+    for (path, review) in files {
+        let file_idx = diff_files.iter().position(|f| f.display_path() == path);
+        for (line, comments) in &review.line_comments {
+            for comment in comments {
+                let side = comment.side.unwrap_or(LineSide::New);
+                let present = file_idx
+                    .is_some_and(|idx| side_lines(&diff_files[idx], side).contains_key(line));
+                if !present {
+                    stale.push(StaleComment {
+                        path: path.clone(),
+                        line: *line,
+                        side,
+                        id: comment.id.clone(),
+                        content: comment.content.clone(),
+                        file_idx,
+                    });
+                }
+            }
+        }
+    }
+    stale.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    stale
+}
+
+/// A diff line as a fenced code block tagged with the file's extension,
+/// followed by an empty line to write the comment on
+fn fenced_quote(path: &std::path::Path, content: &str) -> String {
+    let lang = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    format!("```{lang}\n{}\n```\n", content.trim_end())
+}
+
+/// Replace 1-based lines `range` of `text` with `replacement`, keeping the
+/// file's line endings. None when the range runs past the end of the text.
+fn splice_lines(text: &str, range: LineRange, replacement: &str) -> Option<String> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let start = range.start.checked_sub(1)? as usize;
+    let end = range.end as usize;
+    if end > lines.len() || start >= end {
+        return None;
+    }
+    // The replaced line's ending, or the file's when it is the unterminated last line
+    let terminated = lines[start..]
+        .iter()
+        .chain(&lines[..start])
+        .find(|line| line.ends_with('\n'));
+    let newline = match terminated {
+        Some(line) if line.ends_with("\r\n") => "\r\n",
+        _ => "\n",
+    };
+    let replaced: Vec<&str> = replacement.lines().collect();
+    let mut spliced = lines[..start].concat();
+    spliced.push_str(&replaced.join(newline));
+    // A last line without a newline stays without one
+    if lines[end - 1].ends_with('\n') && !replaced.is_empty() {
+        spliced.push_str(newline);
+    }
+    spliced.push_str(&lines[end..].concat());
+    Some(spliced)
+}
+
+/// Whether lines `range` of `text` still read as the reviewed lines,
+/// whichever line endings the file uses
+fn lines_unchanged(text: &str, range: LineRange, reviewed: &[&str]) -> bool {
+    let current = text
+        .split('\n')
+        .skip(range.start.saturating_sub(1) as usize)
+        .take((range.end + 1 - range.start) as usize)
+        .map(|line| line.trim_end_matches('\r'));
+    current.eq(reviewed.iter().map(|line| line.trim_end_matches('\r')))
+}
+
+/// Lines `start..=end` (1-based) of a file as context lines
+fn context_lines(lines: &[&str], start: u32, end: u32) -> Vec<DiffLine> {
+    if start == 0 || start > end {
+        return Vec::new();
+    }
+    (start..=end)
+        .filter_map(|line_num| {
+            let content = lines.get(line_num as usize - 1)?;
+            Some(DiffLine {
+                origin: LineOrigin::Context,
+                content: content.to_string(),
+                old_lineno: Some(line_num),
+                new_lineno: Some(line_num),
+                highlighted_spans: None,
+            })
+        })
+        .collect()
+}
+
+/// Replace the line ranges of a `@@ -a,b +c,d @@ section` header, keeping the section text
+fn rewrite_hunk_header(
+    header: &str,
+    old_start: u32,
+    old_count: u32,
+    new_start: u32,
+    new_count: u32,
+) -> String {
+    let section = header
+        .strip_prefix("@@")
+        .and_then(|rest| rest.split_once("@@"))
+        .map(|(_, section)| section)
+        .unwrap_or("");
+    format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@{section}")
+}
+
+#[cfg(test)]
+mod hunk_header_tests {
+    use super::*;
+
+    #[test]
+    fn should_rewrite_ranges_and_keep_section() {
+        // given a header with a function section
+        let header = "@@ -10,4 +12,5 @@ fn main() {";
+
+        // when the hunk grows by three lines above and two below
+        let rewritten = rewrite_hunk_header(header, 7, 9, 9, 10);
+
+        // then the ranges change and the section is preserved
+        assert_eq!(rewritten, "@@ -7,9 +9,10 @@ fn main() {");
+    }
+
+    #[test]
+    fn should_rewrite_header_without_section() {
+        // given a bare header
+        let header = "@@ -1,3 +1,3 @@";
+
+        // when rewritten
+        let rewritten = rewrite_hunk_header(header, 1, 8, 1, 8);
+
+        // then no trailing text is added
+        assert_eq!(rewritten, "@@ -1,8 +1,8 @@");
+    }
+}
+
+#[cfg(test)]
+mod commit_range_tests {
+    use super::*;
+    use crate::persistence::storage::tests::with_test_reviews_dir;
+    use git2::{Repository, Signature};
+    use std::path::Path;
+
+    /// Write `content` to `path` and commit it, returning the commit id
+    fn commit(repo: &Repository, path: &str, content: &str) -> String {
+        let root = repo.workdir().unwrap();
+        std::fs::write(root.join(path), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "change", &tree, &parents)
+            .unwrap()
+            .to_string()
+    }
+
+    fn numbered_lines(count: u32, replace: &[(u32, &str)]) -> String {
+        (1..=count)
+            .map(|n| match replace.iter().find(|(line, _)| *line == n) {
+                Some((_, text)) => format!("{text}\n"),
+                None => format!("line {n}\n"),
+            })
+            .collect()
+    }
+
+    /// An app reviewing the last commit of a repo whose working tree has
+    /// moved on from it, so reading the working tree gives the wrong text
+    fn app_reviewing_last_commit(root: &Path, before: &str, after: &str, worktree: &str) -> App {
+        let repo = Repository::init(root).unwrap();
+        commit(&repo, "a.rs", before);
+        let last = commit(&repo, "a.rs", after);
+        std::fs::write(root.join("a.rs"), worktree).unwrap();
+
+        let mut app = App::new(
+            Some(root),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        app.load_commit_range(vec![last]).unwrap();
+        app
+    }
+
+    fn file_idx(app: &App, path: &str) -> usize {
+        app.diff_files
+            .iter()
+            .position(|f| f.display_path() == Path::new(path))
+            .unwrap()
+    }
+
+    /// Put the cursor on a new-side diff line of a file
+    fn cursor_to_new_line(app: &mut App, file_idx: usize, line: u32) {
+        app.diff_state.cursor_line = app
+            .line_annotations
+            .iter()
+            .position(|a| {
+                matches!(a, AnnotatedLine::DiffLine { file_idx: f, new_lineno: Some(n), .. }
+                    if *f == file_idx && *n == line)
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn should_show_function_bodies_from_the_reviewed_commit() {
+        // given a function changed in the commit and changed again in the working tree
+        let _reviews = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let before = "fn run() {\n    let a = 1;\n    let b = 2;\n}\n";
+        let after = "fn run() {\n    let a = 10;\n    let b = 2;\n}\n";
+        let worktree = "fn run() {\n    let a = 10;\n    let b = 20;\n}\n";
+        let mut app = app_reviewing_last_commit(temp.path(), before, after, worktree);
+        let idx = file_idx(&app, "a.rs");
+        cursor_to_new_line(&mut app, idx, 2);
+
+        // when
+        app.open_function_diff().unwrap();
+
+        // then both sides come from the commit and its parent
+        let view = app.function_diff.as_ref().unwrap();
+        assert_eq!(view.name, "run");
+        assert_eq!(view.old_lines, before.lines().collect::<Vec<_>>());
+        assert_eq!(view.new_lines, after.lines().collect::<Vec<_>>());
+    }
+
+    /// Put a suggested change on new-side line `line` of `path`, returning its id
+    fn suggest(app: &mut App, path: &str, line: u32, content: &str) -> String {
+        let comment = Comment::new(
+            content.to_string(),
+            CommentType::CodeSuggestion,
+            Some(LineSide::New),
+        );
+        let id = comment.id.clone();
+        app.session
+            .get_file_mut(&PathBuf::from(path))
+            .unwrap()
+            .add_line_comment(line, comment);
+        id
+    }
+
+    #[test]
+    fn should_refuse_to_apply_suggestion_to_a_commit() {
+        // given a suggestion on a reviewed commit whose line is also in the working tree
+        let _reviews = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let after = "fn run() {}\n";
+        let mut app = app_reviewing_last_commit(temp.path(), "fn old() {}\n", after, after);
+        let id = suggest(&mut app, "a.rs", 1, "fn new() {}");
+
+        // when
+        let result = app.apply_suggestion(&id);
+
+        // then the file on disk is left alone
+        assert!(result.is_err());
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("a.rs")).unwrap(),
+            after
+        );
+    }
+
+    #[test]
+    fn should_apply_suggestion_to_crlf_file() {
+        // given an uncommitted change to a CRLF file
+        let _reviews = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        commit(&repo, "a.txt", "one\r\ntwo\r\nthree\r\n");
+        std::fs::write(temp.path().join("a.txt"), "one\r\n2\r\nthree\r\n").unwrap();
+        let mut app = App::new(
+            Some(temp.path()),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        let id = suggest(&mut app, "a.txt", 2, "TWO");
+
+        // when
+        app.apply_suggestion(&id).unwrap();
+
+        // then the line is replaced and the line endings kept
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("a.txt")).unwrap(),
+            "one\r\nTWO\r\nthree\r\n"
+        );
+    }
+
+    #[test]
+    fn should_expand_hunk_from_the_reviewed_commit() {
+        // given line 15 changed in the commit and line 10 since, in the working tree
+        let _reviews = with_test_reviews_dir();
+        let temp = tempfile::tempdir().unwrap();
+        let mut app = app_reviewing_last_commit(
+            temp.path(),
+            &numbered_lines(30, &[]),
+            &numbered_lines(30, &[(15, "changed")]),
+            &numbered_lines(30, &[(10, "uncommitted"), (15, "changed")]),
+        );
+        let idx = file_idx(&app, "a.rs");
+
+        // when
+        app.expand_hunk(idx, 0).unwrap();
+
+        // then line 10 is the committed one
+        let line_10 = app.diff_files[idx].hunks[0]
+            .lines
+            .iter()
+            .find(|line| line.new_lineno == Some(10))
+            .unwrap();
+        assert_eq!(line_10.content, "line 10");
+    }
+}
+
+#[cfg(test)]
+mod context_tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_context_setting_when_diff_cannot_be_reloaded() {
+        // given a diff read from stdin, which cannot be regenerated
+        let temp = tempfile::tempdir().unwrap();
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n";
+        let mut app = App::from_stdin_diff(
+            diff.to_string(),
+            Some(temp.path()),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+
+        // when
+        let result = app.set_context_lines(10);
+
+        // then the error is reported and the old setting stays
+        assert!(result.is_err());
+        assert_eq!(
+            app.diff_settings.context_lines,
+            DiffSettings::DEFAULT_CONTEXT_LINES
+        );
+    }
+}
+
+#[cfg(test)]
+mod undo_delete_tests {
+    use super::*;
+
+    fn app_for_diff() -> (tempfile::TempDir, App) {
+        let temp = tempfile::tempdir().unwrap();
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n";
+        let app = App::from_stdin_diff(
+            diff.to_string(),
+            Some(temp.path()),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        (temp, app)
+    }
+
+    fn note(text: &str) -> Comment {
+        Comment::new(text.to_string(), CommentType::Note, None)
+    }
+
+    fn review(app: &mut App) -> &mut FileReview {
+        app.session.get_file_mut(&PathBuf::from("a.rs")).unwrap()
+    }
+
+    /// Ids of every comment in the session, in a fixed order
+    fn comment_ids(app: &App) -> Vec<String> {
+        let review = &app.session.files[&PathBuf::from("a.rs")];
+        review
+            .file_comments
+            .iter()
+            .chain(review.line_comments.values().flatten())
+            .chain(app.session.general_comments().into_iter().map(|(_, c)| c))
+            .map(|c| c.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn should_restore_each_kind_of_comment_in_place() {
+        // given two comments in each slot: a line, the file, a directory and the session
+        let (_temp, mut app) = app_for_diff();
+        for i in 0..2 {
+            review(&mut app).add_line_comment(1, note(&format!("line {i}")));
+            review(&mut app).add_file_comment(note(&format!("file {i}")));
+            app.session
+                .add_general_comment(Some(PathBuf::from("src")), note(&format!("dir {i}")));
+            app.session
+                .add_general_comment(None, note(&format!("session {i}")));
+        }
+        let before = comment_ids(&app);
+
+        // when the first of each is deleted, then all are undone
+        for id in [0, 2, 4, 6].map(|i| before[i].clone()) {
+            assert!(app.delete_comment_by_id(&id));
+        }
+        assert_eq!(comment_ids(&app).len(), 4);
+        while app.undo_delete_comment() {}
+
+        // then every comment is back at its position
+        assert_eq!(comment_ids(&app), before);
+        assert!(app.deleted_comments.is_empty());
+    }
+
+    #[test]
+    fn should_remember_only_the_latest_deletions() {
+        // given more session comments than undo remembers
+        let (_temp, mut app) = app_for_diff();
+        for i in 0..UNDO_DELETE_LIMIT + 1 {
+            app.session.add_general_comment(None, note(&i.to_string()));
+        }
+
+        // when all are deleted and undone
+        for id in comment_ids(&app) {
+            app.delete_comment_by_id(&id);
+        }
+        let mut restored = 0;
+        while app.undo_delete_comment() {
+            restored += 1;
+        }
+
+        // then the first deletion is gone for good
+        assert_eq!(restored, UNDO_DELETE_LIMIT);
+        assert_eq!(app.session.session_comments[0].content, "1");
+    }
+
+    #[test]
+    fn should_not_restore_into_a_file_that_left_the_review() {
+        // given a deleted file comment whose file was then dropped from the review
+        let (_temp, mut app) = app_for_diff();
+        let comment = note("gone");
+        let id = comment.id.clone();
+        review(&mut app).add_file_comment(comment);
+        app.delete_comment_by_id(&id);
+        app.session.files.remove(&PathBuf::from("a.rs"));
+
+        // when
+        let undone = app.undo_delete_comment();
+
+        // then the undo is used up and says why nothing came back
+        assert!(undone);
+        assert!(app.deleted_comments.is_empty());
+        let message = app.message.as_ref().unwrap();
+        assert_eq!(message.content, "a.rs is no longer in the review");
+    }
+}
+
+#[cfg(test)]
+mod draft_tests {
+    use super::*;
+
+    fn app_for_diff() -> (tempfile::TempDir, App) {
+        let temp = tempfile::tempdir().unwrap();
+        let diff =
+            "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n-a\n+b\n c\n";
+        let app = App::from_stdin_diff(
+            diff.to_string(),
+            Some(temp.path()),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        (temp, app)
+    }
+
+    /// Open a comment on new-side `line` and type `text` into it
+    fn write_comment(app: &mut App, line: u32, text: &str) {
+        app.enter_comment_mode(false, Some((line, LineSide::New)));
+        app.comment_buffer = text.to_string();
+        app.comment_cursor = text.len();
+    }
+
+    #[test]
+    fn should_restore_cancelled_draft_on_the_same_line() {
+        // given a cancelled comment on line 1
+        let (_temp, mut app) = app_for_diff();
+        write_comment(&mut app, 1, "half a thought");
+        app.cancel_comment();
+
+        // when the comment is reopened there and the draft restored
+        app.enter_comment_mode(false, Some((1, LineSide::New)));
+        app.restore_comment_draft();
+
+        // then
+        assert_eq!(app.comment_buffer, "half a thought");
+        assert_eq!(app.comment_cursor, app.comment_buffer.len());
+        assert!(app.comment_drafts.is_empty());
+    }
+
+    #[test]
+    fn should_start_empty_on_another_line() {
+        // given a draft on line 1
+        let (_temp, mut app) = app_for_diff();
+        write_comment(&mut app, 1, "half a thought");
+        app.cancel_comment();
+
+        // when a comment opens on line 2, or on the old side of line 1
+        app.enter_comment_mode(false, Some((2, LineSide::New)));
+        app.restore_comment_draft();
+        let on_line_2 = app.comment_buffer.clone();
+        app.exit_comment_mode();
+        app.enter_comment_mode(false, Some((1, LineSide::Old)));
+        app.restore_comment_draft();
+
+        // then neither gets the draft, which stays for line 1
+        assert_eq!(on_line_2, "");
+        assert_eq!(app.comment_buffer, "");
+        assert_eq!(app.comment_drafts.len(), 1);
+    }
+
+    #[test]
+    fn should_drop_draft_once_saved() {
+        // given a draft on line 1
+        let (_temp, mut app) = app_for_diff();
+        write_comment(&mut app, 1, "half a thought");
+        app.cancel_comment();
+
+        // when a comment is saved there without restoring it
+        write_comment(&mut app, 1, "a whole thought");
+        app.save_comment();
+
+        // then the draft is gone
+        assert!(app.comment_drafts.is_empty());
+        assert_eq!(app.session.files[&PathBuf::from("a.rs")].comment_count(), 1);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod comment_list_tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,3 +1,3 @@\n one\n-two\n+TWO\n three\ndiff --git a/b.rs b/b.rs\n--- a/b.rs\n+++ b/b.rs\n@@ -1 +1 @@\n-x\n+y\n";
+
+    /// An app over a.rs and b.rs with comments added out of order: on
+    /// b.rs:1, a.rs:3, a.rs:1, the file a.rs, the review and `src/`
+    pub(crate) fn app_with_comments() -> (tempfile::TempDir, App) {
+        let temp = tempfile::tempdir().unwrap();
+        let mut app = App::from_stdin_diff(
+            DIFF.to_string(),
+            Some(temp.path()),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        let note = |text: &str| Comment::new(text.to_string(), CommentType::Note, None);
+        let (a, b) = (PathBuf::from("a.rs"), PathBuf::from("b.rs"));
+        let session = &mut app.session;
+        session
+            .get_file_mut(&b)
+            .unwrap()
+            .add_line_comment(1, note("b1"));
+        let a_review = session.get_file_mut(&a).unwrap();
+        a_review.add_line_comment(3, note("a3"));
+        a_review.add_line_comment(1, note("a1"));
+        a_review.add_file_comment(note("a"));
+        session.add_general_comment(None, note("review"));
+        session.add_general_comment(Some(PathBuf::from("src")), note("src"));
+        app.rebuild_annotations();
+        (temp, app)
+    }
+
+    /// Select the listed comment whose text is `text`
+    fn select(app: &mut App, text: &str) -> String {
+        let view = app.comment_list.as_mut().unwrap();
+        view.cursor = view
+            .entries
+            .iter()
+            .position(|entry| entry.first_line == text)
+            .unwrap();
+        view.entries[view.cursor].id.clone()
+    }
+
+    #[test]
+    fn should_list_general_then_file_then_line_comments() {
+        // given
+        let (_temp, mut app) = app_with_comments();
+
+        // when
+        app.open_comment_list();
+
+        // then
+        assert_eq!(app.input_mode, InputMode::CommentList);
+        let locations: Vec<&str> = app
+            .comment_list
+            .as_ref()
+            .unwrap()
+            .entries
+            .iter()
+            .map(|entry| entry.location.as_str())
+            .collect();
+        assert_eq!(
+            locations,
+            ["src/", "(review)", "a.rs", "a.rs:1", "a.rs:3", "b.rs:1"]
+        );
+    }
+
+    #[test]
+    fn should_jump_to_line_comment() {
+        // given the list open on the comment on a.rs:3
+        let (_temp, mut app) = app_with_comments();
+        app.open_comment_list();
+        let id = select(&mut app, "a3");
+
+        // when
+        let jumped = app.jump_to_comment_entry();
+
+        // then the list is closed and the diff cursor is on the comment
+        assert!(jumped);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.comment_list.is_none());
+        assert_eq!(app.comment_id_at_cursor(), Some(id));
+        assert_eq!(app.current_file_path(), Some(&PathBuf::from("a.rs")));
+    }
+
+    #[test]
+    fn should_resolve_from_the_list_and_keep_it_open() {
+        // given the list open on the comment on b.rs:1
+        let (_temp, mut app) = app_with_comments();
+        app.open_comment_list();
+        let id = select(&mut app, "b1");
+
+        // when
+        app.toggle_resolved_comment_entry();
+
+        // then the comment and its row are resolved
+        assert!(app.session.find_comment(&id).unwrap().resolved);
+        let view = app.comment_list.as_ref().unwrap();
+        assert!(view.entries[view.cursor].resolved);
+        assert_eq!(app.input_mode, InputMode::CommentList);
+
+        // and toggling again reopens it
+        app.toggle_resolved_comment_entry();
+        assert!(!app.session.find_comment(&id).unwrap().resolved);
+    }
+
+    #[test]
+    fn should_not_open_an_empty_list() {
+        // given a review without comments
+        let temp = tempfile::tempdir().unwrap();
+        let mut app = App::from_stdin_diff(
+            DIFF.to_string(),
+            Some(temp.path()),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+
+        // when
+        app.open_comment_list();
+
+        // then
+        assert!(app.comment_list.is_none());
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+}
+
+#[cfg(test)]
+mod comments_pane_tests {
+    use super::comment_list_tests::app_with_comments;
+    use super::*;
+
+    fn pane_texts(app: &App) -> Vec<String> {
+        app.comments_pane_entries()
+            .into_iter()
+            .map(|entry| entry.first_line)
+            .collect()
+    }
+
+    #[test]
+    fn should_list_comments_in_comment_list_order() {
+        // given
+        let (_temp, app) = app_with_comments();
+
+        // then
+        assert_eq!(pane_texts(&app), ["src", "review", "a", "a1", "a3", "b1"]);
+    }
+
+    #[test]
+    fn should_keep_selection_within_the_comments() {
+        // given
+        let (_temp, mut app) = app_with_comments();
+
+        // when moving past either end
+        app.comments_pane_up(3);
+        let at_top = app.comments_pane.cursor;
+        app.comments_pane_down(4);
+        let moved = app.comments_pane.cursor;
+        app.comments_pane_down(10);
+
+        // then the selection stops at the first and last comment
+        assert_eq!(at_top, 0);
+        assert_eq!(moved, 4);
+        assert_eq!(app.comments_pane.cursor, 5);
+    }
+
+    #[test]
+    fn should_clamp_selection_when_the_last_comment_is_deleted() {
+        // given the pane focused on its last comment
+        let (_temp, mut app) = app_with_comments();
+        app.focused_panel = FocusedPanel::Comments;
+        app.comments_pane_down(usize::MAX);
+
+        // when it is deleted
+        app.delete_pane_comment();
+
+        // then the new last comment is selected
+        assert_eq!(app.comments_pane.cursor, 4);
+        assert_eq!(pane_texts(&app)[app.comments_pane.cursor], "a3");
+        assert_eq!(app.focused_panel, FocusedPanel::Comments);
+    }
+
+    #[test]
+    fn should_leave_the_pane_once_every_comment_is_deleted() {
+        // given the pane focused
+        let (_temp, mut app) = app_with_comments();
+        app.show_file_list = true;
+        app.focused_panel = FocusedPanel::Comments;
+
+        // when every comment is deleted from it
+        while app.has_comments_pane() {
+            app.delete_pane_comment();
+        }
+
+        // then the diff takes the focus back
+        assert_eq!(app.focused_panel, FocusedPanel::Diff);
+        assert_eq!(app.comments_pane.cursor, 0);
+    }
+}
+
+#[cfg(test)]
+mod commit_filter_tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn commit(id: &str, summary: &str, author: &str) -> CommitInfo {
+        CommitInfo {
+            id: id.to_string(),
+            short_id: id[..7].to_string(),
+            summary: summary.to_string(),
+            author: author.to_string(),
+            time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn should_match_summary_author_or_sha() {
+        // given
+        let commit = commit("3f9c2a1b7d", "Fix retry backoff", "Dana Reyes");
+
+        // then each field matches, ignoring case
+        assert!(commit_matches_filter(&commit, "RETRY"));
+        assert!(commit_matches_filter(&commit, "dana"));
+        assert!(commit_matches_filter(&commit, "3f9c2"));
+        assert!(commit_matches_filter(&commit, ""));
+    }
+
+    #[test]
+    fn should_require_every_word() {
+        // given
+        let commit = commit("3f9c2a1b7d", "Fix retry backoff", "Dana Reyes");
+
+        // then words may come from different fields, but all must match
+        assert!(commit_matches_filter(&commit, "backoff reyes"));
+        assert!(!commit_matches_filter(&commit, "backoff jitter"));
+    }
+
+    #[test]
+    fn should_parse_author_and_since_terms() {
+        // when
+        let query = parse_commit_query("retry author:dana since:2024-01-01").unwrap();
+
+        // then
+        assert_eq!(query.author.as_deref(), Some("dana"));
+        assert_eq!(query.since, chrono::NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert!(commit_matches_filter(
+            &commit("3f9c2a1b7d", "Fix retry backoff", "Dana Reyes"),
+            "retry author:someone-else"
+        ));
+    }
+
+    #[test]
+    fn should_reject_invalid_since_date() {
+        assert!(parse_commit_query("since:last-week").is_err());
+        assert_eq!(parse_commit_query("retry").unwrap(), CommitQuery::default());
+    }
+}
+
+#[cfg(test)]
+mod suggestion_tests {
+    use super::*;
+
+    #[test]
+    fn should_replace_commented_lines() {
+        // given
+        let text = "fn main() {\n    let x = 1;\n    run(x);\n}\n";
+
+        // when lines 2-3 become one line
+        let spliced = splice_lines(text, LineRange::new(2, 3), "    run(1);");
+
+        // then
+        assert_eq!(spliced.as_deref(), Some("fn main() {\n    run(1);\n}\n"));
+    }
+
+    #[test]
+    fn should_keep_crlf_and_missing_final_newline() {
+        // given
+        let text = "a\r\nb";
+
+        // when
+        let spliced = splice_lines(text, LineRange::single(2), "c\nd");
+
+        // then
+        assert_eq!(spliced.as_deref(), Some("a\r\nc\r\nd"));
+    }
+
+    #[test]
+    fn should_reject_range_past_end() {
+        assert_eq!(splice_lines("a\n", LineRange::new(1, 2), "b"), None);
+    }
+
+    #[test]
+    fn should_compare_reviewed_lines_without_line_endings() {
+        // given a CRLF file whose diff lines may keep the carriage return
+        let text = "a\r\nb\r\nc\r\n";
+
+        // then
+        assert!(lines_unchanged(text, LineRange::new(2, 3), &["b", "c"]));
+        assert!(lines_unchanged(text, LineRange::single(2), &["b\r"]));
+        assert!(!lines_unchanged(text, LineRange::single(2), &["x"]));
+        assert!(!lines_unchanged(
+            text,
+            LineRange::new(3, 5),
+            &["c", "d", "e"]
+        ));
+    }
+}
+
+#[cfg(test)]
+mod comment_group_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_group_names_in_singular_and_plural() {
+        assert_eq!(CommentGroup::parse("issues"), Some(CommentGroup::Issues));
+        assert_eq!(CommentGroup::parse("Note"), Some(CommentGroup::Notes));
+        assert_eq!(CommentGroup::parse("praise"), Some(CommentGroup::Praise));
+        assert_eq!(
+            CommentGroup::parse("suggestion"),
+            Some(CommentGroup::Suggestions)
+        );
+        assert_eq!(CommentGroup::parse("todo"), None);
+    }
+}
+
+#[cfg(test)]
+mod stale_tests {
+    use super::*;
+    use crate::model::DiffHunk;
+
+    fn file_with_new_lines(path: &str, lines: &[u32]) -> DiffFile {
+        DiffFile {
+            old_path: None,
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: String::new(),
+                lines: lines
+                    .iter()
+                    .map(|&n| DiffLine {
+                        origin: LineOrigin::Addition,
+                        content: format!("line {n}"),
+                        old_lineno: None,
+                        new_lineno: Some(n),
+                        highlighted_spans: None,
+                    })
+                    .collect(),
+                old_start: 0,
+                old_count: 0,
+                new_start: lines.first().copied().unwrap_or(1),
+                new_count: lines.len() as u32,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    fn review_with_comments(path: &str, lines: &[u32]) -> (PathBuf, FileReview) {
+        let mut review = FileReview::new(PathBuf::from(path), FileStatus::Modified);
+        for &line in lines {
+            review.add_line_comment(
+                line,
+                Comment::new(format!("on {line}"), CommentType::Note, Some(LineSide::New)),
+            );
+        }
+        (PathBuf::from(path), review)
+    }
+
+    #[test]
+    fn should_flag_comments_on_lines_outside_the_diff() {
+        // given
+        let files = HashMap::from([review_with_comments("a.rs", &[2, 9])]);
+        let diff = vec![file_with_new_lines("a.rs", &[1, 2, 3])];
+
+        // when
+        let stale = find_stale_comments(&files, &diff);
+
+        // then
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].line, 9);
+        assert_eq!(stale[0].file_idx, Some(0));
+    }
+
+    #[test]
+    fn should_flag_every_comment_of_a_file_that_left_the_diff() {
+        // given
+        let files = HashMap::from([review_with_comments("gone.rs", &[1, 2])]);
+        let diff = vec![file_with_new_lines("a.rs", &[1, 2])];
+
+        // when
+        let stale = find_stale_comments(&files, &diff);
+
+        // then
+        assert_eq!(stale.iter().map(|c| c.line).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(stale.iter().all(|c| c.file_idx.is_none()));
+    }
+}
+
+#[cfg(test)]
+mod quote_tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn should_fence_line_with_file_extension() {
+        // when
+        let quote = fenced_quote(Path::new("src/net.rs"), "    retry(3);  ");
+
+        // then
+        assert_eq!(quote, "```rs\n    retry(3);\n```\n");
+    }
+
+    #[test]
+    fn should_fence_line_without_extension() {
+        assert_eq!(
+            fenced_quote(Path::new("Makefile"), "all: build"),
+            "```\nall: build\n```\n"
+        );
+    }
+}
+
+#[cfg(test)]
+mod selection_tests {
+    use super::*;
+    use crate::model::DiffHunk;
+
+    fn line(origin: LineOrigin, old: Option<u32>, new: Option<u32>, content: &str) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno: old,
+            new_lineno: new,
+            highlighted_spans: None,
+        }
+    }
+
+    #[test]
+    fn should_copy_one_side_of_the_selection_with_or_without_prefixes() {
+        // given line 2 changed from `b` to `B`
+        let file = DiffFile {
+            old_path: None,
+            new_path: Some(PathBuf::from("a.rs")),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,3 +1,3 @@".to_string(),
+                lines: vec![
+                    line(LineOrigin::Context, Some(1), Some(1), "a"),
+                    line(LineOrigin::Deletion, Some(2), None, "b"),
+                    line(LineOrigin::Addition, None, Some(2), "B"),
+                    line(LineOrigin::Context, Some(3), Some(3), "c"),
+                ],
+                old_start: 1,
+                old_count: 3,
+                new_start: 1,
+                new_count: 3,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        };
+
+        // when
+        let plain = selection_text(&file, LineRange::new(1, 2), LineSide::New, false);
+        let prefixed = selection_text(&file, LineRange::new(2, 3), LineSide::Old, true);
+
+        // then
+        assert_eq!(plain, "a\nB\n");
+        assert_eq!(prefixed, "-b\n c\n");
     }
 }
 
@@ -2241,6 +7188,10 @@ mod tree_tests {
             status: FileStatus::Modified,
             hunks: vec![],
             is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
         }
     }
 
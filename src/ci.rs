@@ -0,0 +1,150 @@
+//! Running in CI: the branch a pull or merge request targets, read from the
+//! provider's environment, so a pipeline can review against it and export
+//! without the UI.
+
+use crate::app::App;
+use crate::error::{Result, TuicrError};
+use crate::output::{generate_export_content, generate_handoff};
+use crate::todo::find_todos;
+
+/// Environment variables naming a pull/merge request's target branch, with
+/// the CI provider that sets them
+const TARGET_BRANCH_VARS: &[(&str, &str)] = &[
+    ("GITHUB_BASE_REF", "GitHub Actions"),
+    ("CI_MERGE_REQUEST_TARGET_BRANCH_NAME", "GitLab CI"),
+    ("BITBUCKET_PR_DESTINATION_BRANCH", "Bitbucket Pipelines"),
+    ("SYSTEM_PULLREQUEST_TARGETBRANCH", "Azure Pipelines"),
+    ("BUILDKITE_PULL_REQUEST_BASE_BRANCH", "Buildkite"),
+    ("CHANGE_TARGET", "Jenkins"),
+];
+
+/// The branch a CI run's pull/merge request goes into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CiTarget {
+    pub provider: &'static str,
+    pub base_branch: String,
+}
+
+/// The target of the pull/merge request this CI run builds, if any
+pub fn detect() -> Option<CiTarget> {
+    detect_with(|name| std::env::var(name).ok())
+}
+
+fn detect_with(var: impl Fn(&str) -> Option<String>) -> Option<CiTarget> {
+    TARGET_BRANCH_VARS.iter().find_map(|&(name, provider)| {
+        let value = var(name)?;
+        // Azure Pipelines gives the full ref
+        let branch = value.trim().trim_start_matches("refs/heads/");
+        (!branch.is_empty()).then(|| CiTarget {
+            provider,
+            base_branch: branch.to_string(),
+        })
+    })
+}
+
+/// What a headless run prints: the review export, or while there are no
+/// comments yet, the files to review and their state
+pub fn headless_output(app: &App) -> Result<String> {
+    let todos = find_todos(&app.diff_files);
+    match generate_export_content(&app.session, &app.diff_source, &todos, &app.config) {
+        Err(TuicrError::NoComments) => Ok(generate_handoff(
+            &app.session,
+            &app.diff_source,
+            &app.config.export_labels,
+            app.reviewer.as_deref(),
+        )),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::model::{Comment, CommentType};
+    use crate::theme::Theme;
+    use std::collections::HashMap;
+
+    fn detect_in(vars: &[(&str, &str)]) -> Option<CiTarget> {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        detect_with(|name| vars.get(name).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn should_read_the_target_branch_of_each_provider() {
+        assert_eq!(
+            detect_in(&[("GITHUB_BASE_REF", "main")]),
+            Some(CiTarget {
+                provider: "GitHub Actions",
+                base_branch: "main".to_string(),
+            })
+        );
+        assert_eq!(
+            detect_in(&[("SYSTEM_PULLREQUEST_TARGETBRANCH", "refs/heads/release/2.0")])
+                .map(|target| target.base_branch),
+            Some("release/2.0".to_string())
+        );
+    }
+
+    #[test]
+    fn should_ignore_builds_that_are_not_for_a_request() {
+        // given GitHub Actions on a push and Buildkite outside a pull request
+        let vars = [
+            ("GITHUB_BASE_REF", ""),
+            ("BUILDKITE_PULL_REQUEST_BASE_BRANCH", ""),
+        ];
+
+        // then
+        assert_eq!(detect_in(&vars), None);
+        assert_eq!(detect_in(&[("CI", "true")]), None);
+    }
+
+    fn app_for_diff() -> (tempfile::TempDir, App) {
+        let temp = tempfile::tempdir().unwrap();
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n";
+        let app = App::from_stdin_diff(
+            diff.to_string(),
+            Some(temp.path()),
+            Theme::default(),
+            Config::default(),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        (temp, app)
+    }
+
+    #[test]
+    fn should_print_handoff_until_there_are_comments() {
+        // given a review without comments
+        let (_temp, app) = app_for_diff();
+
+        // when
+        let output = headless_output(&app).unwrap();
+
+        // then the files to review are printed
+        let handoff = generate_handoff(
+            &app.session,
+            &app.diff_source,
+            &app.config.export_labels,
+            app.reviewer.as_deref(),
+        );
+        assert_eq!(output, handoff);
+    }
+
+    #[test]
+    fn should_print_export_once_commented() {
+        // given a review with a comment
+        let (_temp, mut app) = app_for_diff();
+        app.session.add_general_comment(
+            None,
+            Comment::new("ship it".to_string(), CommentType::Note, None),
+        );
+
+        // when
+        let output = headless_output(&app).unwrap();
+
+        // then the review is exported
+        assert!(output.contains("ship it"));
+    }
+}
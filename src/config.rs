@@ -0,0 +1,399 @@
+//! User configuration for tuicr.
+//!
+//! Read from `config.json` in the platform config directory
+//! (e.g. `~/.config/tuicr/config.json` on Linux). Every field is optional;
+//! missing fields fall back to their defaults.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use serde::Deserialize;
+
+use crate::error::Result;
+use crate::model::{CommentType, Severity};
+
+/// What has to be true for a review to count as complete
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionCriteria {
+    /// Every changed file is marked reviewed, vendored files included
+    AllFiles,
+    /// Every non-vendored file is marked reviewed
+    #[default]
+    NonVendoredFiles,
+    /// No blocking (issue) comments remain open
+    BlockingResolved,
+}
+
+/// How the export treats comments marked resolved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolvedExport {
+    /// List them under a "Resolved" heading after the open comments
+    #[default]
+    Section,
+    /// Leave them out of the export
+    Omit,
+}
+
+/// Order of the file and line comments in the export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportOrder {
+    /// By path, then line
+    #[default]
+    File,
+    /// Most severe first, by path within a severity; comments without one last
+    Severity,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Glob patterns for vendored paths, in addition to `linguist-vendored`
+    /// entries in `.gitattributes`
+    pub vendor_globs: Vec<String>,
+    /// When the review counts as complete (header progress, `:done`, export prompt)
+    pub review_complete: CompletionCriteria,
+    /// Detect copied files in git diffs (same as `--find-copies`)
+    pub find_copies: bool,
+    /// Show the commit log of checked-out submodules whose pointer moved
+    pub expand_submodules: bool,
+    /// Unchanged lines around each hunk (`-U<n>`); None keeps the default of 3
+    pub context_lines: Option<u32>,
+    /// Directories (or glob patterns) holding database migrations
+    pub migration_dirs: Vec<String>,
+    /// Where the tests of a source file live, when naming conventions are not enough
+    pub test_mappings: Vec<TestMapping>,
+    /// On quit, write the export and a session snapshot to timestamped files
+    /// under the data directory, whatever happens to the clipboard
+    pub backup_exports: bool,
+    /// GitLab instance and credentials for `--mr`
+    pub gitlab: GitLabConfig,
+    /// Width of the file list panel
+    pub file_list_width: FileListWidth,
+    /// Show the file list even when the diff touches a single file
+    pub keep_file_list: bool,
+    /// Milliseconds between wake-ups while background work (update check,
+    /// `:impact`) runs; None keeps the default of 100. Idle, tuicr only
+    /// wakes up for input.
+    pub tick_interval_ms: Option<u64>,
+    /// Whether resolved comments are exported in their own section or left out
+    pub resolved_comments: ResolvedExport,
+    /// Page scrolling moves the view and leaves the cursor where it is
+    pub free_scroll: bool,
+    /// Labels the export uses for comment types, independent of the UI
+    pub export_labels: ExportLabels,
+    /// Write when each comment was made, and last edited, into the export
+    pub export_timestamps: bool,
+    /// Name recorded on the comments you write; None uses git `user.name`
+    pub reviewer_name: Option<String>,
+    /// Keep a compressed copy of the diff in the saved session, for
+    /// `--snapshot` and exact code excerpts in the export
+    pub snapshot_diff: bool,
+    /// Leave comments less severe than this out of the export; comments
+    /// without a severity are always exported
+    pub export_min_severity: Option<Severity>,
+    /// Whether exported comments follow the files or their severity
+    pub export_order: ExportOrder,
+    /// Make file paths and commit SHAs clickable (OSC 8); None turns them
+    /// on in terminals known to support it
+    pub hyperlinks: Option<bool>,
+    /// Milliseconds a chord prefix (`g`, `z`, `d`, `;`) waits for the second
+    /// key; None keeps the default of 1000
+    pub chord_timeout_ms: Option<u64>,
+    /// Custom two-key chords running a `:` command, like `"gc": "clip"`
+    pub chords: BTreeMap<String, String>,
+    /// Length guidance shown while writing a comment
+    pub comment_limits: CommentLimits,
+    /// New-contributor mode: explain hunk headers and review keys in place
+    pub guide: bool,
+}
+
+/// File list width as a fixed number of columns (`32`) or a share of the
+/// terminal width (`"25%"`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "WidthSpec")]
+pub enum FileListWidth {
+    Columns(u16),
+    Percent(u16),
+}
+
+impl Default for FileListWidth {
+    fn default() -> Self {
+        Self::Percent(20)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WidthSpec {
+    Columns(u16),
+    Text(String),
+}
+
+impl TryFrom<WidthSpec> for FileListWidth {
+    type Error = String;
+
+    fn try_from(spec: WidthSpec) -> std::result::Result<Self, Self::Error> {
+        let invalid = || "file_list_width must be columns like 32 or a percentage like \"25%\"";
+        let width = match spec {
+            WidthSpec::Columns(columns) => Self::Columns(columns),
+            WidthSpec::Text(text) => match text.trim().strip_suffix('%') {
+                Some(percent) => Self::Percent(percent.trim().parse().map_err(|_| invalid())?),
+                None => Self::Columns(text.trim().parse().map_err(|_| invalid())?),
+            },
+        };
+        match width {
+            Self::Columns(0) | Self::Percent(0) => Err(invalid().to_string()),
+            Self::Percent(percent) if percent >= 100 => Err(invalid().to_string()),
+            width => Ok(width),
+        }
+    }
+}
+
+/// Where `--mr` looks up merge requests. Without a `url`, the instance is
+/// taken from the `origin` remote's host; without a `token`, `GITLAB_TOKEN`
+/// is used, and public projects need neither.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct GitLabConfig {
+    /// Base URL of the instance, e.g. `https://gitlab.example.com`
+    pub url: Option<String>,
+    /// Personal access token with `read_api` scope
+    pub token: Option<String>,
+    /// Project path such as `group/project`, when it differs from the remote
+    pub project: Option<String>,
+}
+
+/// Label written in exports for each comment type, e.g.
+/// `{"issue": "BLOCKER", "suggestion": "NIT"}`. Types left out keep their
+/// own name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ExportLabels {
+    pub note: Option<String>,
+    pub suggestion: Option<String>,
+    pub code_suggestion: Option<String>,
+    pub issue: Option<String>,
+    pub praise: Option<String>,
+}
+
+impl ExportLabels {
+    pub fn label(&self, comment_type: CommentType) -> &str {
+        let custom = match comment_type {
+            CommentType::Note => &self.note,
+            CommentType::Suggestion => &self.suggestion,
+            CommentType::CodeSuggestion => &self.code_suggestion,
+            CommentType::Issue => &self.issue,
+            CommentType::Praise => &self.praise,
+        };
+        custom.as_deref().unwrap_or(comment_type.as_str())
+    }
+}
+
+/// How long a comment may get: `max_chars` is what the forge accepts in one
+/// comment (GitHub's 65536 by default), `soft_words` where a comment is
+/// probably better split up. `null` turns either off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct CommentLimits {
+    pub max_chars: Option<usize>,
+    pub soft_words: Option<usize>,
+}
+
+impl Default for CommentLimits {
+    fn default() -> Self {
+        Self {
+            max_chars: Some(65536),
+            soft_words: Some(300),
+        }
+    }
+}
+
+impl CommentLimits {
+    /// Whether `text` is longer than the forge takes
+    pub fn too_long(&self, text: &str) -> bool {
+        self.max_chars.is_some_and(|max| text.chars().count() > max)
+    }
+
+    /// Whether `text` is long enough to be worth splitting
+    pub fn wordy(&self, text: &str) -> bool {
+        self.soft_words
+            .is_some_and(|max| text.split_whitespace().count() > max)
+    }
+}
+
+/// Maps source files to their test files, e.g.
+/// `{"source": "lib/**/*.rb", "tests": ["spec/**/{stem}_spec.rb"]}`.
+/// `{stem}`, `{name}` and `{dir}` in a test pattern are replaced with the
+/// source file's stem, file name and directory.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TestMapping {
+    pub source: String,
+    pub tests: Vec<String>,
+}
+
+impl Config {
+    /// Location of the config file, if the platform has a config directory
+    pub fn path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "tuicr").map(|dirs| dirs.config_dir().join("config.json"))
+    }
+
+    /// Load the config file. A missing file yields the default config.
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(serde_json::from_str(content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_default_missing_fields() {
+        // when
+        let config = Config::parse("{}").unwrap();
+
+        // then
+        assert!(config.vendor_globs.is_empty());
+        assert_eq!(config.review_complete, CompletionCriteria::NonVendoredFiles);
+        assert!(!config.backup_exports);
+        assert!(!config.free_scroll);
+        assert!(!config.export_timestamps);
+    }
+
+    #[test]
+    fn should_parse_file_list_width() {
+        // when / then
+        let width = |json: &str| Config::parse(json).map(|c| c.file_list_width).ok();
+        assert_eq!(width("{}"), Some(FileListWidth::Percent(20)));
+        assert_eq!(
+            width(r#"{"file_list_width": 32}"#),
+            Some(FileListWidth::Columns(32))
+        );
+        assert_eq!(
+            width(r#"{"file_list_width": "25%"}"#),
+            Some(FileListWidth::Percent(25))
+        );
+        assert_eq!(
+            width(r#"{"file_list_width": "40"}"#),
+            Some(FileListWidth::Columns(40))
+        );
+        assert_eq!(width(r#"{"file_list_width": "150%"}"#), None);
+        assert_eq!(width(r#"{"file_list_width": "wide"}"#), None);
+    }
+
+    #[test]
+    fn should_parse_gitlab_settings() {
+        // when
+        let config = Config::parse(r#"{"gitlab": {"url": "https://gitlab.example.com"}}"#).unwrap();
+
+        // then
+        assert_eq!(
+            config.gitlab.url.as_deref(),
+            Some("https://gitlab.example.com")
+        );
+        assert_eq!(config.gitlab.token, None);
+    }
+
+    #[test]
+    fn should_parse_completion_criteria() {
+        // when
+        let config = Config::parse(r#"{"review_complete": "blocking_resolved"}"#).unwrap();
+
+        // then
+        assert_eq!(config.review_complete, CompletionCriteria::BlockingResolved);
+    }
+
+    #[test]
+    fn should_parse_export_severity_settings() {
+        // when
+        let config =
+            Config::parse(r#"{"export_min_severity": "major", "export_order": "severity"}"#)
+                .unwrap();
+
+        // then
+        assert_eq!(config.export_min_severity, Some(Severity::Major));
+        assert_eq!(config.export_order, ExportOrder::Severity);
+        assert_eq!(Config::parse("{}").unwrap().export_order, ExportOrder::File);
+    }
+
+    #[test]
+    fn should_parse_vendor_globs() {
+        // when
+        let config = Config::parse(r#"{"vendor_globs": ["vendor/**", "*.min.js"]}"#).unwrap();
+
+        // then
+        assert_eq!(config.vendor_globs, vec!["vendor/**", "*.min.js"]);
+    }
+
+    #[test]
+    fn should_parse_context_lines() {
+        // when
+        let config = Config::parse(r#"{"context_lines": 8}"#).unwrap();
+
+        // then
+        assert_eq!(config.context_lines, Some(8));
+        assert_eq!(Config::parse("{}").unwrap().context_lines, None);
+    }
+
+    #[test]
+    fn should_parse_migration_dirs() {
+        // when
+        let config = Config::parse(r#"{"migration_dirs": ["db/migrations"]}"#).unwrap();
+
+        // then
+        assert_eq!(config.migration_dirs, vec!["db/migrations"]);
+    }
+
+    #[test]
+    fn should_reject_invalid_json() {
+        assert!(Config::parse("{ not json").is_err());
+    }
+
+    #[test]
+    fn should_parse_test_mappings() {
+        // when
+        let config = Config::parse(
+            r#"{"test_mappings": [{"source": "lib/**/*.rb", "tests": ["spec/**/{stem}_spec.rb"]}]}"#,
+        )
+        .unwrap();
+
+        // then
+        assert_eq!(
+            config.test_mappings,
+            vec![TestMapping {
+                source: "lib/**/*.rb".to_string(),
+                tests: vec!["spec/**/{stem}_spec.rb".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn should_parse_comment_limits_and_keep_defaults() {
+        // when
+        let config = Config::parse(r#"{"comment_limits": {"max_chars": 10}}"#).unwrap();
+
+        // then
+        assert_eq!(config.comment_limits.max_chars, Some(10));
+        assert_eq!(config.comment_limits.soft_words, Some(300));
+        assert!(config.comment_limits.too_long("eleven char"));
+        assert!(!config.comment_limits.too_long("ten chars!"));
+        let unlimited = Config::parse(r#"{"comment_limits": {"max_chars": null}}"#).unwrap();
+        assert!(!unlimited.comment_limits.too_long(&"x".repeat(100_000)));
+    }
+}
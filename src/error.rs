@@ -31,6 +31,12 @@ pub enum TuicrError {
 
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
+
+    #[error("GitLab error: {0}")]
+    GitLab(String),
+
+    #[error("Unreadable findings report: {0}")]
+    Findings(String),
 }
 
 pub type Result<T> = std::result::Result<T, TuicrError>;
@@ -0,0 +1,251 @@
+//! Static analysis findings imported with `--import-findings`.
+//!
+//! Three report shapes are recognized: ESLint's `--format json` array, the
+//! newline-delimited JSON of `cargo clippy --message-format=json`, and the
+//! `path:line:col: warning: message [check]` lines clang-tidy prints. Paths
+//! are made relative to the repository root when they are absolute.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::error::{Result, TuicrError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub tool: &'static str,
+    pub path: PathBuf,
+    pub line: u32,
+    /// Last line of a multi-line finding
+    pub end_line: u32,
+    /// Error-level findings become issues, everything else notes
+    pub is_error: bool,
+    pub message: String,
+    /// Rule or check name, e.g. `clippy::needless_return` or `no-unused-vars`
+    pub rule: Option<String>,
+}
+
+impl Finding {
+    /// The text of the comment the finding becomes
+    pub fn comment_text(&self) -> String {
+        match &self.rule {
+            Some(rule) => format!("{} ({rule})", self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// Parse a findings report, detecting its format. `root` is the repository
+/// root that absolute paths are made relative to.
+pub fn parse_findings(text: &str, root: &Path) -> Result<Vec<Finding>> {
+    let parsed = if let Ok(Value::Array(files)) = serde_json::from_str::<Value>(text) {
+        parse_eslint(&files)
+    } else if text.trim_start().starts_with('{') {
+        parse_cargo(text)
+    } else {
+        parse_clang_tidy(text)
+    };
+    let Some(mut findings) = parsed else {
+        return Err(TuicrError::Findings(
+            "expected ESLint JSON, cargo clippy JSON or clang-tidy output".to_string(),
+        ));
+    };
+    for finding in &mut findings {
+        if let Ok(relative) = finding.path.strip_prefix(root) {
+            finding.path = relative.to_path_buf();
+        }
+    }
+    Ok(findings)
+}
+
+/// `[{"filePath": ..., "messages": [{"ruleId", "severity", "message", "line", "endLine"}]}]`
+fn parse_eslint(files: &[Value]) -> Option<Vec<Finding>> {
+    let mut findings = Vec::new();
+    for file in files {
+        let path = file.get("filePath")?.as_str()?;
+        for message in file.get("messages")?.as_array()? {
+            let Some(line) = message.get("line").and_then(Value::as_u64) else {
+                continue;
+            };
+            let line = line as u32;
+            findings.push(Finding {
+                tool: "eslint",
+                path: PathBuf::from(path),
+                line,
+                end_line: message
+                    .get("endLine")
+                    .and_then(Value::as_u64)
+                    .map_or(line, |end| end as u32),
+                is_error: message.get("severity").and_then(Value::as_u64) == Some(2),
+                message: message.get("message")?.as_str()?.to_string(),
+                rule: message
+                    .get("ruleId")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            });
+        }
+    }
+    Some(findings)
+}
+
+/// One `{"reason": "compiler-message", "message": {...}}` object per line,
+/// anchored at the primary span
+fn parse_cargo(text: &str) -> Option<Vec<Finding>> {
+    let mut findings = Vec::new();
+    let mut recognized = false;
+    for line in text.lines().filter(|l| l.trim_start().starts_with('{')) {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if value.get("reason").and_then(Value::as_str) != Some("compiler-message") {
+            recognized |= value.get("reason").is_some();
+            continue;
+        }
+        recognized = true;
+        let Some(message) = value.get("message") else {
+            continue;
+        };
+        let Some(span) = message
+            .get("spans")
+            .and_then(Value::as_array)
+            .and_then(|spans| {
+                spans
+                    .iter()
+                    .find(|s| s.get("is_primary").and_then(Value::as_bool) == Some(true))
+            })
+        else {
+            continue;
+        };
+        let (Some(path), Some(start), Some(end)) = (
+            span.get("file_name").and_then(Value::as_str),
+            span.get("line_start").and_then(Value::as_u64),
+            span.get("line_end").and_then(Value::as_u64),
+        ) else {
+            continue;
+        };
+        let level = message.get("level").and_then(Value::as_str);
+        findings.push(Finding {
+            tool: "clippy",
+            path: PathBuf::from(path),
+            line: start as u32,
+            end_line: end as u32,
+            is_error: level == Some("error"),
+            message: message
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            rule: message
+                .get("code")
+                .and_then(|code| code.get("code"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        });
+    }
+    recognized.then_some(findings)
+}
+
+/// `src/a.cpp:12:5: warning: use nullptr [modernize-use-nullptr]`; notes
+/// attached to a diagnostic are skipped
+fn parse_clang_tidy(text: &str) -> Option<Vec<Finding>> {
+    let mut findings = Vec::new();
+    for line in text.lines() {
+        let mut parts = line.splitn(5, ':');
+        let (Some(path), Some(lineno), Some(_column), Some(level), Some(rest)) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            continue;
+        };
+        let Ok(lineno) = lineno.parse::<u32>() else {
+            continue;
+        };
+        let level = level.trim();
+        if level != "warning" && level != "error" {
+            continue;
+        }
+        let rest = rest.trim();
+        let (message, rule) = match rest.strip_suffix(']').and_then(|r| r.rsplit_once(" [")) {
+            Some((message, rule)) => (message, Some(rule.to_string())),
+            None => (rest, None),
+        };
+        findings.push(Finding {
+            tool: "clang-tidy",
+            path: PathBuf::from(path),
+            line: lineno,
+            end_line: lineno,
+            is_error: level == "error",
+            message: message.to_string(),
+            rule,
+        });
+    }
+    (!findings.is_empty()).then_some(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_eslint_json_relative_to_root() {
+        // given an ESLint report with absolute paths
+        let text = r#"[{"filePath": "/repo/src/app.js", "messages": [
+            {"ruleId": "no-unused-vars", "severity": 2, "message": "'x' is unused", "line": 3, "column": 7},
+            {"ruleId": null, "severity": 1, "message": "Parsing warning", "line": 9, "endLine": 11}
+        ]}]"#;
+
+        // when parsed
+        let findings = parse_findings(text, Path::new("/repo")).unwrap();
+
+        // then paths are relative and severities map to errors
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].path, PathBuf::from("src/app.js"));
+        assert!(findings[0].is_error);
+        assert_eq!(findings[0].comment_text(), "'x' is unused (no-unused-vars)");
+        assert_eq!((findings[1].line, findings[1].end_line), (9, 11));
+        assert_eq!(findings[1].rule, None);
+    }
+
+    #[test]
+    fn should_parse_clippy_messages_at_their_primary_span() {
+        // given cargo JSON output with an artifact line and a clippy warning
+        let text = concat!(
+            r#"{"reason": "compiler-artifact", "target": {}}"#,
+            "\n",
+            r#"{"reason": "compiler-message", "message": {"message": "unneeded `return` statement", "level": "warning", "code": {"code": "clippy::needless_return"}, "spans": [{"file_name": "src/lib.rs", "line_start": 4, "line_end": 4, "is_primary": false}, {"file_name": "src/lib.rs", "line_start": 7, "line_end": 8, "is_primary": true}]}}"#,
+        );
+
+        // when parsed
+        let findings = parse_findings(text, Path::new("/repo")).unwrap();
+
+        // then the primary span locates the finding
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].tool, "clippy");
+        assert_eq!((findings[0].line, findings[0].end_line), (7, 8));
+        assert_eq!(findings[0].rule.as_deref(), Some("clippy::needless_return"));
+    }
+
+    #[test]
+    fn should_parse_clang_tidy_warnings_and_skip_notes() {
+        // given clang-tidy output with a note under the warning
+        let text = "/repo/src/a.cpp:12:5: warning: use nullptr [modernize-use-nullptr]\n    int *p = 0;\n/repo/src/a.cpp:3:1: note: declared here\n";
+
+        // when parsed
+        let findings = parse_findings(text, Path::new("/repo")).unwrap();
+
+        // then only the warning is kept, with its check name
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].path, PathBuf::from("src/a.cpp"));
+        assert_eq!(findings[0].line, 12);
+        assert_eq!(findings[0].message, "use nullptr");
+        assert_eq!(findings[0].rule.as_deref(), Some("modernize-use-nullptr"));
+    }
+
+    #[test]
+    fn should_reject_unrecognized_reports() {
+        assert!(parse_findings("hello\n", Path::new("/repo")).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+//! Function boundaries for the function diff popup, from tree-sitter parse trees.
+//!
+//! Each supported language lists the node kinds that define a named function
+//! or method. Files in other languages report "no grammar" rather than
+//! guessing.
+
+use std::path::Path;
+
+use tree_sitter::{Language, Node, Parser};
+
+use crate::model::{DiffHunk, LineOrigin};
+
+/// A function located in one version of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSpan {
+    pub name: String,
+    /// 0-based index of the definition line
+    pub start: usize,
+    /// 0-based index of the last line of the body (inclusive)
+    pub end: usize,
+}
+
+/// A grammar and the node kinds that define functions in it
+struct Grammar {
+    language: Language,
+    function_kinds: &'static [&'static str],
+}
+
+impl Grammar {
+    fn for_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        let (language, function_kinds): (Language, &'static [&'static str]) = match ext.as_str() {
+            "rs" => (tree_sitter_rust::LANGUAGE.into(), &["function_item"]),
+            "py" | "pyi" => (
+                tree_sitter_python::LANGUAGE.into(),
+                &["function_definition"],
+            ),
+            "go" => (
+                tree_sitter_go::LANGUAGE.into(),
+                &["function_declaration", "method_declaration"],
+            ),
+            "js" | "jsx" | "mjs" | "cjs" => (tree_sitter_javascript::LANGUAGE.into(), JS_KINDS),
+            "ts" | "mts" | "cts" => (tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(), JS_KINDS),
+            "tsx" => (tree_sitter_typescript::LANGUAGE_TSX.into(), JS_KINDS),
+            "java" => (
+                tree_sitter_java::LANGUAGE.into(),
+                &["method_declaration", "constructor_declaration"],
+            ),
+            "c" | "h" => (tree_sitter_c::LANGUAGE.into(), &["function_definition"]),
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => {
+                (tree_sitter_cpp::LANGUAGE.into(), &["function_definition"])
+            }
+            "cs" => (
+                tree_sitter_c_sharp::LANGUAGE.into(),
+                &[
+                    "method_declaration",
+                    "constructor_declaration",
+                    "local_function_statement",
+                ],
+            ),
+            "rb" => (
+                tree_sitter_ruby::LANGUAGE.into(),
+                &["method", "singleton_method"],
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            language,
+            function_kinds,
+        })
+    }
+}
+
+/// Named functions, methods and functions bound to a variable (`const f = () => {}`)
+const JS_KINDS: &[&str] = &[
+    "function_declaration",
+    "generator_function_declaration",
+    "method_definition",
+    "arrow_function",
+    "function_expression",
+];
+
+/// Whether the function diff can find functions in this file
+pub fn has_grammar(path: &Path) -> bool {
+    Grammar::for_path(path).is_some()
+}
+
+/// Every named function in `source`, outermost first. None when there is no
+/// grammar for the file's language.
+pub fn function_spans(path: &Path, source: &str) -> Option<Vec<FunctionSpan>> {
+    let grammar = Grammar::for_path(path)?;
+    let mut parser = Parser::new();
+    parser.set_language(&grammar.language).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut spans = Vec::new();
+    let mut cursor = tree.walk();
+    let mut visit = vec![tree.root_node()];
+    while let Some(node) = visit.pop() {
+        if grammar.function_kinds.contains(&node.kind())
+            && let Some(name) = function_name(node, source)
+        {
+            spans.push(FunctionSpan {
+                name,
+                start: node.start_position().row,
+                end: node.end_position().row,
+            });
+        }
+        // Push in reverse so children are visited in source order
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        visit.extend(children.into_iter().rev());
+    }
+    Some(spans)
+}
+
+/// The name a function node is defined under
+fn function_name(node: Node, source: &str) -> Option<String> {
+    let text = |n: Node| n.utf8_text(source.as_bytes()).ok().map(str::to_string);
+    if let Some(name) = node.child_by_field_name("name") {
+        return text(name);
+    }
+    // C and C++ nest the name in declarators: `int *(*f)(void)`, `Foo::bar()`
+    if let Some(mut declarator) = node.child_by_field_name("declarator") {
+        while let Some(inner) = declarator.child_by_field_name("declarator") {
+            declarator = inner;
+        }
+        return text(declarator);
+    }
+    // Anonymous functions take the name of the variable or field they are assigned to
+    let parent = node.parent()?;
+    match parent.kind() {
+        "variable_declarator" => text(parent.child_by_field_name("name")?),
+        "pair" => text(parent.child_by_field_name("key")?),
+        "assignment_expression" => text(parent.child_by_field_name("left")?),
+        _ => None,
+    }
+}
+
+/// The innermost function that contains `line_idx`, if any
+pub fn enclosing_function(spans: &[FunctionSpan], line_idx: usize) -> Option<&FunctionSpan> {
+    spans
+        .iter()
+        .filter(|span| (span.start..=span.end).contains(&line_idx))
+        .min_by_key(|span| span.end - span.start)
+}
+
+/// Find a function by name, preferring the definition nearest to `near`
+pub fn find_function<'a>(
+    spans: &'a [FunctionSpan],
+    name: &str,
+    near: usize,
+) -> Option<&'a FunctionSpan> {
+    spans
+        .iter()
+        .filter(|span| span.name == name)
+        .min_by_key(|span| span.start.abs_diff(near))
+}
+
+/// Rebuild the old version of a file from its new contents and the diff hunks
+pub fn reconstruct_old_file(new_lines: &[String], hunks: &[DiffHunk]) -> Vec<String> {
+    let mut old = Vec::with_capacity(new_lines.len());
+    let mut next = 1usize;
+
+    for hunk in hunks {
+        let new_start = hunk.new_start as usize;
+        // A hunk with no new-side lines sits *after* line `new_start`
+        let before_end = if hunk.new_count == 0 {
+            new_start
+        } else {
+            new_start.saturating_sub(1)
+        };
+        for line_num in next..=before_end {
+            if let Some(line) = new_lines.get(line_num - 1) {
+                old.push(line.clone());
+            }
+        }
+        old.extend(
+            hunk.lines
+                .iter()
+                .filter(|line| line.origin != LineOrigin::Addition)
+                .map(|line| line.content.clone()),
+        );
+        next = before_end + 1 + hunk.new_count as usize;
+    }
+
+    if next >= 1 && next <= new_lines.len() {
+        old.extend(new_lines[next - 1..].iter().cloned());
+    }
+    old
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DiffLine;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn diff_line(origin: LineOrigin, content: &str) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno: None,
+            new_lineno: None,
+            highlighted_spans: None,
+        }
+    }
+
+    fn enclosing(path: &str, source: &str, line_idx: usize) -> Option<FunctionSpan> {
+        let spans = function_spans(Path::new(path), source).unwrap();
+        enclosing_function(&spans, line_idx).cloned()
+    }
+
+    #[test]
+    fn should_find_rust_function_with_multi_line_signature() {
+        // given a Rust file with two functions
+        let src = "fn a() {\n    1\n}\n\npub async fn b(\n    x: u32,\n) -> u32 {\n    x\n}\n";
+
+        // when looking up the function around the body of `b`
+        let span = enclosing("lib.rs", src, 7).unwrap();
+
+        // then the whole of `b` is returned, including its signature
+        assert_eq!(span.name, "b");
+        assert_eq!((span.start, span.end), (4, 8));
+    }
+
+    #[test]
+    fn should_ignore_braces_in_strings_and_comments() {
+        // given a function whose string and comment hold unbalanced braces
+        let src = "fn a() {\n    let s = \"}\";\n    // {{\n    s\n}\n\nfn b() {}\n";
+
+        // when
+        let span = enclosing("lib.rs", src, 3).unwrap();
+
+        // then the body ends at its real closing brace
+        assert_eq!(span.name, "a");
+        assert_eq!((span.start, span.end), (0, 4));
+    }
+
+    #[test]
+    fn should_find_python_method() {
+        // given a Python method followed by another method
+        let src = "class A:\n    def f(self):\n        x = 1\n\n        return x\n    def g(self):\n        pass\n";
+
+        // when looking up the function around `return x`
+        let span = enclosing("a.py", src, 4).unwrap();
+
+        // then the body stops before the next method
+        assert_eq!(span.name, "f");
+        assert_eq!((span.start, span.end), (1, 4));
+    }
+
+    #[test]
+    fn should_find_java_method_without_a_keyword() {
+        // given
+        let src = "class A {\n    public int size() {\n        return n;\n    }\n}\n";
+
+        // when
+        let span = enclosing("A.java", src, 2).unwrap();
+
+        // then
+        assert_eq!(span.name, "size");
+        assert_eq!((span.start, span.end), (1, 3));
+    }
+
+    #[test]
+    fn should_find_qualified_cpp_method() {
+        // given
+        let src = "int Foo::bar(int x) {\n    return x;\n}\n";
+
+        // then
+        assert_eq!(enclosing("foo.cpp", src, 1).unwrap().name, "Foo::bar");
+    }
+
+    #[test]
+    fn should_find_ruby_method_and_go_method() {
+        let ruby = "class A\n  def run\n    1\n  end\nend\n";
+        let go = "func (s *Server) Start(ctx context.Context) error {\n\treturn nil\n}\n";
+
+        assert_eq!(enclosing("a.rb", ruby, 2).unwrap().name, "run");
+        assert_eq!(enclosing("s.go", go, 1).unwrap().name, "Start");
+    }
+
+    #[test]
+    fn should_name_arrow_function_after_its_variable() {
+        let src = "const handler = async (req) => {\n  return req;\n};\n";
+
+        assert_eq!(enclosing("a.ts", src, 1).unwrap().name, "handler");
+    }
+
+    #[test]
+    fn should_prefer_innermost_function() {
+        // given a Rust function nested in another
+        let src = "fn outer() {\n    fn inner() {\n        1\n    }\n}\n";
+
+        // then
+        assert_eq!(enclosing("lib.rs", src, 2).unwrap().name, "inner");
+    }
+
+    #[test]
+    fn should_not_match_lines_outside_functions() {
+        // given code after a function has closed
+        let src = "fn a() {\n}\nconst X: u32 = 1;\n";
+
+        // when looking up the constant
+        // then there is no enclosing function
+        assert_eq!(enclosing("lib.rs", src, 2), None);
+    }
+
+    #[test]
+    fn should_report_no_grammar_for_unknown_languages() {
+        assert_eq!(function_spans(Path::new("notes.txt"), "fn a() {}"), None);
+        assert!(!has_grammar(Path::new("Makefile")));
+    }
+
+    #[test]
+    fn should_reconstruct_old_file_from_hunks() {
+        // given a new file where line 2 replaced an old line and line 4 was removed
+        let new = lines("a\nB\nc\ne\n");
+        let hunks = vec![
+            DiffHunk {
+                header: "@@ -1,3 +1,3 @@".to_string(),
+                lines: vec![
+                    diff_line(LineOrigin::Context, "a"),
+                    diff_line(LineOrigin::Deletion, "b"),
+                    diff_line(LineOrigin::Addition, "B"),
+                    diff_line(LineOrigin::Context, "c"),
+                ],
+                old_start: 1,
+                old_count: 3,
+                new_start: 1,
+                new_count: 3,
+            },
+            DiffHunk {
+                header: "@@ -4,1 +3,0 @@".to_string(),
+                lines: vec![diff_line(LineOrigin::Deletion, "d")],
+                old_start: 4,
+                old_count: 1,
+                new_start: 3,
+                new_count: 0,
+            },
+        ];
+
+        // when the old side is rebuilt
+        let old = reconstruct_old_file(&new, &hunks);
+
+        // then deletions come back and additions are dropped
+        assert_eq!(old, lines("a\nb\nc\nd\ne\n"));
+    }
+}
@@ -0,0 +1,304 @@
+//! `tuicr gc`: keep the saved reviews directory from growing without bound.
+//!
+//! Finished sessions (every file done and every general comment resolved)
+//! untouched for a while are moved to an `archive` folder, where they no
+//! longer get loaded on startup. So are sessions whose repository cannot be
+//! found, since it may only be on an unmounted drive or a moved checkout;
+//! `--delete-missing` deletes those instead. The report ends with the disk
+//! usage of the data directory.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::error::Result;
+use crate::model::ReviewSession;
+use crate::persistence::{ARCHIVE_DIR, get_reviews_dir, load_all_session_files};
+
+/// Days without changes before a finished review is archived
+pub const DEFAULT_ARCHIVE_DAYS: i64 = 3;
+
+/// What `gc` does to each saved session file
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GcPlan {
+    /// Sessions of repositories that cannot be found
+    pub missing: Vec<PathBuf>,
+    /// Finished sessions untouched for at least the archive age
+    pub archive: Vec<PathBuf>,
+}
+
+/// Sort saved sessions into ones whose repository is missing and ones to archive
+pub fn plan(
+    sessions: &[(PathBuf, ReviewSession)],
+    now: DateTime<Utc>,
+    days: i64,
+    repo_exists: impl Fn(&Path) -> bool,
+) -> GcPlan {
+    let mut plan = GcPlan::default();
+    for (path, session) in sessions {
+        if !repo_exists(&session.repo_path) {
+            plan.missing.push(path.clone());
+        } else if is_finished(session) && (now - session.updated_at).num_days() >= days {
+            plan.archive.push(path.clone());
+        }
+    }
+    plan
+}
+
+/// Every file is done and no general comment is left open. A session with no
+/// files was never started, so it is not finished.
+fn is_finished(session: &ReviewSession) -> bool {
+    !session.files.is_empty()
+        && session.files.values().all(|file| file.is_done())
+        && session
+            .session_comments
+            .iter()
+            .chain(session.directory_comments.values().flatten())
+            .all(|comment| comment.resolved)
+}
+
+/// Carry out `plan` in `reviews_dir`. Sessions of missing repositories are
+/// archived with the finished ones unless `delete_missing` is set.
+pub fn apply(plan: &GcPlan, reviews_dir: &Path, delete_missing: bool) -> Result<()> {
+    let mut to_archive: Vec<&PathBuf> = plan.archive.iter().collect();
+    if delete_missing {
+        for path in &plan.missing {
+            fs::remove_file(path)?;
+        }
+    } else {
+        to_archive.extend(&plan.missing);
+    }
+    if to_archive.is_empty() {
+        return Ok(());
+    }
+    let archive_dir = reviews_dir.join(ARCHIVE_DIR);
+    fs::create_dir_all(&archive_dir)?;
+    for path in to_archive {
+        if let Some(name) = path.file_name() {
+            fs::rename(path, archive_dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn format_report(
+    plan: &GcPlan,
+    days: i64,
+    usage: u64,
+    dry_run: bool,
+    delete_missing: bool,
+) -> String {
+    let (missing, archived) = match (dry_run, delete_missing) {
+        (true, true) => ("Would remove", "Would archive"),
+        (true, false) => ("Would archive", "Would archive"),
+        (false, true) => ("Removed", "Archived"),
+        (false, false) => ("Archived", "Archived"),
+    };
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "{missing} {} for repositories that could not be found",
+        sessions_noun(plan.missing.len())
+    );
+    let _ = writeln!(
+        report,
+        "{archived} {} finished more than {days} days ago",
+        sessions_noun(plan.archive.len())
+    );
+    let _ = writeln!(report, "Data directory uses {}", format_size(usage));
+    report
+}
+
+fn sessions_noun(count: usize) -> String {
+    let noun = if count == 1 { "session" } else { "sessions" };
+    format!("{count} {noun}")
+}
+
+/// Human-readable byte count, in the largest unit that keeps it above one
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Total size of the files under `dir`
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Clean up the reviews directory and print what was done; with `dry_run`
+/// only print what would be done
+pub fn run(days: i64, dry_run: bool, delete_missing: bool) -> Result<()> {
+    let reviews_dir = get_reviews_dir()?;
+    let sessions = load_all_session_files()?;
+    let plan = plan(&sessions, Utc::now(), days, Path::exists);
+
+    if !dry_run {
+        apply(&plan, &reviews_dir, delete_missing)?;
+    }
+
+    let usage = reviews_dir.parent().map_or(0, dir_size);
+    print!(
+        "{}",
+        format_report(&plan, days, usage, dry_run, delete_missing)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Comment, CommentType, FileStatus, ReviewState, SessionDiffSource};
+    use chrono::Duration;
+
+    fn session(repo: &str, age_days: i64, reviewed: &[bool]) -> (PathBuf, ReviewSession) {
+        let mut session = ReviewSession::new(
+            PathBuf::from(repo),
+            "abc1234".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        for (i, done) in reviewed.iter().enumerate() {
+            let path = PathBuf::from(format!("src/{i}.rs"));
+            session.add_file(path.clone(), FileStatus::Modified);
+            session.get_file_mut(&path).unwrap().state = if *done {
+                ReviewState::Approved
+            } else {
+                ReviewState::Unreviewed
+            };
+        }
+        session.updated_at = Utc::now() - Duration::days(age_days);
+        (PathBuf::from(format!("{repo}.json")), session)
+    }
+
+    #[test]
+    fn should_set_aside_missing_repos_and_archive_old_finished_sessions() {
+        // given
+        let sessions = vec![
+            session("/gone/app", 0, &[false]),
+            session("/repo/done-old", 5, &[true, true]),
+            session("/repo/done-new", 1, &[true]),
+            session("/repo/unfinished", 9, &[true, false]),
+        ];
+
+        // when
+        let plan = plan(&sessions, Utc::now(), 3, |repo| !repo.starts_with("/gone"));
+
+        // then
+        assert_eq!(plan.missing, vec![PathBuf::from("/gone/app.json")]);
+        assert_eq!(plan.archive, vec![PathBuf::from("/repo/done-old.json")]);
+    }
+
+    #[test]
+    fn should_not_archive_untouched_or_general_comment_sessions() {
+        // given an old session with no files, and one with only an open general comment
+        let untouched = session("/repo/untouched", 9, &[]);
+        let mut commented = session("/repo/commented", 9, &[]);
+        commented.1.session_comments.push(Comment::new(
+            "Split this PR".to_string(),
+            CommentType::Issue,
+            None,
+        ));
+        let mut open_dir_comment = session("/repo/dir", 9, &[true]);
+        open_dir_comment.1.directory_comments.insert(
+            PathBuf::from("src"),
+            vec![Comment::new(
+                "Module layout?".to_string(),
+                CommentType::Note,
+                None,
+            )],
+        );
+
+        // when
+        let plan = plan(
+            &[untouched, commented, open_dir_comment],
+            Utc::now(),
+            3,
+            |_| true,
+        );
+
+        // then
+        assert!(plan.archive.is_empty());
+    }
+
+    #[test]
+    fn should_archive_sessions_of_unmounted_repos_instead_of_deleting() {
+        // given a session whose repository is on a drive that is not mounted
+        let reviews = tempfile::tempdir().unwrap();
+        let file = reviews.path().join("usb-review.json");
+        fs::write(&file, "{}").unwrap();
+        let (_, session) = session("/media/usb/app", 0, &[false]);
+        let plan = plan(&[(file.clone(), session)], Utc::now(), 3, Path::exists);
+
+        // when
+        apply(&plan, reviews.path(), false).unwrap();
+
+        // then the session is kept in the archive
+        assert!(!file.exists());
+        assert!(
+            reviews
+                .path()
+                .join(ARCHIVE_DIR)
+                .join("usb-review.json")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn should_delete_sessions_of_missing_repos_when_asked() {
+        // given
+        let reviews = tempfile::tempdir().unwrap();
+        let file = reviews.path().join("gone.json");
+        fs::write(&file, "{}").unwrap();
+        let plan = GcPlan {
+            missing: vec![file.clone()],
+            archive: Vec::new(),
+        };
+
+        // when
+        apply(&plan, reviews.path(), true).unwrap();
+
+        // then
+        assert!(!file.exists());
+        assert!(!reviews.path().join(ARCHIVE_DIR).exists());
+    }
+
+    #[test]
+    fn should_format_report() {
+        // given
+        let plan = GcPlan {
+            missing: vec![PathBuf::from("a.json")],
+            archive: Vec::new(),
+        };
+
+        // when
+        let report = format_report(&plan, 3, 2560, true, false);
+
+        // then
+        assert_eq!(
+            report,
+            "Would archive 1 session for repositories that could not be found\nWould archive 0 sessions finished more than 3 days ago\nData directory uses 2.5 KiB\n"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! GitLab merge requests as a diff source (`tuicr --mr <IID>`).
+//!
+//! The merge request's diff refs come from the REST API; its head is fetched
+//! from the `origin` remote (`refs/merge-requests/<IID>/head`) when it is not
+//! already present, and the commits between base and head are reviewed like
+//! a commit range.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use ureq::Agent;
+
+use crate::config::GitLabConfig;
+use crate::error::{Result, TuicrError};
+
+const REMOTE: &str = "origin";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeRequest {
+    pub iid: u64,
+    pub title: String,
+    /// Commit the merge request's diff starts from
+    pub base_sha: String,
+    pub head_sha: String,
+}
+
+/// Look up a merge request of the project behind the `origin` remote
+pub fn fetch_merge_request(root: &Path, config: &GitLabConfig, iid: u64) -> Result<MergeRequest> {
+    let remote_url = git(root, &["remote", "get-url", REMOTE])?;
+    let (host, remote_project) = parse_remote_url(remote_url.trim()).ok_or_else(|| {
+        TuicrError::GitLab(format!(
+            "Cannot tell the GitLab project from remote {}",
+            remote_url.trim()
+        ))
+    })?;
+    let project = config.project.clone().unwrap_or(remote_project);
+    let base_url = config
+        .url
+        .clone()
+        .unwrap_or_else(|| format!("https://{host}"));
+    let url = format!(
+        "{}/api/v4/projects/{}/merge_requests/{iid}",
+        base_url.trim_end_matches('/'),
+        project.replace('/', "%2F")
+    );
+
+    let agent: Agent = Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .build()
+        .into();
+    let mut request = agent.get(&url);
+    if let Some(token) = config
+        .token
+        .clone()
+        .or_else(|| std::env::var("GITLAB_TOKEN").ok())
+    {
+        request = request.header("PRIVATE-TOKEN", token);
+    }
+    let body: serde_json::Value = request
+        .call()
+        .map_err(|e| TuicrError::GitLab(format!("Failed to fetch !{iid} from {project}: {e}")))?
+        .into_body()
+        .read_json()
+        .map_err(|e| TuicrError::GitLab(format!("Failed to parse response: {e}")))?;
+
+    parse_merge_request(&body)
+        .ok_or_else(|| TuicrError::GitLab(format!("!{iid} has no diff refs yet")))
+}
+
+/// Commits of the merge request, oldest first, fetching its head if needed
+pub fn merge_request_commits(root: &Path, mr: &MergeRequest) -> Result<Vec<String>> {
+    let head = format!("{}^{{commit}}", mr.head_sha);
+    if git(root, &["cat-file", "-e", &head]).is_err() {
+        let refspec = format!("refs/merge-requests/{}/head", mr.iid);
+        git(root, &["fetch", "--quiet", REMOTE, &refspec])?;
+    }
+
+    let range = format!("{}..{}", mr.base_sha, mr.head_sha);
+    let output = git(root, &["rev-list", "--reverse", "--first-parent", &range])?;
+    Ok(output.lines().map(str::to_string).collect())
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(root)
+        .args(args)
+        .output()
+        .map_err(|e| TuicrError::VcsCommand(format!("Failed to run git: {e}")))?;
+    if !output.status.success() {
+        return Err(TuicrError::VcsCommand(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn parse_merge_request(body: &serde_json::Value) -> Option<MergeRequest> {
+    let refs = body.get("diff_refs")?;
+    Some(MergeRequest {
+        iid: body.get("iid")?.as_u64()?,
+        title: body
+            .get("title")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        base_sha: refs.get("base_sha")?.as_str()?.to_string(),
+        head_sha: refs.get("head_sha")?.as_str()?.to_string(),
+    })
+}
+
+/// Host and project path of a remote URL, for `https://host/group/project.git`,
+/// `ssh://git@host:2222/group/project.git` and `git@host:group/project.git`
+pub fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+
+    let (host, path) = match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+            // An ssh port says nothing about where the web API listens
+            let host = if scheme.starts_with("http") {
+                host
+            } else {
+                host.split_once(':').map_or(host, |(h, _)| h)
+            };
+            (host, path)
+        }
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            (
+                authority.rsplit_once('@').map_or(authority, |(_, h)| h),
+                path,
+            )
+        }
+    };
+
+    (!host.is_empty() && path.contains('/')).then(|| (host.to_string(), path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_project_from_remote_urls() {
+        let expected = Some(("gitlab.com".to_string(), "group/sub/app".to_string()));
+        assert_eq!(
+            parse_remote_url("git@gitlab.com:group/sub/app.git"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("https://gitlab.com/group/sub/app.git"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@gitlab.com:2222/group/sub/app"),
+            expected
+        );
+        assert_eq!(
+            parse_remote_url("https://gitlab.example.com:8443/team/app"),
+            Some((
+                "gitlab.example.com:8443".to_string(),
+                "team/app".to_string()
+            ))
+        );
+        assert_eq!(parse_remote_url("/srv/git/app.git"), None);
+    }
+
+    #[test]
+    fn should_parse_merge_request_diff_refs() {
+        // given
+        let body = serde_json::json!({
+            "iid": 45,
+            "title": "Add retries",
+            "diff_refs": {"base_sha": "aaa", "head_sha": "ccc", "start_sha": "bbb"}
+        });
+
+        // when
+        let mr = parse_merge_request(&body).unwrap();
+
+        // then
+        assert_eq!(
+            mr,
+            MergeRequest {
+                iid: 45,
+                title: "Add retries".to_string(),
+                base_sha: "aaa".to_string(),
+                head_sha: "ccc".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn should_reject_merge_request_without_diff_refs() {
+        let body = serde_json::json!({"iid": 45, "diff_refs": null});
+
+        assert_eq!(parse_merge_request(&body), None);
+    }
+}
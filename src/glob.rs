@@ -0,0 +1,177 @@
+//! Minimal glob matching for repository-relative paths.
+//!
+//! Supports the subset of gitignore/gitattributes syntax we need:
+//! `*` (any run of characters within a path component), `?` (one character),
+//! `**` (any number of components), a leading `/` to anchor at the root, and a
+//! trailing `/` to match everything under a directory, as in gitignore.
+//! Patterns without a slash match the file name at any depth.
+//!
+//! `.gitattributes` differs on the trailing `/`: attributes never apply to a
+//! directory's contents, so such a pattern matches no file there. Use
+//! `matches_attributes_path` for those.
+
+use std::path::Path;
+
+/// Check whether a repository-relative path matches a glob pattern
+pub fn matches_path(pattern: &str, path: &Path) -> bool {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return false;
+    }
+
+    let path = path.to_string_lossy().replace('\\', "/");
+    let anchored = pattern.starts_with('/');
+    let mut pattern = pattern.trim_start_matches('/').to_string();
+    if pattern.ends_with('/') {
+        pattern.push_str("**");
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    if !anchored && !pattern.contains(&'/') {
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        let name: Vec<char> = name.chars().collect();
+        return match_from(&pattern, &name);
+    }
+
+    let path: Vec<char> = path.chars().collect();
+    match_from(&pattern, &path)
+}
+
+/// Check whether a `.gitattributes` pattern applies to a repository-relative
+/// path. A pattern with a trailing `/` names a directory and matches no file.
+pub fn matches_attributes_path(pattern: &str, path: &Path) -> bool {
+    !pattern.trim().ends_with('/') && matches_path(pattern, path)
+}
+
+/// Check whether a path is selected by a `git diff -- <pathspec>` style argument.
+/// A spec without wildcards selects that file or everything under that directory;
+/// a spec with wildcards is matched as a glob, from the repository root when it
+/// contains a slash and against file names at any depth otherwise.
+pub fn matches_pathspec(spec: &str, path: &Path) -> bool {
+    let spec = spec.trim().trim_start_matches("./");
+    if spec.is_empty() || spec == "." {
+        return true;
+    }
+    if spec.contains(['*', '?']) {
+        return if spec.contains('/') {
+            matches_path(&format!("/{}", spec.trim_start_matches('/')), path)
+        } else {
+            matches_path(spec, path)
+        };
+    }
+    path.starts_with(spec.trim_matches('/'))
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            // "**/" may also match zero directories
+            if rest.first() == Some(&'/') && match_from(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|i| match_from(rest, &text[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if match_from(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => {
+            text.first().is_some_and(|c| *c != '/') && match_from(&pattern[1..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        matches_path(pattern, Path::new(path))
+    }
+
+    #[test]
+    fn should_match_file_name_at_any_depth_without_slash() {
+        assert!(matches("*.min.js", "static/js/app.min.js"));
+        assert!(matches("Cargo.lock", "Cargo.lock"));
+        assert!(matches("Cargo.lock", "crates/foo/Cargo.lock"));
+        assert!(!matches("*.min.js", "static/js/app.js"));
+    }
+
+    #[test]
+    fn should_match_full_path_when_pattern_has_slash() {
+        assert!(matches("vendor/**", "vendor/github.com/lib/a.go"));
+        assert!(matches("third_party/*.c", "third_party/zlib.c"));
+        assert!(!matches("third_party/*.c", "third_party/zlib/inflate.c"));
+        assert!(!matches("vendor/**", "src/vendor/a.go"));
+    }
+
+    #[test]
+    fn should_match_zero_or_more_directories_with_double_star() {
+        assert!(matches("**/generated/*.rs", "generated/api.rs"));
+        assert!(matches("**/generated/*.rs", "src/a/generated/api.rs"));
+        assert!(matches("docs/**/*.md", "docs/guide/intro.md"));
+    }
+
+    #[test]
+    fn should_treat_trailing_slash_as_directory_contents() {
+        assert!(matches("node_modules/", "node_modules/react/index.js"));
+        assert!(!matches("node_modules/", "src/node_modules.rs"));
+    }
+
+    #[test]
+    fn should_not_match_directory_patterns_in_attributes() {
+        let path = Path::new("node_modules/react/index.js");
+
+        assert!(!matches_attributes_path("node_modules/", path));
+        assert!(matches_attributes_path("node_modules/**", path));
+        assert!(matches_attributes_path("*.js", path));
+    }
+
+    #[test]
+    fn should_anchor_leading_slash_to_root() {
+        assert!(matches("/build.rs", "build.rs"));
+        assert!(!matches("/build.rs", "crates/foo/build.rs"));
+    }
+
+    #[test]
+    fn should_match_single_character_with_question_mark() {
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file10.txt"));
+    }
+
+    #[test]
+    fn should_not_match_empty_pattern() {
+        assert!(!matches("", "anything"));
+        assert!(!matches("   ", "anything"));
+    }
+
+    #[test]
+    fn should_match_pathspecs_as_prefixes_or_globs() {
+        assert!(matches_pathspec("src/", Path::new("src/app.rs")));
+        assert!(matches_pathspec(
+            "crates/foo",
+            Path::new("crates/foo/lib.rs")
+        ));
+        assert!(matches_pathspec("README.md", Path::new("README.md")));
+        assert!(matches_pathspec("src/*.rs", Path::new("src/app.rs")));
+        assert!(matches_pathspec("*.proto", Path::new("api/v1/user.proto")));
+        assert!(matches_pathspec(".", Path::new("docs/guide.md")));
+        assert!(!matches_pathspec(
+            "crates/foo",
+            Path::new("crates/foobar/lib.rs")
+        ));
+        assert!(!matches_pathspec("src/*.rs", Path::new("src/ui/mod.rs")));
+        assert!(!matches_pathspec("app.rs", Path::new("src/app.rs")));
+    }
+}
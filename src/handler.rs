@@ -1,113 +1,160 @@
-use crate::app::{self, App, FileTreeItem, FocusedPanel};
-use crate::input::Action;
-use crate::output::{export_to_clipboard, generate_export_content};
-use crate::persistence::save_session;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crossterm::event::KeyEvent;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{self, App, CommentGroup, FileTreeItem, FocusedPanel, GeneralTarget, InputMode};
+use crate::error::TuicrError;
+use crate::input::chord::ChordStep;
+use crate::input::paste::normalize_pasted_text;
+use crate::input::{Action, map_comments_pane_key, map_key_to_action};
+use crate::output::{
+    copy_to_clipboard, export_to_clipboard, generate_export_content, generate_handoff,
+    read_clipboard,
+};
+use crate::persistence::{save_backup_export, set_aside_damaged_session};
 use crate::text_edit::{
-    delete_char_before, delete_word_before, next_char_boundary, prev_char_boundary,
+    WrapWidth, delete_grapheme_before, delete_word_before, insert_char, insert_text,
+    is_whitespace_grapheme, line_down, line_end, line_start, line_up, next_grapheme_boundary,
+    next_word_end, next_word_start, prev_grapheme_boundary, prev_word_start, word_end_after,
+    word_start_before,
 };
+use crate::todo::find_todos;
+use crate::ui::comment_panel;
 
 /// Export review: either to clipboard or set pending stdout output based on app.output_to_stdout.
 /// When output_to_stdout is true, stores the content and sets should_quit.
-fn handle_export(app: &mut App) {
-    if app.output_to_stdout {
-        match generate_export_content(&app.session, &app.diff_source) {
-            Ok(content) => {
-                app.pending_stdout_output = Some(content);
-                app.should_quit = true;
-            }
-            Err(e) => app.set_warning(format!("{e}")),
+/// With `preview`, the text is shown first and only exported once accepted.
+fn handle_export(app: &mut App, preview: bool) {
+    let todos = find_todos(&app.diff_files);
+    let content = match generate_export_content(&app.session, &app.diff_source, &todos, &app.config)
+    {
+        Ok(content) => content,
+        Err(e) => {
+            app.set_warning(format!("{e}"));
+            return;
         }
+    };
+    if preview {
+        app.open_export_preview(content);
     } else {
-        match export_to_clipboard(&app.session, &app.diff_source) {
-            Ok(msg) => app.set_message(msg),
-            Err(e) => app.set_warning(format!("{e}")),
-        }
+        deliver_export(app, content);
     }
 }
 
-fn comment_line_start(buffer: &str, cursor: usize) -> usize {
-    let cursor = cursor.min(buffer.len());
-    match buffer[..cursor].rfind('\n') {
-        Some(pos) => pos + 1,
-        None => 0,
+/// Send generated export text to stdout or the clipboard
+fn deliver_export(app: &mut App, content: String) {
+    if app.output_to_stdout {
+        app.pending_stdout_output = Some(content);
+        app.should_quit = true;
+    } else {
+        let previous = read_clipboard();
+        match copy_to_clipboard(&content) {
+            Ok(msg) => {
+                app.remember_clipboard_copy(previous, content);
+                if app.clipboard_history.is_empty() {
+                    app.set_message(msg);
+                } else {
+                    app.set_message(format!("{msg} (:clip undo restores the previous contents)"));
+                }
+            }
+            Err(e) => save_export_after_clipboard_error(app, &content, e),
+        }
     }
 }
 
-fn comment_line_end(buffer: &str, cursor: usize) -> usize {
-    let cursor = cursor.min(buffer.len());
-    match buffer[cursor..].find('\n') {
-        Some(pos) => cursor + pos,
-        None => buffer.len(),
+/// When no clipboard works, save the export to a file so the review is not lost
+fn save_export_after_clipboard_error(app: &mut App, content: &str, error: TuicrError) {
+    match save_backup_export(&app.session, content) {
+        Ok(path) => app.set_warning(format!("{error}; review saved to {}", path.display())),
+        Err(_) => app.set_warning(format!("{error}")),
     }
 }
 
-fn comment_word_left(buffer: &str, cursor: usize) -> usize {
-    let cursor = cursor.min(buffer.len());
-    if cursor == 0 {
-        return 0;
-    }
-    let before = &buffer[..cursor];
-    let mut idx = 0;
-    let mut found_word = false;
-    for (pos, ch) in before.char_indices().rev() {
-        if !ch.is_whitespace() {
-            idx = pos;
-            found_word = true;
-            break;
+/// Hand the rest of the review to another reviewer: the files left and the
+/// open comments go to the clipboard, or stdout with `--stdout`
+fn handle_handoff(app: &mut App) {
+    let content = generate_handoff(
+        &app.session,
+        &app.diff_source,
+        &app.config.export_labels,
+        app.reviewer.as_deref(),
+    );
+    deliver_export(app, content);
+}
+
+/// Copy commit trailers summing up the review
+fn copy_trailer(app: &mut App) {
+    let trailer = app.review_trailer();
+    let previous = read_clipboard();
+    match copy_to_clipboard(&trailer) {
+        Ok(_) => {
+            app.remember_clipboard_copy(previous, trailer);
+            app.set_message("Review trailer copied");
         }
+        Err(e) => app.set_warning(format!("{e}")),
     }
+}
 
-    if !found_word {
-        return 0;
+/// Text on the system clipboard, ready to insert into a text field
+fn clipboard_text(app: &mut App, multiline: bool) -> Option<String> {
+    match read_clipboard() {
+        Some(text) => Some(normalize_pasted_text(&text, multiline)),
+        None => {
+            app.set_warning("Clipboard can't be read here; use your terminal's paste instead");
+            None
+        }
     }
+}
 
-    for (pos, ch) in before[..idx].char_indices().rev() {
-        if ch.is_whitespace() {
-            return pos + ch.len_utf8();
+/// Put back the clipboard text the last export replaced
+fn undo_clipboard(app: &mut App) {
+    let Some(previous) = app.clipboard_history.pop() else {
+        app.set_warning("No earlier clipboard contents to restore");
+        return;
+    };
+    match copy_to_clipboard(&previous) {
+        Ok(_) => {
+            // The restored text is the user's, so a later export keeps it again
+            app.last_clipboard_copy = None;
+            app.set_message("Previous clipboard contents restored");
+        }
+        Err(e) => {
+            app.clipboard_history.push(previous);
+            app.set_warning(format!("{e}"));
         }
-        idx = pos;
     }
+}
 
-    idx
+/// How the comment input wraps in the diff view, for Up/Down to move by
+/// screen rows; None when long lines scroll instead
+fn comment_wrap(app: &App) -> Option<WrapWidth> {
+    app.diff_state.wrap_lines.then(|| WrapWidth {
+        width: app.diff_state.viewport_width,
+        indent: comment_panel::INPUT_BORDER.width(),
+    })
 }
 
 fn comment_word_right(buffer: &str, cursor: usize) -> usize {
     let cursor = cursor.min(buffer.len());
-    if cursor >= buffer.len() {
-        return buffer.len();
-    }
-
-    let mut chars = buffer[cursor..].char_indices();
-    if let Some((_, ch)) = chars.next()
-        && ch.is_whitespace()
-    {
-        for (pos, ch) in buffer[cursor..].char_indices() {
-            if !ch.is_whitespace() {
-                return cursor + pos;
-            }
-        }
-        return buffer.len();
-    }
-
-    let mut word_end = buffer.len();
-    for (pos, ch) in buffer[cursor..].char_indices() {
-        if ch.is_whitespace() {
-            word_end = cursor + pos;
-            break;
-        }
-    }
-
-    if word_end >= buffer.len() {
-        return buffer.len();
-    }
+    let mut graphemes = buffer[cursor..].grapheme_indices(true).peekable();
 
-    for (pos, ch) in buffer[word_end..].char_indices() {
-        if !ch.is_whitespace() {
-            return word_end + pos;
-        }
-    }
+    // Skip the rest of the current word, then the whitespace after it
+    while graphemes
+        .next_if(|(_, g)| !is_whitespace_grapheme(g))
+        .is_some()
+    {}
+    while graphemes
+        .next_if(|(_, g)| is_whitespace_grapheme(g))
+        .is_some()
+    {}
 
-    buffer.len()
+    graphemes
+        .peek()
+        .map_or(buffer.len(), |(pos, _)| cursor + pos)
 }
 
 /// Handle actions in Help mode (scrolling only)
@@ -127,10 +174,197 @@ pub fn handle_help_action(app: &mut App, action: Action) {
     }
 }
 
+/// Handle actions in the function diff popup
+pub fn handle_function_diff_action(app: &mut App, action: Action) {
+    let viewport = app
+        .function_diff
+        .as_ref()
+        .map_or(0, |view| view.viewport_height);
+    match action {
+        Action::CursorDown(n) => app.function_diff_scroll_down(n),
+        Action::CursorUp(n) => app.function_diff_scroll_up(n),
+        Action::HalfPageDown => app.function_diff_scroll_down(viewport / 2),
+        Action::HalfPageUp => app.function_diff_scroll_up(viewport / 2),
+        Action::PageDown => app.function_diff_scroll_down(viewport),
+        Action::PageUp => app.function_diff_scroll_up(viewport),
+        Action::GoToTop => app.function_diff_scroll_up(usize::MAX),
+        Action::GoToBottom => app.function_diff_scroll_down(usize::MAX),
+        Action::ToggleHelp => app.close_function_diff(),
+        Action::Quit => app.should_quit = true,
+        _ => {}
+    }
+}
+
+/// Handle actions in the structural diff popup
+pub fn handle_structural_diff_action(app: &mut App, action: Action) {
+    let viewport = app
+        .structural_diff
+        .as_ref()
+        .map_or(0, |view| view.viewport_height);
+    match action {
+        Action::CursorDown(n) => app.structural_diff_scroll_down(n),
+        Action::CursorUp(n) => app.structural_diff_scroll_up(n),
+        Action::HalfPageDown => app.structural_diff_scroll_down(viewport / 2),
+        Action::HalfPageUp => app.structural_diff_scroll_up(viewport / 2),
+        Action::PageDown => app.structural_diff_scroll_down(viewport),
+        Action::PageUp => app.structural_diff_scroll_up(viewport),
+        Action::GoToTop => app.structural_diff_scroll_up(usize::MAX),
+        Action::GoToBottom => app.structural_diff_scroll_down(usize::MAX),
+        Action::ToggleHelp => app.close_structural_diff(),
+        Action::Quit => app.should_quit = true,
+        _ => {}
+    }
+}
+
+/// Handle actions in the migration up/down popup
+pub fn handle_migration_action(app: &mut App, action: Action) {
+    let viewport = app
+        .migration_view
+        .as_ref()
+        .map_or(0, |view| view.viewport_height);
+    match action {
+        Action::CursorDown(n) => app.migration_view_scroll_down(n),
+        Action::CursorUp(n) => app.migration_view_scroll_up(n),
+        Action::HalfPageDown => app.migration_view_scroll_down(viewport / 2),
+        Action::HalfPageUp => app.migration_view_scroll_up(viewport / 2),
+        Action::PageDown => app.migration_view_scroll_down(viewport),
+        Action::PageUp => app.migration_view_scroll_up(viewport),
+        Action::GoToTop => app.migration_view_scroll_up(usize::MAX),
+        Action::GoToBottom => app.migration_view_scroll_down(usize::MAX),
+        Action::ToggleHelp => app.close_migration_view(),
+        Action::Quit => app.should_quit = true,
+        _ => {}
+    }
+}
+
+/// Handle actions in the public API summary
+pub fn handle_public_api_action(app: &mut App, action: Action) {
+    match action {
+        Action::CommitSelectUp => app.public_api_select_up(),
+        Action::CommitSelectDown => app.public_api_select_down(),
+        Action::ConfirmCommitSelect => app.jump_to_public_api_change(),
+        Action::ExitMode => app.close_public_api(),
+        _ => {}
+    }
+}
+
+/// Handle actions in the impact search results
+pub fn handle_impact_action(app: &mut App, action: Action) {
+    let viewport = app
+        .impact_view
+        .as_ref()
+        .map_or(0, |view| view.viewport_height);
+    match action {
+        Action::CursorDown(n) => app.impact_view_scroll_down(n),
+        Action::CursorUp(n) => app.impact_view_scroll_up(n),
+        Action::HalfPageDown => app.impact_view_scroll_down(viewport / 2),
+        Action::HalfPageUp => app.impact_view_scroll_up(viewport / 2),
+        Action::PageDown => app.impact_view_scroll_down(viewport),
+        Action::PageUp => app.impact_view_scroll_up(viewport),
+        Action::GoToTop => app.impact_view_scroll_up(usize::MAX),
+        Action::GoToBottom => app.impact_view_scroll_down(usize::MAX),
+        Action::ToggleHelp => app.close_impact_view(),
+        Action::Quit => app.should_quit = true,
+        _ => {}
+    }
+}
+
+/// Handle actions in the export preview popup
+pub fn handle_export_preview_action(app: &mut App, action: Action) {
+    let viewport = app
+        .export_preview
+        .as_ref()
+        .map_or(0, |preview| preview.viewport_height);
+    match action {
+        Action::CursorDown(n) => app.export_preview_scroll_down(n),
+        Action::CursorUp(n) => app.export_preview_scroll_up(n),
+        Action::HalfPageDown => app.export_preview_scroll_down(viewport / 2),
+        Action::HalfPageUp => app.export_preview_scroll_up(viewport / 2),
+        Action::PageDown => app.export_preview_scroll_down(viewport),
+        Action::PageUp => app.export_preview_scroll_up(viewport),
+        Action::GoToTop => app.export_preview_scroll_up(usize::MAX),
+        Action::GoToBottom => app.export_preview_scroll_down(usize::MAX),
+        Action::ConfirmYes => {
+            if let Some(content) = app.close_export_preview() {
+                deliver_export(app, content);
+            }
+        }
+        Action::ConfirmNo => {
+            app.close_export_preview();
+            app.set_message("Export cancelled");
+        }
+        _ => {}
+    }
+}
+
+/// Handle actions in the test mapping panel
+pub fn handle_test_map_action(app: &mut App, action: Action) {
+    match action {
+        Action::CommitSelectUp => app.test_map_select_up(),
+        Action::CommitSelectDown => app.test_map_select_down(),
+        Action::ConfirmCommitSelect => app.jump_to_test_map_entry(),
+        Action::ExitMode => app.close_test_map(),
+        _ => {}
+    }
+}
+
+/// Handle actions in the TODO panel
+pub fn handle_todo_action(app: &mut App, action: Action) {
+    match action {
+        Action::CommitSelectUp => app.todo_select_up(),
+        Action::CommitSelectDown => app.todo_select_down(),
+        Action::ToggleCommitSelect => app.comment_on_todo(),
+        Action::ConfirmCommitSelect => app.jump_to_todo(),
+        Action::ExitMode => app.close_todos(),
+        _ => {}
+    }
+}
+
+pub fn handle_comment_list_action(app: &mut App, action: Action) {
+    match action {
+        Action::CommitSelectUp => app.comment_list_select_up(),
+        Action::CommitSelectDown => app.comment_list_select_down(),
+        Action::ConfirmCommitSelect => {
+            app.jump_to_comment_entry();
+        }
+        Action::EditComment => app.edit_comment_entry(),
+        Action::ToggleResolved => app.toggle_resolved_comment_entry(),
+        Action::ExitMode => app.close_comment_list(),
+        _ => {}
+    }
+}
+
+pub fn handle_stale_comment_action(app: &mut App, action: Action) {
+    match action {
+        Action::CommitSelectUp => app.stale_select_up(),
+        Action::CommitSelectDown => app.stale_select_down(),
+        Action::ToggleCommitSelect => app.delete_stale_comment(),
+        Action::ConfirmCommitSelect => app.jump_to_stale_comment(),
+        Action::ExitMode => app.close_stale_comments(),
+        _ => {}
+    }
+}
+
+pub fn handle_attachment_action(app: &mut App, action: Action) {
+    match action {
+        Action::CommitSelectUp => app.attachment_select_up(),
+        Action::CommitSelectDown => app.attachment_select_down(),
+        Action::ToggleCommitSelect => app.remove_selected_attachment(),
+        Action::ConfirmCommitSelect | Action::ExitMode => app.close_attachments(),
+        _ => {}
+    }
+}
+
 /// Handle actions in Command mode (text input for :commands)
 pub fn handle_command_action(app: &mut App, action: Action) {
     match action {
         Action::InsertChar(c) => app.command_buffer.push(c),
+        Action::InsertText(text) => app.command_buffer.push_str(&text),
+        Action::PasteClipboard => {
+            if let Some(text) = clipboard_text(app, false) {
+                app.command_buffer.push_str(&text);
+            }
+        }
         Action::DeleteChar => {
             app.command_buffer.pop();
         }
@@ -146,37 +380,76 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                     }
                 }
                 "q!" | "quit!" => app.should_quit = true,
-                "w" | "write" => match save_session(&app.session) {
-                    Ok(path) => {
+                "w" | "write" => match app.save_session() {
+                    Ok(Some(path)) => {
                         app.dirty = false;
                         app.set_message(format!("Saved to {}", path.display()));
                     }
-                    Err(e) => app.set_error(format!("Save failed: {e}")),
-                },
-                "x" | "wq" => match save_session(&app.session) {
-                    Ok(_) => {
+                    Ok(None) => {
                         app.dirty = false;
-                        if app.session.has_comments() {
-                            if app.output_to_stdout {
-                                // Skip confirmation dialog, export directly
-                                handle_export(app);
-                                return;
-                            }
-                            app.exit_command_mode();
-                            app.enter_confirm_mode(app::ConfirmAction::CopyAndQuit);
-                            return;
-                        } else {
-                            app.should_quit = true;
-                        }
+                        app.set_warning("Reopened snapshot: changes are not saved");
                     }
                     Err(e) => app.set_error(format!("Save failed: {e}")),
                 },
+                "x" | "wq" => {
+                    write_and_quit(app);
+                    return;
+                }
+                "done" => {
+                    if app.is_review_complete() {
+                        write_and_quit(app);
+                        return;
+                    }
+                    if let Some(idx) = app.first_incomplete_file() {
+                        app.jump_to_file(idx);
+                    }
+                    app.set_warning(format!("Review incomplete: {}", app.progress_summary()));
+                }
                 "e" | "reload" => match app.reload_diff_files() {
-                    Ok(count) => app.set_message(format!("Reloaded {count} files")),
+                    Ok(count) => {
+                        let stale = app.stale_comment_count();
+                        match (app.session.lost_anchor_count(), stale) {
+                            (0, 0) => app.set_message(format!("Reloaded {count} files")),
+                            (lost, 0) => app.set_warning(format!(
+                                "Reloaded {count} files; the code of {lost} comment(s) changed (marked ⚠)"
+                            )),
+                            (_, stale) => app.set_warning(format!(
+                                "Reloaded {count} files; {stale} comment(s) lost their line (see :stale)"
+                            )),
+                        }
+                    }
                     Err(e) => app.set_error(format!("Reload failed: {e}")),
                 },
-                "clip" | "export" => handle_export(app),
+                "clip" | "export" => handle_export(app, false),
+                "clip --preview" | "export --preview" => {
+                    // Leave command mode first so the popup's mode sticks
+                    app.exit_command_mode();
+                    handle_export(app, true);
+                    return;
+                }
+                "clip undo" => undo_clipboard(app),
+                "trailer" => copy_trailer(app),
+                "handoff" => handle_handoff(app),
                 "clear" => app.clear_all_comments(),
+                "clearcomments" => {
+                    let path = app.current_file_path().cloned();
+                    match path {
+                        Some(path)
+                            if app
+                                .session
+                                .files
+                                .get(&path)
+                                .is_some_and(|review| review.comment_count() > 0) =>
+                        {
+                            // Leave command mode first so the prompt's mode sticks
+                            app.exit_command_mode();
+                            app.enter_confirm_mode(app::ConfirmAction::ClearFileComments(path));
+                            return;
+                        }
+                        Some(path) => app.set_message(format!("No comments on {}", path.display())),
+                        None => app.set_warning("No file selected"),
+                    }
+                }
                 "version" => {
                     app.set_message(format!("tuicr v{}", env!("CARGO_PKG_VERSION")));
                 }
@@ -202,7 +475,111 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                 },
                 "set wrap" => app.set_diff_wrap(true),
                 "set wrap!" => app.toggle_diff_wrap(),
+                "set freescroll!" => app.toggle_free_scroll(),
+                "set guide!" | "guide" => app.toggle_guide(),
+                "set showall" | "set noshowall" => {
+                    let show = cmd == "set showall";
+                    match app.set_show_ignored(show) {
+                        Ok(()) if show => app.set_message("Showing files from .tuicrignore"),
+                        Ok(()) => match app.ignored_file_count {
+                            0 => app.set_message("No files excluded by .tuicrignore"),
+                            n => app.set_message(format!("Hiding {n} files from .tuicrignore")),
+                        },
+                        Err(e) => app.set_error(format!("Reload failed: {e}")),
+                    }
+                }
                 "diff" => app.toggle_diff_view_mode(),
+                "plan" => app.toggle_plan_mode(),
+                "structure" | "struct" => {
+                    // Leave command mode first so the popup's mode sticks
+                    app.exit_command_mode();
+                    if let Err(e) = app.open_structural_diff() {
+                        app.set_error(format!("Failed to load file: {e}"));
+                    }
+                    return;
+                }
+                "api" => {
+                    // Leave command mode first so the popup's mode sticks
+                    app.exit_command_mode();
+                    app.open_public_api();
+                    return;
+                }
+                "impact" => app.start_impact_search(),
+                "todos" | "todo" => {
+                    // Leave command mode first so the panel's mode sticks
+                    app.exit_command_mode();
+                    app.open_todos();
+                    return;
+                }
+                "comments" => {
+                    // Leave command mode first so the panel's mode sticks
+                    app.exit_command_mode();
+                    app.open_comment_list();
+                    return;
+                }
+                "stale" => {
+                    // Leave command mode first so the panel's mode sticks
+                    app.exit_command_mode();
+                    app.open_stale_comments();
+                    return;
+                }
+                "attach" | "attachments" => {
+                    // Leave command mode first so the panel's mode sticks
+                    app.exit_command_mode();
+                    app.open_attachments();
+                    return;
+                }
+                _ if cmd == "show" || cmd.starts_with("show ") => {
+                    let words: Vec<&str> = cmd["show".len()..]
+                        .split_whitespace()
+                        .filter(|word| *word != "all")
+                        .collect();
+                    match words
+                        .iter()
+                        .map(|word| CommentGroup::parse(word).ok_or(*word))
+                        .collect::<Result<Vec<_>, _>>()
+                    {
+                        Ok(groups) => app.show_only_comment_groups(&groups),
+                        Err(word) => app.set_error(format!(
+                            "Unknown comment type '{word}' (notes, suggestions, issues, praise or all)"
+                        )),
+                    }
+                }
+                _ if cmd.starts_with("attach ") => {
+                    let arg = cmd["attach ".len()..].trim();
+                    match app.attach(arg) {
+                        Ok(path) => app.set_message(format!("Attached {}", path.display())),
+                        Err(e) => app.set_error(format!("Failed to attach {arg}: {e}")),
+                    }
+                }
+                "tests" => {
+                    // Leave command mode first so the panel's mode sticks
+                    app.exit_command_mode();
+                    app.open_test_map();
+                    return;
+                }
+                "migration" | "mig" => {
+                    // Leave command mode first so the popup's mode sticks
+                    app.exit_command_mode();
+                    if let Err(e) = app.open_migration_view() {
+                        app.set_error(format!("Failed to load migration: {e}"));
+                    }
+                    return;
+                }
+                "worktree" | "worktrees" => {
+                    // Leave command mode first so the picker returns to normal mode
+                    app.exit_command_mode();
+                    if let Err(e) = app.enter_worktree_select_mode() {
+                        app.set_error(format!("Failed to list worktrees: {e}"));
+                    }
+                    return;
+                }
+                "plan clear" => app.clear_review_plan(),
+                "base" => {
+                    if let Err(e) = app.review_against_base(None) {
+                        app.set_error(format!("Failed to review against base: {e}"));
+                    }
+                }
                 "commits" => {
                     if let Err(e) = app.enter_commit_select_mode() {
                         app.set_error(format!("Failed to load commits: {e}"));
@@ -210,7 +587,10 @@ pub fn handle_command_action(app: &mut App, action: Action) {
                         return;
                     }
                 }
-                _ => app.set_message(format!("Unknown command: {cmd}")),
+                _ => match set_context_arg(&cmd) {
+                    Some(arg) => set_context(app, arg),
+                    None => app.set_message(format!("Unknown command: {cmd}")),
+                },
             }
             app.exit_command_mode();
         }
@@ -219,34 +599,56 @@ pub fn handle_command_action(app: &mut App, action: Action) {
     }
 }
 
+/// The line count given to `:set context N` or `:set context=N`
+fn set_context_arg(cmd: &str) -> Option<&str> {
+    let rest = cmd.strip_prefix("set context")?;
+    rest.strip_prefix([' ', '=']).map(str::trim)
+}
+
+/// Regenerate the diff with the context line count `arg`
+fn set_context(app: &mut App, arg: &str) {
+    match arg.parse::<u32>() {
+        Ok(lines) => match app.set_context_lines(lines) {
+            Ok(()) => app.set_message(format!("Context set to {lines} lines")),
+            Err(e) => app.set_error(format!("Failed to change context: {e}")),
+        },
+        Err(_) => app.set_error(format!("Invalid context line count: {arg}")),
+    }
+}
+
+/// Save the session, then export (confirming unless writing to stdout) and quit
+fn write_and_quit(app: &mut App) {
+    match app.save_session() {
+        Ok(_) => {
+            app.dirty = false;
+            if app.session.has_comments() {
+                if app.output_to_stdout {
+                    // Skip confirmation dialog, export directly
+                    handle_export(app, false);
+                    return;
+                }
+                app.exit_command_mode();
+                app.enter_confirm_mode(app::ConfirmAction::CopyAndQuit);
+                return;
+            }
+            app.should_quit = true;
+        }
+        Err(e) => app.set_error(format!("Save failed: {e}")),
+    }
+    app.exit_command_mode();
+}
+
 /// Handle actions in Search mode (text input for /pattern)
 pub fn handle_search_action(app: &mut App, action: Action) {
     match action {
         Action::InsertChar(c) => app.search_buffer.push(c),
+        Action::InsertText(text) => app.search_buffer.push_str(&text),
         Action::DeleteChar => {
             app.search_buffer.pop();
         }
         Action::DeleteWord => {
-            if !app.search_buffer.is_empty() {
-                while app
-                    .search_buffer
-                    .chars()
-                    .last()
-                    .map(|c| c.is_whitespace())
-                    .unwrap_or(false)
-                {
-                    app.search_buffer.pop();
-                }
-                while app
-                    .search_buffer
-                    .chars()
-                    .last()
-                    .map(|c| !c.is_whitespace())
-                    .unwrap_or(false)
-                {
-                    app.search_buffer.pop();
-                }
-            }
+            let end = app.search_buffer.len();
+            delete_word_before(&mut app.search_buffer, end);
         }
         Action::ClearLine => {
             app.search_buffer.clear();
@@ -265,35 +667,72 @@ pub fn handle_search_action(app: &mut App, action: Action) {
 pub fn handle_comment_action(app: &mut App, action: Action) {
     match action {
         Action::InsertChar(c) => {
-            app.comment_buffer.insert(app.comment_cursor, c);
-            app.comment_cursor += c.len_utf8();
+            app.comment_cursor = insert_char(&mut app.comment_buffer, app.comment_cursor, c);
+        }
+        Action::InsertText(text) => {
+            app.comment_cursor = insert_text(&mut app.comment_buffer, app.comment_cursor, &text);
+        }
+        Action::PasteClipboard => {
+            if let Some(text) = clipboard_text(app, true) {
+                app.comment_cursor =
+                    insert_text(&mut app.comment_buffer, app.comment_cursor, &text);
+            }
         }
         Action::DeleteChar => {
-            app.comment_cursor = delete_char_before(&mut app.comment_buffer, app.comment_cursor);
+            app.comment_cursor =
+                delete_grapheme_before(&mut app.comment_buffer, app.comment_cursor);
         }
-        Action::ExitMode => app.exit_comment_mode(),
+        Action::ExitMode => app.cancel_comment(),
         Action::SubmitInput => app.save_comment(),
         Action::CycleCommentType => app.cycle_comment_type(),
+        Action::CycleCommentSeverity => app.cycle_comment_severity(),
+        Action::CycleCommentRequirement => app.cycle_comment_required_action(),
         Action::TextCursorLeft => {
-            app.comment_cursor = prev_char_boundary(&app.comment_buffer, app.comment_cursor);
+            app.comment_cursor = prev_grapheme_boundary(&app.comment_buffer, app.comment_cursor);
         }
         Action::TextCursorRight => {
-            app.comment_cursor = next_char_boundary(&app.comment_buffer, app.comment_cursor);
+            app.comment_cursor = next_grapheme_boundary(&app.comment_buffer, app.comment_cursor);
+        }
+        Action::CursorUp(_) => {
+            app.comment_cursor =
+                line_up(&app.comment_buffer, app.comment_cursor, comment_wrap(app));
+        }
+        Action::CursorDown(_) => {
+            app.comment_cursor =
+                line_down(&app.comment_buffer, app.comment_cursor, comment_wrap(app));
         }
         Action::TextCursorLineStart => {
-            app.comment_cursor = comment_line_start(&app.comment_buffer, app.comment_cursor);
+            app.comment_cursor = line_start(&app.comment_buffer, app.comment_cursor);
         }
         Action::TextCursorLineEnd => {
-            app.comment_cursor = comment_line_end(&app.comment_buffer, app.comment_cursor);
+            app.comment_cursor = line_end(&app.comment_buffer, app.comment_cursor);
         }
         Action::TextCursorWordLeft => {
-            app.comment_cursor = comment_word_left(&app.comment_buffer, app.comment_cursor);
+            app.comment_cursor = word_start_before(&app.comment_buffer, app.comment_cursor);
         }
         Action::TextCursorWordRight => {
             app.comment_cursor = comment_word_right(&app.comment_buffer, app.comment_cursor);
         }
         Action::DeleteWord => {
-            app.comment_cursor = delete_word_before(&mut app.comment_buffer, app.comment_cursor);
+            let start = word_start_before(&app.comment_buffer, app.comment_cursor);
+            app.kill_comment_text(start, app.comment_cursor);
+        }
+        Action::KillWordForward => {
+            let end = word_end_after(&app.comment_buffer, app.comment_cursor);
+            app.kill_comment_text(app.comment_cursor, end);
+        }
+        Action::KillToLineEnd => {
+            // At the end of a line, readline joins it with the next one
+            let mut end = line_end(&app.comment_buffer, app.comment_cursor);
+            if end == app.comment_cursor && end < app.comment_buffer.len() {
+                end += 1;
+            }
+            app.kill_comment_text(app.comment_cursor, end);
+        }
+        Action::Yank => app.yank_comment_text(),
+        Action::RestoreDraft => app.restore_comment_draft(),
+        Action::QuoteAnchor if !app.quote_anchor_in_comment() => {
+            app.set_warning("Only line comments can quote their code");
         }
         Action::ClearLine => {
             app.comment_buffer.clear();
@@ -306,17 +745,105 @@ pub fn handle_comment_action(app: &mut App, action: Action) {
 
 /// Handle actions in Confirm mode (Y/N prompts)
 pub fn handle_confirm_action(app: &mut App, action: Action) {
+    if let Some(app::ConfirmAction::ApplySuggestion(id)) = app.pending_confirm.clone() {
+        match action {
+            Action::ConfirmYes => {
+                app.exit_confirm_mode();
+                match app.apply_suggestion(&id) {
+                    Ok(message) => app.set_message(message),
+                    Err(e) => app.set_error(format!("Failed to apply suggestion: {e}")),
+                }
+            }
+            Action::ConfirmNo => app.exit_confirm_mode(),
+            Action::Quit => app.should_quit = true,
+            _ => {}
+        }
+        return;
+    }
+    if let Some(app::ConfirmAction::SeedFromSession { path, .. }) = app.pending_confirm.clone() {
+        match action {
+            Action::ConfirmYes => {
+                app.exit_confirm_mode();
+                match app.seed_from_session(&path) {
+                    Ok(carried) => app.set_message(format!(
+                        "Carried over {carried} open comment(s) from the earlier review"
+                    )),
+                    Err(e) => app.set_error(format!("Failed to load the earlier review: {e}")),
+                }
+            }
+            Action::ConfirmNo => app.exit_confirm_mode(),
+            Action::Quit => app.should_quit = true,
+            _ => {}
+        }
+        return;
+    }
+    if let Some(app::ConfirmAction::RecoverDamagedSession { path, .. }) =
+        app.pending_confirm.clone()
+    {
+        match action {
+            Action::ConfirmYes => {
+                app.exit_confirm_mode();
+                match app.recover_damaged_session(&path) {
+                    Ok(recovered) => app.set_message(format!(
+                        "Recovered {recovered} comment(s) from the damaged review"
+                    )),
+                    Err(e) => app.set_error(format!("Recovery failed: {e}")),
+                }
+            }
+            Action::ConfirmNo => {
+                app.exit_confirm_mode();
+                match set_aside_damaged_session(&path) {
+                    Ok(kept) => {
+                        app.set_message(format!("Kept the damaged review as {}", kept.display()))
+                    }
+                    Err(e) => app.set_error(format!("Failed to set the damaged review aside: {e}")),
+                }
+            }
+            Action::Quit => app.should_quit = true,
+            _ => {}
+        }
+        return;
+    }
+    if let Some(app::ConfirmAction::ClearFileComments(path)) = app.pending_confirm.clone() {
+        match action {
+            Action::ConfirmYes => {
+                app.exit_confirm_mode();
+                app.clear_file_comments(&path);
+            }
+            Action::ConfirmNo => app.exit_confirm_mode(),
+            Action::Quit => app.should_quit = true,
+            _ => {}
+        }
+        return;
+    }
     match action {
         Action::ConfirmYes => {
             if let Some(app::ConfirmAction::CopyAndQuit) = app.pending_confirm {
+                let todos = find_todos(&app.diff_files);
                 if app.output_to_stdout {
-                    match generate_export_content(&app.session, &app.diff_source) {
+                    match generate_export_content(
+                        &app.session,
+                        &app.diff_source,
+                        &todos,
+                        &app.config,
+                    ) {
                         Ok(content) => app.pending_stdout_output = Some(content),
                         Err(e) => app.set_warning(format!("{e}")),
                     }
                 } else {
-                    match export_to_clipboard(&app.session, &app.diff_source) {
+                    match export_to_clipboard(&app.session, &app.diff_source, &todos, &app.config) {
                         Ok(msg) => app.set_message(msg),
+                        Err(e @ TuicrError::Clipboard(_)) => {
+                            match generate_export_content(
+                                &app.session,
+                                &app.diff_source,
+                                &todos,
+                                &app.config,
+                            ) {
+                                Ok(content) => save_export_after_clipboard_error(app, &content, e),
+                                Err(_) => app.set_warning(format!("{e}")),
+                            }
+                        }
                         Err(e) => app.set_warning(format!("{e}")),
                     }
                 }
@@ -358,6 +885,16 @@ pub fn handle_commit_select_action(app: &mut App, action: Action) {
                 app.set_error(format!("Failed to load commits: {e}"));
             }
         }
+        Action::EnterSearchMode => app.enter_commit_filter_mode(),
+        Action::EnterVisualMode => app.toggle_commit_anchor(),
+        // Esc cancels a `v` range before anything else
+        Action::ExitMode if app.commit_anchor.is_some() => app.cancel_commit_anchor(),
+        // Esc first drops a kept filter, then leaves the picker
+        Action::ExitMode if !app.commit_filter.is_empty() => {
+            if let Err(e) = app.clear_commit_filter() {
+                app.set_error(format!("Failed to load commits: {e}"));
+            }
+        }
         Action::ExitMode => {
             if let Err(e) = app.exit_commit_select_mode() {
                 app.set_error(format!("Failed to reload working tree: {e}"));
@@ -368,6 +905,52 @@ pub fn handle_commit_select_action(app: &mut App, action: Action) {
     }
 }
 
+/// Handle typing a `/` filter in the commit picker; the list narrows as
+/// the filter changes
+pub fn handle_commit_filter_action(app: &mut App, action: Action) {
+    let mut filter = app.commit_filter.clone();
+    match action {
+        Action::InsertChar(c) => filter.push(c),
+        Action::InsertText(text) => filter.push_str(&text),
+        Action::DeleteChar => {
+            filter.pop();
+        }
+        Action::DeleteWord => {
+            let kept = filter
+                .trim_end()
+                .trim_end_matches(|c: char| !c.is_whitespace())
+                .len();
+            filter.truncate(kept);
+        }
+        Action::ClearLine => filter.clear(),
+        Action::SubmitInput | Action::ExitMode => {
+            let keep = matches!(action, Action::SubmitInput);
+            if let Err(e) = app.exit_commit_filter_mode(keep) {
+                app.set_error(format!("Failed to load commits: {e}"));
+            }
+            return;
+        }
+        _ => return,
+    }
+    app.set_commit_filter(filter);
+}
+
+/// Handle actions in the worktree picker
+pub fn handle_worktree_select_action(app: &mut App, action: Action) {
+    match action {
+        Action::CommitSelectUp => app.worktree_select_up(),
+        Action::CommitSelectDown => app.worktree_select_down(),
+        Action::ConfirmCommitSelect => {
+            if let Err(e) = app.confirm_worktree_selection() {
+                app.exit_worktree_select_mode();
+                app.set_error(format!("Failed to open worktree: {e}"));
+            }
+        }
+        Action::ExitMode => app.exit_worktree_select_mode(),
+        _ => {}
+    }
+}
+
 /// Handle actions in VisualSelect mode
 pub fn handle_visual_action(app: &mut App, action: Action) {
     match action {
@@ -399,17 +982,43 @@ pub fn handle_visual_action(app: &mut App, action: Action) {
                 app.exit_visual_mode();
             }
         }
+        Action::CopySelection | Action::CopySelectionWithPrefixes => {
+            copy_selection(app, action == Action::CopySelectionWithPrefixes)
+        }
         Action::ExitMode => app.exit_visual_mode(),
         Action::Quit => app.should_quit = true,
         _ => {}
     }
 }
 
+/// Copy the visually selected diff lines, leaving visual mode
+fn copy_selection(app: &mut App, prefixes: bool) {
+    let Some(text) = app.visual_selection_text(prefixes) else {
+        app.set_warning("Invalid selection - cannot span old and new lines");
+        app.exit_visual_mode();
+        return;
+    };
+    let lines = text.lines().count();
+    let previous = read_clipboard();
+    match copy_to_clipboard(&text) {
+        Ok(_) => {
+            app.remember_clipboard_copy(previous, text);
+            app.set_message(format!("Copied {lines} line(s)"));
+        }
+        Err(e) => app.set_warning(format!("{e}")),
+    }
+    app.exit_visual_mode();
+}
+
 /// Handle actions when file list panel is focused
 pub fn handle_file_list_action(app: &mut App, action: Action) {
     match action {
         Action::CursorDown(n) => app.file_list_down(n),
         Action::CursorUp(n) => app.file_list_up(n),
+        Action::HalfPageDown => app.file_list_down(app.file_list_state.viewport_height / 2),
+        Action::HalfPageUp => app.file_list_up(app.file_list_state.viewport_height / 2),
+        Action::PageDown => app.file_list_down(app.file_list_state.viewport_height),
+        Action::PageUp => app.file_list_up(app.file_list_state.viewport_height),
         Action::ScrollLeft(n) => app.file_list_state.scroll_left(n),
         Action::ScrollRight(n) => app.file_list_state.scroll_right(n),
         Action::SelectFile | Action::ToggleExpand => {
@@ -420,20 +1029,76 @@ pub fn handle_file_list_action(app: &mut App, action: Action) {
                         app.jump_to_file(file_idx);
                         app.focused_panel = FocusedPanel::Diff;
                     }
+                    FileTreeItem::VendoredGroup { .. } => app.toggle_vendored_group(),
                 }
             }
         }
-        Action::ToggleReviewed => {
+        Action::CycleReviewState => {
             if let Some(FileTreeItem::File { file_idx, .. }) = app.get_selected_tree_item() {
-                app.toggle_reviewed_for_file_idx(file_idx, false);
+                app.cycle_review_state_for_file_idx(file_idx, false);
+            } else {
+                app.set_warning("Select a file to change its review state");
+            }
+        }
+        Action::ToggleExcluded => {
+            if let Some(FileTreeItem::File { file_idx, .. }) = app.get_selected_tree_item() {
+                app.toggle_excluded_for_file_idx(file_idx);
+            } else {
+                app.set_warning("Select a file to exclude from the review");
+            }
+        }
+        Action::AddFileComment => match app.get_selected_tree_item() {
+            Some(FileTreeItem::Directory { path, .. }) => {
+                app.enter_general_comment_mode(GeneralTarget::Directory(PathBuf::from(path)));
+            }
+            _ => handle_shared_normal_action(app, action),
+        },
+        Action::MoveFileDown | Action::MoveFileUp => {
+            if app.plan_mode {
+                app.move_file_in_plan(action == Action::MoveFileDown);
             } else {
-                app.set_warning("Select a file to toggle reviewed");
+                app.set_message("Use :plan to reorder files");
             }
         }
         _ => handle_shared_normal_action(app, action),
     }
 }
 
+/// The actions a key stands for in normal mode. Comments pane keys that act
+/// at once come first, then chords: a prefix waits for its second key
+pub fn normal_key_actions(app: &mut App, key: KeyEvent, now: Instant) -> Vec<Action> {
+    if app.focused_panel == FocusedPanel::Comments
+        && let Some(action) = map_comments_pane_key(key)
+    {
+        app.chords.cancel();
+        return vec![action];
+    }
+    match app.chords.press(key, now) {
+        ChordStep::Complete(action) => vec![action],
+        ChordStep::Pending(interrupted) => interrupted.into_iter().collect(),
+        ChordStep::Pass(interrupted) => interrupted
+            .into_iter()
+            .chain([map_key_to_action(key, InputMode::Normal)])
+            .collect(),
+    }
+}
+
+/// Handle actions when the comments pane is focused
+pub fn handle_comments_pane_action(app: &mut App, action: Action) {
+    match action {
+        Action::CursorDown(n) => app.comments_pane_down(n),
+        Action::CursorUp(n) => app.comments_pane_up(n),
+        Action::GoToTop => app.comments_pane.cursor = 0,
+        Action::GoToBottom => app.comments_pane_down(usize::MAX),
+        Action::SelectFile => app.jump_to_pane_comment(),
+        Action::EditComment => app.edit_pane_comment(),
+        Action::ToggleResolved => app.toggle_resolved_pane_comment(),
+        Action::DeleteCommentAtCursor => app.delete_pane_comment(),
+        Action::ExitMode => app.focused_panel = FocusedPanel::Diff,
+        _ => handle_shared_normal_action(app, action),
+    }
+}
+
 /// Handle actions when diff panel is focused
 pub fn handle_diff_action(app: &mut App, action: Action) {
     match action {
@@ -441,6 +1106,18 @@ pub fn handle_diff_action(app: &mut App, action: Action) {
         Action::CursorUp(n) => app.cursor_up(n),
         Action::ScrollLeft(n) => app.scroll_left(n),
         Action::ScrollRight(n) => app.scroll_right(n),
+        Action::WordForward => app.move_column(next_word_start),
+        Action::WordBackward => app.move_column(prev_word_start),
+        Action::WordEnd if app.cursor_on_comment() => {
+            app.enter_edit_mode();
+        }
+        Action::WordEnd => app.move_column(next_word_end),
+        Action::ExitMode if app.diff_state.cursor_col.is_some() => {
+            app.diff_state.cursor_col = None;
+        }
+        Action::AddLineComment | Action::AddFileComment if app.cursor_in_general_section() => {
+            app.enter_general_comment_mode(GeneralTarget::Session);
+        }
         Action::SelectFile => {
             // Check if cursor is on an expander line or expanded content
             if let Some((gap_id, is_expanded)) = app.get_gap_at_cursor() {
@@ -453,6 +1130,15 @@ pub fn handle_diff_action(app: &mut App, action: Action) {
                         app.set_error(format!("Failed to expand: {e}"));
                     }
                 }
+            } else if let Some((file_idx, hunk_idx)) = app.hunk_header_at_cursor()
+                && let Err(e) = app.expand_hunk(file_idx, hunk_idx)
+            {
+                app.set_error(format!("Failed to expand: {e}"));
+            }
+        }
+        Action::ShowFunctionDiff => {
+            if let Err(e) = app.open_function_diff() {
+                app.set_error(format!("Failed to load function: {e}"));
             }
         }
         _ => handle_shared_normal_action(app, action),
@@ -486,16 +1172,47 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
         }
         Action::NextFile => app.next_file(),
         Action::PrevFile => app.prev_file(),
+        Action::ReviewAgainstBase => {
+            if let Err(e) = app.review_against_base(None) {
+                app.set_error(format!("Failed to review against base: {e}"));
+            }
+        }
         Action::NextHunk => app.next_hunk(),
         Action::PrevHunk => app.prev_hunk(),
-        Action::ToggleReviewed => app.toggle_reviewed(),
+        Action::CenterCursor => app.center_cursor(),
+        Action::CursorToView => app.cursor_to_view(),
+        Action::ToggleFreeScroll => app.toggle_free_scroll(),
+        Action::JumpToAlternateFile => app.jump_to_alternate_file(),
+        Action::ToggleFileList => app.toggle_file_list(),
+        Action::FocusFileList => app.focused_panel = FocusedPanel::FileList,
+        Action::FocusDiff => app.focused_panel = FocusedPanel::Diff,
+        Action::DeleteCommentAtCursor if !app.delete_comment_at_cursor() => {
+            app.set_message("No comment at cursor");
+        }
+        Action::RunCommand(command) => {
+            app.enter_command_mode();
+            app.command_buffer = command;
+            handle_command_action(app, Action::SubmitInput);
+        }
+        Action::CycleReviewState => app.cycle_review_state(),
+        Action::ToggleExcluded => app.toggle_excluded(),
         Action::ToggleFocus => {
+            if !app.show_file_list {
+                app.show_file_list = true;
+                app.focused_panel = FocusedPanel::FileList;
+                return;
+            }
             app.focused_panel = match app.focused_panel {
                 FocusedPanel::FileList => FocusedPanel::Diff,
-                FocusedPanel::Diff => FocusedPanel::FileList,
+                FocusedPanel::Diff if app.has_comments_pane() => FocusedPanel::Comments,
+                FocusedPanel::Diff | FocusedPanel::Comments => FocusedPanel::FileList,
             };
         }
         Action::ExpandAll => {
+            // In the diff, `o` expands a collapsed generated file instead
+            if app.focused_panel == FocusedPanel::Diff && app.toggle_generated_file() {
+                return;
+            }
             app.expand_all_dirs();
             app.set_message("All directories expanded");
         }
@@ -510,17 +1227,40 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
             let line = app.get_line_at_cursor();
             if line.is_some() {
                 app.enter_comment_mode(false, line);
+            } else if app.cursor_on_special_entry() {
+                // Special entries have no lines, so the comment attaches to the file
+                app.enter_comment_mode(true, None);
             } else {
                 app.set_message("Move cursor to a diff line to add a line comment");
             }
         }
         Action::AddFileComment => app.enter_comment_mode(true, None),
-        Action::EditComment => {
-            if !app.enter_edit_mode() {
-                app.set_message("No comment at cursor");
-            }
+        Action::QuoteLineComment if !app.enter_quote_comment_mode() => {
+            app.set_message("Move cursor to a diff line to quote it");
+        }
+        Action::EditComment if !app.enter_edit_mode() => {
+            app.set_message("No comment at cursor");
         }
-        Action::ExportToClipboard => handle_export(app),
+        Action::ReplyToComment if !app.enter_reply_mode() => {
+            app.set_message("No comment at cursor");
+        }
+        Action::ToggleResolved if !app.toggle_resolved_at_cursor() => {
+            app.set_message("No comment at cursor");
+        }
+        Action::ShowCommentList => app.open_comment_list(),
+        Action::ToggleCommentGroup(group) => app.toggle_comment_group(group),
+        Action::ApplySuggestion => match app.suggestion_at_cursor() {
+            Some(id) => app.enter_confirm_mode(app::ConfirmAction::ApplySuggestion(id)),
+            None => app.set_message("No suggested change at cursor"),
+        },
+        Action::UndoDeleteComment if !app.undo_delete_comment() => {
+            app.set_message("No deleted comment to restore");
+        }
+        Action::YankComment if !app.yank_comment_at_cursor() => {
+            app.set_message("No comment at cursor");
+        }
+        Action::PasteComment => app.paste_comment(),
+        Action::ExportToClipboard => handle_export(app, false),
         Action::SearchNext => {
             app.search_next_in_diff();
         }
@@ -537,3 +1277,132 @@ fn handle_shared_normal_action(app: &mut App, action: Action) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::comment_list_tests::app_with_comments;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    #[test]
+    fn should_read_context_count_with_space_or_equals() {
+        assert_eq!(set_context_arg("set context 10"), Some("10"));
+        assert_eq!(set_context_arg("set context=0"), Some("0"));
+        assert_eq!(set_context_arg("set contexts 5"), None);
+        assert_eq!(set_context_arg("set context"), None);
+    }
+
+    #[test]
+    fn should_cycle_focus_through_the_comments_pane() {
+        // given a review with comments and the file list shown
+        let (_temp, mut app) = app_with_comments();
+        app.show_file_list = true;
+        app.focused_panel = FocusedPanel::FileList;
+
+        // when Tab is pressed three times
+        let mut focus = Vec::new();
+        for _ in 0..3 {
+            handle_shared_normal_action(&mut app, Action::ToggleFocus);
+            focus.push(app.focused_panel);
+        }
+
+        // then the focus goes diff, comments, file list
+        assert_eq!(
+            focus,
+            [
+                FocusedPanel::Diff,
+                FocusedPanel::Comments,
+                FocusedPanel::FileList
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_the_comments_pane_without_comments() {
+        // given the diff focused and no comments left
+        let (_temp, mut app) = app_with_comments();
+        app.show_file_list = true;
+        app.focused_panel = FocusedPanel::Diff;
+        app.session.clear_comments();
+
+        // when
+        handle_shared_normal_action(&mut app, Action::ToggleFocus);
+
+        // then
+        assert_eq!(app.focused_panel, FocusedPanel::FileList);
+    }
+
+    fn feed(app: &mut App, c: char) {
+        let key = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        for action in normal_key_actions(app, key, Instant::now()) {
+            handle_comments_pane_action(app, action);
+        }
+    }
+
+    #[test]
+    fn should_go_to_the_first_pane_comment_on_gg() {
+        // given the comments pane focused further down the list
+        let (_temp, mut app) = app_with_comments();
+        app.focused_panel = FocusedPanel::Comments;
+        app.comments_pane_down(3);
+        assert_ne!(app.comments_pane.cursor, 0);
+
+        // when
+        feed(&mut app, 'g');
+        feed(&mut app, 'g');
+
+        // then
+        assert_eq!(app.comments_pane.cursor, 0);
+    }
+
+    #[test]
+    fn should_delete_the_pane_comment_on_a_single_d() {
+        // given the comments pane focused
+        let (_temp, mut app) = app_with_comments();
+        app.focused_panel = FocusedPanel::Comments;
+        let before = app.comments_pane_entries().len();
+
+        // when
+        feed(&mut app, 'd');
+
+        // then
+        assert_eq!(app.comments_pane_entries().len(), before - 1);
+    }
+
+    #[test]
+    fn should_edit_the_comment_under_the_cursor_on_e() {
+        // given the cursor on a comment line
+        let (_temp, mut app) = app_with_comments();
+        app.focused_panel = FocusedPanel::Diff;
+        app.diff_state.cursor_line = (0..app.line_annotations.len())
+            .find(|&line| {
+                app.diff_state.cursor_line = line;
+                app.cursor_on_comment()
+            })
+            .unwrap();
+
+        // when
+        handle_diff_action(&mut app, Action::WordEnd);
+
+        // then
+        assert_eq!(app.input_mode, InputMode::Comment);
+        assert!(app.editing_comment_id.is_some());
+    }
+
+    #[test]
+    fn should_close_a_popup_list_on_q_without_quitting() {
+        // given the test map open
+        let (_temp, mut app) = app_with_comments();
+        app.open_test_map();
+        assert_eq!(app.input_mode, InputMode::TestMap);
+
+        // when
+        let key = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        let action = map_key_to_action(key, app.input_mode);
+        handle_test_map_action(&mut app, action);
+
+        // then
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(!app.should_quit);
+    }
+}
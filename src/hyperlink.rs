@@ -0,0 +1,298 @@
+//! OSC 8 hyperlinks on file paths and commit SHAs, so Cmd/Ctrl+click opens
+//! them from the TUI.
+//!
+//! ratatui has no notion of links: the UI records where each one is drawn,
+//! and after the frame those cells are written out again wrapped in the
+//! escape sequence. Terminals without OSC 8 support are left alone.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+use crossterm::queue;
+use crossterm::style::{
+    Attribute, Color as CtColor, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+/// A link drawn on one row of the screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    pub area: Rect,
+    pub url: String,
+}
+
+/// Where clickable paths and SHAs point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkTargets {
+    /// Absolute repository root, for `file://` URLs
+    pub root: PathBuf,
+    /// Web URL of the `origin` remote's project, for commit links
+    pub forge: Option<String>,
+}
+
+impl LinkTargets {
+    /// Targets for the repository at `root`, or None when links are off:
+    /// `setting` is the `hyperlinks` config, None meaning "if supported"
+    pub fn detect(root: &Path, setting: Option<bool>) -> Option<Self> {
+        let enabled = setting.unwrap_or_else(|| supported_by(|name| std::env::var(name).ok()));
+        if !enabled {
+            return None;
+        }
+        let forge = git2::Repository::open(root).ok().and_then(|repo| {
+            let remote = repo.find_remote("origin").ok()?;
+            forge_url(remote.url()?)
+        });
+        Some(Self {
+            root: std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()),
+            forge,
+        })
+    }
+
+    /// `file://` URL of a path relative to the repository root
+    pub fn file(&self, path: &Path) -> String {
+        file_url(&self.root.join(path))
+    }
+
+    /// Forge page of a commit, when the remote is a known web host
+    pub fn commit(&self, sha: &str) -> Option<String> {
+        let forge = self.forge.as_deref()?;
+        let page = if forge.contains("gitlab") {
+            "-/commit"
+        } else if forge.contains("bitbucket") {
+            "commits"
+        } else {
+            "commit"
+        };
+        Some(format!("{forge}/{page}/{sha}"))
+    }
+}
+
+/// Whether the terminal described by the environment renders OSC 8 links.
+/// tmux swallows them unless configured to pass them on, so it counts as no.
+fn supported_by(env: impl Fn(&str) -> Option<String>) -> bool {
+    if env("TMUX").is_some() {
+        return false;
+    }
+    let term_program = env("TERM_PROGRAM").unwrap_or_default();
+    let term = env("TERM").unwrap_or_default();
+    matches!(
+        term_program.as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+    ) || env("KITTY_WINDOW_ID").is_some()
+        || env("WT_SESSION").is_some()
+        || env("VTE_VERSION")
+            .and_then(|version| version.parse::<u32>().ok())
+            .is_some_and(|version| version >= 5000)
+        || term.contains("kitty")
+        || term.starts_with("foot")
+        || term == "alacritty"
+}
+
+/// `https://host/group/project` for a remote URL in any form git accepts
+fn forge_url(remote: &str) -> Option<String> {
+    let (host, project) = crate::gitlab::parse_remote_url(remote)?;
+    Some(format!("https://{host}/{project}"))
+}
+
+/// `file://` URL of an absolute path, percent-encoding what URLs can't hold
+fn file_url(path: &Path) -> String {
+    let mut url = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => {
+                let _ = write!(url, "%{byte:02X}");
+            }
+        }
+    }
+    url
+}
+
+/// The cells of a link as they were drawn, up to the last non-blank one
+#[derive(Debug, Clone)]
+pub struct DrawnLink {
+    x: u16,
+    y: u16,
+    url: String,
+    cells: Vec<Cell>,
+}
+
+/// Pick the drawn cells of each link out of a finished frame
+pub fn drawn_links(buffer: &Buffer, links: &[Hyperlink]) -> Vec<DrawnLink> {
+    links
+        .iter()
+        .filter_map(|link| {
+            let area = link.area.intersection(buffer.area);
+            let mut cells: Vec<Cell> = (area.x..area.right())
+                .map(|x| buffer[(x, area.y)].clone())
+                .collect();
+            while cells
+                .last()
+                .is_some_and(|cell| cell.symbol().trim().is_empty())
+            {
+                cells.pop();
+            }
+            (!cells.is_empty()).then(|| DrawnLink {
+                x: area.x,
+                y: area.y,
+                url: link.url.clone(),
+                cells,
+            })
+        })
+        .collect()
+}
+
+/// Write the links over the frame, keeping the cursor where ratatui left it
+pub fn write_links(out: &mut impl Write, links: &[DrawnLink]) -> io::Result<()> {
+    if links.is_empty() {
+        return Ok(());
+    }
+    queue!(out, SavePosition)?;
+    for link in links {
+        queue!(
+            out,
+            MoveTo(link.x, link.y),
+            Print(format!("\x1b]8;;{}\x1b\\", link.url))
+        )?;
+        let mut skip = 0;
+        for cell in &link.cells {
+            // The cell after a wide character is covered by it
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            queue!(
+                out,
+                SetAttribute(Attribute::Reset),
+                SetForegroundColor(crossterm_color(cell.fg)),
+                SetBackgroundColor(crossterm_color(cell.bg)),
+            )?;
+            for (modifier, attribute) in [
+                (Modifier::BOLD, Attribute::Bold),
+                (Modifier::DIM, Attribute::Dim),
+                (Modifier::ITALIC, Attribute::Italic),
+                (Modifier::UNDERLINED, Attribute::Underlined),
+                (Modifier::REVERSED, Attribute::Reverse),
+                (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+            ] {
+                if cell.modifier.contains(modifier) {
+                    queue!(out, SetAttribute(attribute))?;
+                }
+            }
+            queue!(out, Print(cell.symbol()))?;
+            skip = cell.symbol().width().saturating_sub(1);
+        }
+        queue!(out, Print("\x1b]8;;\x1b\\"), SetAttribute(Attribute::Reset))?;
+    }
+    queue!(out, RestorePosition)?;
+    out.flush()
+}
+
+fn crossterm_color(color: Color) -> CtColor {
+    match color {
+        Color::Reset => CtColor::Reset,
+        Color::Black => CtColor::Black,
+        Color::Red => CtColor::DarkRed,
+        Color::Green => CtColor::DarkGreen,
+        Color::Yellow => CtColor::DarkYellow,
+        Color::Blue => CtColor::DarkBlue,
+        Color::Magenta => CtColor::DarkMagenta,
+        Color::Cyan => CtColor::DarkCyan,
+        Color::Gray => CtColor::Grey,
+        Color::DarkGray => CtColor::DarkGrey,
+        Color::LightRed => CtColor::Red,
+        Color::LightGreen => CtColor::Green,
+        Color::LightYellow => CtColor::Yellow,
+        Color::LightBlue => CtColor::Blue,
+        Color::LightMagenta => CtColor::Magenta,
+        Color::LightCyan => CtColor::Cyan,
+        Color::White => CtColor::White,
+        Color::Rgb(r, g, b) => CtColor::Rgb { r, g, b },
+        Color::Indexed(i) => CtColor::AnsiValue(i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn should_detect_terminals_that_render_links() {
+        assert!(supported_by(env(&[("TERM_PROGRAM", "WezTerm")])));
+        assert!(supported_by(env(&[("VTE_VERSION", "7003")])));
+        assert!(supported_by(env(&[("TERM", "xterm-kitty")])));
+        assert!(!supported_by(env(&[("TERM", "xterm-256color")])));
+        assert!(!supported_by(env(&[
+            ("TERM_PROGRAM", "iTerm.app"),
+            ("TMUX", "/tmp/tmux-1000/default,1,0"),
+        ])));
+    }
+
+    #[test]
+    fn should_link_files_and_commits() {
+        // given
+        let targets = LinkTargets {
+            root: PathBuf::from("/home/me/my repo"),
+            forge: forge_url("git@github.com:me/app.git"),
+        };
+
+        // then
+        assert_eq!(
+            targets.file(Path::new("src/main.rs")),
+            "file:///home/me/my%20repo/src/main.rs"
+        );
+        assert_eq!(
+            targets.commit("abc1234").as_deref(),
+            Some("https://github.com/me/app/commit/abc1234")
+        );
+        let gitlab = LinkTargets {
+            forge: forge_url("https://gitlab.example.com/group/app.git"),
+            ..targets
+        };
+        assert_eq!(
+            gitlab.commit("abc1234").as_deref(),
+            Some("https://gitlab.example.com/group/app/-/commit/abc1234")
+        );
+    }
+
+    #[test]
+    fn should_write_drawn_cells_inside_the_link() {
+        // given "ab" drawn at the start of a row
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 1));
+        buffer.set_string(0, 0, "ab", ratatui::style::Style::default());
+        let links = drawn_links(
+            &buffer,
+            &[Hyperlink {
+                area: Rect::new(0, 0, 6, 1),
+                url: "file:///x".to_string(),
+            }],
+        );
+
+        // when
+        let mut out = Vec::new();
+        write_links(&mut out, &links).unwrap();
+
+        // then the trailing blanks are not linked
+        let written = String::from_utf8(out).unwrap();
+        let start = written.find("\x1b]8;;file:///x\x1b\\").unwrap();
+        let end = written.rfind("\x1b]8;;\x1b\\").unwrap();
+        assert!(written[start..end].contains('a'));
+        assert!(written[start..end].contains('b'));
+        assert_eq!(links[0].cells.len(), 2);
+    }
+}
@@ -0,0 +1,245 @@
+//! Callers of functions whose signature changed.
+//!
+//! A signature change is a removed and an added definition line naming the
+//! same function in one file. Each name is then
+//! searched across the repository with ripgrep as a whole word; files that
+//! mention it but are not part of the diff may need the same update.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Result, TuicrError};
+use crate::model::{DiffFile, LineOrigin};
+
+/// Keywords that open a definition line; diff lines come without the rest of
+/// the file, so there is nothing to parse
+const DEFINITION_KEYWORDS: &[&str] = &["fn", "def", "func", "function", "sub"];
+/// How many leading words may precede the keyword (`pub(crate) async fn`)
+const MAX_MODIFIERS: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedSignature {
+    pub name: String,
+    /// File declaring the function
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+}
+
+/// Files outside the diff that mention a changed function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolImpact {
+    pub signature: ChangedSignature,
+    /// Repository-relative path and number of matching lines
+    pub references: Vec<(PathBuf, usize)>,
+}
+
+/// Functions whose definition line was both removed and re-added with
+/// different text, in file order
+pub fn changed_signatures(files: &[DiffFile]) -> Vec<ChangedSignature> {
+    let mut signatures = Vec::new();
+    for file in files {
+        let mut removed: Vec<(String, String)> = Vec::new();
+        let mut added: Vec<(String, String)> = Vec::new();
+        for line in file.hunks.iter().flat_map(|hunk| hunk.lines.iter()) {
+            let Some(name) = definition_name(&line.content) else {
+                continue;
+            };
+            let text = line.content.trim().to_string();
+            match line.origin {
+                LineOrigin::Deletion => removed.push((name, text)),
+                LineOrigin::Addition => added.push((name, text)),
+                LineOrigin::Context => {}
+            }
+        }
+
+        for (name, new) in added {
+            let Some(pos) = removed.iter().position(|(old_name, _)| *old_name == name) else {
+                continue;
+            };
+            let (_, old) = removed.remove(pos);
+            if old != new && !signatures.iter().any(|s: &ChangedSignature| s.name == name) {
+                signatures.push(ChangedSignature {
+                    name,
+                    path: file.display_path().clone(),
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+    signatures
+}
+
+/// The function name if this line looks like a function definition
+fn definition_name(line: &str) -> Option<String> {
+    let mut rest = line.trim_start();
+    for _ in 0..=MAX_MODIFIERS {
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        rest = rest[word_end..].trim_start();
+        if DEFINITION_KEYWORDS.contains(&word) {
+            return identifier_after_keyword(rest);
+        }
+        if word.is_empty()
+            || !word
+                .chars()
+                .all(|c| c.is_alphanumeric() || "()_".contains(c))
+        {
+            return None;
+        }
+    }
+    None
+}
+
+fn identifier_after_keyword(rest: &str) -> Option<String> {
+    // Go methods put the receiver before the name: `func (r *T) Name(`
+    let rest = match rest.strip_prefix('(') {
+        Some(receiver) => receiver.split_once(')')?.1.trim_start(),
+        None => rest,
+    };
+    let name: String = rest
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Search the repository for each changed function, skipping files in `changed`.
+/// Runs one ripgrep per name, so call it off the UI thread.
+pub fn find_references(
+    root: &Path,
+    signatures: Vec<ChangedSignature>,
+    changed: &HashSet<PathBuf>,
+) -> Result<Vec<SymbolImpact>> {
+    let mut impacts = Vec::new();
+    for signature in signatures {
+        let output = Command::new("rg")
+            .current_dir(root)
+            .args(["--count", "--word-regexp", "--fixed-strings", "--"])
+            .arg(&signature.name)
+            .output()
+            .map_err(|e| TuicrError::VcsCommand(format!("Failed to run rg: {e}")))?;
+        // Exit status 1 means no matches
+        if !output.status.success() && output.status.code() != Some(1) {
+            return Err(TuicrError::VcsCommand(format!(
+                "rg failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let references = parse_counts(&String::from_utf8_lossy(&output.stdout), changed);
+        if !references.is_empty() {
+            impacts.push(SymbolImpact {
+                signature,
+                references,
+            });
+        }
+    }
+    Ok(impacts)
+}
+
+/// Parse `path:count` lines of `rg --count`, sorted by path
+fn parse_counts(output: &str, changed: &HashSet<PathBuf>) -> Vec<(PathBuf, usize)> {
+    let mut references: Vec<(PathBuf, usize)> = output
+        .lines()
+        .filter_map(|line| {
+            let (path, count) = line.rsplit_once(':')?;
+            let path = PathBuf::from(path.strip_prefix("./").unwrap_or(path));
+            let count = count.parse().ok()?;
+            (!changed.contains(&path)).then_some((path, count))
+        })
+        .collect();
+    references.sort();
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DiffHunk, DiffLine, FileStatus};
+
+    fn file(path: &str, lines: &[(LineOrigin, &str)]) -> DiffFile {
+        DiffFile {
+            old_path: Some(PathBuf::from(path)),
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,1 +1,1 @@".to_string(),
+                lines: lines
+                    .iter()
+                    .map(|(origin, content)| DiffLine {
+                        origin: *origin,
+                        content: content.to_string(),
+                        old_lineno: None,
+                        new_lineno: None,
+                        highlighted_spans: None,
+                    })
+                    .collect(),
+                old_start: 1,
+                old_count: 1,
+                new_start: 1,
+                new_count: 1,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    #[test]
+    fn should_find_changed_signatures() {
+        // given one function with a new parameter, one moved unchanged and one added
+        let files = vec![file(
+            "src/lib.rs",
+            &[
+                (LineOrigin::Deletion, "pub fn parse(input: &str) -> Ast {"),
+                (LineOrigin::Deletion, "fn helper() {"),
+                (
+                    LineOrigin::Addition,
+                    "pub fn parse(input: &str, strict: bool) -> Ast {",
+                ),
+                (LineOrigin::Addition, "fn helper() {"),
+                (LineOrigin::Addition, "fn brand_new() {"),
+            ],
+        )];
+
+        // when
+        let signatures = changed_signatures(&files);
+
+        // then only the real signature change is reported
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].name, "parse");
+        assert_eq!(signatures[0].old, "pub fn parse(input: &str) -> Ast {");
+    }
+
+    #[test]
+    fn should_read_go_method_name_after_receiver() {
+        assert_eq!(
+            definition_name("func (s *Server) Start(ctx context.Context) error {"),
+            Some("Start".to_string())
+        );
+    }
+
+    #[test]
+    fn should_skip_changed_files_in_counts() {
+        // given rg output listing a changed and an unchanged file
+        let changed: HashSet<PathBuf> = [PathBuf::from("src/lib.rs")].into_iter().collect();
+        let output = "src/lib.rs:4\n./src/cli.rs:2\nbenches/parse.rs:1\n";
+
+        // when
+        let references = parse_counts(output, &changed);
+
+        // then
+        assert_eq!(
+            references,
+            vec![
+                (PathBuf::from("benches/parse.rs"), 1),
+                (PathBuf::from("src/cli.rs"), 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Indentation warnings for whitespace-sensitive languages.
+//!
+//! In Python or YAML a hunk that shifts a block by one level, or slips a tab
+//! in among spaces, changes meaning while looking like noise in a unified diff.
+//! These checks flag such hunks so the renderer can annotate their headers.
+//! The git backend expands tabs while parsing, so for git diffs only the
+//! re-indentation check can fire; tab/space mixing is caught for hg, jj and
+//! stdin diffs, which keep the raw line content.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::model::{DiffHunk, LineOrigin};
+
+const INDENT_SENSITIVE_EXTENSIONS: &[&str] = &[
+    "py", "pyi", "pyw", "yaml", "yml", "coffee", "nim", "haml", "pug", "sass", "slim", "styl",
+];
+
+/// Whether indentation changes the meaning of this file
+pub fn is_indent_sensitive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| INDENT_SENSITIVE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Warnings for one hunk of an indentation-sensitive file
+pub fn hunk_warnings(hunk: &DiffHunk) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let reindented = reindented_lines(hunk);
+    if reindented > 0 {
+        let plural = if reindented == 1 { "" } else { "s" };
+        warnings.push(format!(
+            "indentation depth changed on {reindented} otherwise unchanged line{plural}"
+        ));
+    }
+    if mixes_tabs_and_spaces(hunk) {
+        warnings.push("mixed tabs and spaces".to_string());
+    }
+
+    warnings
+}
+
+/// Count added lines that only differ from a removed line by leading whitespace
+fn reindented_lines(hunk: &DiffHunk) -> usize {
+    let mut removed: HashMap<&str, Vec<&str>> = HashMap::new();
+    for line in &hunk.lines {
+        if line.origin == LineOrigin::Deletion && !line.content.trim().is_empty() {
+            let (indent, code) = split_indent(&line.content);
+            removed.entry(code).or_default().push(indent);
+        }
+    }
+
+    let mut count = 0;
+    for line in &hunk.lines {
+        if line.origin != LineOrigin::Addition {
+            continue;
+        }
+        let (indent, code) = split_indent(&line.content);
+        if let Some(indents) = removed.get_mut(code)
+            && let Some(pos) = indents.iter().position(|old| *old != indent)
+            && !indents.contains(&indent)
+        {
+            indents.swap_remove(pos);
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Whether added lines indent with tabs while the rest of the hunk uses spaces
+/// (or vice versa), or an added line mixes both in its own indentation
+fn mixes_tabs_and_spaces(hunk: &DiffHunk) -> bool {
+    let mut added_tabs = false;
+    let mut added_spaces = false;
+    let mut other_tabs = false;
+    let mut other_spaces = false;
+
+    for line in &hunk.lines {
+        let (indent, code) = split_indent(&line.content);
+        if code.is_empty() {
+            continue;
+        }
+        let tabs = indent.contains('\t');
+        let spaces = indent.contains(' ');
+        if line.origin == LineOrigin::Addition {
+            if tabs && spaces {
+                return true;
+            }
+            added_tabs |= tabs;
+            added_spaces |= spaces;
+        } else {
+            other_tabs |= tabs;
+            other_spaces |= spaces;
+        }
+    }
+
+    (added_tabs && (added_spaces || other_spaces)) || (added_spaces && other_tabs)
+}
+
+fn split_indent(content: &str) -> (&str, &str) {
+    let code = content.trim_start_matches([' ', '\t']);
+    (&content[..content.len() - code.len()], code.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DiffLine;
+
+    fn hunk(lines: &[(LineOrigin, &str)]) -> DiffHunk {
+        DiffHunk {
+            header: "@@ -1,1 +1,1 @@".to_string(),
+            lines: lines
+                .iter()
+                .map(|(origin, content)| DiffLine {
+                    origin: *origin,
+                    content: content.to_string(),
+                    old_lineno: None,
+                    new_lineno: None,
+                    highlighted_spans: None,
+                })
+                .collect(),
+            old_start: 1,
+            old_count: 1,
+            new_start: 1,
+            new_count: 1,
+        }
+    }
+
+    #[test]
+    fn should_only_check_indent_sensitive_extensions() {
+        assert!(is_indent_sensitive(Path::new("app/main.py")));
+        assert!(is_indent_sensitive(Path::new(".github/workflows/ci.YML")));
+        assert!(!is_indent_sensitive(Path::new("src/main.rs")));
+        assert!(!is_indent_sensitive(Path::new("Makefile")));
+    }
+
+    #[test]
+    fn should_warn_when_block_is_reindented() {
+        // given a hunk that moves a return statement out of an if block
+        let hunk = hunk(&[
+            (LineOrigin::Context, "    if ok:"),
+            (LineOrigin::Deletion, "        return x"),
+            (LineOrigin::Addition, "    return x"),
+        ]);
+
+        // when checked
+        let warnings = hunk_warnings(&hunk);
+
+        // then the depth change is reported
+        assert_eq!(
+            warnings,
+            vec!["indentation depth changed on 1 otherwise unchanged line"]
+        );
+    }
+
+    #[test]
+    fn should_warn_on_tab_added_among_spaces() {
+        // given a space-indented block gaining a tab-indented line
+        let hunk = hunk(&[
+            (LineOrigin::Context, "    a = 1"),
+            (LineOrigin::Addition, "\tb = 2"),
+        ]);
+
+        // when checked
+        let warnings = hunk_warnings(&hunk);
+
+        // then mixing is reported
+        assert_eq!(warnings, vec!["mixed tabs and spaces"]);
+    }
+
+    #[test]
+    fn should_not_warn_on_plain_edits() {
+        // given a hunk that changes code at the same depth
+        let hunk = hunk(&[
+            (LineOrigin::Deletion, "    a = 1"),
+            (LineOrigin::Addition, "    a = 2"),
+            (LineOrigin::Addition, ""),
+        ]);
+
+        // when checked / then nothing is reported
+        assert!(hunk_warnings(&hunk).is_empty());
+    }
+}
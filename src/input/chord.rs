@@ -0,0 +1,223 @@
+//! Two-key chords in normal mode: `gg`, `zz`, `dd`, `;e` and the like, plus
+//! custom ones from the config that run a `:` command.
+//!
+//! A prefix key waits for the next key until the timeout. When the next key
+//! completes no chord, or none comes in time, a prefix that also has a
+//! single-key action (a custom `x?` chord shadowing `x`) runs that action.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::{Action, map_key_to_action};
+use crate::app::InputMode;
+
+/// How long a prefix waits for the second key by default
+pub const DEFAULT_CHORD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// What a key in normal mode turned out to be
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordStep {
+    /// The key starts a chord: wait for the next one, after running the
+    /// action of a prefix it interrupted
+    Pending(Option<Action>),
+    /// The key completes a chord
+    Complete(Action),
+    /// Not a chord: handle the key as usual, after the action of a prefix
+    /// it interrupted
+    Pass(Option<Action>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Chords {
+    chords: Vec<([char; 2], Action)>,
+    timeout: Duration,
+    pending: Option<(KeyEvent, Instant)>,
+}
+
+impl Chords {
+    /// Built-in chords plus `custom` ones (two keys to a `:` command, which
+    /// win over built-ins on the same keys). Entries that are not exactly
+    /// two keys are ignored.
+    pub fn new(custom: &BTreeMap<String, String>, timeout_ms: Option<u64>) -> Self {
+        let mut chords: Vec<([char; 2], Action)> = custom
+            .iter()
+            .filter_map(|(keys, command)| {
+                let mut chars = keys.chars();
+                let keys = [chars.next()?, chars.next()?];
+                let command = command.trim().trim_start_matches(':');
+                (chars.next().is_none() && !command.is_empty())
+                    .then(|| (keys, Action::RunCommand(command.to_string())))
+            })
+            .collect();
+        chords.extend([
+            (['g', 'g'], Action::GoToTop),
+            (['g', 'a'], Action::JumpToAlternateFile),
+            (['z', 'z'], Action::CenterCursor),
+            (['z', 'v'], Action::CursorToView),
+            (['z', 'f'], Action::ToggleFreeScroll),
+            (['d', 'd'], Action::DeleteCommentAtCursor),
+            ([';', 'e'], Action::ToggleFileList),
+            ([';', 'h'], Action::FocusFileList),
+            ([';', 'l'], Action::FocusDiff),
+        ]);
+        Self {
+            chords,
+            timeout: timeout_ms.map_or(DEFAULT_CHORD_TIMEOUT, Duration::from_millis),
+            pending: None,
+        }
+    }
+
+    /// The prefix waiting for its second key, for the status bar
+    pub fn pending(&self) -> Option<char> {
+        self.pending.and_then(|(key, _)| chord_char(&key))
+    }
+
+    /// Time left before the pending prefix gives up
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.pending
+            .map(|(_, started)| self.timeout.saturating_sub(now.duration_since(started)))
+    }
+
+    /// Drop a prefix whose time ran out, returning its own action if it has one
+    pub fn expire(&mut self, now: Instant) -> Option<Action> {
+        let (key, started) = self.pending?;
+        if now.duration_since(started) < self.timeout {
+            return None;
+        }
+        self.pending = None;
+        own_action(key)
+    }
+
+    /// Forget a pending prefix, when normal mode is left some other way
+    pub fn cancel(&mut self) {
+        self.pending = None;
+    }
+
+    /// Feed a normal-mode key
+    pub fn press(&mut self, key: KeyEvent, now: Instant) -> ChordStep {
+        let c = chord_char(&key);
+        let interrupted = match self.pending.take() {
+            Some((prefix, started)) => {
+                let second = c.filter(|_| now.duration_since(started) < self.timeout);
+                if let Some(action) = chord_char(&prefix)
+                    .zip(second)
+                    .and_then(|(first, second)| self.find([first, second]))
+                {
+                    return ChordStep::Complete(action);
+                }
+                own_action(prefix)
+            }
+            None => None,
+        };
+        if c.is_some_and(|c| self.chords.iter().any(|(keys, _)| keys[0] == c)) {
+            self.pending = Some((key, now));
+            ChordStep::Pending(interrupted)
+        } else {
+            ChordStep::Pass(interrupted)
+        }
+    }
+
+    fn find(&self, keys: [char; 2]) -> Option<Action> {
+        self.chords
+            .iter()
+            .find(|(chord, _)| *chord == keys)
+            .map(|(_, action)| action.clone())
+    }
+}
+
+/// The character of a key that can take part in a chord: no Ctrl or Alt
+fn chord_char(key: &KeyEvent) -> Option<char> {
+    match key.code {
+        KeyCode::Char(c)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            Some(c)
+        }
+        _ => None,
+    }
+}
+
+/// What a prefix key does on its own
+fn own_action(key: KeyEvent) -> Option<Action> {
+    Some(map_key_to_action(key, InputMode::Normal)).filter(|action| *action != Action::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn should_complete_builtin_chords() {
+        // given
+        let mut chords = Chords::new(&BTreeMap::new(), None);
+        let now = Instant::now();
+
+        // when
+        let first = chords.press(key('g'), now);
+        let second = chords.press(key('g'), now);
+
+        // then
+        assert_eq!(first, ChordStep::Pending(None));
+        assert_eq!(second, ChordStep::Complete(Action::GoToTop));
+        assert_eq!(chords.pending(), None);
+    }
+
+    #[test]
+    fn should_pass_the_second_key_when_no_chord_matches() {
+        // given
+        let mut chords = Chords::new(&BTreeMap::new(), None);
+        let now = Instant::now();
+        chords.press(key('z'), now);
+
+        // when
+        let step = chords.press(key('j'), now);
+
+        // then
+        assert_eq!(step, ChordStep::Pass(None));
+    }
+
+    #[test]
+    fn should_run_custom_chords_and_replay_shadowed_keys() {
+        // given "xc" runs `:clip`, so `x` has to wait for the next key
+        let custom = BTreeMap::from([("xc".to_string(), ":clip".to_string())]);
+        let mut chords = Chords::new(&custom, Some(500));
+        let now = Instant::now();
+
+        // when
+        chords.press(key('x'), now);
+        let completed = chords.press(key('c'), now);
+        chords.press(key('x'), now);
+        let expired = chords.expire(now + Duration::from_millis(500));
+
+        // then
+        assert_eq!(
+            completed,
+            ChordStep::Complete(Action::RunCommand("clip".to_string()))
+        );
+        assert_eq!(expired, Some(Action::ToggleExcluded));
+        assert_eq!(chords.pending(), None);
+    }
+
+    #[test]
+    fn should_not_complete_a_chord_after_the_timeout() {
+        // given
+        let mut chords = Chords::new(&BTreeMap::new(), Some(200));
+        let now = Instant::now();
+        chords.press(key('d'), now);
+
+        // when
+        let step = chords.press(key('d'), now + Duration::from_millis(300));
+
+        // then the late `d` starts a new chord
+        assert_eq!(step, ChordStep::Pending(None));
+        assert_eq!(chords.pending(), Some('d'));
+    }
+}
@@ -1,6 +1,6 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::app::InputMode;
+use crate::app::{CommentGroup, InputMode};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
@@ -17,21 +17,40 @@ pub enum Action {
     PrevFile,
     NextHunk,
     PrevHunk,
-    PendingZCommand,
-    PendingSemicolonCommand,
+    CenterCursor,
+    CursorToView,
+    ToggleFreeScroll,
+    JumpToAlternateFile,
     ScrollLeft(usize),
     ScrollRight(usize),
 
     // Panel focus
     ToggleFocus,
     SelectFile,
+    ToggleFileList,
+    FocusFileList,
+    FocusDiff,
 
     // Review actions
-    ToggleReviewed,
+    CycleReviewState,
+    ToggleExcluded,
     AddLineComment,
+    QuoteLineComment,
     AddFileComment,
     EditComment,
-    PendingDCommand,
+    ReplyToComment,
+    ToggleResolved,
+    ApplySuggestion,
+    /// Column cursor word motions within the diff line (`w`, `b`, `e`)
+    WordForward,
+    WordBackward,
+    WordEnd,
+    UndoDeleteComment,
+    DeleteCommentAtCursor,
+    YankComment,
+    PasteComment,
+    CopySelection,
+    CopySelectionWithPrefixes,
     SearchNext,
     SearchPrev,
 
@@ -42,6 +61,8 @@ pub enum Action {
     // Session
     Quit,
     ExportToClipboard,
+    /// Run a `:` command, for custom chords
+    RunCommand(String),
 
     // Mode changes
     EnterCommandMode,
@@ -51,9 +72,17 @@ pub enum Action {
 
     // Text input
     InsertChar(char),
+    /// Pasted text, inserted in one go
+    InsertText(String),
+    PasteClipboard,
+    QuoteAnchor,
     DeleteChar,
     DeleteWord,
     ClearLine,
+    KillToLineEnd,
+    KillWordForward,
+    Yank,
+    RestoreDraft,
     SubmitInput,
     TextCursorLeft,
     TextCursorRight,
@@ -62,8 +91,10 @@ pub enum Action {
     TextCursorWordLeft,
     TextCursorWordRight,
 
-    // Comment type
+    // Comment type, severity and required action
     CycleCommentType,
+    CycleCommentSeverity,
+    CycleCommentRequirement,
 
     // Confirm dialog
     ConfirmYes,
@@ -79,6 +110,22 @@ pub enum Action {
     ExpandAll,
     CollapseAll,
 
+    // Function diff popup
+    ShowFunctionDiff,
+
+    // Review plan
+    MoveFileDown,
+    MoveFileUp,
+
+    // Review the branch against its base
+    ReviewAgainstBase,
+
+    // Comment list popup
+    ShowCommentList,
+
+    // Hide or show comments of a type in the diff
+    ToggleCommentGroup(CommentGroup),
+
     // No-op
     None,
 }
@@ -87,15 +134,38 @@ pub fn map_key_to_action(key: KeyEvent, mode: InputMode) -> Action {
     match mode {
         InputMode::Normal => map_normal_mode(key),
         InputMode::Command => map_command_mode(key),
-        InputMode::Search => map_search_mode(key),
+        InputMode::Search | InputMode::CommitFilter => map_search_mode(key),
         InputMode::Comment => map_comment_mode(key),
-        InputMode::Help => map_help_mode(key),
+        // The function and structural diff popups scroll and close like help
+        InputMode::Help
+        | InputMode::FunctionDiff
+        | InputMode::StructuralDiff
+        | InputMode::Migration
+        | InputMode::Impact => map_help_mode(key),
         InputMode::Confirm => map_confirm_mode(key),
+        InputMode::ExportPreview => map_export_preview_mode(key),
         InputMode::CommitSelect => map_commit_select_mode(key),
+        InputMode::WorktreeSelect
+        | InputMode::PublicApi
+        | InputMode::TestMap
+        | InputMode::Todos
+        | InputMode::StaleComments
+        | InputMode::Attachments => map_popup_list_mode(key),
+        InputMode::CommentList => map_comment_list_mode(key),
         InputMode::VisualSelect => map_visual_mode(key),
     }
 }
 
+/// Keys that act at once while the comments pane has focus: `d` deletes the
+/// selected comment. The rest, chords included, work as anywhere else in
+/// normal mode
+pub fn map_comments_pane_key(key: KeyEvent) -> Option<Action> {
+    match (key.code, key.modifiers) {
+        (KeyCode::Char('d'), KeyModifiers::NONE) => Some(Action::DeleteCommentAtCursor),
+        _ => None,
+    }
+}
+
 fn map_normal_mode(key: KeyEvent) -> Action {
     match (key.code, key.modifiers) {
         // Cursor movement (vim-like: cursor moves, scroll follows when needed)
@@ -107,10 +177,8 @@ fn map_normal_mode(key: KeyEvent) -> Action {
         (KeyCode::Char('b'), KeyModifiers::CONTROL) => Action::PageUp,
         (KeyCode::PageDown, KeyModifiers::NONE) => Action::PageDown,
         (KeyCode::PageUp, KeyModifiers::NONE) => Action::PageUp,
-        (KeyCode::Char('g'), KeyModifiers::NONE) => Action::GoToTop,
         (KeyCode::Char('G'), _) => Action::GoToBottom,
-        (KeyCode::Char('z'), KeyModifiers::NONE) => Action::PendingZCommand,
-        (KeyCode::Char(';'), _) => Action::PendingSemicolonCommand,
+        // `g`, `z`, `d` and `;` only start chords, see `chord`
 
         // File navigation (use _ for modifiers since shift is implicit in the character)
         (KeyCode::Char('}'), _) => Action::NextFile,
@@ -127,11 +195,22 @@ fn map_normal_mode(key: KeyEvent) -> Action {
         (KeyCode::Char('l') | KeyCode::Right, KeyModifiers::NONE) => Action::ScrollRight(4),
 
         // Review actions
-        (KeyCode::Char('r'), KeyModifiers::NONE) => Action::ToggleReviewed,
+        (KeyCode::Char('r'), KeyModifiers::NONE) => Action::CycleReviewState,
+        (KeyCode::Char('x'), KeyModifiers::NONE) => Action::ToggleExcluded,
         (KeyCode::Char('c'), KeyModifiers::NONE) => Action::AddLineComment,
         (KeyCode::Char('C'), _) => Action::AddFileComment,
+        (KeyCode::Char('>'), _) => Action::QuoteLineComment,
         (KeyCode::Char('i'), KeyModifiers::NONE) => Action::EditComment,
-        (KeyCode::Char('d'), KeyModifiers::NONE) => Action::PendingDCommand,
+        // `e` also edits the comment under the cursor; on a diff line it is a motion
+        (KeyCode::Char('e'), KeyModifiers::NONE) => Action::WordEnd,
+        (KeyCode::Char('w'), KeyModifiers::NONE) => Action::WordForward,
+        (KeyCode::Char('b'), KeyModifiers::NONE) => Action::WordBackward,
+        (KeyCode::Char('R'), _) => Action::ReplyToComment,
+        (KeyCode::Char('m'), KeyModifiers::NONE) => Action::ToggleResolved,
+        (KeyCode::Char('A'), _) => Action::ApplySuggestion,
+        (KeyCode::Char('u'), KeyModifiers::NONE) => Action::UndoDeleteComment,
+        (KeyCode::Char('Y'), _) => Action::YankComment,
+        (KeyCode::Char('p'), KeyModifiers::NONE) => Action::PasteComment,
         (KeyCode::Char('v') | KeyCode::Char('V'), _) => Action::EnterVisualMode,
         (KeyCode::Char('y'), KeyModifiers::NONE) => Action::ExportToClipboard,
         (KeyCode::Char('n'), KeyModifiers::NONE) => Action::SearchNext,
@@ -149,6 +228,21 @@ fn map_normal_mode(key: KeyEvent) -> Action {
         (KeyCode::Char(' '), KeyModifiers::NONE) => Action::ToggleExpand,
         (KeyCode::Char('o'), KeyModifiers::NONE) => Action::ExpandAll,
         (KeyCode::Char('O'), _) => Action::CollapseAll,
+        (KeyCode::Char('F'), _) => Action::ShowFunctionDiff,
+        (KeyCode::Char('J'), _) => Action::MoveFileDown,
+        (KeyCode::Char('K'), _) => Action::MoveFileUp,
+        (KeyCode::Char('B'), _) => Action::ReviewAgainstBase,
+        (KeyCode::Char('L'), _) => Action::ShowCommentList,
+        (KeyCode::Char('1'), KeyModifiers::NONE) => Action::ToggleCommentGroup(CommentGroup::Notes),
+        (KeyCode::Char('2'), KeyModifiers::NONE) => {
+            Action::ToggleCommentGroup(CommentGroup::Suggestions)
+        }
+        (KeyCode::Char('3'), KeyModifiers::NONE) => {
+            Action::ToggleCommentGroup(CommentGroup::Issues)
+        }
+        (KeyCode::Char('4'), KeyModifiers::NONE) => {
+            Action::ToggleCommentGroup(CommentGroup::Praise)
+        }
 
         _ => Action::None,
     }
@@ -161,6 +255,7 @@ fn map_command_mode(key: KeyEvent) -> Action {
         (KeyCode::Backspace, KeyModifiers::NONE) => Action::DeleteChar,
         (KeyCode::Char('w'), KeyModifiers::CONTROL) => Action::DeleteWord,
         (KeyCode::Char('u'), KeyModifiers::CONTROL) => Action::ClearLine,
+        (KeyCode::Char('v'), KeyModifiers::CONTROL) => Action::PasteClipboard,
         (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => Action::InsertChar(c),
         _ => Action::None,
     }
@@ -192,6 +287,10 @@ fn map_comment_mode(key: KeyEvent) -> Action {
         (KeyCode::Char('j'), KeyModifiers::CONTROL) => Action::InsertChar('\n'),
         // Comment type: Tab to cycle
         (KeyCode::Tab, KeyModifiers::NONE) => Action::CycleCommentType,
+        // Severity: Shift-Tab to cycle
+        (KeyCode::BackTab, _) => Action::CycleCommentSeverity,
+        // Required action: Ctrl-T to cycle
+        (KeyCode::Char('t'), KeyModifiers::CONTROL) => Action::CycleCommentRequirement,
         // Cursor movement
         (KeyCode::Char('a'), KeyModifiers::CONTROL) => Action::TextCursorLineStart,
         (KeyCode::Char('e'), KeyModifiers::CONTROL) => Action::TextCursorLineEnd,
@@ -219,6 +318,8 @@ fn map_comment_mode(key: KeyEvent) -> Action {
         }
         (KeyCode::Left, KeyModifiers::NONE) => Action::TextCursorLeft,
         (KeyCode::Right, KeyModifiers::NONE) => Action::TextCursorRight,
+        (KeyCode::Up, KeyModifiers::NONE) => Action::CursorUp(1),
+        (KeyCode::Down, KeyModifiers::NONE) => Action::CursorDown(1),
         // Editing
         (KeyCode::Backspace, mods)
             if mods.contains(KeyModifiers::SUPER) || mods.contains(KeyModifiers::META) =>
@@ -228,6 +329,12 @@ fn map_comment_mode(key: KeyEvent) -> Action {
         (KeyCode::Backspace, KeyModifiers::NONE) => Action::DeleteChar,
         (KeyCode::Char('w'), KeyModifiers::CONTROL) => Action::DeleteWord,
         (KeyCode::Char('u'), KeyModifiers::CONTROL) => Action::ClearLine,
+        (KeyCode::Char('k'), KeyModifiers::CONTROL) => Action::KillToLineEnd,
+        (KeyCode::Char('d'), KeyModifiers::ALT) => Action::KillWordForward,
+        (KeyCode::Char('y'), KeyModifiers::CONTROL) => Action::Yank,
+        (KeyCode::Char('r'), KeyModifiers::CONTROL) => Action::RestoreDraft,
+        (KeyCode::Char('v'), KeyModifiers::CONTROL) => Action::PasteClipboard,
+        (KeyCode::Char('q'), KeyModifiers::CONTROL) => Action::QuoteAnchor,
         (KeyCode::Char(c), _) => Action::InsertChar(c),
         _ => Action::None,
     }
@@ -262,6 +369,17 @@ fn map_confirm_mode(key: KeyEvent) -> Action {
     }
 }
 
+/// Scroll like help; accept or cancel like a confirm prompt
+fn map_export_preview_mode(key: KeyEvent) -> Action {
+    match (key.code, key.modifiers) {
+        (KeyCode::Char('y') | KeyCode::Enter, KeyModifiers::NONE) => Action::ConfirmYes,
+        (KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc, KeyModifiers::NONE) => {
+            Action::ConfirmNo
+        }
+        _ => map_help_mode(key),
+    }
+}
+
 fn map_commit_select_mode(key: KeyEvent) -> Action {
     match key.code {
         KeyCode::Char('j') | KeyCode::Down => Action::CommitSelectDown,
@@ -270,6 +388,34 @@ fn map_commit_select_mode(key: KeyEvent) -> Action {
         KeyCode::Enter => Action::ConfirmCommitSelect,
         KeyCode::Esc => Action::ExitMode,
         KeyCode::Char('q') => Action::Quit,
+        KeyCode::Char('/') => Action::EnterSearchMode,
+        KeyCode::Char('v') => Action::EnterVisualMode,
+        _ => Action::None,
+    }
+}
+
+/// Popup lists navigate like the commit picker, and `q` closes them like `Esc`
+fn map_popup_list_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => Action::CommitSelectDown,
+        KeyCode::Char('k') | KeyCode::Up => Action::CommitSelectUp,
+        KeyCode::Char(' ') => Action::ToggleCommitSelect,
+        KeyCode::Enter => Action::ConfirmCommitSelect,
+        KeyCode::Esc | KeyCode::Char('q') => Action::ExitMode,
+        _ => Action::None,
+    }
+}
+
+/// The comment list navigates like the other popup lists and also edits and
+/// resolves the selected comment
+fn map_comment_list_mode(key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => Action::CommitSelectDown,
+        KeyCode::Char('k') | KeyCode::Up => Action::CommitSelectUp,
+        KeyCode::Enter => Action::ConfirmCommitSelect,
+        KeyCode::Char('i') => Action::EditComment,
+        KeyCode::Char('m') => Action::ToggleResolved,
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('L') => Action::ExitMode,
         _ => Action::None,
     }
 }
@@ -282,6 +428,9 @@ fn map_visual_mode(key: KeyEvent) -> Action {
         // Create range comment
         (KeyCode::Char('c'), KeyModifiers::NONE) => Action::AddRangeComment,
         (KeyCode::Enter, KeyModifiers::NONE) => Action::AddRangeComment,
+        // Copy the selected code, or the diff lines with their prefixes
+        (KeyCode::Char('y'), KeyModifiers::NONE) => Action::CopySelection,
+        (KeyCode::Char('Y'), _) => Action::CopySelectionWithPrefixes,
         // Cancel selection
         (KeyCode::Esc, KeyModifiers::NONE) => Action::ExitMode,
         (KeyCode::Char('v') | KeyCode::Char('V'), _) => Action::ExitMode,
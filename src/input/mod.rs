@@ -1,5 +1,7 @@
+pub mod chord;
 pub mod handler;
 pub mod keybindings;
 pub mod mode;
+pub mod paste;
 
-pub use keybindings::{Action, map_key_to_action};
+pub use keybindings::{Action, map_comments_pane_key, map_key_to_action};
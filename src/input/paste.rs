@@ -0,0 +1,110 @@
+//! Pasted text arriving in modes that treat every key as a command.
+//!
+//! With bracketed paste the terminal delivers a paste as one event. Terminals
+//! without it type the text out key by key, so keys that all arrive at once
+//! while tuicr sits idle are treated as a paste too.
+
+use std::time::Duration;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+
+/// Fewest keys in a burst before it counts as a paste
+const PASTE_BURST_MIN_KEYS: usize = 4;
+
+/// How long tuicr must have waited for the first key of a burst. Keys that
+/// were already there when it started waiting piled up while it was busy,
+/// and may just be typed ahead.
+const PASTE_IDLE_WAIT: Duration = Duration::from_millis(5);
+
+/// Whether `first` and the events that arrived with it look like pasted
+/// text, after waiting `waited` for `first`. A held-down key repeats a single
+/// key and does not count; events other than keys are ignored.
+pub fn is_paste_burst(first: &KeyEvent, pending: &[Event], waited: Duration) -> bool {
+    if waited < PASTE_IDLE_WAIT {
+        return false;
+    }
+    let keys: Vec<KeyCode> = std::iter::once(first)
+        .chain(pending.iter().filter_map(|event| match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some(key),
+            _ => None,
+        }))
+        .map(|key| key.code)
+        .filter(|code| matches!(code, KeyCode::Char(_) | KeyCode::Enter | KeyCode::Tab))
+        .collect();
+    keys.len() >= PASTE_BURST_MIN_KEYS && keys.iter().any(|code| *code != keys[0])
+}
+
+/// Pasted text as typed into a text field: terminals send line breaks as
+/// carriage returns, and single-line fields drop them
+pub fn normalize_pasted_text(text: &str, multiline: bool) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    if multiline {
+        text
+    } else {
+        text.replace('\n', " ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    /// Long enough idle for the first key to have arrived on its own
+    const IDLE: Duration = Duration::from_millis(100);
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn should_detect_burst_of_different_keys() {
+        // given "rdq" queued behind "x", as a paste would type it
+        let pending: Vec<Event> = "rdq".chars().map(|c| Event::Key(key(c))).collect();
+
+        // then
+        assert!(is_paste_burst(&key('x'), &pending, IDLE));
+    }
+
+    #[test]
+    fn should_not_treat_keys_typed_ahead_while_busy_as_paste() {
+        // given ":wq⏎" typed while a reload kept tuicr busy
+        let pending: Vec<Event> = vec![
+            Event::Key(key('w')),
+            Event::Key(key('q')),
+            Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+        ];
+
+        // then the keys were already waiting and run as typed
+        assert!(!is_paste_burst(&key(':'), &pending, Duration::ZERO));
+    }
+
+    #[test]
+    fn should_only_count_key_events() {
+        // given a resize and two keys behind the first
+        let pending = vec![
+            Event::Resize(80, 24),
+            Event::Key(key('a')),
+            Event::Key(key('b')),
+        ];
+
+        // then three keys are too few for a paste
+        assert!(!is_paste_burst(&key('x'), &pending, IDLE));
+    }
+
+    #[test]
+    fn should_not_treat_repeated_key_as_paste() {
+        // given a held-down j with a backlog of repeats
+        let pending: Vec<Event> = "jjjjj".chars().map(|c| Event::Key(key(c))).collect();
+
+        // then
+        assert!(!is_paste_burst(&key('j'), &pending, IDLE));
+        assert!(!is_paste_burst(&key('j'), &[Event::Key(key('k'))], IDLE));
+    }
+
+    #[test]
+    fn should_normalize_line_breaks() {
+        assert_eq!(normalize_pasted_text("a\r\nb\rc", true), "a\nb\nc");
+        assert_eq!(normalize_pasted_text("a\r\nb", false), "a b");
+    }
+}
@@ -1,26 +1,47 @@
+mod api_spec;
 mod app;
+mod ci;
+mod config;
 mod error;
+mod findings;
+mod function_scope;
+mod gc;
+mod gitlab;
+mod glob;
 mod handler;
+mod hyperlink;
+mod impact;
+mod indent_guard;
 mod input;
+mod migration;
 mod model;
+mod moved;
 mod output;
 mod persistence;
+mod public_api;
+mod status;
+mod structural;
 mod syntax;
+mod test_mapping;
 mod text_edit;
 mod theme;
+mod todo;
+mod trailer;
 mod ui;
 mod update;
 mod vcs;
 
+use std::collections::VecDeque;
 use std::fs::File;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
 use crossterm::{
+    cursor::SetCursorStyle,
     event::{
-        self, Event, KeyEventKind, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
-        PushKeyboardEnhancementFlags,
+        self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyEventKind,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     execute,
     terminal::{
@@ -31,27 +52,57 @@ use crossterm::{
 use ratatui::{Terminal, backend::CrosstermBackend};
 
 use app::{App, FocusedPanel, InputMode};
+use config::Config;
 use handler::{
-    handle_command_action, handle_comment_action, handle_commit_select_action,
-    handle_confirm_action, handle_diff_action, handle_file_list_action, handle_help_action,
-    handle_search_action, handle_visual_action,
+    handle_attachment_action, handle_command_action, handle_comment_action,
+    handle_comment_list_action, handle_comments_pane_action, handle_commit_filter_action,
+    handle_commit_select_action, handle_confirm_action, handle_diff_action,
+    handle_export_preview_action, handle_file_list_action, handle_function_diff_action,
+    handle_help_action, handle_impact_action, handle_migration_action, handle_public_api_action,
+    handle_search_action, handle_stale_comment_action, handle_structural_diff_action,
+    handle_test_map_action, handle_todo_action, handle_visual_action,
+    handle_worktree_select_action, normal_key_actions,
 };
-use input::{Action, map_key_to_action};
-use theme::{parse_cli_args, resolve_theme};
+use input::{Action, map_key_to_action, paste};
+use theme::{Subcommand, parse_cli_args, parse_subcommand, resolve_theme};
 
 /// Timeout for the "press Ctrl+C again to exit" feature
 const CTRL_C_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often the loop wakes up while background work is running
+const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(100);
 
 fn main() -> anyhow::Result<()> {
     // Setup panic hook to restore terminal on panic
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
-        let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+        let _ = execute!(
+            io::stdout(),
+            PopKeyboardEnhancementFlags,
+            DisableBracketedPaste,
+            SetCursorStyle::DefaultUserShape
+        );
         let _ = disable_raw_mode();
         let _ = execute!(io::stdout(), LeaveAlternateScreen);
         original_hook(panic_info);
     }));
 
+    // Subcommands print and exit without touching the terminal
+    match parse_subcommand() {
+        Some(Subcommand::Status { days, prompt }) => {
+            status::run(days, prompt)?;
+            return Ok(());
+        }
+        Some(Subcommand::Gc {
+            days,
+            dry_run,
+            delete_missing,
+        }) => {
+            gc::run(days, dry_run, delete_missing)?;
+            return Ok(());
+        }
+        None => {}
+    }
+
     // Check keyboard enhancement support before enabling raw mode
     let keyboard_enhancement_supported = matches!(supports_keyboard_enhancement(), Ok(true));
 
@@ -59,9 +110,15 @@ fn main() -> anyhow::Result<()> {
     // This also configures syntax highlighting colors before diff parsing
     let cli_args = parse_cli_args();
     let theme = resolve_theme(cli_args.theme);
+    // A pull/merge request build reviews against its target, headless
+    let ci_target = if cli_args.no_ci || cli_args.read_stdin || cli_args.snapshot.is_some() {
+        None
+    } else {
+        ci::detect()
+    };
 
     // Start update check in background (non-blocking)
-    let update_rx = if !cli_args.no_update_check {
+    let mut update_rx = if !cli_args.no_update_check && ci_target.is_none() {
         let (tx, rx) = mpsc::channel();
         std::thread::spawn(move || {
             let result = update::check_for_updates();
@@ -72,21 +129,114 @@ fn main() -> anyhow::Result<()> {
         None
     };
 
+    let mut config = Config::load().unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to load config, using defaults: {e}");
+        Config::default()
+    });
+    config.find_copies |= cli_args.find_copies;
+
     // Initialize app
-    let mut app = match App::new(theme, cli_args.output_to_stdout) {
+    // With `-`, the diff comes from stdin; crossterm falls back to /dev/tty for key input
+    let app_result = if let Some(session_file) = &cli_args.snapshot {
+        App::from_snapshot(session_file, theme, config, cli_args.output_to_stdout)
+    } else if cli_args.read_stdin {
+        let mut diff_text = String::new();
+        io::stdin().read_to_string(&mut diff_text)?;
+        App::from_stdin_diff(
+            diff_text,
+            cli_args.repo_path.as_deref(),
+            theme,
+            config,
+            cli_args.output_to_stdout,
+            cli_args.pathspecs.clone(),
+        )
+    } else {
+        App::new(
+            cli_args.repo_path.as_deref(),
+            theme,
+            config,
+            cli_args.output_to_stdout,
+            cli_args.pathspecs.clone(),
+        )
+    };
+    let mut app = match app_result {
         Ok(mut app) => {
             app.supports_keyboard_enhancement = keyboard_enhancement_supported;
+            if cli_args.pick_worktree
+                && let Err(e) = app.enter_worktree_select_mode()
+            {
+                app.set_error(format!("Failed to list worktrees: {e}"));
+            }
+            if let Some(iid) = cli_args.merge_request
+                && let Err(e) = app.review_merge_request(iid)
+            {
+                app.set_error(format!("Failed to load merge request !{iid}: {e}"));
+            }
+            if let Some(target) = &ci_target {
+                let problem = match app.review_against_base(Some(&target.base_branch)) {
+                    Ok(0) => Some("no commits are ahead of it".to_string()),
+                    Ok(_) => None,
+                    Err(e) => Some(e.to_string()),
+                };
+                if let Some(problem) = problem {
+                    eprintln!(
+                        "Error: {} build targets {}, but {problem}",
+                        target.provider, target.base_branch
+                    );
+                    std::process::exit(1);
+                }
+            }
+            if ci_target.is_none() {
+                app.offer_damaged_session();
+                app.offer_previous_session();
+            }
+            if let Some(report) = &cli_args.import_findings {
+                match app.import_findings(report) {
+                    Ok((attached, 0)) => {
+                        app.set_message(format!("Imported {attached} finding(s) on changed lines"))
+                    }
+                    Ok((attached, outside)) => app.set_message(format!(
+                        "Imported {attached} finding(s) on changed lines; {outside} outside the diff skipped"
+                    )),
+                    Err(e) => app.set_error(format!(
+                        "Failed to import findings from {}: {e}",
+                        report.display()
+                    )),
+                }
+            }
             app
         }
         Err(e) => {
             eprintln!("Error: {e}");
-            eprintln!(
-                "\nMake sure you're in a git, jujutsu, or mercurial repository with uncommitted changes."
-            );
+            if cli_args.snapshot.is_some() {
+                eprintln!("\nMake sure the session file was saved with `snapshot_diff` enabled.");
+            } else if cli_args.read_stdin {
+                eprintln!("\nMake sure a unified diff is piped in, e.g. `git diff | tuicr -`.");
+            } else if let Some(path) = &cli_args.repo_path {
+                eprintln!(
+                    "\nMake sure {} is inside a git, jujutsu, or mercurial repository with uncommitted changes.",
+                    path.display()
+                );
+            } else {
+                eprintln!(
+                    "\nMake sure you're in a git, jujutsu, or mercurial repository with uncommitted changes."
+                );
+            }
             std::process::exit(1);
         }
     };
 
+    if ci_target.is_some() {
+        match ci::headless_output(&app) {
+            Ok(output) => print!("{output}"),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     // Setup terminal
     // When --stdout is used, render TUI to /dev/tty so stdout is free for export output
     enable_raw_mode()?;
@@ -95,7 +245,8 @@ fn main() -> anyhow::Result<()> {
     } else {
         Box::new(io::stdout())
     };
-    execute!(tty_output, EnterAlternateScreen)?;
+    // Bracketed paste delivers pasted text as one event instead of keystrokes
+    execute!(tty_output, EnterAlternateScreen, EnableBracketedPaste)?;
 
     // Enable keyboard enhancement for better modifier key detection (e.g., Alt+Enter)
     // This is supported by modern terminals like Kitty, iTerm2, WezTerm, etc.
@@ -108,32 +259,55 @@ fn main() -> anyhow::Result<()> {
     let backend = CrosstermBackend::new(tty_output);
     let mut terminal = Terminal::new(backend)?;
 
-    // Track pending z command for zz centering
-    let mut pending_z = false;
-    // Track pending d command for dd delete
-    let mut pending_d = false;
-    // Track pending ; command for ;e toggle file list
-    let mut pending_semicolon = false;
     // Track pending Ctrl+C for "press twice to exit" (with timestamp for 2s timeout)
     let mut pending_ctrl_c: Option<Instant> = None;
+    // Events read ahead while checking for a paste burst
+    let mut queued_events: VecDeque<Event> = VecDeque::new();
+    // Whether the terminal cursor is currently a bar
+    let mut bar_cursor = false;
 
     // Main loop
     loop {
         // Render
-        terminal.draw(|frame| {
+        let frame = terminal.draw(|frame| {
             ui::render(frame, &mut app);
         })?;
+        let links = hyperlink::drawn_links(frame.buffer, &app.hyperlinks);
+        let _ = hyperlink::write_links(terminal.backend_mut(), &links);
 
-        // Check for update result (non-blocking)
-        if let Some(ref rx) = update_rx
-            && let Ok(
-                update::UpdateCheckResult::UpdateAvailable(info)
-                | update::UpdateCheckResult::AheadOfRelease(info),
-            ) = rx.try_recv()
-        {
-            app.update_info = Some(info);
+        // A bar marks the insertion point while typing; other modes hide
+        // the cursor and leave the user's own shape in place
+        if app.input_mode.is_text_input() != bar_cursor {
+            bar_cursor = !bar_cursor;
+            let style = if bar_cursor {
+                SetCursorStyle::SteadyBar
+            } else {
+                SetCursorStyle::DefaultUserShape
+            };
+            let _ = execute!(terminal.backend_mut(), style);
         }
 
+        // Check for update result (non-blocking); stop ticking for it once it is in
+        if let Some(rx) = &update_rx {
+            match rx.try_recv() {
+                Ok(
+                    update::UpdateCheckResult::UpdateAvailable(info)
+                    | update::UpdateCheckResult::AheadOfRelease(info),
+                ) => {
+                    app.update_info = Some(info);
+                    update_rx = None;
+                }
+                Ok(_) | Err(mpsc::TryRecvError::Disconnected) => update_rx = None,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
+        // Pick up a finished `:impact` search
+        app.poll_impact_search();
+
+        // Pick up diffstats for the commit picker
+        app.poll_commit_stats();
+
         // Auto-clear expired pending Ctrl+C state and message
         if let Some(first_press) = pending_ctrl_c
             && first_press.elapsed() >= CTRL_C_EXIT_TIMEOUT
@@ -142,10 +316,64 @@ fn main() -> anyhow::Result<()> {
             app.message = None;
         }
 
+        // A chord prefix that waited in vain does what it does on its own
+        if let Some(action) = app.chords.expire(Instant::now()) {
+            dispatch_action(&mut app, action);
+            if app.should_quit {
+                break;
+            }
+            continue;
+        }
+
+        // Block until input arrives, waking up only while background work
+        // runs or a pending Ctrl+C or chord has to expire, so an idle tuicr
+        // uses no CPU
+        let tick_interval = app
+            .config
+            .tick_interval_ms
+            .map_or(DEFAULT_TICK_INTERVAL, Duration::from_millis);
+        let mut timeout =
+            (update_rx.is_some() || app.impact_search.is_some() || app.commit_stats_rx.is_some())
+                .then_some(tick_interval);
+        if let Some(first_press) = pending_ctrl_c {
+            let remaining = CTRL_C_EXIT_TIMEOUT.saturating_sub(first_press.elapsed());
+            timeout = Some(timeout.map_or(remaining, |tick| tick.min(remaining)));
+        }
+        if let Some(remaining) = app.chords.remaining(Instant::now()) {
+            timeout = Some(timeout.map_or(remaining, |tick| tick.min(remaining)));
+        }
+        let wait_started = Instant::now();
+        let has_event = match timeout {
+            _ if !queued_events.is_empty() => true,
+            Some(timeout) => event::poll(timeout)?,
+            None => true,
+        };
+
         // Handle events
-        if event::poll(Duration::from_millis(100))? {
-            let event = event::read()?;
+        if has_event {
+            let event = match queued_events.pop_front() {
+                Some(event) => event,
+                None => event::read()?,
+            };
             match event {
+                Event::Paste(text) => {
+                    let multiline = app.input_mode == InputMode::Comment;
+                    if matches!(
+                        app.input_mode,
+                        InputMode::Comment
+                            | InputMode::Command
+                            | InputMode::Search
+                            | InputMode::CommitFilter
+                    ) {
+                        let text = paste::normalize_pasted_text(&text, multiline);
+                        dispatch_action(&mut app, Action::InsertText(text));
+                    } else {
+                        app.set_warning(format!(
+                            "Ignored pasted text ({} characters)",
+                            text.chars().count()
+                        ));
+                    }
+                }
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
                     // Handle Ctrl+C twice to exit (works across all input modes)
                     // In Comment mode, first Ctrl+C also cancels the comment
@@ -156,7 +384,7 @@ fn main() -> anyhow::Result<()> {
                     {
                         // If in comment mode, cancel the comment first
                         if app.input_mode == InputMode::Comment {
-                            app.exit_comment_mode();
+                            app.cancel_comment();
                         }
 
                         if let Some(first_press) = pending_ctrl_c
@@ -167,6 +395,7 @@ fn main() -> anyhow::Result<()> {
                             continue;
                         }
                         // First Ctrl+C (or timeout expired) - show warning and start timer
+                        app.chords.cancel();
                         pending_ctrl_c = Some(Instant::now());
                         app.set_message("Press Ctrl+C again to exit");
                         continue;
@@ -178,82 +407,33 @@ fn main() -> anyhow::Result<()> {
                         app.message = None;
                     }
 
-                    // Handle pending z command for zz centering
-                    if pending_z {
-                        pending_z = false;
-                        if key.code == crossterm::event::KeyCode::Char('z') {
-                            app.center_cursor();
-                            continue;
+                    // Keys arriving together with this one came from a paste in
+                    // a terminal without bracketed paste; as commands they would
+                    // toggle, delete and quit at random
+                    if app.input_mode == InputMode::Normal && queued_events.is_empty() {
+                        let waited = wait_started.elapsed();
+                        while event::poll(Duration::ZERO)? {
+                            queued_events.push_back(event::read()?);
                         }
-                        // Otherwise fall through to normal handling
-                    }
-
-                    // Handle pending d command for dd delete comment
-                    if pending_d {
-                        pending_d = false;
-                        if key.code == crossterm::event::KeyCode::Char('d') {
-                            if !app.delete_comment_at_cursor() {
-                                app.set_message("No comment at cursor");
-                            }
+                        let pending = queued_events.make_contiguous();
+                        if paste::is_paste_burst(&key, pending, waited) {
+                            let before = queued_events.len();
+                            queued_events.retain(|event| !matches!(event, Event::Key(_)));
+                            let count = before - queued_events.len() + 1;
+                            app.set_warning(format!("Ignored pasted text ({count} keys)"));
                             continue;
                         }
-                        // Otherwise fall through to normal handling
                     }
 
-                    // Handle pending ; command for ;e toggle file list, ;h/;l panel focus
-                    if pending_semicolon {
-                        pending_semicolon = false;
-                        match key.code {
-                            crossterm::event::KeyCode::Char('e') => {
-                                app.toggle_file_list();
-                                continue;
-                            }
-                            crossterm::event::KeyCode::Char('h') => {
-                                app.focused_panel = app::FocusedPanel::FileList;
-                                continue;
-                            }
-                            crossterm::event::KeyCode::Char('l') => {
-                                app.focused_panel = app::FocusedPanel::Diff;
-                                continue;
-                            }
-                            _ => {}
+                    if app.input_mode == InputMode::Normal {
+                        for action in normal_key_actions(&mut app, key, Instant::now()) {
+                            dispatch_action(&mut app, action);
                         }
-                        // Otherwise fall through to normal handling
+                        continue;
                     }
 
                     let action = map_key_to_action(key, app.input_mode);
-
-                    // Handle pending command setters (these work in any mode)
-                    match action {
-                        Action::PendingZCommand => {
-                            pending_z = true;
-                            continue;
-                        }
-                        Action::PendingDCommand => {
-                            pending_d = true;
-                            continue;
-                        }
-                        Action::PendingSemicolonCommand => {
-                            pending_semicolon = true;
-                            continue;
-                        }
-                        _ => {}
-                    }
-
-                    // Dispatch by input mode
-                    match app.input_mode {
-                        InputMode::Help => handle_help_action(&mut app, action),
-                        InputMode::Command => handle_command_action(&mut app, action),
-                        InputMode::Search => handle_search_action(&mut app, action),
-                        InputMode::Comment => handle_comment_action(&mut app, action),
-                        InputMode::Confirm => handle_confirm_action(&mut app, action),
-                        InputMode::CommitSelect => handle_commit_select_action(&mut app, action),
-                        InputMode::VisualSelect => handle_visual_action(&mut app, action),
-                        InputMode::Normal => match app.focused_panel {
-                            FocusedPanel::FileList => handle_file_list_action(&mut app, action),
-                            FocusedPanel::Diff => handle_diff_action(&mut app, action),
-                        },
-                    }
+                    dispatch_action(&mut app, action);
                 }
                 _ => {}
             }
@@ -265,10 +445,20 @@ fn main() -> anyhow::Result<()> {
     }
 
     // Restore terminal
-    let _ = execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags);
+    let _ = execute!(
+        terminal.backend_mut(),
+        PopKeyboardEnhancementFlags,
+        DisableBracketedPaste,
+        SetCursorStyle::DefaultUserShape
+    );
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
 
+    // Keep a copy of the review in case the clipboard loses it
+    if let Err(e) = app.write_backup_export() {
+        eprintln!("Warning: Failed to write backup export: {e}");
+    }
+
     // Print pending stdout output if --stdout was used
     if let Some(output) = app.pending_stdout_output {
         print!("{output}");
@@ -276,3 +466,42 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Dispatch an action to the handler of the current input mode
+fn dispatch_action(app: &mut App, action: Action) {
+    let was_commenting = app.input_mode == InputMode::Comment;
+    dispatch_mode_action(app, action);
+    if !was_commenting && app.input_mode == InputMode::Comment {
+        app.offer_comment_draft();
+    }
+}
+
+fn dispatch_mode_action(app: &mut App, action: Action) {
+    match app.input_mode {
+        InputMode::Help => handle_help_action(app, action),
+        InputMode::FunctionDiff => handle_function_diff_action(app, action),
+        InputMode::StructuralDiff => handle_structural_diff_action(app, action),
+        InputMode::Migration => handle_migration_action(app, action),
+        InputMode::PublicApi => handle_public_api_action(app, action),
+        InputMode::TestMap => handle_test_map_action(app, action),
+        InputMode::Todos => handle_todo_action(app, action),
+        InputMode::StaleComments => handle_stale_comment_action(app, action),
+        InputMode::CommentList => handle_comment_list_action(app, action),
+        InputMode::Attachments => handle_attachment_action(app, action),
+        InputMode::Impact => handle_impact_action(app, action),
+        InputMode::ExportPreview => handle_export_preview_action(app, action),
+        InputMode::Command => handle_command_action(app, action),
+        InputMode::Search => handle_search_action(app, action),
+        InputMode::Comment => handle_comment_action(app, action),
+        InputMode::Confirm => handle_confirm_action(app, action),
+        InputMode::CommitSelect => handle_commit_select_action(app, action),
+        InputMode::CommitFilter => handle_commit_filter_action(app, action),
+        InputMode::WorktreeSelect => handle_worktree_select_action(app, action),
+        InputMode::VisualSelect => handle_visual_action(app, action),
+        InputMode::Normal => match app.focused_panel {
+            FocusedPanel::FileList => handle_file_list_action(app, action),
+            FocusedPanel::Diff => handle_diff_action(app, action),
+            FocusedPanel::Comments => handle_comments_pane_action(app, action),
+        },
+    }
+}
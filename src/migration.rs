@@ -0,0 +1,223 @@
+//! Helpers for reviewing SQL migrations.
+//!
+//! Files under the configured `migration_dirs` are tagged in the file list,
+//! their added lines are scanned for statements that destroy data, and the
+//! companion migration (up ↔ down) is located by the common naming
+//! conventions of golang-migrate/sqlx (`N_name.up.sql`), diesel (`up.sql`
+//! next to `down.sql`), Flyway undo scripts (`V1__x.sql` ↔ `U1__x.sql`) and
+//! `_up`/`_down` suffixes.
+
+use std::path::{Path, PathBuf};
+
+use crate::model::{DiffHunk, LineOrigin};
+
+/// Object kinds reported by name after `DROP`
+const DROP_KINDS: &[&str] = &[
+    "TABLE",
+    "COLUMN",
+    "INDEX",
+    "VIEW",
+    "SCHEMA",
+    "DATABASE",
+    "CONSTRAINT",
+    "TYPE",
+    "SEQUENCE",
+    "TRIGGER",
+    "FUNCTION",
+];
+
+/// Whether the file lives under one of the configured migration directories
+pub fn is_migration(path: &Path, dirs: &[String]) -> bool {
+    dirs.iter().any(|dir| {
+        let pattern = if dir.ends_with('/') || dir.contains('*') {
+            dir.clone()
+        } else {
+            format!("{dir}/")
+        };
+        crate::glob::matches_path(&pattern, path)
+    })
+}
+
+/// Warnings for destructive statements among a hunk's added lines,
+/// e.g. `destructive: DROP TABLE, DROP COLUMN`
+pub fn destructive_warnings(hunk: &DiffHunk) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for line in &hunk.lines {
+        if line.origin != LineOrigin::Addition {
+            continue;
+        }
+        for statement in destructive_statements(&line.content) {
+            if !found.contains(&statement) {
+                found.push(statement);
+            }
+        }
+    }
+
+    if found.is_empty() {
+        Vec::new()
+    } else {
+        vec![format!("destructive: {}", found.join(", "))]
+    }
+}
+
+fn destructive_statements(line: &str) -> Vec<String> {
+    let code = line
+        .split("--")
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    let words: Vec<&str> = code
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut statements = Vec::new();
+    for (idx, word) in words.iter().enumerate() {
+        match *word {
+            "DROP" => match words.get(idx + 1) {
+                Some(kind) if DROP_KINDS.contains(kind) => statements.push(format!("DROP {kind}")),
+                // `ALTER TABLE t DROP name` drops a column in Postgres and MySQL
+                _ if words.contains(&"ALTER") => statements.push("DROP COLUMN".to_string()),
+                _ => statements.push("DROP".to_string()),
+            },
+            "TRUNCATE" => statements.push("TRUNCATE".to_string()),
+            _ => {}
+        }
+    }
+    statements
+}
+
+/// The migration that pairs with `path`: the down script for an up script,
+/// or the up script for a down script. Returns `(up, down)`.
+pub fn migration_pair(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let name = path.file_name()?.to_str()?;
+    let sibling = |other: String| path.with_file_name(other);
+
+    for (up, down) in [(".up.", ".down."), ("_up.", "_down.")] {
+        if let Some(pos) = name.rfind(up) {
+            let other = format!("{}{down}{}", &name[..pos], &name[pos + up.len()..]);
+            return Some((path.to_path_buf(), sibling(other)));
+        }
+        if let Some(pos) = name.rfind(down) {
+            let other = format!("{}{up}{}", &name[..pos], &name[pos + down.len()..]);
+            return Some((sibling(other), path.to_path_buf()));
+        }
+    }
+
+    match name {
+        "up.sql" => return Some((path.to_path_buf(), sibling("down.sql".to_string()))),
+        "down.sql" => return Some((sibling("up.sql".to_string()), path.to_path_buf())),
+        _ => {}
+    }
+
+    // Flyway: V<version>__<desc>.sql is undone by U<version>__<desc>.sql
+    let versioned = |prefix: char| {
+        name.strip_prefix(prefix)
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()) && rest.contains("__"))
+    };
+    if let Some(rest) = versioned('V') {
+        return Some((path.to_path_buf(), sibling(format!("U{rest}"))));
+    }
+    if let Some(rest) = versioned('U') {
+        return Some((sibling(format!("V{rest}")), path.to_path_buf()));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DiffLine;
+
+    fn hunk(lines: &[(LineOrigin, &str)]) -> DiffHunk {
+        DiffHunk {
+            header: "@@ -1,1 +1,1 @@".to_string(),
+            lines: lines
+                .iter()
+                .map(|(origin, content)| DiffLine {
+                    origin: *origin,
+                    content: content.to_string(),
+                    old_lineno: None,
+                    new_lineno: None,
+                    highlighted_spans: None,
+                })
+                .collect(),
+            old_start: 1,
+            old_count: 1,
+            new_start: 1,
+            new_count: 1,
+        }
+    }
+
+    #[test]
+    fn should_match_configured_directories() {
+        let dirs = vec!["db/migrations".to_string(), "migrations/".to_string()];
+        assert!(is_migration(Path::new("db/migrations/001_init.sql"), &dirs));
+        assert!(is_migration(Path::new("migrations/2024/up.sql"), &dirs));
+        assert!(!is_migration(Path::new("src/db/migrations.rs"), &dirs));
+        assert!(!is_migration(Path::new("db/schema.sql"), &dirs));
+    }
+
+    #[test]
+    fn should_flag_destructive_added_statements() {
+        // given a hunk that adds drops and removes an older drop
+        let hunk = hunk(&[
+            (LineOrigin::Deletion, "DROP TABLE legacy;"),
+            (LineOrigin::Addition, "drop table users;"),
+            (LineOrigin::Addition, "ALTER TABLE orders DROP COLUMN note;"),
+            (
+                LineOrigin::Addition,
+                "ALTER TABLE orders DROP total; -- no longer used",
+            ),
+            (LineOrigin::Addition, "CREATE TABLE drop_log (id int);"),
+        ]);
+
+        // when checked
+        let warnings = destructive_warnings(&hunk);
+
+        // then each kind of drop is listed once, ignoring deletions and identifiers
+        assert_eq!(warnings, vec!["destructive: DROP TABLE, DROP COLUMN"]);
+    }
+
+    #[test]
+    fn should_not_flag_comments_or_additive_changes() {
+        // given additive statements and a commented-out drop
+        let hunk = hunk(&[
+            (
+                LineOrigin::Addition,
+                "ALTER TABLE users ADD COLUMN age int;",
+            ),
+            (LineOrigin::Addition, "-- DROP TABLE users;"),
+        ]);
+
+        // when checked / then nothing is reported
+        assert!(destructive_warnings(&hunk).is_empty());
+    }
+
+    #[test]
+    fn should_pair_up_and_down_migrations() {
+        assert_eq!(
+            migration_pair(Path::new("m/001_users.up.sql")),
+            Some((
+                PathBuf::from("m/001_users.up.sql"),
+                PathBuf::from("m/001_users.down.sql")
+            ))
+        );
+        assert_eq!(
+            migration_pair(Path::new("m/2024_init/down.sql")),
+            Some((
+                PathBuf::from("m/2024_init/up.sql"),
+                PathBuf::from("m/2024_init/down.sql")
+            ))
+        );
+        assert_eq!(
+            migration_pair(Path::new("sql/V2__add_index.sql")),
+            Some((
+                PathBuf::from("sql/V2__add_index.sql"),
+                PathBuf::from("sql/U2__add_index.sql")
+            ))
+        );
+        assert_eq!(migration_pair(Path::new("sql/Views.sql")), None);
+    }
+}
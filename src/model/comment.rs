@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+use std::iter::once;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::conflict::ConflictSide;
+
 /// Which side of the diff a line comment belongs to
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum LineSide {
     /// Comment on a deleted line (keyed by old_lineno)
@@ -47,11 +52,14 @@ impl LineRange {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CommentType {
     Note,
     Suggestion,
+    /// Replacement code for the commented lines
+    #[serde(rename = "code_suggestion")]
+    CodeSuggestion,
     Issue,
     Praise,
 }
@@ -61,10 +69,165 @@ impl CommentType {
         match self {
             CommentType::Note => "NOTE",
             CommentType::Suggestion => "SUGGESTION",
+            CommentType::CodeSuggestion => "SUGGESTED CHANGE",
             CommentType::Issue => "ISSUE",
             CommentType::Praise => "PRAISE",
         }
     }
+
+    /// Whether this comment has to be addressed before the review is complete
+    pub fn is_blocking(&self) -> bool {
+        matches!(self, CommentType::Issue)
+    }
+}
+
+/// How much a comment matters, independent of its type. Ordered most
+/// severe first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Blocker,
+    Major,
+    Minor,
+    Nit,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Blocker => "BLOCKER",
+            Severity::Major => "MAJOR",
+            Severity::Minor => "MINOR",
+            Severity::Nit => "NIT",
+        }
+    }
+
+    /// Whether this is `min` or more severe
+    pub fn at_least(self, min: Severity) -> bool {
+        self <= min
+    }
+
+    /// The severity after `current` in the comment input: none, then blocker
+    /// down to nit
+    pub fn cycle(current: Option<Severity>) -> Option<Severity> {
+        match current {
+            None => Some(Severity::Blocker),
+            Some(Severity::Blocker) => Some(Severity::Major),
+            Some(Severity::Major) => Some(Severity::Minor),
+            Some(Severity::Minor) => Some(Severity::Nit),
+            Some(Severity::Nit) => None,
+        }
+    }
+}
+
+/// What response a comment expects, independent of its type and severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RequiredAction {
+    /// Change the code
+    Fix,
+    /// Reply to the question
+    Answer,
+    /// Think it over; changing nothing is fine
+    Consider,
+    /// Nothing to do, for the record
+    None,
+}
+
+impl RequiredAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequiredAction::Fix => "fix",
+            RequiredAction::Answer => "answer",
+            RequiredAction::Consider => "consider",
+            RequiredAction::None => "none",
+        }
+    }
+
+    /// The action after `current` in the comment input: unset, then fix
+    /// through none
+    pub fn cycle(current: Option<RequiredAction>) -> Option<RequiredAction> {
+        match current {
+            None => Some(RequiredAction::Fix),
+            Some(RequiredAction::Fix) => Some(RequiredAction::Answer),
+            Some(RequiredAction::Answer) => Some(RequiredAction::Consider),
+            Some(RequiredAction::Consider) => Some(RequiredAction::None),
+            Some(RequiredAction::None) => None,
+        }
+    }
+}
+
+/// Display columns of a line a comment points at, 1-based and inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Fingerprint of the code a line comment was written on, used to find
+/// those lines again after the file changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentAnchor {
+    /// Hash of the commented lines
+    pub lines: u64,
+    /// Hash of the commented lines and one line either side
+    pub context: u64,
+}
+
+impl ContentAnchor {
+    /// Fingerprint lines `range` of `lines` (line number to content on the
+    /// comment's side). None when a commented line is not in `lines`.
+    pub fn capture(lines: &BTreeMap<u32, &str>, range: LineRange) -> Option<Self> {
+        let commented = (range.start..=range.end)
+            .map(|lineno| lines.get(&lineno).copied())
+            .collect::<Option<Vec<&str>>>()?;
+        let before = range
+            .start
+            .checked_sub(1)
+            .and_then(|lineno| lines.get(&lineno).copied())
+            .unwrap_or_default();
+        let after = lines.get(&(range.end + 1)).copied().unwrap_or_default();
+        Some(Self {
+            lines: fingerprint(commented.iter().copied()),
+            context: fingerprint(once(before).chain(commented).chain(once(after))),
+        })
+    }
+
+    /// Where the commented lines are in `lines` now: the match nearest the
+    /// `previous` range, preferring one whose surrounding lines match too.
+    /// None when the code is gone.
+    pub fn locate(&self, lines: &BTreeMap<u32, &str>, previous: LineRange) -> Option<LineRange> {
+        let span = previous.end - previous.start;
+        let candidates: Vec<(LineRange, ContentAnchor)> = lines
+            .keys()
+            .filter_map(|&start| {
+                let range = LineRange::new(start, start + span);
+                Some((range, Self::capture(lines, range)?))
+            })
+            .collect();
+        let nearest = |matches: &dyn Fn(&ContentAnchor) -> bool| {
+            candidates
+                .iter()
+                .filter(|(_, anchor)| matches(anchor))
+                .min_by_key(|(range, _)| range.start.abs_diff(previous.start))
+                .map(|(range, _)| *range)
+        };
+        nearest(&|anchor| anchor.context == self.context)
+            .or_else(|| nearest(&|anchor| anchor.lines == self.lines))
+    }
+}
+
+/// FNV-1a over the lines, ignoring trailing whitespace; stable across
+/// builds, unlike `DefaultHasher`, since it is saved with the session
+fn fingerprint<'a>(lines: impl Iterator<Item = &'a str>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for line in lines {
+        for byte in line.trim_end().bytes().chain(once(b'\n')) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +252,62 @@ pub struct Comment {
     /// None for file-level comments or single-line comments (backward compatibility)
     #[serde(default)]
     pub line_range: Option<LineRange>,
+    /// Conflict section the commented line sits in, for conflicted files
+    #[serde(default)]
+    pub conflict_side: Option<ConflictSide>,
+    /// Replies threaded under this comment, oldest first
+    #[serde(default)]
+    pub replies: Vec<Reply>,
+    /// Marked as addressed; resolved comments no longer block the review
+    #[serde(default)]
+    pub resolved: bool,
+    /// Fingerprint of the commented code (for line comments)
+    #[serde(default)]
+    pub anchor: Option<ContentAnchor>,
+    /// The commented code was not found again after the file changed
+    #[serde(default)]
+    pub anchor_lost: bool,
+    /// Part of the line the comment is about, for single-line comments
+    #[serde(default)]
+    pub columns: Option<ColumnRange>,
+    /// Tool that reported an imported finding (`clippy`, `eslint`), None for
+    /// comments written in the review
+    #[serde(default)]
+    pub source: Option<String>,
+    /// When the text or type was last changed; None if never edited
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Reviewer who wrote the comment, when known
+    #[serde(default)]
+    pub author: Option<String>,
+    /// How much the comment matters, when the reviewer said
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    /// What the author of the change is expected to do about it
+    #[serde(default)]
+    pub action: Option<RequiredAction>,
+}
+
+/// A reply in a comment thread; it shares the parent's anchor and type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reply {
+    pub id: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    /// Reviewer who wrote the reply, when known
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+impl Reply {
+    pub fn new(content: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            content,
+            created_at: Utc::now(),
+            author: None,
+        }
+    }
 }
 
 impl Comment {
@@ -101,9 +320,59 @@ impl Comment {
             line_context: None,
             side,
             line_range: None,
+            conflict_side: None,
+            replies: Vec::new(),
+            resolved: false,
+            anchor: None,
+            anchor_lost: false,
+            columns: None,
+            source: None,
+            updated_at: None,
+            author: None,
+            severity: None,
+            action: None,
         }
     }
 
+    /// Replace the text, type, severity and required action, recording the
+    /// time when any of them changes
+    pub fn update(
+        &mut self,
+        content: String,
+        comment_type: CommentType,
+        severity: Option<Severity>,
+        action: Option<RequiredAction>,
+    ) {
+        if self.content != content
+            || self.comment_type != comment_type
+            || self.severity != severity
+            || self.action != action
+        {
+            self.content = content;
+            self.comment_type = comment_type;
+            self.severity = severity;
+            self.action = action;
+            self.updated_at = Some(Utc::now());
+        }
+    }
+
+    /// Whether this comment still has to be addressed: an open issue, or an
+    /// open comment of any type marked as a blocker
+    pub fn blocks_review(&self) -> bool {
+        !self.resolved
+            && (self.comment_type.is_blocking() || self.severity == Some(Severity::Blocker))
+    }
+
+    /// A fresh comment with the same text, type, severity and required
+    /// action, for the same issue elsewhere; replies and resolution stay
+    /// with the original
+    pub fn duplicate(&self, side: Option<LineSide>) -> Self {
+        let mut copy = Self::new(self.content.clone(), self.comment_type, side);
+        copy.severity = self.severity;
+        copy.action = self.action;
+        copy
+    }
+
     /// Create a new comment with a line range
     pub fn new_with_range(
         content: String,
@@ -119,6 +388,17 @@ impl Comment {
             line_context: None,
             side,
             line_range: Some(line_range),
+            conflict_side: None,
+            replies: Vec::new(),
+            resolved: false,
+            anchor: None,
+            anchor_lost: false,
+            columns: None,
+            source: None,
+            updated_at: None,
+            author: None,
+            severity: None,
+            action: None,
         }
     }
 }
@@ -127,6 +407,57 @@ impl Comment {
 mod tests {
     use super::*;
 
+    mod content_anchor_tests {
+        use super::*;
+
+        fn numbered<'a>(first: u32, lines: &[&'a str]) -> BTreeMap<u32, &'a str> {
+            (first..).zip(lines.iter().copied()).collect()
+        }
+
+        #[test]
+        fn should_follow_lines_moved_by_an_insertion_above() {
+            // given a comment on `retry(3);` at line 2
+            let before = numbered(1, &["fn run() {", "    retry(3);", "}"]);
+            let anchor = ContentAnchor::capture(&before, LineRange::single(2)).unwrap();
+
+            // when two lines are inserted above
+            let after = numbered(1, &["use x;", "", "fn run() {", "    retry(3);", "}"]);
+
+            // then
+            assert_eq!(
+                anchor.locate(&after, LineRange::single(2)),
+                Some(LineRange::single(4))
+            );
+        }
+
+        #[test]
+        fn should_prefer_the_copy_with_matching_surroundings() {
+            // given the commented line appears twice after the change
+            let before = numbered(1, &["a", "x = 1", "b"]);
+            let anchor = ContentAnchor::capture(&before, LineRange::single(2)).unwrap();
+            let after = numbered(1, &["x = 1", "c", "a", "x = 1", "b"]);
+
+            // then the one still between `a` and `b` wins over the nearer one
+            assert_eq!(
+                anchor.locate(&after, LineRange::single(2)),
+                Some(LineRange::single(4))
+            );
+        }
+
+        #[test]
+        fn should_lose_anchor_when_code_is_gone() {
+            // given
+            let before = numbered(1, &["a", "x = 1", "b"]);
+            let anchor = ContentAnchor::capture(&before, LineRange::new(1, 2)).unwrap();
+
+            // when the line changes
+            let after = numbered(1, &["a", "x = 2", "b"]);
+
+            // then
+            assert_eq!(anchor.locate(&after, LineRange::new(1, 2)), None);
+        }
+    }
+
     mod line_range_tests {
         use super::*;
 
@@ -280,6 +611,24 @@ mod tests {
             let comment: Comment = serde_json::from_str(json).unwrap();
             assert!(comment.line_range.is_none());
             assert_eq!(comment.content, "Test comment");
+            assert!(comment.replies.is_empty());
+        }
+
+        #[test]
+        fn replies_round_trip_in_order() {
+            let mut comment = Comment::new("Why?".to_string(), CommentType::Note, None);
+            comment.replies.push(Reply::new("Because".to_string()));
+            comment.replies.push(Reply::new("Fair".to_string()));
+
+            let json = serde_json::to_string(&comment).unwrap();
+            let restored: Comment = serde_json::from_str(&json).unwrap();
+
+            let contents: Vec<&str> = restored
+                .replies
+                .iter()
+                .map(|r| r.content.as_str())
+                .collect();
+            assert_eq!(contents, ["Because", "Fair"]);
         }
 
         #[test]
@@ -299,5 +648,74 @@ mod tests {
             assert_eq!(range.start, 10);
             assert_eq!(range.end, 15);
         }
+
+        #[test]
+        fn duplicate_keeps_the_feedback_but_not_the_thread() {
+            let mut comment = Comment::new("Unwrap".to_string(), CommentType::Issue, None);
+            comment.severity = Some(Severity::Major);
+            comment.action = Some(RequiredAction::Fix);
+            comment.resolved = true;
+            comment.replies.push(Reply::new("done".to_string()));
+
+            let copy = comment.duplicate(Some(LineSide::Old));
+
+            assert_ne!(copy.id, comment.id);
+            assert_eq!(copy.content, "Unwrap");
+            assert_eq!(copy.comment_type, CommentType::Issue);
+            assert_eq!(copy.severity, Some(Severity::Major));
+            assert_eq!(copy.action, Some(RequiredAction::Fix));
+            assert_eq!(copy.side, Some(LineSide::Old));
+            assert!(!copy.resolved);
+            assert!(copy.replies.is_empty());
+        }
+
+        #[test]
+        fn update_records_severity_changes() {
+            let mut comment = Comment::new("Typo".to_string(), CommentType::Note, None);
+            comment.update(
+                "Typo".to_string(),
+                CommentType::Note,
+                Some(Severity::Nit),
+                None,
+            );
+            assert_eq!(comment.severity, Some(Severity::Nit));
+            assert!(comment.updated_at.is_some());
+        }
+
+        #[test]
+        fn required_action_round_trips_and_defaults_to_unset() {
+            let mut comment = Comment::new("Why?".to_string(), CommentType::Note, None);
+            comment.action = Some(RequiredAction::Answer);
+            let json = serde_json::to_string(&comment).unwrap();
+            assert!(json.contains(r#""action":"answer""#));
+            let parsed: Comment = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed.action, Some(RequiredAction::Answer));
+
+            let legacy = json.replace(r#","action":"answer""#, "");
+            let parsed: Comment = serde_json::from_str(&legacy).unwrap();
+            assert_eq!(parsed.action, None);
+        }
+
+        #[test]
+        fn severity_cycles_through_none_and_orders_most_severe_first() {
+            let mut severity = None;
+            let mut seen = Vec::new();
+            for _ in 0..5 {
+                severity = Severity::cycle(severity);
+                seen.push(severity);
+            }
+            assert_eq!(
+                seen,
+                vec![
+                    Some(Severity::Blocker),
+                    Some(Severity::Major),
+                    Some(Severity::Minor),
+                    Some(Severity::Nit),
+                    None,
+                ]
+            );
+            assert!(Severity::Blocker.at_least(Severity::Major));
+            assert!(!Severity::Nit.at_least(Severity::Major));
+        }
     }
 }
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+use super::diff_types::{DiffHunk, LineOrigin};
+
+/// Section of a conflict region a line belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictSide {
+    /// Between `<<<<<<<` and `|||||||`/`=======`
+    Ours,
+    /// Between `|||||||` and `=======` (diff3 conflict style)
+    Base,
+    /// Between `=======` and `>>>>>>>`
+    Theirs,
+}
+
+impl ConflictSide {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConflictSide::Ours => "ours",
+            ConflictSide::Base => "base",
+            ConflictSide::Theirs => "theirs",
+        }
+    }
+}
+
+/// How a diff line takes part in a merge conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictLine {
+    Marker,
+    Section(ConflictSide),
+}
+
+/// Tag every line of a conflicted file's hunks with its conflict role.
+///
+/// Regions are tracked across hunks because a long "ours" section can be
+/// split by the diff into separate hunks. Deleted lines are not part of the
+/// working tree file and are never tagged.
+pub fn tag_conflict_lines(hunks: &[DiffHunk]) -> Vec<Vec<Option<ConflictLine>>> {
+    let mut side: Option<ConflictSide> = None;
+    hunks
+        .iter()
+        .map(|hunk| {
+            hunk.lines
+                .iter()
+                .map(|line| {
+                    if line.origin == LineOrigin::Deletion {
+                        return None;
+                    }
+                    let content = line.content.as_str();
+                    if content.starts_with("<<<<<<<") {
+                        side = Some(ConflictSide::Ours);
+                    } else if content.starts_with("|||||||") && side.is_some() {
+                        side = Some(ConflictSide::Base);
+                    } else if content.starts_with("=======") && side.is_some() {
+                        side = Some(ConflictSide::Theirs);
+                    } else if content.starts_with(">>>>>>>") && side.is_some() {
+                        side = None;
+                    } else {
+                        return side.map(ConflictLine::Section);
+                    }
+                    Some(ConflictLine::Marker)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::DiffLine;
+
+    fn hunk(lines: &[(LineOrigin, &str)]) -> DiffHunk {
+        DiffHunk {
+            header: "@@ -1,1 +1,1 @@".to_string(),
+            lines: lines
+                .iter()
+                .map(|(origin, content)| DiffLine {
+                    origin: *origin,
+                    content: content.to_string(),
+                    old_lineno: None,
+                    new_lineno: None,
+                    highlighted_spans: None,
+                })
+                .collect(),
+            old_start: 1,
+            old_count: 1,
+            new_start: 1,
+            new_count: 1,
+        }
+    }
+
+    #[test]
+    fn should_tag_ours_base_and_theirs_sections() {
+        // given a diff3-style conflict region
+        let hunks = vec![hunk(&[
+            (LineOrigin::Context, "before"),
+            (LineOrigin::Addition, "<<<<<<< HEAD"),
+            (LineOrigin::Context, "ours"),
+            (LineOrigin::Addition, "||||||| base"),
+            (LineOrigin::Addition, "base"),
+            (LineOrigin::Addition, "======="),
+            (LineOrigin::Addition, "theirs"),
+            (LineOrigin::Addition, ">>>>>>> feature"),
+            (LineOrigin::Context, "after"),
+        ])];
+
+        // when tagged
+        let tags = tag_conflict_lines(&hunks);
+
+        // then each section is labelled and markers are recognised
+        use ConflictLine::*;
+        assert_eq!(
+            tags[0],
+            vec![
+                None,
+                Some(Marker),
+                Some(Section(ConflictSide::Ours)),
+                Some(Marker),
+                Some(Section(ConflictSide::Base)),
+                Some(Marker),
+                Some(Section(ConflictSide::Theirs)),
+                Some(Marker),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_carry_region_across_hunks() {
+        // given a region whose "ours" side is split into two hunks
+        let hunks = vec![
+            hunk(&[
+                (LineOrigin::Addition, "<<<<<<< HEAD"),
+                (LineOrigin::Context, "ours 1"),
+            ]),
+            hunk(&[
+                (LineOrigin::Context, "ours 9"),
+                (LineOrigin::Addition, "======="),
+                (LineOrigin::Deletion, "old line"),
+                (LineOrigin::Addition, "theirs"),
+                (LineOrigin::Addition, ">>>>>>> other"),
+            ]),
+        ];
+
+        // when tagged
+        let tags = tag_conflict_lines(&hunks);
+
+        // then the second hunk continues the open region and skips deletions
+        assert_eq!(
+            tags[1],
+            vec![
+                Some(ConflictLine::Section(ConflictSide::Ours)),
+                Some(ConflictLine::Marker),
+                None,
+                Some(ConflictLine::Section(ConflictSide::Theirs)),
+                Some(ConflictLine::Marker),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_ignore_separator_outside_region() {
+        // given a markdown-style rule with no open conflict
+        let hunks = vec![hunk(&[(LineOrigin::Addition, "=======")])];
+
+        // when tagged / then it is not a marker
+        assert_eq!(tag_conflict_lines(&hunks)[0], vec![None]);
+    }
+}
@@ -1,6 +1,9 @@
 use ratatui::style::Style;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Path of the synthetic entry holding the messages of the reviewed commits
+pub const COMMIT_MESSAGE_PATH: &str = "COMMIT_MESSAGE";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -10,6 +13,8 @@ pub enum FileStatus {
     Deleted,
     Renamed,
     Copied,
+    /// Unmerged path with conflict markers in the working tree
+    Conflicted,
 }
 
 impl FileStatus {
@@ -20,10 +25,276 @@ impl FileStatus {
             FileStatus::Deleted => 'D',
             FileStatus::Renamed => 'R',
             FileStatus::Copied => 'C',
+            FileStatus::Conflicted => 'U',
         }
     }
 }
 
+/// Kind of tree entry on one side of a change, derived from its git file mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Executable,
+    Symlink,
+    Submodule,
+}
+
+impl EntryKind {
+    /// Map an octal git file mode (e.g. `0o120000`) to an entry kind
+    pub fn from_mode(mode: u32) -> Option<Self> {
+        match mode {
+            0o100644 | 0o100664 => Some(EntryKind::File),
+            0o100755 => Some(EntryKind::Executable),
+            0o120000 => Some(EntryKind::Symlink),
+            0o160000 => Some(EntryKind::Submodule),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryKind::File => "file",
+            EntryKind::Executable => "executable",
+            EntryKind::Symlink => "symlink",
+            EntryKind::Submodule => "submodule",
+        }
+    }
+
+    fn is_special(&self) -> bool {
+        matches!(self, EntryKind::Symlink | EntryKind::Submodule)
+    }
+}
+
+/// A change to a non-regular entry, summarized in place of content hunks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecialChange {
+    /// Symlink created, removed, or retargeted
+    Symlink {
+        old_target: Option<String>,
+        new_target: Option<String>,
+    },
+    /// Entry switched kind, e.g. a regular file replaced by a symlink
+    TypeChange {
+        old_kind: EntryKind,
+        new_kind: EntryKind,
+    },
+    /// Submodule added, removed, or moved to another commit
+    Submodule {
+        old_commit: Option<String>,
+        new_commit: Option<String>,
+        /// The checked-out submodule has uncommitted changes
+        dirty: bool,
+    },
+}
+
+impl SpecialChange {
+    /// Classify a change from the entry kind on each side.
+    /// Symlink targets and submodule commits are recovered from the hunks,
+    /// where git stores them as single-line content (`Subproject commit <sha>`
+    /// for submodules). Returns None for regular file changes.
+    pub fn classify(
+        old_kind: Option<EntryKind>,
+        new_kind: Option<EntryKind>,
+        hunks: &[DiffHunk],
+    ) -> Option<Self> {
+        if let (Some(old_kind), Some(new_kind)) = (old_kind, new_kind)
+            && old_kind != new_kind
+            && (old_kind.is_special() || new_kind.is_special())
+        {
+            return Some(SpecialChange::TypeChange { old_kind, new_kind });
+        }
+
+        let target = |origin: LineOrigin| {
+            hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .find(|line| line.origin == origin)
+                .map(|line| line.content.clone())
+        };
+
+        let is_submodule = |kind: Option<EntryKind>| kind == Some(EntryKind::Submodule);
+        if is_submodule(old_kind) || is_submodule(new_kind) {
+            let commit = |origin: LineOrigin| {
+                target(origin).and_then(|content| {
+                    let sha = content.strip_prefix("Subproject commit ")?.trim();
+                    Some(sha.to_string())
+                })
+            };
+            let new_commit = commit(LineOrigin::Addition);
+            let dirty = new_commit
+                .as_deref()
+                .is_some_and(|sha| sha.ends_with("-dirty"));
+            return Some(SpecialChange::Submodule {
+                old_commit: commit(LineOrigin::Deletion),
+                new_commit: new_commit.map(|sha| sha.trim_end_matches("-dirty").to_string()),
+                dirty,
+            });
+        }
+
+        let is_symlink = |kind: Option<EntryKind>| kind == Some(EntryKind::Symlink);
+        if !is_symlink(old_kind) && !is_symlink(new_kind) {
+            return None;
+        }
+
+        Some(SpecialChange::Symlink {
+            old_target: target(LineOrigin::Deletion),
+            new_target: target(LineOrigin::Addition),
+        })
+    }
+
+    /// One-line description shown in place of the diff body
+    pub fn summary(&self) -> String {
+        match self {
+            SpecialChange::Symlink {
+                old_target,
+                new_target,
+            } => match (old_target, new_target) {
+                (Some(old), Some(new)) => format!("symlink: {old} → {new}"),
+                (None, Some(new)) => format!("new symlink → {new}"),
+                (Some(old), None) => format!("deleted symlink → {old}"),
+                (None, None) => "symlink (target unchanged)".to_string(),
+            },
+            SpecialChange::TypeChange { old_kind, new_kind } => {
+                format!("type change: {} → {}", old_kind.label(), new_kind.label())
+            }
+            SpecialChange::Submodule {
+                old_commit,
+                new_commit,
+                dirty,
+            } => {
+                let summary = match (old_commit, new_commit) {
+                    (Some(old), Some(new)) if old == new => {
+                        format!("submodule at {}", short_sha(new))
+                    }
+                    (Some(old), Some(new)) => {
+                        format!("submodule: {} → {}", short_sha(old), short_sha(new))
+                    }
+                    (None, Some(new)) => format!("new submodule at {}", short_sha(new)),
+                    (Some(old), None) => format!("removed submodule (was {})", short_sha(old)),
+                    (None, None) => "submodule".to_string(),
+                };
+                if *dirty {
+                    format!("{summary} (uncommitted changes)")
+                } else {
+                    summary
+                }
+            }
+        }
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..7.min(sha.len())]
+}
+
+/// What is known about a binary file, shown in place of "(binary file)".
+/// Git LFS pointers are small text files, so they are diffed as text and
+/// recognized afterwards; the object IDs and sizes are read off the pointer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinaryDetail {
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    /// `oid` of the Git LFS pointer on each side, e.g. `sha256:4d7a…`
+    pub old_oid: Option<String>,
+    pub new_oid: Option<String>,
+    /// Set when either side is a Git LFS pointer
+    pub lfs: bool,
+}
+
+impl BinaryDetail {
+    /// Recognize a Git LFS pointer file from its text hunks. A side counts as
+    /// a pointer when every line is a pointer key and both `oid` and `size`
+    /// are present; returns None unless at least one side is a pointer.
+    pub fn from_lfs_pointers(hunks: &[DiffHunk]) -> Option<Self> {
+        let side = |skip: LineOrigin| {
+            let mut oid = None;
+            let mut size = None;
+            for line in hunks.iter().flat_map(|hunk| hunk.lines.iter()) {
+                if line.origin == skip {
+                    continue;
+                }
+                let content = line.content.trim_end();
+                if let Some(value) = content.strip_prefix("oid ") {
+                    oid = Some(value.to_string());
+                } else if let Some(value) = content.strip_prefix("size ") {
+                    size = Some(value.parse::<u64>().ok()?);
+                } else if !content.starts_with("version ") && !content.starts_with("ext-") {
+                    return None;
+                }
+            }
+            oid.zip(size)
+        };
+
+        let old = side(LineOrigin::Addition);
+        let new = side(LineOrigin::Deletion);
+        if old.is_none() && new.is_none() {
+            return None;
+        }
+        let (old_oid, old_size) = old.unzip();
+        let (new_oid, new_size) = new.unzip();
+        Some(BinaryDetail {
+            old_size,
+            new_size,
+            old_oid,
+            new_oid,
+            lfs: true,
+        })
+    }
+
+    /// One-line description shown in place of the diff body
+    pub fn summary(&self) -> String {
+        let kind = if self.lfs {
+            "Git LFS object"
+        } else {
+            "binary file"
+        };
+        let sizes = match (self.old_size, self.new_size) {
+            (Some(old), Some(new)) if old == new => format_size(new),
+            (Some(old), Some(new)) => {
+                let delta = if new >= old {
+                    format!("+{}", format_size(new - old))
+                } else {
+                    format!("-{}", format_size(old - new))
+                };
+                format!("{} → {} ({delta})", format_size(old), format_size(new))
+            }
+            (None, Some(new)) => format!("new, {}", format_size(new)),
+            (Some(old), None) => format!("deleted, was {}", format_size(old)),
+            (None, None) => return format!("({kind})"),
+        };
+        match (&self.old_oid, &self.new_oid) {
+            (Some(old), Some(new)) if old != new => {
+                format!("{kind}: {} → {}, {sizes}", short_oid(old), short_oid(new))
+            }
+            (_, Some(oid)) | (Some(oid), None) => {
+                format!("{kind}: {}, {sizes}", short_oid(oid))
+            }
+            (None, None) => format!("{kind}: {sizes}"),
+        }
+    }
+}
+
+/// `sha256:4d7a214…` shortened to `4d7a214`
+fn short_oid(oid: &str) -> &str {
+    let hash = oid.split_once(':').map_or(oid, |(_, hash)| hash);
+    short_sha(hash)
+}
+
+/// Human-readable byte count, e.g. `812 B` or `1.4 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LineOrigin {
     Context,
@@ -47,10 +318,8 @@ pub struct DiffHunk {
     pub header: String,
     pub lines: Vec<DiffLine>,
     /// Starting line number in the old file (from @@ header)
-    #[allow(dead_code)]
     pub old_start: u32,
     /// Number of lines from the old file in this hunk
-    #[allow(dead_code)]
     pub old_count: u32,
     /// Starting line number in the new file (from @@ header)
     pub new_start: u32,
@@ -65,13 +334,452 @@ pub struct DiffFile {
     pub status: FileStatus,
     pub hunks: Vec<DiffHunk>,
     pub is_binary: bool,
+    /// Set for symlinks and other non-regular entries. `hunks` is empty when
+    /// present, except for an expanded submodule commit log.
+    pub special: Option<SpecialChange>,
+    /// Similarity percentage reported for renamed or copied files
+    pub similarity: Option<u8>,
+    /// Sizes and LFS object IDs for binary files and Git LFS pointers.
+    /// `hunks` is empty when present.
+    pub binary_detail: Option<BinaryDetail>,
+    /// Old and new octal mode of a regular file whose mode changed,
+    /// e.g. `(0o100644, 0o100755)` when the executable bit was set
+    pub mode_change: Option<(u32, u32)>,
+}
+
+/// The pair of modes when both sides are regular files with different modes.
+/// Symlink and submodule switches are type changes instead.
+pub fn mode_change(old_mode: Option<u32>, new_mode: Option<u32>) -> Option<(u32, u32)> {
+    let (old, new) = (old_mode?, new_mode?);
+    let is_regular = |mode| {
+        matches!(
+            EntryKind::from_mode(mode),
+            Some(EntryKind::File | EntryKind::Executable)
+        )
+    };
+    (old != new && is_regular(old) && is_regular(new)).then_some((old, new))
 }
 
 impl DiffFile {
+    /// A synthetic added file whose lines are the commit messages, so they can be
+    /// commented on like code. `messages` pairs short commit ids with messages;
+    /// with more than one commit each message starts with a `commit <id>` line.
+    pub fn commit_message(messages: &[(String, String)]) -> Self {
+        let mut text: Vec<String> = Vec::new();
+        for (short_id, message) in messages {
+            if messages.len() > 1 {
+                if !text.is_empty() {
+                    text.push(String::new());
+                }
+                text.push(format!("commit {short_id}"));
+            }
+            text.extend(message.trim_end().lines().map(str::to_string));
+        }
+
+        let count = text.len() as u32;
+        let lines = text
+            .into_iter()
+            .enumerate()
+            .map(|(i, content)| DiffLine {
+                origin: LineOrigin::Addition,
+                content,
+                old_lineno: None,
+                new_lineno: Some(i as u32 + 1),
+                highlighted_spans: None,
+            })
+            .collect();
+        Self {
+            old_path: None,
+            new_path: Some(PathBuf::from(COMMIT_MESSAGE_PATH)),
+            status: FileStatus::Added,
+            hunks: vec![DiffHunk {
+                header: format!("@@ -0,0 +1,{count} @@"),
+                lines,
+                old_start: 0,
+                old_count: 0,
+                new_start: 1,
+                new_count: count,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    /// Status letter shown in the file list and file header. Symlink
+    /// changes get `@` (as in `ls -F`) so they stand out from file edits.
+    pub fn status_char(&self) -> char {
+        let touches_symlink = match &self.special {
+            Some(SpecialChange::Symlink { .. }) => true,
+            Some(SpecialChange::TypeChange { old_kind, new_kind }) => {
+                *old_kind == EntryKind::Symlink || *new_kind == EntryKind::Symlink
+            }
+            _ => false,
+        };
+        if touches_symlink {
+            '@'
+        } else {
+            self.status.as_char()
+        }
+    }
+
+    /// Whether this is the synthetic commit message entry
+    pub fn is_commit_message(&self) -> bool {
+        self.old_path.is_none() && self.new_path.as_deref() == Some(Path::new(COMMIT_MESSAGE_PATH))
+    }
+
     pub fn display_path(&self) -> &PathBuf {
         self.new_path
             .as_ref()
             .or(self.old_path.as_ref())
             .expect("DiffFile must have at least one path")
     }
+
+    /// Original path of a renamed file, or the source of a copied one,
+    /// when it differs from the new path
+    pub fn source_path(&self) -> Option<&PathBuf> {
+        if !matches!(self.status, FileStatus::Renamed | FileStatus::Copied) {
+            return None;
+        }
+        self.old_path
+            .as_ref()
+            .filter(|old| Some(*old) != self.new_path.as_ref())
+    }
+
+    /// " (92%)" when a similarity score is known, empty otherwise
+    pub fn similarity_suffix(&self) -> String {
+        self.similarity
+            .map(|score| format!(" ({score}%)"))
+            .unwrap_or_default()
+    }
+
+    /// Path label for the diff header: `old.rs → new.rs (92%)` for renames and copies
+    pub fn header_label(&self) -> String {
+        let label = match self.source_path() {
+            Some(old) => format!(
+                "{} → {}{}",
+                old.display(),
+                self.display_path().display(),
+                self.similarity_suffix()
+            ),
+            None => self.display_path().display().to_string(),
+        };
+        match self.mode_change {
+            Some((old, new)) => format!("{label} (mode {old:o} → {new:o})"),
+            None => label,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(lines: &[(LineOrigin, &str)]) -> DiffHunk {
+        DiffHunk {
+            header: "@@ -1 +1 @@".to_string(),
+            lines: lines
+                .iter()
+                .map(|(origin, content)| DiffLine {
+                    origin: *origin,
+                    content: content.to_string(),
+                    old_lineno: None,
+                    new_lineno: None,
+                    highlighted_spans: None,
+                })
+                .collect(),
+            old_start: 1,
+            old_count: 1,
+            new_start: 1,
+            new_count: 1,
+        }
+    }
+
+    fn renamed_file(old: &str, new: &str, similarity: Option<u8>) -> DiffFile {
+        DiffFile {
+            old_path: Some(PathBuf::from(old)),
+            new_path: Some(PathBuf::from(new)),
+            status: FileStatus::Renamed,
+            hunks: Vec::new(),
+            is_binary: false,
+            special: None,
+            similarity,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    #[test]
+    fn should_mark_symlink_changes_with_their_own_glyph() {
+        // given a renamed file turned into a symlink retarget
+        let mut file = renamed_file("link", "link", None);
+        assert_eq!(file.status_char(), 'R');
+
+        // when
+        file.special = Some(SpecialChange::Symlink {
+            old_target: Some("a".to_string()),
+            new_target: Some("b".to_string()),
+        });
+
+        // then
+        assert_eq!(file.status_char(), '@');
+    }
+
+    #[test]
+    fn should_label_rename_with_similarity() {
+        let file = renamed_file("src/old.rs", "src/new.rs", Some(92));
+
+        assert_eq!(file.header_label(), "src/old.rs → src/new.rs (92%)");
+        assert_eq!(file.source_path(), Some(&PathBuf::from("src/old.rs")));
+    }
+
+    #[test]
+    fn should_label_rename_without_similarity() {
+        let file = renamed_file("old.rs", "new.rs", None);
+
+        assert_eq!(file.header_label(), "old.rs → new.rs");
+    }
+
+    #[test]
+    fn should_label_modified_file_with_path_only() {
+        let mut file = renamed_file("a.rs", "a.rs", None);
+        file.status = FileStatus::Modified;
+
+        assert_eq!(file.header_label(), "a.rs");
+        assert_eq!(file.source_path(), None);
+    }
+
+    #[test]
+    fn should_label_copy_with_source_path() {
+        let mut file = renamed_file("src/template.rs", "src/copy.rs", Some(100));
+        file.status = FileStatus::Copied;
+
+        assert_eq!(file.header_label(), "src/template.rs → src/copy.rs (100%)");
+    }
+
+    #[test]
+    fn should_map_git_modes_to_entry_kinds() {
+        assert_eq!(EntryKind::from_mode(0o100644), Some(EntryKind::File));
+        assert_eq!(EntryKind::from_mode(0o100755), Some(EntryKind::Executable));
+        assert_eq!(EntryKind::from_mode(0o120000), Some(EntryKind::Symlink));
+        assert_eq!(EntryKind::from_mode(0o160000), Some(EntryKind::Submodule));
+        assert_eq!(EntryKind::from_mode(0), None);
+    }
+
+    #[test]
+    fn should_extract_symlink_targets_from_hunks() {
+        // given
+        let hunks = vec![hunk(&[
+            (LineOrigin::Deletion, "../old/target"),
+            (LineOrigin::Addition, "../new/target"),
+        ])];
+
+        // when
+        let special =
+            SpecialChange::classify(Some(EntryKind::Symlink), Some(EntryKind::Symlink), &hunks);
+
+        // then
+        let special = special.expect("symlink change should be classified");
+        assert_eq!(special.summary(), "symlink: ../old/target → ../new/target");
+    }
+
+    #[test]
+    fn should_classify_file_replaced_by_symlink_as_type_change() {
+        let special = SpecialChange::classify(Some(EntryKind::File), Some(EntryKind::Symlink), &[]);
+
+        assert_eq!(
+            special,
+            Some(SpecialChange::TypeChange {
+                old_kind: EntryKind::File,
+                new_kind: EntryKind::Symlink,
+            })
+        );
+    }
+
+    #[test]
+    fn should_summarize_submodule_pointer_change() {
+        // given
+        let hunks = vec![hunk(&[
+            (
+                LineOrigin::Deletion,
+                "Subproject commit 1111111aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            ),
+            (
+                LineOrigin::Addition,
+                "Subproject commit 2222222bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb-dirty",
+            ),
+        ])];
+
+        // when
+        let special = SpecialChange::classify(
+            Some(EntryKind::Submodule),
+            Some(EntryKind::Submodule),
+            &hunks,
+        );
+
+        // then
+        let special = special.expect("submodule change should be classified");
+        assert_eq!(
+            special.summary(),
+            "submodule: 1111111 → 2222222 (uncommitted changes)"
+        );
+    }
+
+    #[test]
+    fn should_summarize_new_submodule() {
+        let hunks = vec![hunk(&[(
+            LineOrigin::Addition,
+            "Subproject commit 3333333ccccccccccccccccccccccccccccccccc",
+        )])];
+
+        let special = SpecialChange::classify(None, Some(EntryKind::Submodule), &hunks);
+
+        assert_eq!(
+            special.map(|s| s.summary()),
+            Some("new submodule at 3333333".to_string())
+        );
+    }
+
+    #[test]
+    fn should_not_classify_regular_file_changes() {
+        assert_eq!(
+            SpecialChange::classify(Some(EntryKind::File), Some(EntryKind::File), &[]),
+            None
+        );
+        assert_eq!(
+            SpecialChange::classify(Some(EntryKind::File), Some(EntryKind::Executable), &[]),
+            None
+        );
+    }
+
+    fn lfs_pointer(oid: &str, size: u64) -> Vec<String> {
+        vec![
+            "version https://git-lfs.github.com/spec/v1".to_string(),
+            format!("oid sha256:{oid}"),
+            format!("size {size}"),
+        ]
+    }
+
+    #[test]
+    fn should_recognize_changed_lfs_pointer() {
+        // given a pointer whose object and size changed
+        let old = lfs_pointer(
+            "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393",
+            1024,
+        );
+        let new = lfs_pointer(
+            "8c0d2e4f9a1b3c5d7e9f0a2b4c6d8e0f1a3b5c7d9e0f2a4b6c8d0e2f4a6b8c0d",
+            1536,
+        );
+        let hunks = vec![hunk(&[
+            (LineOrigin::Context, old[0].as_str()),
+            (LineOrigin::Deletion, old[1].as_str()),
+            (LineOrigin::Deletion, old[2].as_str()),
+            (LineOrigin::Addition, new[1].as_str()),
+            (LineOrigin::Addition, new[2].as_str()),
+        ])];
+
+        // when
+        let detail = BinaryDetail::from_lfs_pointers(&hunks).unwrap();
+
+        // then
+        assert_eq!(detail.old_size, Some(1024));
+        assert_eq!(detail.new_size, Some(1536));
+        assert_eq!(
+            detail.summary(),
+            "Git LFS object: 4d7a214 → 8c0d2e4, 1.0 KiB → 1.5 KiB (+512 B)"
+        );
+    }
+
+    #[test]
+    fn should_recognize_added_lfs_pointer() {
+        let new = lfs_pointer(
+            "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393",
+            3 * 1024 * 1024,
+        );
+        let lines: Vec<(LineOrigin, &str)> = new
+            .iter()
+            .map(|l| (LineOrigin::Addition, l.as_str()))
+            .collect();
+
+        let detail = BinaryDetail::from_lfs_pointers(&[hunk(&lines)]).unwrap();
+
+        assert_eq!(detail.summary(), "Git LFS object: 4d7a214, new, 3.0 MiB");
+    }
+
+    #[test]
+    fn should_not_treat_text_mentioning_oid_as_lfs_pointer() {
+        let hunks = vec![hunk(&[
+            (LineOrigin::Addition, "oid sha256:abc"),
+            (LineOrigin::Addition, "size 12"),
+            (LineOrigin::Addition, "some other text"),
+        ])];
+
+        assert_eq!(BinaryDetail::from_lfs_pointers(&hunks), None);
+    }
+
+    #[test]
+    fn should_summarize_binary_sizes() {
+        let detail = BinaryDetail {
+            old_size: Some(2048),
+            new_size: Some(1024),
+            ..BinaryDetail::default()
+        };
+        assert_eq!(
+            detail.summary(),
+            "binary file: 2.0 KiB → 1.0 KiB (-1.0 KiB)"
+        );
+        assert_eq!(BinaryDetail::default().summary(), "(binary file)");
+        assert_eq!(format_size(812), "812 B");
+    }
+
+    #[test]
+    fn should_build_commit_message_entry() {
+        // given two commits
+        let messages = vec![
+            (
+                "abc1234".to_string(),
+                "Add parser\n\nHandles nested blocks.\n".to_string(),
+            ),
+            ("def5678".to_string(), "Fix typo".to_string()),
+        ];
+
+        // when
+        let file = DiffFile::commit_message(&messages);
+
+        // then every message line is an added, numbered line
+        assert!(file.is_commit_message());
+        let lines: Vec<&str> = file.hunks[0]
+            .lines
+            .iter()
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "commit abc1234",
+                "Add parser",
+                "",
+                "Handles nested blocks.",
+                "",
+                "commit def5678",
+                "Fix typo",
+            ]
+        );
+        assert_eq!(file.hunks[0].lines[6].new_lineno, Some(7));
+        assert_eq!(file.hunks[0].new_count, 7);
+    }
+
+    #[test]
+    fn should_detect_mode_change_between_regular_files() {
+        assert_eq!(
+            mode_change(Some(0o100644), Some(0o100755)),
+            Some((0o100644, 0o100755))
+        );
+        assert_eq!(mode_change(Some(0o100644), Some(0o100644)), None);
+        assert_eq!(mode_change(Some(0o100644), Some(0o120000)), None);
+        assert_eq!(mode_change(None, Some(0o100755)), None);
+    }
 }
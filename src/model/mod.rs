@@ -1,7 +1,17 @@
 pub mod comment;
+pub mod conflict;
 pub mod diff_types;
 pub mod review;
+pub mod snapshot;
 
-pub use comment::{Comment, CommentType, LineRange, LineSide};
-pub use diff_types::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
-pub use review::{ReviewSession, SessionDiffSource};
+pub use comment::{
+    ColumnRange, Comment, CommentType, ContentAnchor, LineRange, LineSide, Reply, RequiredAction,
+    Severity,
+};
+pub use conflict::{ConflictLine, ConflictSide, tag_conflict_lines};
+pub use diff_types::{
+    BinaryDetail, DiffFile, DiffHunk, DiffLine, EntryKind, FileStatus, LineOrigin, SpecialChange,
+    mode_change,
+};
+pub use review::{FileReview, ReviewSession, ReviewState, SessionDiffSource};
+pub use snapshot::DiffSnapshot;
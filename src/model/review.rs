@@ -1,15 +1,88 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 use super::comment::Comment;
 use super::diff_types::FileStatus;
+use super::snapshot::DiffSnapshot;
+
+/// How far the review of a file has got; `r` steps through them in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewState {
+    #[default]
+    Unreviewed,
+    InProgress,
+    NeedsSecondLook,
+    Approved,
+}
+
+impl ReviewState {
+    pub const ALL: [ReviewState; 4] = [
+        ReviewState::Unreviewed,
+        ReviewState::InProgress,
+        ReviewState::NeedsSecondLook,
+        ReviewState::Approved,
+    ];
+
+    /// The state `r` moves to, wrapping from approved back to unreviewed
+    pub fn next(self) -> Self {
+        match self {
+            ReviewState::Unreviewed => ReviewState::InProgress,
+            ReviewState::InProgress => ReviewState::NeedsSecondLook,
+            ReviewState::NeedsSecondLook => ReviewState::Approved,
+            ReviewState::Approved => ReviewState::Unreviewed,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReviewState::Unreviewed => "unreviewed",
+            ReviewState::InProgress => "in progress",
+            ReviewState::NeedsSecondLook => "needs a second look",
+            ReviewState::Approved => "approved",
+        }
+    }
+
+    /// Mark shown in the file list checkbox and file headers
+    pub fn mark(self) -> &'static str {
+        match self {
+            ReviewState::Unreviewed => " ",
+            ReviewState::InProgress => "~",
+            ReviewState::NeedsSecondLook => "?",
+            ReviewState::Approved => "✓",
+        }
+    }
+
+    /// Sessions saved before review states stored `"reviewed": true/false`
+    fn deserialize_compat<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Reviewed(bool),
+            State(ReviewState),
+        }
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Reviewed(true) => ReviewState::Approved,
+            Stored::Reviewed(false) => ReviewState::Unreviewed,
+            Stored::State(state) => state,
+        })
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileReview {
     pub path: PathBuf,
-    pub reviewed: bool,
+    #[serde(
+        alias = "reviewed",
+        deserialize_with = "ReviewState::deserialize_compat"
+    )]
+    pub state: ReviewState,
+    /// Left out of the review with `x` ("not mine to review"): no longer
+    /// counted toward progress and folded in the diff, but still listed
+    #[serde(default)]
+    pub excluded: bool,
     pub status: FileStatus,
     pub file_comments: Vec<Comment>,
     pub line_comments: HashMap<u32, Vec<Comment>>,
@@ -19,17 +92,43 @@ impl FileReview {
     pub fn new(path: PathBuf, status: FileStatus) -> Self {
         Self {
             path,
-            reviewed: false,
+            state: ReviewState::Unreviewed,
+            excluded: false,
             status,
             file_comments: Vec::new(),
             line_comments: HashMap::new(),
         }
     }
 
+    pub fn is_reviewed(&self) -> bool {
+        self.state == ReviewState::Approved
+    }
+
+    /// Nothing left to do on the file: approved, or excluded from the review
+    pub fn is_done(&self) -> bool {
+        self.excluded || self.is_reviewed()
+    }
+
     pub fn comment_count(&self) -> usize {
         self.file_comments.len() + self.line_comments.values().map(|v| v.len()).sum::<usize>()
     }
 
+    /// Drop the file and line comments, returning how many there were
+    pub fn clear_comments(&mut self) -> usize {
+        let cleared = self.comment_count();
+        self.file_comments.clear();
+        self.line_comments.clear();
+        cleared
+    }
+
+    pub fn blocking_count(&self) -> usize {
+        self.file_comments
+            .iter()
+            .chain(self.line_comments.values().flatten())
+            .filter(|c| c.blocks_review())
+            .count()
+    }
+
     pub fn add_file_comment(&mut self, comment: Comment) {
         self.file_comments.push(comment);
     }
@@ -64,6 +163,25 @@ pub struct ReviewSession {
     pub updated_at: DateTime<Utc>,
     pub files: HashMap<PathBuf, FileReview>,
     pub session_notes: Option<String>,
+    /// File order chosen with `:plan`; empty means the default tree order
+    #[serde(default)]
+    pub review_plan: Vec<PathBuf>,
+    /// Comments on the review as a whole, shown in the "General" section
+    #[serde(default)]
+    pub session_comments: Vec<Comment>,
+    /// Comments anchored to a directory rather than a single file
+    #[serde(default)]
+    pub directory_comments: BTreeMap<PathBuf, Vec<Comment>>,
+    /// Pathspecs from `tuicr -- <PATH>...`; reloads only load matching files
+    #[serde(default)]
+    pub pathspecs: Vec<String>,
+    /// Evidence attached with `:attach` (benchmark output, screenshots,
+    /// profiles), relative to the repository when inside it
+    #[serde(default)]
+    pub attachments: Vec<PathBuf>,
+    /// The diff as it was when last saved, with `snapshot_diff` on
+    #[serde(default)]
+    pub snapshot: Option<DiffSnapshot>,
 }
 
 impl ReviewSession {
@@ -86,13 +204,15 @@ impl ReviewSession {
             updated_at: now,
             files: HashMap::new(),
             session_notes: None,
+            review_plan: Vec::new(),
+            session_comments: Vec::new(),
+            directory_comments: BTreeMap::new(),
+            pathspecs: Vec::new(),
+            attachments: Vec::new(),
+            snapshot: None,
         }
     }
 
-    pub fn reviewed_count(&self) -> usize {
-        self.files.values().filter(|f| f.reviewed).count()
-    }
-
     pub fn add_file(&mut self, path: PathBuf, status: FileStatus) {
         self.files
             .entry(path.clone())
@@ -104,20 +224,456 @@ impl ReviewSession {
     }
 
     pub fn has_comments(&self) -> bool {
-        self.files.values().any(|f| f.comment_count() > 0)
+        self.files.values().any(|f| f.comment_count() > 0) || !self.general_comments().is_empty()
+    }
+
+    pub fn blocking_count(&self) -> usize {
+        let general = self
+            .general_comments()
+            .into_iter()
+            .filter(|(_, c)| c.blocks_review())
+            .count();
+        general
+            + self
+                .files
+                .values()
+                .map(FileReview::blocking_count)
+                .sum::<usize>()
+    }
+
+    /// Line comments whose code was not found again after a reload
+    pub fn lost_anchor_count(&self) -> usize {
+        self.files
+            .values()
+            .flat_map(|review| review.line_comments.values().flatten())
+            .filter(|comment| comment.anchor_lost)
+            .count()
+    }
+
+    /// Comments of any type not marked resolved
+    pub fn unresolved_count(&self) -> usize {
+        let file_comments = self.files.values().flat_map(|review| {
+            review
+                .file_comments
+                .iter()
+                .chain(review.line_comments.values().flatten())
+        });
+        self.general_comments()
+            .into_iter()
+            .map(|(_, comment)| comment)
+            .chain(file_comments)
+            .filter(|comment| !comment.resolved)
+            .count()
+    }
+
+    /// Whether this session has anything an earlier review hasn't: a
+    /// comment, or a file with a review state or excluded
+    pub fn is_untouched(&self) -> bool {
+        !self.has_comments()
+            && self
+                .files
+                .values()
+                .all(|review| review.state == ReviewState::Unreviewed && !review.excluded)
+    }
+
+    /// Start from an earlier review of the same branch, for a follow-up
+    /// after changes were requested: its unresolved comments and the state
+    /// of each file, for files that are in this review too. Returns how many
+    /// comments were carried over.
+    pub fn seed_from(&mut self, previous: &ReviewSession) -> usize {
+        let mut carried = 0;
+        for (path, old) in &previous.files {
+            let Some(review) = self.files.get_mut(path) else {
+                continue;
+            };
+            review.state = old.state;
+            review.excluded = old.excluded;
+            for comment in old.file_comments.iter().filter(|c| !c.resolved) {
+                review.add_file_comment(comment.clone());
+                carried += 1;
+            }
+            for (line, comments) in &old.line_comments {
+                for comment in comments.iter().filter(|c| !c.resolved) {
+                    review.add_line_comment(*line, comment.clone());
+                    carried += 1;
+                }
+            }
+        }
+        for (dir, comment) in previous.general_comments() {
+            if !comment.resolved {
+                self.add_general_comment(dir.cloned(), comment.clone());
+                carried += 1;
+            }
+        }
+        carried
+    }
+
+    /// Take back what was salvaged from a damaged save of this review: file
+    /// states and comments for files still in it, every other comment as a
+    /// session comment so none is lost. Returns how many comments came back.
+    pub fn recover_from(&mut self, salvaged: &ReviewSession) -> usize {
+        let mut recovered = 0;
+        for (path, old) in &salvaged.files {
+            recovered += old.comment_count();
+            let Some(review) = self.files.get_mut(path) else {
+                self.session_comments.extend(
+                    old.file_comments
+                        .iter()
+                        .chain(old.line_comments.values().flatten())
+                        .cloned(),
+                );
+                continue;
+            };
+            review.state = old.state;
+            review.excluded = old.excluded;
+            review
+                .file_comments
+                .extend(old.file_comments.iter().cloned());
+            for (line, comments) in &old.line_comments {
+                review
+                    .line_comments
+                    .entry(*line)
+                    .or_default()
+                    .extend(comments.iter().cloned());
+            }
+        }
+        for (dir, comment) in salvaged.general_comments() {
+            self.add_general_comment(dir.cloned(), comment.clone());
+            recovered += 1;
+        }
+        recovered
+    }
+
+    /// Comments without a file anchor: directory comments (by path), then
+    /// session comments, which have no directory
+    pub fn general_comments(&self) -> Vec<(Option<&PathBuf>, &Comment)> {
+        self.directory_comments
+            .iter()
+            .flat_map(|(dir, comments)| comments.iter().map(move |c| (Some(dir), c)))
+            .chain(self.session_comments.iter().map(|c| (None, c)))
+            .collect()
+    }
+
+    /// Add a comment on a directory, or on the session when `dir` is None
+    pub fn add_general_comment(&mut self, dir: Option<PathBuf>, comment: Comment) {
+        match dir {
+            Some(dir) => self
+                .directory_comments
+                .entry(dir)
+                .or_default()
+                .push(comment),
+            None => self.session_comments.push(comment),
+        }
+    }
+
+    pub fn find_general_comment_mut(&mut self, id: &str) -> Option<&mut Comment> {
+        self.directory_comments
+            .values_mut()
+            .flatten()
+            .chain(self.session_comments.iter_mut())
+            .find(|c| c.id == id)
+    }
+
+    /// Find any comment, file, line or general, by id
+    /// Find a comment by id anywhere in the session
+    pub fn find_comment(&self, id: &str) -> Option<&Comment> {
+        self.files
+            .values()
+            .flat_map(|review| {
+                review
+                    .file_comments
+                    .iter()
+                    .chain(review.line_comments.values().flatten())
+            })
+            .chain(self.directory_comments.values().flatten())
+            .chain(self.session_comments.iter())
+            .find(|c| c.id == id)
+    }
+
+    pub fn find_comment_mut(&mut self, id: &str) -> Option<&mut Comment> {
+        self.files
+            .values_mut()
+            .flat_map(|review| {
+                review
+                    .file_comments
+                    .iter_mut()
+                    .chain(review.line_comments.values_mut().flatten())
+            })
+            .chain(self.directory_comments.values_mut().flatten())
+            .chain(self.session_comments.iter_mut())
+            .find(|c| c.id == id)
+    }
+
+    /// Remove a directory or session comment by id. Returns the comment
+    /// with its directory and position, if it existed.
+    pub fn remove_general_comment(
+        &mut self,
+        id: &str,
+    ) -> Option<(Option<PathBuf>, usize, Comment)> {
+        if let Some(idx) = self.session_comments.iter().position(|c| c.id == id) {
+            return Some((None, idx, self.session_comments.remove(idx)));
+        }
+        for (dir, comments) in self.directory_comments.iter_mut() {
+            if let Some(idx) = comments.iter().position(|c| c.id == id) {
+                let removed = (Some(dir.clone()), idx, comments.remove(idx));
+                self.directory_comments
+                    .retain(|_, comments| !comments.is_empty());
+                return Some(removed);
+            }
+        }
+        None
+    }
+
+    /// Put a directory or session comment back at `index`, as returned by
+    /// `remove_general_comment`
+    pub fn insert_general_comment(&mut self, dir: Option<PathBuf>, index: usize, comment: Comment) {
+        let comments = match dir {
+            Some(dir) => self.directory_comments.entry(dir).or_default(),
+            None => &mut self.session_comments,
+        };
+        comments.insert(index.min(comments.len()), comment);
     }
 
     pub fn clear_comments(&mut self) -> usize {
-        let mut cleared = 0;
+        let mut cleared = self.general_comments().len();
+        self.session_comments.clear();
+        self.directory_comments.clear();
         for file in self.files.values_mut() {
-            cleared += file.comment_count();
-            file.file_comments.clear();
-            file.line_comments.clear();
+            cleared += file.clear_comments();
         }
         cleared
     }
 
     pub fn is_file_reviewed(&self, path: &PathBuf) -> bool {
-        self.files.get(path).map(|r| r.reviewed).unwrap_or(false)
+        self.file_state(path) == ReviewState::Approved
+    }
+
+    pub fn is_file_excluded(&self, path: &PathBuf) -> bool {
+        self.files.get(path).is_some_and(|r| r.excluded)
+    }
+
+    pub fn file_state(&self, path: &PathBuf) -> ReviewState {
+        self.files.get(path).map(|r| r.state).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{CommentType, LineSide, Severity};
+    use std::path::Path;
+
+    #[test]
+    fn should_load_reviewed_flag_of_older_sessions() {
+        // given file reviews saved with the old boolean and with a state
+        let old = r#"{"path": "a.rs", "reviewed": true, "status": "modified", "file_comments": [], "line_comments": {}}"#;
+        let new = r#"{"path": "b.rs", "state": "needs_second_look", "status": "modified", "file_comments": [], "line_comments": {}}"#;
+
+        // when loaded
+        let old: FileReview = serde_json::from_str(old).unwrap();
+        let new: FileReview = serde_json::from_str(new).unwrap();
+
+        // then a reviewed file counts as approved
+        assert_eq!(old.state, ReviewState::Approved);
+        assert_eq!(new.state, ReviewState::NeedsSecondLook);
+    }
+
+    #[test]
+    fn should_cycle_through_review_states() {
+        let mut state = ReviewState::default();
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            state = state.next();
+            seen.push(state);
+        }
+        assert_eq!(
+            seen,
+            [
+                ReviewState::InProgress,
+                ReviewState::NeedsSecondLook,
+                ReviewState::Approved,
+                ReviewState::Unreviewed,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_seed_open_comments_and_file_states_from_an_earlier_review() {
+        // given an earlier review with an open and a resolved comment
+        let mut previous = ReviewSession::new(
+            PathBuf::from("/repo"),
+            "abc".to_string(),
+            Some("feature".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        previous.add_file(PathBuf::from("a.rs"), FileStatus::Modified);
+        previous.add_file(PathBuf::from("gone.rs"), FileStatus::Modified);
+        let review = previous.get_file_mut(&PathBuf::from("a.rs")).unwrap();
+        review.state = ReviewState::NeedsSecondLook;
+        review.add_line_comment(3, Comment::new("fix".to_string(), CommentType::Issue, None));
+        let mut resolved = Comment::new("done".to_string(), CommentType::Note, None);
+        resolved.resolved = true;
+        review.add_file_comment(resolved);
+        previous.add_general_comment(
+            None,
+            Comment::new("overall".to_string(), CommentType::Note, None),
+        );
+        let mut session = ReviewSession::new(
+            PathBuf::from("/repo"),
+            "def".to_string(),
+            Some("feature".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("a.rs"), FileStatus::Modified);
+        assert!(session.is_untouched());
+
+        // when
+        let carried = session.seed_from(&previous);
+
+        // then
+        assert_eq!(carried, 2);
+        let review = &session.files[&PathBuf::from("a.rs")];
+        assert_eq!(review.state, ReviewState::NeedsSecondLook);
+        assert_eq!(review.line_comments[&3].len(), 1);
+        assert!(review.file_comments.is_empty());
+        assert_eq!(session.session_comments.len(), 1);
+        assert!(!session.files.contains_key(&PathBuf::from("gone.rs")));
+        assert!(!session.is_untouched());
+    }
+
+    #[test]
+    fn should_count_open_blocker_severity_as_blocking() {
+        // given a blocker note on a line, a resolved blocker and a blocker general comment
+        let mut session = ReviewSession::new(
+            PathBuf::from("/repo"),
+            "abc".to_string(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("a.rs"), FileStatus::Modified);
+        let mut blocker = Comment::new("race".to_string(), CommentType::Note, None);
+        blocker.severity = Some(Severity::Blocker);
+        let mut resolved = blocker.duplicate(None);
+        resolved.resolved = true;
+        let mut nit = Comment::new("typo".to_string(), CommentType::Note, None);
+        nit.severity = Some(Severity::Nit);
+        let review = session.get_file_mut(&PathBuf::from("a.rs")).unwrap();
+        review.add_line_comment(3, blocker.duplicate(Some(LineSide::New)));
+        review.add_line_comment(4, resolved);
+        review.add_file_comment(nit);
+        session.add_general_comment(None, blocker);
+
+        // then only the open blockers count
+        assert_eq!(
+            session
+                .get_file_mut(&PathBuf::from("a.rs"))
+                .unwrap()
+                .blocking_count(),
+            1
+        );
+        assert_eq!(session.blocking_count(), 2);
+    }
+
+    #[test]
+    fn should_put_removed_general_comments_back_in_place() {
+        // given two session comments and one directory comment
+        let mut session = ReviewSession::new(
+            PathBuf::from("/repo"),
+            "abc".to_string(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        let note = |text: &str| Comment::new(text.to_string(), CommentType::Note, None);
+        let (first, second, dir) = (note("first"), note("second"), note("dir"));
+        let (first_id, dir_id) = (first.id.clone(), dir.id.clone());
+        session.add_general_comment(None, first);
+        session.add_general_comment(None, second);
+        session.add_general_comment(Some(PathBuf::from("src")), dir);
+
+        // when both are removed
+        let (from, index, comment) = session.remove_general_comment(&first_id).unwrap();
+        assert_eq!((from.as_deref(), index), (None, 0));
+        session.insert_general_comment(from, index, comment);
+        let (from, index, comment) = session.remove_general_comment(&dir_id).unwrap();
+
+        // then an emptied directory is dropped until its comment comes back
+        assert_eq!((from.as_deref(), index), (Some(Path::new("src")), 0));
+        assert!(session.directory_comments.is_empty());
+        session.insert_general_comment(from, index, comment);
+        assert_eq!(session.directory_comments[Path::new("src")][0].id, dir_id);
+        assert_eq!(session.session_comments[0].id, first_id);
+        assert_eq!(session.remove_general_comment("missing").map(|r| r.1), None);
+    }
+
+    #[test]
+    fn should_clear_the_comments_of_one_file() {
+        // given
+        let mut session = ReviewSession::new(
+            PathBuf::from("/repo"),
+            "abc".to_string(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("a.rs"), FileStatus::Modified);
+        session.add_file(PathBuf::from("b.rs"), FileStatus::Modified);
+        let review = session.get_file_mut(&PathBuf::from("a.rs")).unwrap();
+        review.add_file_comment(Comment::new("redo".to_string(), CommentType::Note, None));
+        review.add_line_comment(3, Comment::new("fix".to_string(), CommentType::Issue, None));
+        session
+            .get_file_mut(&PathBuf::from("b.rs"))
+            .unwrap()
+            .add_line_comment(1, Comment::new("keep".to_string(), CommentType::Note, None));
+
+        // when
+        let cleared = session
+            .get_file_mut(&PathBuf::from("a.rs"))
+            .unwrap()
+            .clear_comments();
+
+        // then
+        assert_eq!(cleared, 2);
+        assert_eq!(session.files[&PathBuf::from("a.rs")].comment_count(), 0);
+        assert_eq!(session.files[&PathBuf::from("b.rs")].comment_count(), 1);
+    }
+
+    #[test]
+    fn should_recover_salvaged_comments_without_losing_any() {
+        // given a salvaged review with a resolved line comment on a file
+        // still in the review and a comment on one that is gone
+        let mut salvaged = ReviewSession::new(
+            PathBuf::new(),
+            String::new(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        salvaged.add_file(PathBuf::from("a.rs"), FileStatus::Modified);
+        salvaged.add_file(PathBuf::from("gone.rs"), FileStatus::Modified);
+        let review = salvaged.get_file_mut(&PathBuf::from("a.rs")).unwrap();
+        review.state = ReviewState::Approved;
+        let mut resolved = Comment::new("fixed".to_string(), CommentType::Issue, None);
+        resolved.resolved = true;
+        review.add_line_comment(4, resolved);
+        salvaged
+            .get_file_mut(&PathBuf::from("gone.rs"))
+            .unwrap()
+            .add_file_comment(Comment::new("moved?".to_string(), CommentType::Note, None));
+        let mut session = ReviewSession::new(
+            PathBuf::from("/repo"),
+            "abc".to_string(),
+            None,
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("a.rs"), FileStatus::Modified);
+
+        // when
+        let recovered = session.recover_from(&salvaged);
+
+        // then
+        assert_eq!(recovered, 2);
+        let review = &session.files[&PathBuf::from("a.rs")];
+        assert_eq!(review.state, ReviewState::Approved);
+        assert!(review.line_comments[&4][0].resolved);
+        assert_eq!(session.session_comments[0].content, "moved?");
+        assert!(!session.files.contains_key(&PathBuf::from("gone.rs")));
     }
 }
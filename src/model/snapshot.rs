@@ -0,0 +1,266 @@
+//! A copy of the reviewed diff kept in the session when `snapshot_diff` is on.
+//!
+//! The diff is written back out as git-style unified text, gzipped and base64
+//! encoded. That is enough to reopen a finished review with `--snapshot` after
+//! the branch is gone, and for the export to quote commented lines exactly as
+//! they were reviewed.
+
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+use super::comment::{LineRange, LineSide};
+use super::diff_types::{DiffFile, FileStatus, LineOrigin};
+use crate::error::{Result, TuicrError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// Base64 of the gzipped unified diff
+    pub diff: String,
+}
+
+impl DiffSnapshot {
+    pub fn capture(files: &[DiffFile]) -> Result<Self> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(unified_diff(files).as_bytes())?;
+        Ok(Self {
+            taken_at: Utc::now(),
+            diff: BASE64.encode(encoder.finish()?),
+        })
+    }
+
+    /// The unified diff the snapshot was taken of
+    pub fn diff_text(&self) -> Result<String> {
+        let compressed = BASE64
+            .decode(&self.diff)
+            .map_err(|e| TuicrError::CorruptedSession(format!("diff snapshot: {e}")))?;
+        let mut text = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut text)?;
+        Ok(text)
+    }
+}
+
+/// Write diff files out as a git-style unified diff that the diff parser
+/// reads back. Hunk headers are recomputed, since expanded context grows hunks.
+pub fn unified_diff(files: &[DiffFile]) -> String {
+    let mut out = String::new();
+    for file in files {
+        let new = file.display_path();
+        let old = file.old_path.as_ref().unwrap_or(new);
+        let _ = writeln!(out, "diff --git a/{} b/{}", old.display(), new.display());
+        match file.status {
+            FileStatus::Added => out.push_str("new file mode 100644\n"),
+            FileStatus::Deleted => out.push_str("deleted file mode 100644\n"),
+            FileStatus::Renamed => {
+                let _ = writeln!(out, "rename from {}", old.display());
+                let _ = writeln!(out, "rename to {}", new.display());
+            }
+            FileStatus::Copied => {
+                let _ = writeln!(out, "copy from {}", old.display());
+                let _ = writeln!(out, "copy to {}", new.display());
+            }
+            _ => {}
+        }
+        if file.is_binary {
+            let _ = writeln!(
+                out,
+                "Binary files a/{} and b/{} differ",
+                old.display(),
+                new.display()
+            );
+            continue;
+        }
+        if file.hunks.is_empty() {
+            continue;
+        }
+        match file.status {
+            FileStatus::Added => out.push_str("--- /dev/null\n"),
+            _ => {
+                let _ = writeln!(out, "--- a/{}", old.display());
+            }
+        }
+        match file.status {
+            FileStatus::Deleted => out.push_str("+++ /dev/null\n"),
+            _ => {
+                let _ = writeln!(out, "+++ b/{}", new.display());
+            }
+        }
+        for hunk in &file.hunks {
+            let old_count = hunk
+                .lines
+                .iter()
+                .filter(|l| l.origin != LineOrigin::Addition)
+                .count();
+            let new_count = hunk
+                .lines
+                .iter()
+                .filter(|l| l.origin != LineOrigin::Deletion)
+                .count();
+            let old_start = hunk
+                .lines
+                .iter()
+                .find_map(|l| l.old_lineno)
+                .unwrap_or(hunk.old_start);
+            let new_start = hunk
+                .lines
+                .iter()
+                .find_map(|l| l.new_lineno)
+                .unwrap_or(hunk.new_start);
+            let _ = writeln!(
+                out,
+                "@@ -{old_start},{old_count} +{new_start},{new_count} @@"
+            );
+            for line in &hunk.lines {
+                let sign = match line.origin {
+                    LineOrigin::Context => ' ',
+                    LineOrigin::Addition => '+',
+                    LineOrigin::Deletion => '-',
+                };
+                let _ = writeln!(out, "{sign}{}", line.content);
+            }
+        }
+    }
+    out
+}
+
+/// The lines of `path` in `range` on `side`, as they appear in `diff`. None
+/// when any of them is not part of the diff.
+pub fn excerpt(diff: &str, path: &Path, range: LineRange, side: LineSide) -> Option<Vec<String>> {
+    let mut in_file = false;
+    let (mut old_line, mut new_line) = (0u32, 0u32);
+    let mut found = Vec::new();
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            in_file = paths
+                .rsplit_once(" b/")
+                .is_some_and(|(_, new)| Path::new(new) == path);
+            continue;
+        }
+        if !in_file {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix("@@ -") {
+            let mut numbers = header.split(' ');
+            let start = |part: Option<&str>| {
+                part.and_then(|p| p.trim_start_matches('+').split(',').next()?.parse().ok())
+            };
+            old_line = start(numbers.next()).unwrap_or(0);
+            new_line = start(numbers.next()).unwrap_or(0);
+            continue;
+        }
+        if line.starts_with("--- ") || line.starts_with("+++ ") {
+            continue;
+        }
+        let (sign, content) = match line.char_indices().nth(1) {
+            Some((i, _)) => (&line[..i], &line[i..]),
+            None => (line, ""),
+        };
+        let lineno = match (sign, side) {
+            ("+", LineSide::New) | (" ", LineSide::New) => Some(new_line),
+            ("-", LineSide::Old) | (" ", LineSide::Old) => Some(old_line),
+            _ => None,
+        };
+        if lineno.is_some_and(|n| range.contains(n)) {
+            found.push(content.to_string());
+        }
+        match sign {
+            "+" => new_line += 1,
+            "-" => old_line += 1,
+            " " => {
+                old_line += 1;
+                new_line += 1;
+            }
+            _ => {}
+        }
+    }
+    let expected = (range.end + 1 - range.start) as usize;
+    (found.len() == expected).then_some(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DiffHunk, DiffLine};
+    use std::path::PathBuf;
+
+    fn line(origin: LineOrigin, content: &str, old: Option<u32>, new: Option<u32>) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno: old,
+            new_lineno: new,
+            highlighted_spans: None,
+        }
+    }
+
+    fn modified_file() -> DiffFile {
+        DiffFile {
+            old_path: Some(PathBuf::from("src/lib.rs")),
+            new_path: Some(PathBuf::from("src/lib.rs")),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -4,3 +4,3 @@".to_string(),
+                lines: vec![
+                    line(LineOrigin::Context, "fn main() {", Some(4), Some(4)),
+                    line(LineOrigin::Deletion, "    run(1);", Some(5), None),
+                    line(LineOrigin::Addition, "    run(2);", None, Some(5)),
+                    line(LineOrigin::Context, "}", Some(6), Some(6)),
+                ],
+                old_start: 4,
+                old_count: 3,
+                new_start: 4,
+                new_count: 3,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    #[test]
+    fn should_roundtrip_the_diff_through_the_snapshot() {
+        // given
+        let files = vec![modified_file()];
+
+        // when
+        let snapshot = DiffSnapshot::capture(&files).unwrap();
+
+        // then
+        assert_eq!(snapshot.diff_text().unwrap(), unified_diff(&files));
+        assert!(
+            unified_diff(&files)
+                .contains("@@ -4,3 +4,3 @@\n fn main() {\n-    run(1);\n+    run(2);\n }\n")
+        );
+    }
+
+    #[test]
+    fn should_quote_commented_lines_from_either_side() {
+        // given
+        let diff = unified_diff(&[modified_file()]);
+        let path = Path::new("src/lib.rs");
+
+        // then
+        assert_eq!(
+            excerpt(&diff, path, LineRange::new(4, 5), LineSide::New),
+            Some(vec!["fn main() {".to_string(), "    run(2);".to_string()])
+        );
+        assert_eq!(
+            excerpt(&diff, path, LineRange::single(5), LineSide::Old),
+            Some(vec!["    run(1);".to_string()])
+        );
+        assert_eq!(
+            excerpt(&diff, path, LineRange::new(6, 9), LineSide::New),
+            None
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Moved-code detection, in the spirit of `git diff --color-moved`.
+//!
+//! A run of deleted lines that reappears verbatim as a run of added lines,
+//! in the same file or another one, is tagged as moved on both sides. Like
+//! git's default mode, a block only counts once it holds at least
+//! `MIN_ALNUM_CHARS` alphanumeric characters, so shuffled braces and blank
+//! lines are left alone. Trailing whitespace is ignored; indentation is not.
+
+use std::collections::HashMap;
+
+use crate::model::{DiffFile, LineOrigin};
+
+const MIN_ALNUM_CHARS: usize = 20;
+
+/// A deleted or added line, in diff order
+struct ChangedLine<'a> {
+    file_idx: usize,
+    hunk_idx: usize,
+    line_idx: usize,
+    content: &'a str,
+    /// Consecutive lines of one origin share a run
+    run: usize,
+}
+
+fn changed_lines(files: &[DiffFile], origin: LineOrigin) -> Vec<ChangedLine<'_>> {
+    let mut lines = Vec::new();
+    let mut run = 0;
+    for (file_idx, file) in files.iter().enumerate() {
+        if file.is_commit_message() {
+            continue;
+        }
+        for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+            let mut in_run = false;
+            for (line_idx, line) in hunk.lines.iter().enumerate() {
+                if line.origin != origin {
+                    if in_run {
+                        run += 1;
+                    }
+                    in_run = false;
+                    continue;
+                }
+                in_run = true;
+                lines.push(ChangedLine {
+                    file_idx,
+                    hunk_idx,
+                    line_idx,
+                    content: line.content.trim_end(),
+                    run,
+                });
+            }
+            run += 1;
+        }
+    }
+    lines
+}
+
+fn alnum_count(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+/// Moved lines per `(file_idx, hunk_idx)`, indexed like `hunk.lines`.
+/// Hunks without moved lines are absent.
+pub fn detect_moved_lines(files: &[DiffFile]) -> HashMap<(usize, usize), Vec<bool>> {
+    let deleted = changed_lines(files, LineOrigin::Deletion);
+    let added = changed_lines(files, LineOrigin::Addition);
+
+    let mut by_content: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, line) in added.iter().enumerate() {
+        if alnum_count(line.content) > 0 {
+            by_content.entry(line.content).or_default().push(idx);
+        }
+    }
+
+    let mut moved: Vec<&ChangedLine> = Vec::new();
+    let mut added_used = vec![false; added.len()];
+    let mut i = 0;
+    while i < deleted.len() {
+        let Some(candidates) = by_content.get(deleted[i].content) else {
+            i += 1;
+            continue;
+        };
+
+        // Longest block starting here that matches an unused added block
+        let mut best: Option<(usize, usize)> = None;
+        for &start in candidates {
+            let mut len = 0;
+            while let (Some(del), Some(add)) = (deleted.get(i + len), added.get(start + len)) {
+                if del.run != deleted[i].run
+                    || add.run != added[start].run
+                    || added_used[start + len]
+                    || del.content != add.content
+                {
+                    break;
+                }
+                len += 1;
+            }
+            if len > best.map_or(0, |(_, best_len)| best_len) {
+                best = Some((start, len));
+            }
+        }
+
+        let Some((start, len)) = best else {
+            i += 1;
+            continue;
+        };
+        let alnum: usize = deleted[i..i + len]
+            .iter()
+            .map(|line| alnum_count(line.content))
+            .sum();
+        if alnum < MIN_ALNUM_CHARS {
+            i += 1;
+            continue;
+        }
+        moved.extend(&deleted[i..i + len]);
+        moved.extend(&added[start..start + len]);
+        added_used[start..start + len].fill(true);
+        i += len;
+    }
+
+    let mut result: HashMap<(usize, usize), Vec<bool>> = HashMap::new();
+    for line in moved {
+        let flags = result
+            .entry((line.file_idx, line.hunk_idx))
+            .or_insert_with(|| vec![false; files[line.file_idx].hunks[line.hunk_idx].lines.len()]);
+        flags[line.line_idx] = true;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DiffHunk, DiffLine, FileStatus};
+    use std::path::PathBuf;
+
+    fn file(path: &str, lines: &[(LineOrigin, &str)]) -> DiffFile {
+        DiffFile {
+            old_path: Some(PathBuf::from(path)),
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,1 +1,1 @@".to_string(),
+                lines: lines
+                    .iter()
+                    .map(|(origin, content)| DiffLine {
+                        origin: *origin,
+                        content: content.to_string(),
+                        old_lineno: None,
+                        new_lineno: None,
+                        highlighted_spans: None,
+                    })
+                    .collect(),
+                old_start: 1,
+                old_count: 1,
+                new_start: 1,
+                new_count: 1,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    #[test]
+    fn should_tag_block_moved_between_files() {
+        // given a function moved from one file to another, plus a real edit
+        let files = vec![
+            file(
+                "src/a.rs",
+                &[
+                    (LineOrigin::Deletion, "fn parse_header(input: &str) {"),
+                    (LineOrigin::Deletion, "    validate(input);"),
+                    (LineOrigin::Deletion, "}"),
+                    (LineOrigin::Context, ""),
+                    (LineOrigin::Addition, "let retries = 3;"),
+                ],
+            ),
+            file(
+                "src/b.rs",
+                &[
+                    (LineOrigin::Addition, "fn parse_header(input: &str) {"),
+                    (LineOrigin::Addition, "    validate(input);"),
+                    (LineOrigin::Addition, "}"),
+                ],
+            ),
+        ];
+
+        // when
+        let moved = detect_moved_lines(&files);
+
+        // then the block is moved on both sides and the edit is not
+        assert_eq!(moved[&(0, 0)], vec![true, true, true, false, false]);
+        assert_eq!(moved[&(1, 0)], vec![true, true, true]);
+    }
+
+    #[test]
+    fn should_ignore_short_blocks() {
+        // given only a closing brace and a short line reappearing
+        let files = vec![file(
+            "src/a.rs",
+            &[
+                (LineOrigin::Deletion, "}"),
+                (LineOrigin::Deletion, "x += 1;"),
+                (LineOrigin::Addition, "y = 2;"),
+                (LineOrigin::Addition, "x += 1;"),
+                (LineOrigin::Addition, "}"),
+            ],
+        )];
+
+        // when / then
+        assert!(detect_moved_lines(&files).is_empty());
+    }
+
+    #[test]
+    fn should_not_match_reindented_lines() {
+        let files = vec![file(
+            "src/a.rs",
+            &[
+                (LineOrigin::Deletion, "call_something_long(argument_one);"),
+                (
+                    LineOrigin::Addition,
+                    "    call_something_long(argument_one);",
+                ),
+            ],
+        )];
+
+        assert!(detect_moved_lines(&files).is_empty());
+    }
+}
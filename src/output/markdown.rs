@@ -1,54 +1,111 @@
 use std::fmt::Write;
 use std::io::Write as IoWrite;
+use std::path::{Path, PathBuf};
 
 use arboard::Clipboard;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
 use crate::app::DiffSource;
+use crate::config::{Config, ExportLabels, ExportOrder, ResolvedExport};
 use crate::error::{Result, TuicrError};
-use crate::model::{LineRange, LineSide, ReviewSession};
-
-/// (file_path, line_range, side, comment_type, content)
-type CommentEntry<'a> = (
-    String,
-    Option<LineRange>,
-    Option<LineSide>,
-    &'a str,
-    &'a str,
-);
-
-/// Generate markdown content from the review session.
+use crate::model::snapshot::excerpt;
+use crate::model::{Comment, CommentType, LineRange, LineSide, Reply, ReviewSession, ReviewState};
+use crate::todo::TodoMarker;
+
+/// (file_path, line_range, comment)
+type CommentEntry<'a> = (String, Option<LineRange>, &'a Comment);
+
+/// Generate markdown content from the review session, followed by the TODO
+/// markers the diff adds.
 /// Returns the markdown string or an error if there are no comments.
 pub fn generate_export_content(
     session: &ReviewSession,
     diff_source: &DiffSource,
+    todos: &[TodoMarker],
+    config: &Config,
 ) -> Result<String> {
     if !session.has_comments() {
         return Err(TuicrError::NoComments);
     }
-    Ok(generate_markdown(session, diff_source))
+    let mut md = generate_markdown(session, diff_source, config);
+    write_todo_section(&mut md, todos);
+    Ok(md)
 }
 
-pub fn export_to_clipboard(session: &ReviewSession, diff_source: &DiffSource) -> Result<String> {
-    let content = generate_export_content(session, diff_source)?;
+pub fn export_to_clipboard(
+    session: &ReviewSession,
+    diff_source: &DiffSource,
+    todos: &[TodoMarker],
+    config: &Config,
+) -> Result<String> {
+    let content = generate_export_content(session, diff_source, todos, config)?;
+    copy_to_clipboard(&content)
+}
 
+/// Put text on the clipboard, returning a status message
+pub fn copy_to_clipboard(content: &str) -> Result<String> {
     // Prefer OSC 52 in tmux/SSH where arboard may silently fail
     if should_prefer_osc52() {
-        copy_osc52(&content)?;
+        copy_osc52(content)?;
+        if std::env::var("TMUX").is_ok() {
+            return Ok(
+                "Review copied via terminal (tmux needs `set -g set-clipboard on`)".to_string(),
+            );
+        }
         return Ok("Review copied to clipboard (via terminal)".to_string());
     }
 
     // Try arboard (system clipboard) first, fall back to OSC 52 for SSH/remote sessions
-    match Clipboard::new().and_then(|mut cb| cb.set_text(&content)) {
+    match Clipboard::new().and_then(|mut cb| cb.set_text(content)) {
         Ok(_) => Ok("Review copied to clipboard".to_string()),
-        Err(_) => {
+        Err(e) => {
+            let reason = clipboard_failure_reason(&e);
             // Fall back to OSC 52 escape sequence (works over SSH)
-            copy_osc52(&content)?;
-            Ok("Review copied to clipboard (via terminal)".to_string())
+            copy_osc52(content).map_err(|osc_err| {
+                TuicrError::Clipboard(format!("{reason}; terminal fallback failed: {osc_err}"))
+            })?;
+            Ok(format!(
+                "System clipboard unavailable ({reason}); copied via terminal instead"
+            ))
         }
     }
 }
 
+/// Explain a system clipboard failure in terms of the display environment,
+/// falling back to the backend's own error.
+fn clipboard_failure_reason(error: &arboard::Error) -> String {
+    clipboard_environment_hint(
+        std::env::var_os("WAYLAND_DISPLAY").is_some(),
+        std::env::var_os("DISPLAY").is_some(),
+    )
+    .map_or_else(|| error.to_string(), str::to_string)
+}
+
+/// What is likely missing for the system clipboard on Linux and the BSDs.
+/// macOS and Windows always have a clipboard, so there is nothing to suggest.
+fn clipboard_environment_hint(wayland: bool, x11: bool) -> Option<&'static str> {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        return None;
+    }
+    match (wayland, x11) {
+        (true, _) => Some(
+            "the Wayland compositor offers no clipboard access; \
+             install wl-clipboard or use a compositor with data-control support",
+        ),
+        (false, false) => Some("no display server found, the session looks headless"),
+        (false, true) => None,
+    }
+}
+
+/// Read the system clipboard's current text, if it can be read.
+/// OSC 52 is write-only, so this is None in tmux/SSH sessions.
+pub fn read_clipboard() -> Option<String> {
+    if should_prefer_osc52() {
+        return None;
+    }
+    Clipboard::new().and_then(|mut cb| cb.get_text()).ok()
+}
+
 /// Returns true if we should prefer OSC 52 over the system clipboard.
 ///
 /// In tmux or SSH sessions, arboard may "succeed" but copy to an inaccessible
@@ -76,9 +133,28 @@ fn write_osc52<W: IoWrite>(writer: &mut W, text: &str) -> Result<()> {
     Ok(())
 }
 
-fn generate_markdown(session: &ReviewSession, diff_source: &DiffSource) -> String {
+fn generate_markdown(session: &ReviewSession, diff_source: &DiffSource, config: &Config) -> String {
+    let labels = &config.export_labels;
+    let timestamps = config.export_timestamps;
     let mut md = String::new();
 
+    // Comments below `export_min_severity` are left out
+    let exported = |comment: &Comment| match (comment.severity, config.export_min_severity) {
+        (Some(severity), Some(min)) => severity.at_least(min),
+        _ => true,
+    };
+    let (general, resolved_general): (Vec<_>, Vec<_>) = session
+        .general_comments()
+        .into_iter()
+        .filter(|(_, comment)| exported(comment))
+        .partition(|(_, comment)| !comment.resolved);
+    let mut all_comments = file_comment_entries(session);
+    all_comments.retain(|(_, _, comment)| exported(comment));
+    if config.export_order == ExportOrder::Severity {
+        // Stable, so comments of one severity stay in file order
+        all_comments.sort_by_key(|(_, _, comment)| (comment.severity.is_none(), comment.severity));
+    }
+
     // Intro for agents
     let _ = writeln!(
         md,
@@ -86,7 +162,127 @@ fn generate_markdown(session: &ReviewSession, diff_source: &DiffSource) -> Strin
     );
     let _ = writeln!(md);
 
-    // Include commit range info if reviewing commits
+    write_diff_source(&mut md, diff_source);
+
+    let _ = writeln!(
+        md,
+        "Comment types: {} (problems to fix), {} (improvements), {} (replacement code), {} (observations), {} (positive feedback)",
+        labels.label(CommentType::Issue),
+        labels.label(CommentType::Suggestion),
+        labels.label(CommentType::CodeSuggestion),
+        labels.label(CommentType::Note),
+        labels.label(CommentType::Praise),
+    );
+    let exported = || {
+        general
+            .iter()
+            .chain(&resolved_general)
+            .map(|(_, comment)| *comment)
+            .chain(all_comments.iter().map(|(_, _, comment)| *comment))
+    };
+    if exported().any(|comment| comment.severity.is_some()) {
+        let _ = writeln!(
+            md,
+            "Severities: BLOCKER (must fix before merging), MAJOR, MINOR, NIT (optional polish)"
+        );
+    }
+    if exported().any(|comment| comment.action.is_some()) {
+        let _ = writeln!(
+            md,
+            "Actions: fix (change the code), answer (reply to the question), \
+             consider (your call), none (no response needed)"
+        );
+    }
+    let _ = writeln!(md);
+
+    if let Some(progress) = review_progress(session) {
+        let _ = writeln!(md, "Review progress: {progress}");
+        let _ = writeln!(md);
+    }
+
+    // Session notes/summary
+    if let Some(notes) = &session.session_notes {
+        let _ = writeln!(md, "Summary: {notes}");
+        let _ = writeln!(md);
+    }
+
+    if !session.attachments.is_empty() {
+        let _ = writeln!(md, "Attachments:");
+        for path in &session.attachments {
+            let _ = writeln!(md, "- `{}`", path.display());
+        }
+        let _ = writeln!(md);
+    }
+
+    // Resolved comments are held back for their own section at the end
+    let mut resolved: Vec<String> = Vec::new();
+
+    // Comments on directories or the review as a whole come first
+    if !general.is_empty() {
+        let _ = writeln!(md, "General:");
+        for (i, (dir, comment)) in general.iter().enumerate() {
+            let _ = writeln!(
+                md,
+                "{}. {}",
+                i + 1,
+                format_general_comment(*dir, comment, labels, timestamps)
+            );
+            write_replies(&mut md, &comment.replies);
+        }
+        let _ = writeln!(md);
+    }
+    for (dir, comment) in resolved_general {
+        resolved.push(resolved_entry(
+            format_general_comment(dir, comment, labels, timestamps),
+            comment,
+        ));
+    }
+
+    // Open comments quote their lines from the diff snapshot, when there is one
+    let snapshot = session
+        .snapshot
+        .as_ref()
+        .and_then(|snapshot| snapshot.diff_text().ok());
+
+    // Output numbered list
+    let (open, resolved_files): (Vec<_>, Vec<_>) = all_comments
+        .into_iter()
+        .partition(|(_, _, comment)| !comment.resolved);
+    for (i, (file, line_range, comment)) in open.iter().enumerate() {
+        let _ = writeln!(
+            md,
+            "{}. {}",
+            general.len() + i + 1,
+            format_file_comment(file, *line_range, comment, labels, timestamps)
+        );
+        if let Some(block) = snapshot
+            .as_deref()
+            .and_then(|diff| snapshot_excerpt(diff, file, *line_range, comment))
+        {
+            md.push_str(&block);
+        }
+        write_replies(&mut md, &comment.replies);
+    }
+    for (file, line_range, comment) in resolved_files {
+        resolved.push(resolved_entry(
+            format_file_comment(&file, line_range, comment, labels, timestamps),
+            comment,
+        ));
+    }
+
+    if config.resolved_comments == ResolvedExport::Section && !resolved.is_empty() {
+        let _ = writeln!(md);
+        let _ = writeln!(md, "Resolved (already addressed, no action needed):");
+        for entry in resolved {
+            md.push_str(&entry);
+        }
+    }
+
+    md
+}
+
+/// Include commit range info if reviewing commits
+fn write_diff_source(md: &mut String, diff_source: &DiffSource) {
     match diff_source {
         DiffSource::WorkingTree => {}
         DiffSource::CommitRange(commits) => {
@@ -103,20 +299,10 @@ fn generate_markdown(session: &ReviewSession, diff_source: &DiffSource) -> Strin
             let _ = writeln!(md);
         }
     }
+}
 
-    let _ = writeln!(
-        md,
-        "Comment types: ISSUE (problems to fix), SUGGESTION (improvements), NOTE (observations), PRAISE (positive feedback)"
-    );
-    let _ = writeln!(md);
-
-    // Session notes/summary
-    if let Some(notes) = &session.session_notes {
-        let _ = writeln!(md, "Summary: {notes}");
-        let _ = writeln!(md);
-    }
-
-    // Collect all comments into a flat list
+/// File and line comments in a flat list, by path and then line
+fn file_comment_entries(session: &ReviewSession) -> Vec<CommentEntry<'_>> {
     let mut all_comments: Vec<CommentEntry> = Vec::new();
 
     // Sort files by path for consistent output
@@ -128,13 +314,7 @@ fn generate_markdown(session: &ReviewSession, diff_source: &DiffSource) -> Strin
 
         // File comments (no line number)
         for comment in &review.file_comments {
-            all_comments.push((
-                path_str.clone(),
-                None,
-                None,
-                comment.comment_type.as_str(),
-                &comment.content,
-            ));
+            all_comments.push((path_str.clone(), None, comment));
         }
 
         // Line comments (with line number, sorted)
@@ -147,54 +327,306 @@ fn generate_markdown(session: &ReviewSession, diff_source: &DiffSource) -> Strin
                 let line_range = comment
                     .line_range
                     .or_else(|| Some(LineRange::single(*line)));
-                all_comments.push((
-                    path_str.clone(),
-                    line_range,
-                    comment.side,
-                    comment.comment_type.as_str(),
-                    &comment.content,
-                ));
+                all_comments.push((path_str.clone(), line_range, comment));
             }
         }
     }
+    all_comments
+}
 
-    // Output numbered list
-    for (i, (file, line_range, side, comment_type, content)) in all_comments.iter().enumerate() {
-        let location = match (line_range, side) {
-            // Range on deleted side (old lines)
-            (Some(range), Some(LineSide::Old)) if range.is_single() => {
-                format!("`{}:~{}`", file, range.start)
-            }
-            (Some(range), Some(LineSide::Old)) => {
-                format!("`{}:~{}-~{}`", file, range.start, range.end)
+/// A note for a reviewer taking over a half-finished review: the files left
+/// to review with their state, the ones already done, and the open comments
+pub fn generate_handoff(
+    session: &ReviewSession,
+    diff_source: &DiffSource,
+    labels: &ExportLabels,
+    reviewer: Option<&str>,
+) -> String {
+    let mut md = String::new();
+
+    let mut files: Vec<_> = session.files.values().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let (excluded, in_scope): (Vec<_>, Vec<_>) = files.into_iter().partition(|f| f.excluded);
+    let (reviewed, remaining): (Vec<_>, Vec<_>) =
+        in_scope.into_iter().partition(|f| f.is_reviewed());
+
+    let from = reviewer
+        .map(|name| format!(" from {name}"))
+        .unwrap_or_default();
+    let _ = writeln!(
+        md,
+        "Review handoff{from}: {} of {} files reviewed. Please take over the rest.",
+        reviewed.len(),
+        reviewed.len() + remaining.len()
+    );
+    let _ = writeln!(md);
+    if let Some(branch) = &session.branch_name {
+        let _ = writeln!(md, "Branch: {branch}");
+        let _ = writeln!(md);
+    }
+    write_diff_source(&mut md, diff_source);
+
+    for (heading, group) in [
+        ("Still to review:", &remaining),
+        ("Already reviewed:", &reviewed),
+        ("Left out of the review:", &excluded),
+    ] {
+        if group.is_empty() {
+            continue;
+        }
+        let _ = writeln!(md, "{heading}");
+        for file in group {
+            match file.state {
+                ReviewState::InProgress | ReviewState::NeedsSecondLook if !file.excluded => {
+                    let _ = writeln!(md, "- `{}` ({})", file.path.display(), file.state.label());
+                }
+                _ => {
+                    let _ = writeln!(md, "- `{}`", file.path.display());
+                }
             }
-            // Range on new/context side
-            (Some(range), _) if range.is_single() => {
-                format!("`{}:{}`", file, range.start)
+        }
+        let _ = writeln!(md);
+    }
+
+    let general: Vec<_> = session
+        .general_comments()
+        .into_iter()
+        .filter(|(_, comment)| !comment.resolved)
+        .collect();
+    let entries: Vec<_> = file_comment_entries(session)
+        .into_iter()
+        .filter(|(_, _, comment)| !comment.resolved)
+        .collect();
+    if !general.is_empty() || !entries.is_empty() {
+        let _ = writeln!(md, "Open comments:");
+        for (i, (dir, comment)) in general.iter().enumerate() {
+            let _ = writeln!(
+                md,
+                "{}. {}",
+                i + 1,
+                format_general_comment(*dir, comment, labels, false)
+            );
+            write_replies(&mut md, &comment.replies);
+        }
+        for (i, (file, line_range, comment)) in entries.iter().enumerate() {
+            let _ = writeln!(
+                md,
+                "{}. {}",
+                general.len() + i + 1,
+                format_file_comment(file, *line_range, comment, labels, false)
+            );
+            write_replies(&mut md, &comment.replies);
+        }
+    }
+
+    md
+}
+
+/// `**[TYPE]** `dir/` - content` for a directory comment, without the dir
+/// for a session comment
+fn format_general_comment(
+    dir: Option<&PathBuf>,
+    comment: &Comment,
+    labels: &ExportLabels,
+    timestamps: bool,
+) -> String {
+    let stamp = attribution_note(comment, timestamps);
+    let location = match dir {
+        Some(dir) => format!(" `{}/`{stamp} -", dir.display()),
+        None => stamp,
+    };
+    format!(
+        "**{}**{} {}",
+        comment_label(comment, labels),
+        location,
+        comment.content
+    )
+}
+
+/// `**[TYPE]** `path:lines` - content` for a file or line comment
+fn format_file_comment(
+    file: &str,
+    line_range: Option<LineRange>,
+    comment: &Comment,
+    labels: &ExportLabels,
+    timestamps: bool,
+) -> String {
+    let columns = comment
+        .columns
+        .map(|columns| format!(":{}-{}", columns.start, columns.end))
+        .unwrap_or_default();
+    let mut location = match (line_range, comment.side) {
+        // Range on deleted side (old lines)
+        (Some(range), Some(LineSide::Old)) if range.is_single() => {
+            format!("`{}:~{}{}`", file, range.start, columns)
+        }
+        (Some(range), Some(LineSide::Old)) => {
+            format!("`{}:~{}-~{}`", file, range.start, range.end)
+        }
+        // Range on new/context side
+        (Some(range), _) if range.is_single() => {
+            format!("`{}:{}{}`", file, range.start, columns)
+        }
+        (Some(range), _) => {
+            format!("`{}:{}-{}`", file, range.start, range.end)
+        }
+        // File comment
+        (None, _) => format!("`{file}`"),
+    };
+    if let Some(conflict_side) = comment.conflict_side {
+        let _ = write!(location, " ({})", conflict_side.label());
+    }
+    location.push_str(&attribution_note(comment, timestamps));
+    format!(
+        "**{}** {} - {}",
+        comment_label(comment, labels),
+        location,
+        comment_body(comment)
+    )
+}
+
+/// The commented lines as they were reviewed, as a code block nested under
+/// the comment's list item
+fn snapshot_excerpt(
+    diff: &str,
+    file: &str,
+    line_range: Option<LineRange>,
+    comment: &Comment,
+) -> Option<String> {
+    let side = comment.side.unwrap_or(LineSide::New);
+    let lines = excerpt(diff, Path::new(file), line_range?, side)?;
+    let mut block = String::from("   ```\n");
+    for line in lines {
+        let _ = writeln!(block, "   {line}");
+    }
+    block.push_str("   ```\n");
+    Some(block)
+}
+
+/// `2 approved, 1 in progress, 3 unreviewed, 1 excluded`; None when files
+/// are all unreviewed, since nothing has been recorded then
+fn review_progress(session: &ReviewSession) -> Option<String> {
+    let counts: Vec<(ReviewState, usize)> = ReviewState::ALL
+        .into_iter()
+        .map(|state| {
+            let count = session
+                .files
+                .values()
+                .filter(|file| !file.excluded && file.state == state)
+                .count();
+            (state, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    let excluded = session.files.values().filter(|file| file.excluded).count();
+    if excluded == 0
+        && counts
+            .iter()
+            .all(|(state, _)| *state == ReviewState::Unreviewed)
+    {
+        return None;
+    }
+    let mut parts: Vec<String> = counts
+        .iter()
+        .rev()
+        .map(|(state, count)| format!("{count} {}", state.label()))
+        .collect();
+    if excluded > 0 {
+        parts.push(format!("{excluded} excluded"));
+    }
+    Some(parts.join(", "))
+}
+
+/// `[ISSUE]`, followed by `[BLOCKER]` when the comment has a severity and
+/// `[action: fix]` when it says what response it expects
+fn comment_label(comment: &Comment, labels: &ExportLabels) -> String {
+    let mut label = format!("[{}]", labels.label(comment.comment_type));
+    if let Some(severity) = comment.severity {
+        let _ = write!(label, "[{}]", severity.as_str());
+    }
+    if let Some(action) = comment.action {
+        let _ = write!(label, "[action: {}]", action.as_str());
+    }
+    label
+}
+
+/// Who wrote the comment and, with `timestamps`, when, for audit trails:
+/// ` (Alice, 2024-05-01 14:03 UTC, edited 2024-05-02 09:10 UTC)`
+fn attribution_note(comment: &Comment, timestamps: bool) -> String {
+    const FORMAT: &str = "%Y-%m-%d %H:%M UTC";
+    let mut parts: Vec<String> = comment.author.iter().cloned().collect();
+    if timestamps {
+        parts.push(comment.created_at.format(FORMAT).to_string());
+        if let Some(updated) = comment.updated_at {
+            parts.push(format!("edited {}", updated.format(FORMAT)));
+        }
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
+/// The comment text; suggested changes become a `suggestion` fence that
+/// GitHub and agents can apply as-is, and imported findings name their tool
+fn comment_body(comment: &Comment) -> String {
+    let body = if comment.comment_type == CommentType::CodeSuggestion {
+        format!("replace with:\n```suggestion\n{}\n```", comment.content)
+    } else {
+        comment.content.clone()
+    };
+    match &comment.source {
+        Some(tool) => format!("({tool}) {body}"),
+        None => body,
+    }
+}
+
+/// A bullet in the "Resolved" section, with the comment's replies below it
+fn resolved_entry(text: String, comment: &Comment) -> String {
+    let mut entry = format!("- {text}\n");
+    write_replies(&mut entry, &comment.replies);
+    entry
+}
+
+/// Replies follow their comment as an indented list, in thread order
+fn write_replies(md: &mut String, replies: &[Reply]) {
+    for reply in replies {
+        match &reply.author {
+            Some(author) => {
+                let _ = writeln!(md, "   - Reply from {author}: {}", reply.content);
             }
-            (Some(range), _) => {
-                format!("`{}:{}-{}`", file, range.start, range.end)
+            None => {
+                let _ = writeln!(md, "   - Reply: {}", reply.content);
             }
-            // File comment
-            (None, _) => format!("`{file}`"),
-        };
+        }
+    }
+}
+
+fn write_todo_section(md: &mut String, todos: &[TodoMarker]) {
+    if todos.is_empty() {
+        return;
+    }
+    let _ = writeln!(md);
+    let _ = writeln!(md, "New TODOs:");
+    for todo in todos {
         let _ = writeln!(
             md,
-            "{}. **[{}]** {} - {}",
-            i + 1,
-            comment_type,
-            location,
-            content
+            "- `{}:{}` {}",
+            todo.path.display(),
+            todo.line,
+            todo.text
         );
     }
-
-    md
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{Comment, CommentType, FileStatus, LineRange, LineSide, SessionDiffSource};
+    use crate::model::{
+        ColumnRange, Comment, CommentType, ConflictSide, FileStatus, LineRange, LineSide,
+        RequiredAction, SessionDiffSource, Severity,
+    };
     use std::path::PathBuf;
 
     fn create_test_session() -> ReviewSession {
@@ -208,7 +640,7 @@ mod tests {
 
         // Add a file comment
         if let Some(review) = session.get_file_mut(&PathBuf::from("src/main.rs")) {
-            review.reviewed = true;
+            review.state = ReviewState::Approved;
             review.add_file_comment(Comment::new(
                 "Consider adding documentation".to_string(),
                 CommentType::Suggestion,
@@ -234,7 +666,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("I reviewed your code and have the following comments"));
@@ -247,6 +679,118 @@ mod tests {
         assert!(markdown.contains("Magic number"));
     }
 
+    #[test]
+    fn should_export_general_section_before_file_comments() {
+        // given
+        let mut session = create_test_session();
+        session.add_general_comment(
+            Some(PathBuf::from("src/ui")),
+            Comment::new(
+                "Flatten this module".to_string(),
+                CommentType::Suggestion,
+                None,
+            ),
+        );
+        session.add_general_comment(
+            None,
+            Comment::new("Nice work overall".to_string(), CommentType::Praise, None),
+        );
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains(
+            "General:\n1. **[SUGGESTION]** `src/ui/` - Flatten this module\n2. **[PRAISE]** Nice work overall\n"
+        ));
+        assert!(markdown.contains("3. **[SUGGESTION]** `src/main.rs`"));
+    }
+
+    #[test]
+    fn should_list_attachments_before_comments() {
+        // given
+        let mut session = create_test_session();
+        session.attachments.push(PathBuf::from("bench/results.txt"));
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        let attachments = markdown
+            .find("Attachments:\n- `bench/results.txt`\n")
+            .unwrap();
+        assert!(attachments < markdown.find("[SUGGESTION]").unwrap());
+    }
+
+    #[test]
+    fn should_export_replies_under_their_comment() {
+        // given a reply on the line comment
+        let mut session = create_test_session();
+        let review = session.get_file_mut(&PathBuf::from("src/main.rs")).unwrap();
+        let comment = &mut review.line_comments.get_mut(&42).unwrap()[0];
+        comment
+            .replies
+            .push(Reply::new("It is the buffer size".to_string()));
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then the reply is nested below its comment, not numbered
+        assert!(markdown.contains(
+            "2. **[ISSUE]** `src/main.rs:42` - Magic number should be a constant\n   - Reply: It is the buffer size\n"
+        ));
+    }
+
+    #[test]
+    fn should_section_off_or_omit_resolved_comments() {
+        // given the line comment is resolved
+        let mut session = create_test_session();
+        let review = session.get_file_mut(&PathBuf::from("src/main.rs")).unwrap();
+        review.line_comments.get_mut(&42).unwrap()[0].resolved = true;
+
+        // when
+        let sectioned = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+        let omitted = generate_markdown(
+            &session,
+            &DiffSource::WorkingTree,
+            &Config {
+                resolved_comments: ResolvedExport::Omit,
+                ..Config::default()
+            },
+        );
+
+        // then it is unnumbered under "Resolved", or not exported at all
+        assert!(sectioned.contains("1. **[SUGGESTION]**"));
+        assert!(sectioned.contains(
+            "Resolved (already addressed, no action needed):\n- **[ISSUE]** `src/main.rs:42` - Magic number"
+        ));
+        assert!(!sectioned.contains("2. **[ISSUE]**"));
+        assert!(!omitted.contains("Magic number"));
+        assert!(!omitted.contains("Resolved"));
+    }
+
+    #[test]
+    fn should_export_configured_type_labels() {
+        // given issues are called blockers and suggestions keep their name
+        let session = create_test_session();
+        let config = Config {
+            export_labels: ExportLabels {
+                issue: Some("BLOCKER".to_string()),
+                ..ExportLabels::default()
+            },
+            ..Config::default()
+        };
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &config);
+
+        // then
+        assert!(markdown.contains("**[BLOCKER]** `src/main.rs:42`"));
+        assert!(markdown.contains("**[SUGGESTION]**"));
+        assert!(markdown.contains("Comment types: BLOCKER (problems to fix)"));
+        assert!(!markdown.contains("ISSUE"));
+    }
+
     #[test]
     fn should_number_comments_sequentially() {
         // given
@@ -254,7 +798,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         // Should have 2 numbered comments
@@ -274,7 +818,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let result = export_to_clipboard(&session, &diff_source);
+        let result = export_to_clipboard(&session, &diff_source, &[], &Config::default());
 
         // then
         assert!(result.is_err());
@@ -288,7 +832,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let result = generate_export_content(&session, &diff_source);
+        let result = generate_export_content(&session, &diff_source, &[], &Config::default());
 
         // then
         assert!(result.is_ok());
@@ -298,6 +842,33 @@ mod tests {
         assert!(content.contains("[ISSUE]"));
     }
 
+    #[test]
+    fn should_append_new_todos_to_export() {
+        // given
+        let session = create_test_session();
+        let todos = vec![TodoMarker {
+            marker: "TODO",
+            text: "TODO: handle retries".to_string(),
+            path: PathBuf::from("src/net.rs"),
+            line: 42,
+            file_idx: 0,
+            hunk_idx: 0,
+            line_idx: 3,
+        }];
+
+        // when
+        let content = generate_export_content(
+            &session,
+            &DiffSource::WorkingTree,
+            &todos,
+            &Config::default(),
+        )
+        .unwrap();
+
+        // then
+        assert!(content.ends_with("New TODOs:\n- `src/net.rs:42` TODO: handle retries\n"));
+    }
+
     #[test]
     fn should_fail_generate_export_content_when_no_comments() {
         // given
@@ -310,7 +881,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let result = generate_export_content(&session, &diff_source);
+        let result = generate_export_content(&session, &diff_source, &[], &Config::default());
 
         // then
         assert!(result.is_err());
@@ -327,7 +898,7 @@ mod tests {
         ]);
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("Reviewing commits: abc1234, def4567"));
@@ -340,12 +911,26 @@ mod tests {
         let diff_source = DiffSource::CommitRange(vec!["abc1234567890".to_string()]);
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("Reviewing commit: abc1234"));
     }
 
+    #[test]
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    fn should_explain_missing_clipboard_from_display_environment() {
+        // given/when/then
+        assert!(
+            clipboard_environment_hint(true, false)
+                .is_some_and(|hint| hint.contains("wl-clipboard"))
+        );
+        assert!(
+            clipboard_environment_hint(false, false).is_some_and(|hint| hint.contains("headless"))
+        );
+        assert_eq!(clipboard_environment_hint(false, true), None);
+    }
+
     #[test]
     fn should_write_osc52_escape_sequence() {
         // given
@@ -401,7 +986,7 @@ mod tests {
         // given - simulate what would be copied during export
         let session = create_test_session();
         let diff_source = DiffSource::WorkingTree;
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
         let mut buffer: Vec<u8> = Vec::new();
 
         // when
@@ -417,6 +1002,315 @@ mod tests {
         assert_eq!(decoded, markdown);
     }
 
+    #[test]
+    fn should_export_column_range_after_line() {
+        // given a comment on columns 10-24 of line 42
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("src/main.rs"), FileStatus::Modified);
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/main.rs")) {
+            let mut comment = Comment::new(
+                "Overflows on 32-bit".to_string(),
+                CommentType::Issue,
+                Some(LineSide::New),
+            );
+            comment.columns = Some(ColumnRange { start: 10, end: 24 });
+            review.add_line_comment(42, comment);
+        }
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains("`src/main.rs:42:10-24` - Overflows on 32-bit"));
+    }
+
+    #[test]
+    fn should_export_code_suggestion_as_suggestion_fence() {
+        // given a suggested change keeping its indentation
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("src/main.rs"), FileStatus::Modified);
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/main.rs")) {
+            review.add_line_comment(
+                11,
+                Comment::new_with_range(
+                    "    let size = BUFFER_SIZE;\n    read(size)".to_string(),
+                    CommentType::CodeSuggestion,
+                    Some(LineSide::New),
+                    LineRange::new(10, 11),
+                ),
+            );
+        }
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains(
+            "1. **[SUGGESTED CHANGE]** `src/main.rs:10-11` - replace with:\n```suggestion\n    let size = BUFFER_SIZE;\n    read(size)\n```\n"
+        ));
+    }
+
+    #[test]
+    fn should_count_files_per_review_state() {
+        // given one approved file, one in progress and one untouched
+        let mut session = create_test_session();
+        session.add_file(PathBuf::from("src/lib.rs"), FileStatus::Modified);
+        session.add_file(PathBuf::from("src/util.rs"), FileStatus::Modified);
+        session
+            .get_file_mut(&PathBuf::from("src/lib.rs"))
+            .unwrap()
+            .state = ReviewState::InProgress;
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then each state with files is counted, approved first
+        assert!(markdown.contains("Review progress: 1 approved, 1 in progress, 1 unreviewed"));
+    }
+
+    #[test]
+    fn should_count_excluded_files_apart_from_review_states() {
+        // given an approved file and an excluded one
+        let mut session = create_test_session();
+        session.add_file(PathBuf::from("Cargo.lock"), FileStatus::Modified);
+        session
+            .get_file_mut(&PathBuf::from("Cargo.lock"))
+            .unwrap()
+            .excluded = true;
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains("Review progress: 1 approved, 1 excluded"));
+    }
+
+    #[test]
+    fn should_leave_out_progress_when_nothing_was_reviewed() {
+        let mut session = create_test_session();
+        session
+            .get_file_mut(&PathBuf::from("src/main.rs"))
+            .unwrap()
+            .state = ReviewState::Unreviewed;
+
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        assert!(!markdown.contains("Review progress"));
+    }
+
+    #[test]
+    fn should_attribute_comments_and_replies_to_their_authors() {
+        // given a comment and a reply by different reviewers
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("src/lib.rs"), FileStatus::Modified);
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/lib.rs")) {
+            let mut comment = Comment::new(
+                "Why a mutex?".to_string(),
+                CommentType::Note,
+                Some(LineSide::New),
+            );
+            comment.author = Some("Alice".to_string());
+            let mut reply = Reply::new("Shared across threads".to_string());
+            reply.author = Some("Bob".to_string());
+            comment.replies.push(reply);
+            review.add_line_comment(8, comment);
+        }
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains("`src/lib.rs:8` (Alice) - Why a mutex?"));
+        assert!(markdown.contains("   - Reply from Bob: Shared across threads"));
+    }
+
+    #[test]
+    fn should_quote_commented_lines_from_the_diff_snapshot() {
+        // given a session whose diff was snapshotted
+        let mut session = create_test_session();
+        let diff = "diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -41,2 +41,2 @@\n fn retries() -> u32 {\n-    3\n+    42\n";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, diff.as_bytes()).unwrap();
+        session.snapshot = Some(crate::model::DiffSnapshot {
+            taken_at: chrono::Utc::now(),
+            diff: BASE64.encode(encoder.finish().unwrap()),
+        });
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then the line comment on 42 is followed by the line as reviewed
+        assert!(markdown.contains(
+            "`src/main.rs:42` - Magic number should be a constant\n   ```\n       42\n   ```\n"
+        ));
+    }
+
+    #[test]
+    fn should_hand_off_remaining_files_and_open_comments() {
+        // given one approved file with comments and two left to review
+        let mut session = create_test_session();
+        session.add_file(PathBuf::from("src/lib.rs"), FileStatus::Modified);
+        session.add_file(PathBuf::from("src/util.rs"), FileStatus::Added);
+        session
+            .get_file_mut(&PathBuf::from("src/lib.rs"))
+            .unwrap()
+            .state = ReviewState::NeedsSecondLook;
+
+        // when
+        let handoff = generate_handoff(
+            &session,
+            &DiffSource::WorkingTree,
+            &ExportLabels::default(),
+            Some("Alice"),
+        );
+
+        // then
+        assert!(handoff.starts_with("Review handoff from Alice: 1 of 3 files reviewed."));
+        assert!(handoff.contains(
+            "Still to review:\n- `src/lib.rs` (needs a second look)\n- `src/util.rs`\n\nAlready reviewed:\n- `src/main.rs`\n"
+        ));
+        assert!(handoff.contains("Open comments:\n1. **[SUGGESTION]** `src/main.rs`"));
+        assert!(handoff.contains("2. **[ISSUE]** `src/main.rs:42`"));
+    }
+
+    #[test]
+    fn should_sort_and_filter_comments_by_severity() {
+        // given a nit on the file and a blocker on line 42
+        let mut session = create_test_session();
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/main.rs")) {
+            review.file_comments[0].severity = Some(Severity::Nit);
+            review.line_comments.get_mut(&42).unwrap()[0].severity = Some(Severity::Blocker);
+        }
+
+        // when
+        let by_file = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+        let by_severity = generate_markdown(
+            &session,
+            &DiffSource::WorkingTree,
+            &Config {
+                export_order: ExportOrder::Severity,
+                ..Config::default()
+            },
+        );
+        let major_only = generate_markdown(
+            &session,
+            &DiffSource::WorkingTree,
+            &Config {
+                export_min_severity: Some(Severity::Major),
+                ..Config::default()
+            },
+        );
+
+        // then
+        assert!(by_file.contains("Severities: BLOCKER"));
+        assert!(by_file.contains("1. **[SUGGESTION][NIT]** `src/main.rs`"));
+        assert!(by_file.contains("2. **[ISSUE][BLOCKER]** `src/main.rs:42`"));
+        assert!(by_severity.contains("1. **[ISSUE][BLOCKER]** `src/main.rs:42`"));
+        assert!(by_severity.contains("2. **[SUGGESTION][NIT]** `src/main.rs`"));
+        assert!(major_only.contains("1. **[ISSUE][BLOCKER]**"));
+        assert!(!major_only.contains("Consider adding documentation"));
+    }
+
+    #[test]
+    fn should_suffix_required_actions_and_explain_them() {
+        // given the line comment on 42 has to be fixed
+        let mut session = create_test_session();
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/main.rs")) {
+            review.line_comments.get_mut(&42).unwrap()[0].action = Some(RequiredAction::Fix);
+        }
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains("Actions: fix (change the code)"));
+        assert!(markdown.contains("**[ISSUE][action: fix]** `src/main.rs:42`"));
+        assert!(markdown.contains("**[SUGGESTION]** `src/main.rs`"));
+    }
+
+    #[test]
+    fn should_include_timestamps_when_asked() {
+        // given a line comment written at a known time and edited later
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("src/lib.rs"), FileStatus::Modified);
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/lib.rs")) {
+            let mut comment = Comment::new(
+                "Check the bounds".to_string(),
+                CommentType::Issue,
+                Some(LineSide::New),
+            );
+            comment.created_at = "2024-05-01T14:03:00Z".parse().unwrap();
+            comment.updated_at = Some("2024-05-02T09:10:00Z".parse().unwrap());
+            review.add_line_comment(3, comment);
+        }
+
+        // when
+        let with = generate_markdown(
+            &session,
+            &DiffSource::WorkingTree,
+            &Config {
+                export_timestamps: true,
+                ..Config::default()
+            },
+        );
+        let without = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(with.contains(
+            "`src/lib.rs:3` (2024-05-01 14:03 UTC, edited 2024-05-02 09:10 UTC) - Check the bounds"
+        ));
+        assert!(without.contains("`src/lib.rs:3` - Check the bounds"));
+    }
+
+    #[test]
+    fn should_name_the_tool_of_imported_findings() {
+        // given a clippy finding attached to a line
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("src/lib.rs"), FileStatus::Modified);
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/lib.rs")) {
+            let mut comment = Comment::new(
+                "unneeded `return` statement (clippy::needless_return)".to_string(),
+                CommentType::Note,
+                Some(LineSide::New),
+            );
+            comment.source = Some("clippy".to_string());
+            review.add_line_comment(7, comment);
+        }
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains(
+            "`src/lib.rs:7` - (clippy) unneeded `return` statement (clippy::needless_return)"
+        ));
+    }
+
     #[test]
     fn should_export_single_line_range_as_single_line() {
         // given - a comment with a single-line range should display as L42, not L42-L42
@@ -443,7 +1337,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("`src/main.rs:42`"));
@@ -476,7 +1370,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("`src/main.rs:10-15`"));
@@ -509,7 +1403,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("`src/main.rs:~20-~25`"));
@@ -541,13 +1435,41 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("`src/main.rs:~30`"));
         assert!(!markdown.contains("`src/main.rs:~30-~30`"));
     }
 
+    #[test]
+    fn should_label_conflict_side_of_line_comment() {
+        // given - a comment left on the "theirs" side of a merge conflict
+        let mut session = ReviewSession::new(
+            PathBuf::from("/tmp/test-repo"),
+            "abc1234def".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        session.add_file(PathBuf::from("src/lib.rs"), FileStatus::Conflicted);
+
+        if let Some(review) = session.get_file_mut(&PathBuf::from("src/lib.rs")) {
+            let mut comment = Comment::new(
+                "Keep this side".to_string(),
+                CommentType::Note,
+                Some(LineSide::New),
+            );
+            comment.conflict_side = Some(ConflictSide::Theirs);
+            review.add_line_comment(12, comment);
+        }
+
+        // when
+        let markdown = generate_markdown(&session, &DiffSource::WorkingTree, &Config::default());
+
+        // then
+        assert!(markdown.contains("`src/lib.rs:12` (theirs) - Keep this side"));
+    }
+
     #[test]
     fn should_handle_comment_without_line_range_field() {
         // given - backward compatibility: comment without line_range uses line number
@@ -573,7 +1495,7 @@ mod tests {
         let diff_source = DiffSource::WorkingTree;
 
         // when
-        let markdown = generate_markdown(&session, &diff_source);
+        let markdown = generate_markdown(&session, &diff_source, &Config::default());
 
         // then
         assert!(markdown.contains("`src/main.rs:50`"));
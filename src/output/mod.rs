@@ -1,3 +1,6 @@
 pub mod markdown;
 
-pub use markdown::{export_to_clipboard, generate_export_content};
+pub use markdown::{
+    copy_to_clipboard, export_to_clipboard, generate_export_content, generate_handoff,
+    read_clipboard,
+};
@@ -1,3 +1,7 @@
 pub mod storage;
 
-pub use storage::{load_latest_session_for_context, save_session};
+pub use storage::{
+    ARCHIVE_DIR, find_damaged_session, find_previous_session, get_reviews_dir,
+    load_all_session_files, load_all_sessions, load_latest_session_for_context, load_session,
+    salvage_session, save_backup_export, save_session, set_aside_damaged_session,
+};
@@ -1,18 +1,28 @@
 use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use crate::error::{Result, TuicrError};
-use crate::model::ReviewSession;
 use crate::model::review::SessionDiffSource;
+use crate::model::{Comment, FileReview, ReviewSession};
 
 const SESSION_MAX_AGE_DAYS: u64 = 7;
+/// Folder under the reviews directory that `tuicr gc` moves finished
+/// sessions to
+pub const ARCHIVE_DIR: &str = "archive";
 const SESSION_FILENAME_MIN_PARTS: usize = 6;
 const SESSION_FILENAME_SUFFIX_PARTS: usize = 4;
 const SESSION_FILENAME_DATE_LEN: usize = 8;
 const SESSION_FILENAME_TIME_LEN: usize = 6;
 const FINGERPRINT_HEX_LEN: usize = 8;
+/// Field saved next to the session's own, holding a checksum of them;
+/// sessions saved before it existed load unchecked
+const CHECKSUM_KEY: &str = "checksum";
+/// Extension a damaged session is renamed to once dealt with, so it is kept
+/// but no longer picked up
+const DAMAGED_EXTENSION: &str = "json.damaged";
 
 struct SessionFilenameParts {
     repo_fingerprints: Vec<String>,
@@ -69,7 +79,7 @@ fn is_hex_fingerprint(part: &str) -> bool {
     part.len() == FINGERPRINT_HEX_LEN && part.chars().all(|ch| ch.is_ascii_hexdigit())
 }
 
-fn get_reviews_dir() -> Result<PathBuf> {
+pub fn get_reviews_dir() -> Result<PathBuf> {
     #[cfg(test)]
     if let Some(dir) = std::env::var_os("TUICR_REVIEWS_DIR") {
         let path = PathBuf::from(dir);
@@ -168,17 +178,188 @@ pub fn save_session(session: &ReviewSession) -> Result<PathBuf> {
     let filename = session_filename(session);
     let path = reviews_dir.join(&filename);
 
-    let json = serde_json::to_string_pretty(session)?;
-    fs::write(&path, json)?;
+    fs::write(&path, session_json(session)?)?;
+
+    Ok(path)
+}
+
+/// The session as saved: pretty JSON with a checksum of its contents
+fn session_json(session: &ReviewSession) -> Result<String> {
+    let mut value = serde_json::to_value(session)?;
+    let checksum = content_checksum(&value);
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert(CHECKSUM_KEY.to_string(), checksum.into());
+    }
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+fn content_checksum(value: &serde_json::Value) -> String {
+    format!("{:016x}", fnv1a_64(value.to_string().as_bytes()))
+}
+
+/// Write a timestamped copy of the export and of the session to the
+/// `exports` directory under the reviews directory. Returns the markdown path;
+/// the session snapshot sits next to it with a `.json` extension.
+pub fn save_backup_export(session: &ReviewSession, markdown: &str) -> Result<PathBuf> {
+    let exports_dir = get_reviews_dir()?.join("exports");
+    fs::create_dir_all(&exports_dir)?;
+
+    let filename = session_filename(session);
+    let stem = filename.trim_end_matches(".json");
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let path = exports_dir.join(format!("{stem}_export_{timestamp}.md"));
+
+    fs::write(&path, markdown)?;
+    fs::write(path.with_extension("json"), session_json(session)?)?;
 
     Ok(path)
 }
 
 pub fn load_session(path: &PathBuf) -> Result<ReviewSession> {
     let contents = fs::read_to_string(path)?;
-    let session: ReviewSession =
-        serde_json::from_str(&contents).map_err(|e| TuicrError::CorruptedSession(e.to_string()))?;
-    Ok(session)
+    parse_session(&contents)
+}
+
+/// Parse a saved session, checking it against its checksum when it has one
+fn parse_session(contents: &str) -> Result<ReviewSession> {
+    let corrupted = |e: serde_json::Error| TuicrError::CorruptedSession(e.to_string());
+    let mut value: serde_json::Value = serde_json::from_str(contents).map_err(corrupted)?;
+    let stored = value
+        .as_object_mut()
+        .and_then(|fields| fields.remove(CHECKSUM_KEY));
+    if let Some(stored) = stored
+        && stored.as_str() != Some(content_checksum(&value).as_str())
+    {
+        return Err(TuicrError::CorruptedSession(
+            "checksum mismatch, the file changed after it was saved".to_string(),
+        ));
+    }
+    serde_json::from_value(value).map_err(corrupted)
+}
+
+/// The newest saved session of this repository and diff source that no
+/// longer loads
+pub fn find_damaged_session(
+    repo_path: &Path,
+    diff_source: SessionDiffSource,
+) -> Result<Option<(PathBuf, TuicrError)>> {
+    let fingerprint = repo_path_fingerprint(repo_path);
+    let diff_source = match diff_source {
+        SessionDiffSource::WorkingTree => "worktree",
+        SessionDiffSource::CommitRange => "commits",
+    };
+    let mut damaged: Vec<(SystemTime, PathBuf, TuicrError)> = fs::read_dir(get_reviews_dir()?)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let parts = parse_session_filename(path.file_name()?.to_str()?)?;
+            if parts.diff_source != diff_source || !parts.repo_fingerprints.contains(&fingerprint) {
+                return None;
+            }
+            let error = load_session(&path).err()?;
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, path, error))
+        })
+        .collect();
+    damaged.sort_by_key(|(modified, ..)| *modified);
+    Ok(damaged.pop().map(|(_, path, error)| (path, error)))
+}
+
+/// Whatever file reviews and comments can still be read from a damaged
+/// session, gathered into a session of their own: file reviews that parse
+/// whole keep their state and line numbers, comments outside them end up
+/// as general comments
+pub fn salvage_session(path: &Path) -> Result<ReviewSession> {
+    let contents = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+    let mut salvaged = ReviewSession::new(
+        PathBuf::new(),
+        String::new(),
+        None,
+        SessionDiffSource::WorkingTree,
+    );
+    let mut pos = 0;
+    while let Some(offset) = contents[pos..].find('{') {
+        let start = pos + offset;
+        let rest = &contents[start..];
+        if let Some((review, len)) = parse_prefix::<FileReview>(rest) {
+            salvaged.files.insert(review.path.clone(), review);
+            pos = start + len;
+        } else if let Some((comment, len)) = parse_prefix::<Comment>(rest) {
+            salvaged.session_comments.push(comment);
+            pos = start + len;
+        } else {
+            pos = start + 1;
+        }
+    }
+    Ok(salvaged)
+}
+
+/// A `T` at the start of `text`, with the bytes it took up
+fn parse_prefix<T: DeserializeOwned>(text: &str) -> Option<(T, usize)> {
+    let mut stream = serde_json::Deserializer::from_str(text).into_iter::<T>();
+    let value = stream.next()?.ok()?;
+    Some((value, stream.byte_offset()))
+}
+
+/// Rename a damaged session out of the way, keeping it for a closer look.
+/// Returns where it went.
+pub fn set_aside_damaged_session(path: &Path) -> Result<PathBuf> {
+    let target = path.with_extension(DAMAGED_EXTENSION);
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Every readable saved session, in no particular order
+pub fn load_all_sessions() -> Result<Vec<ReviewSession>> {
+    Ok(load_all_session_files()?
+        .into_iter()
+        .map(|(_, session)| session)
+        .collect())
+}
+
+/// Every readable saved session with the file it was loaded from
+pub fn load_all_session_files() -> Result<Vec<(PathBuf, ReviewSession)>> {
+    load_session_files_in(&get_reviews_dir()?)
+}
+
+/// The latest earlier review of `branch_name` in this repository, saved or
+/// archived, that left something to follow up on; `current_id` is the
+/// session being started, which doesn't count
+pub fn find_previous_session(
+    repo_path: &Path,
+    branch_name: &str,
+    current_id: &str,
+) -> Result<Option<(PathBuf, ReviewSession)>> {
+    let reviews_dir = get_reviews_dir()?;
+    let archive_dir = reviews_dir.join(ARCHIVE_DIR);
+    let mut sessions = load_session_files_in(&reviews_dir)?;
+    if archive_dir.is_dir() {
+        sessions.extend(load_session_files_in(&archive_dir)?);
+    }
+    let repo = normalize_repo_path(repo_path);
+    Ok(sessions
+        .into_iter()
+        .filter(|(_, session)| {
+            session.id != current_id
+                && session.branch_name.as_deref() == Some(branch_name)
+                && normalize_repo_path(&session.repo_path) == repo
+                && !session.is_untouched()
+        })
+        .max_by_key(|(_, session)| session.updated_at))
+}
+
+fn load_session_files_in(dir: &Path) -> Result<Vec<(PathBuf, ReviewSession)>> {
+    let sessions = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        })
+        .filter_map(|path| load_session(&path).ok().map(|session| (path, session)))
+        .collect();
+    Ok(sessions)
 }
 
 pub fn load_latest_session_for_context(
@@ -315,7 +496,7 @@ fn delete_session(path: &PathBuf) -> Result<()> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::model::FileStatus;
     use std::path::PathBuf;
@@ -338,7 +519,7 @@ mod tests {
 
     static TEST_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
-    struct TestReviewsDirGuard<'a> {
+    pub(crate) struct TestReviewsDirGuard<'a> {
         _lock: std::sync::MutexGuard<'a, ()>,
         path: PathBuf,
     }
@@ -352,7 +533,8 @@ mod tests {
         }
     }
 
-    fn with_test_reviews_dir() -> TestReviewsDirGuard<'static> {
+    /// Point session storage at a fresh directory until the guard drops
+    pub(crate) fn with_test_reviews_dir() -> TestReviewsDirGuard<'static> {
         let lock = TEST_LOCK.get_or_init(|| Mutex::new(())).lock().unwrap();
         let path =
             std::env::temp_dir().join(format!("tuicr-reviews-test-{}", uuid::Uuid::new_v4()));
@@ -455,6 +637,147 @@ mod tests {
         let _ = delete_session(&path);
     }
 
+    #[test]
+    fn should_reject_a_changed_session_and_salvage_what_is_readable() {
+        // given a saved review with a line comment, cut off half way
+        // through its general comment
+        let _guard = with_test_reviews_dir();
+        let mut session = create_test_session();
+        session
+            .get_file_mut(&PathBuf::from("src/main.rs"))
+            .unwrap()
+            .add_line_comment(
+                7,
+                Comment::new("unwrap".to_string(), crate::model::CommentType::Issue, None),
+            );
+        session.add_general_comment(
+            None,
+            Comment::new(
+                "overall fine".to_string(),
+                crate::model::CommentType::Note,
+                None,
+            ),
+        );
+        let path = save_session(&session).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let edited = contents.replace("unwrap", "expect");
+        let truncated = &contents[..contents.find("overall fine").unwrap()];
+
+        // when
+        fs::write(&path, &edited).unwrap();
+        let edited_load = load_session(&path);
+        fs::write(&path, truncated).unwrap();
+        let truncated_load = load_session(&path);
+        let found =
+            find_damaged_session(Path::new("/tmp/test-repo"), SessionDiffSource::WorkingTree)
+                .unwrap();
+        let salvaged = salvage_session(&path).unwrap();
+        let set_aside = set_aside_damaged_session(&path).unwrap();
+
+        // then
+        assert!(matches!(
+            edited_load,
+            Err(TuicrError::CorruptedSession(reason)) if reason.contains("checksum")
+        ));
+        assert!(matches!(
+            truncated_load,
+            Err(TuicrError::CorruptedSession(_))
+        ));
+        assert_eq!(found.map(|(found, _)| found), Some(path.clone()));
+        let review = &salvaged.files[&PathBuf::from("src/main.rs")];
+        assert_eq!(review.line_comments[&7][0].content, "unwrap");
+        assert!(salvaged.session_comments.is_empty());
+        assert!(set_aside.to_string_lossy().ends_with(".json.damaged"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn should_write_backup_export_with_session_snapshot() {
+        // given
+        let guard = with_test_reviews_dir();
+        let session = create_test_session();
+
+        // when
+        let path = save_backup_export(&session, "1. **[NOTE]** `a.rs` - x\n").unwrap();
+
+        // then
+        assert!(path.starts_with(guard.path.join("exports")));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "1. **[NOTE]** `a.rs` - x\n"
+        );
+        let snapshot = load_session(&path.with_extension("json")).unwrap();
+        assert_eq!(snapshot.id, session.id);
+    }
+
+    #[test]
+    fn should_load_all_saved_sessions() {
+        // given two sessions and a file that is not a session
+        let guard = with_test_reviews_dir();
+        save_session(&create_test_session()).unwrap();
+        save_session(&create_test_session()).unwrap();
+        fs::write(guard.path.join("broken.json"), "{").unwrap();
+
+        // when
+        let sessions = load_all_sessions().unwrap();
+
+        // then
+        assert_eq!(sessions.len(), 2);
+    }
+
+    #[test]
+    fn should_find_archived_review_of_the_same_branch() {
+        // given a finished review of the branch, archived, and an untouched one
+        let guard = with_test_reviews_dir();
+        let repo = PathBuf::from("/tmp/test-repo");
+        let mut archived = create_session(
+            repo.clone(),
+            "abc",
+            Some("feature"),
+            SessionDiffSource::WorkingTree,
+            None,
+        );
+        archived.session_comments.push(crate::model::Comment::new(
+            "needs a test".to_string(),
+            crate::model::CommentType::Issue,
+            None,
+        ));
+        let path = save_session(&archived).unwrap();
+        fs::create_dir_all(guard.path.join(ARCHIVE_DIR)).unwrap();
+        fs::rename(
+            &path,
+            guard.path.join(ARCHIVE_DIR).join(path.file_name().unwrap()),
+        )
+        .unwrap();
+        let current = create_session(
+            repo.clone(),
+            "def",
+            Some("feature"),
+            SessionDiffSource::WorkingTree,
+            None,
+        );
+        save_session(&current).unwrap();
+
+        // when
+        let found = find_previous_session(&repo, "feature", &current.id).unwrap();
+        let other_branch = find_previous_session(&repo, "main", &current.id).unwrap();
+
+        // then
+        assert_eq!(found.map(|(_, session)| session.id), Some(archived.id));
+        assert!(other_branch.is_none());
+    }
+
+    #[test]
+    fn should_roundtrip_review_plan() {
+        let _guard = with_test_reviews_dir();
+        let mut session = create_test_session();
+        session.review_plan = vec![PathBuf::from("src/model.rs"), PathBuf::from("src/ui.rs")];
+        let path = save_session(&session).unwrap();
+        let loaded = load_session(&path).unwrap();
+        assert_eq!(loaded.review_plan, session.review_plan);
+        let _ = delete_session(&path);
+    }
+
     #[test]
     fn should_sanitize_branch_name_in_filename() {
         let session = create_session(
@@ -0,0 +1,265 @@
+//! Public API changes of Rust crates, read off the diff.
+//!
+//! Rather than building both versions the way `cargo public-api` does, this
+//! scans added and removed lines of `.rs` files for `pub` item declarations.
+//! A removed and an added declaration of the same item in the same file pair
+//! up as a signature change. Items in private modules and multi-line
+//! signatures that only change after their first line are not detected.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::model::{DiffFile, LineOrigin};
+
+const ITEM_KEYWORDS: &[&str] = &[
+    "fn", "struct", "enum", "trait", "type", "const", "static", "mod", "union", "use", "macro",
+];
+const QUALIFIERS: &[&str] = &["async", "unsafe", "extern", "default", "\"C\"", "\"Rust\""];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    pub kind: ApiChangeKind,
+    /// Item kind and name, e.g. `fn parse` or `field name`
+    pub item: String,
+    /// The declaration line after the change, or before it for removals
+    pub signature: String,
+    /// Location of that declaration line in the diff
+    pub file_idx: usize,
+    pub hunk_idx: usize,
+    pub line_idx: usize,
+}
+
+/// Public item declared on a line, as `(kind, name)`
+fn public_item(content: &str) -> Option<(&str, &str)> {
+    let rest = content.trim_start().strip_prefix("pub ")?;
+    let mut words = rest
+        .split(|c: char| c.is_whitespace() || c == '(' || c == '<' || c == ';' || c == '{')
+        .filter(|w| !w.is_empty())
+        .skip_while(|w| QUALIFIERS.contains(w));
+
+    let first = words.next()?;
+    if let Some(field) = first.strip_suffix(':') {
+        return is_identifier(field).then_some(("field", field));
+    }
+    if !ITEM_KEYWORDS.contains(&first) {
+        return None;
+    }
+    let name = words.next()?;
+    let name = if first == "use" {
+        name
+    } else {
+        name.trim_end_matches([':', '!'])
+    };
+    Some((first, name))
+}
+
+fn is_identifier(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Added, removed and changed public items across the Rust files of a diff,
+/// in file order
+pub fn public_api_changes(files: &[DiffFile]) -> Vec<ApiChange> {
+    let mut changes = Vec::new();
+
+    for (file_idx, file) in files.iter().enumerate() {
+        if file.display_path().extension() != Some(std::ffi::OsStr::new("rs"))
+            || is_test_path(file.display_path())
+        {
+            continue;
+        }
+
+        // (kind, name) -> (trimmed line, hunk_idx, line_idx)
+        let mut removed: HashMap<String, (String, usize, usize)> = HashMap::new();
+        let mut added: Vec<(String, String, usize, usize)> = Vec::new();
+        for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+            for (line_idx, line) in hunk.lines.iter().enumerate() {
+                let Some((kind, name)) = public_item(&line.content) else {
+                    continue;
+                };
+                let key = format!("{kind} {name}");
+                let signature = line.content.trim().to_string();
+                match line.origin {
+                    LineOrigin::Deletion => {
+                        removed.insert(key, (signature, hunk_idx, line_idx));
+                    }
+                    LineOrigin::Addition => added.push((key, signature, hunk_idx, line_idx)),
+                    LineOrigin::Context => {}
+                }
+            }
+        }
+
+        let mut file_changes = Vec::new();
+        for (item, signature, hunk_idx, line_idx) in added {
+            let kind = match removed.remove(&item) {
+                Some((old, ..)) if old == signature => continue,
+                Some(_) => ApiChangeKind::Changed,
+                None => ApiChangeKind::Added,
+            };
+            file_changes.push(ApiChange {
+                kind,
+                item,
+                signature,
+                file_idx,
+                hunk_idx,
+                line_idx,
+            });
+        }
+        for (item, (signature, hunk_idx, line_idx)) in removed {
+            file_changes.push(ApiChange {
+                kind: ApiChangeKind::Removed,
+                item,
+                signature,
+                file_idx,
+                hunk_idx,
+                line_idx,
+            });
+        }
+        file_changes.sort_by_key(|c| (c.hunk_idx, c.line_idx));
+        changes.extend(file_changes);
+    }
+
+    changes
+}
+
+/// Tests and benches are not part of a crate's API
+fn is_test_path(path: &Path) -> bool {
+    path.components().any(|c| {
+        matches!(
+            c.as_os_str().to_str(),
+            Some("tests" | "benches" | "examples")
+        )
+    })
+}
+
+/// Semver bump implied by the changes: removals and signature changes break callers
+pub fn semver_impact(changes: &[ApiChange]) -> &'static str {
+    if changes.iter().any(|c| c.kind != ApiChangeKind::Added) {
+        "major (breaking)"
+    } else if changes.is_empty() {
+        "patch"
+    } else {
+        "minor"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DiffHunk, DiffLine, FileStatus};
+    use std::path::PathBuf;
+
+    fn file(path: &str, lines: &[(LineOrigin, &str)]) -> DiffFile {
+        DiffFile {
+            old_path: Some(PathBuf::from(path)),
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,1 +1,1 @@".to_string(),
+                lines: lines
+                    .iter()
+                    .map(|(origin, content)| DiffLine {
+                        origin: *origin,
+                        content: content.to_string(),
+                        old_lineno: None,
+                        new_lineno: None,
+                        highlighted_spans: None,
+                    })
+                    .collect(),
+                old_start: 1,
+                old_count: 1,
+                new_start: 1,
+                new_count: 1,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    #[test]
+    fn should_parse_public_items() {
+        assert_eq!(public_item("pub fn parse(s: &str)"), Some(("fn", "parse")));
+        assert_eq!(
+            public_item("    pub async unsafe fn run<T>()"),
+            Some(("fn", "run"))
+        );
+        assert_eq!(
+            public_item("pub struct Config {"),
+            Some(("struct", "Config"))
+        );
+        assert_eq!(
+            public_item("    pub name: String,"),
+            Some(("field", "name"))
+        );
+        assert_eq!(public_item("pub(crate) fn helper()"), None);
+        assert_eq!(public_item("fn private()"), None);
+    }
+
+    #[test]
+    fn should_classify_added_removed_and_changed_items() {
+        // given a file that changes one signature, drops one item and adds another
+        let files = vec![file(
+            "src/lib.rs",
+            &[
+                (LineOrigin::Deletion, "pub fn parse(s: &str) -> Config {"),
+                (LineOrigin::Deletion, "pub fn legacy() {}"),
+                (
+                    LineOrigin::Addition,
+                    "pub fn parse(s: &str) -> Result<Config> {",
+                ),
+                (LineOrigin::Addition, "pub fn validate() {}"),
+                (LineOrigin::Addition, "fn internal() {}"),
+            ],
+        )];
+
+        // when
+        let changes = public_api_changes(&files);
+
+        // then
+        let summary: Vec<(ApiChangeKind, &str)> =
+            changes.iter().map(|c| (c.kind, c.item.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ApiChangeKind::Removed, "fn legacy"),
+                (ApiChangeKind::Changed, "fn parse"),
+                (ApiChangeKind::Added, "fn validate"),
+            ]
+        );
+        assert_eq!(changes[1].line_idx, 2);
+        assert_eq!(semver_impact(&changes), "major (breaking)");
+    }
+
+    #[test]
+    fn should_ignore_moved_items_and_test_files() {
+        // given an item moved unchanged and a test file adding public helpers
+        let files = vec![
+            file(
+                "src/lib.rs",
+                &[
+                    (LineOrigin::Deletion, "pub fn stable() {}"),
+                    (LineOrigin::Addition, "pub fn stable() {}"),
+                ],
+            ),
+            file(
+                "tests/common/mod.rs",
+                &[(LineOrigin::Addition, "pub fn setup() {}")],
+            ),
+        ];
+
+        // when / then
+        let changes = public_api_changes(&files);
+        assert!(changes.is_empty());
+        assert_eq!(semver_impact(&changes), "patch");
+    }
+}
@@ -0,0 +1,183 @@
+//! `tuicr status`: saved reviews left unfinished for a while.
+//!
+//! A session is unfinished while any of its files is not marked reviewed.
+//! Saved sessions are pruned after a week without changes, so this is the
+//! window in which a half-done review can still be picked up.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::Result;
+use crate::model::ReviewSession;
+use crate::persistence::load_all_sessions;
+
+/// Days without changes before an unfinished review is reported
+pub const DEFAULT_STALE_DAYS: i64 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleSession {
+    pub repo_path: PathBuf,
+    pub branch_name: Option<String>,
+    pub reviewed: usize,
+    pub total: usize,
+    pub comments: usize,
+    /// Whole days since the session was last saved
+    pub age_days: i64,
+}
+
+/// Unfinished sessions untouched for at least `days`, oldest first
+pub fn stale_sessions(
+    sessions: &[ReviewSession],
+    now: DateTime<Utc>,
+    days: i64,
+) -> Vec<StaleSession> {
+    let mut stale: Vec<StaleSession> = sessions
+        .iter()
+        .filter(|session| session.files.values().any(|file| !file.is_done()))
+        .map(|session| StaleSession {
+            repo_path: session.repo_path.clone(),
+            branch_name: session.branch_name.clone(),
+            reviewed: session
+                .files
+                .values()
+                .filter(|file| file.is_reviewed())
+                .count(),
+            total: session.files.len(),
+            comments: session
+                .files
+                .values()
+                .map(|file| file.comment_count())
+                .sum::<usize>()
+                + session.general_comments().len(),
+            age_days: (now - session.updated_at).num_days(),
+        })
+        .filter(|session| session.age_days >= days)
+        .collect();
+    stale.sort_by_key(|session| std::cmp::Reverse(session.age_days));
+    stale
+}
+
+pub fn format_report(stale: &[StaleSession], days: i64) -> String {
+    if stale.is_empty() {
+        return format!("No unfinished reviews older than {days} days\n");
+    }
+
+    let mut report = String::new();
+    let noun = if stale.len() == 1 {
+        "review"
+    } else {
+        "reviews"
+    };
+    let _ = writeln!(
+        report,
+        "{} unfinished {noun} older than {days} days:",
+        stale.len()
+    );
+    for session in stale {
+        let branch = session
+            .branch_name
+            .as_deref()
+            .map(|b| format!(" ({b})"))
+            .unwrap_or_default();
+        let _ = writeln!(
+            report,
+            "  {}{branch} - {}/{} files reviewed, {} comments, last touched {} days ago",
+            session.repo_path.display(),
+            session.reviewed,
+            session.total,
+            session.comments,
+            session.age_days
+        );
+    }
+    report
+}
+
+/// Short marker for shell prompts, empty when nothing is stale
+pub fn format_prompt(stale: &[StaleSession]) -> String {
+    if stale.is_empty() {
+        String::new()
+    } else {
+        format!("tuicr:{}", stale.len())
+    }
+}
+
+/// Print the report, or with `prompt` only the prompt marker
+pub fn run(days: i64, prompt: bool) -> Result<()> {
+    let sessions = load_all_sessions()?;
+    let stale = stale_sessions(&sessions, Utc::now(), days);
+    if prompt {
+        let marker = format_prompt(&stale);
+        if !marker.is_empty() {
+            println!("{marker}");
+        }
+    } else {
+        print!("{}", format_report(&stale, days));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{FileStatus, ReviewState, SessionDiffSource};
+    use chrono::Duration;
+
+    fn session(repo: &str, age_days: i64, reviewed: &[bool]) -> ReviewSession {
+        let mut session = ReviewSession::new(
+            PathBuf::from(repo),
+            "abc1234".to_string(),
+            Some("main".to_string()),
+            SessionDiffSource::WorkingTree,
+        );
+        for (i, done) in reviewed.iter().enumerate() {
+            let path = PathBuf::from(format!("src/{i}.rs"));
+            session.add_file(path.clone(), FileStatus::Modified);
+            session.get_file_mut(&path).unwrap().state = if *done {
+                ReviewState::Approved
+            } else {
+                ReviewState::Unreviewed
+            };
+        }
+        session.updated_at = Utc::now() - Duration::days(age_days);
+        session
+    }
+
+    #[test]
+    fn should_report_old_unfinished_sessions() {
+        // given a recent unfinished, an old finished and two old unfinished sessions
+        let sessions = vec![
+            session("/repo/fresh", 0, &[false]),
+            session("/repo/done", 5, &[true, true]),
+            session("/repo/stale", 3, &[true, false]),
+            session("/repo/older", 6, &[false]),
+        ];
+
+        // when
+        let stale = stale_sessions(&sessions, Utc::now(), 2);
+
+        // then
+        assert_eq!(stale.len(), 2);
+        assert_eq!(stale[0].repo_path, PathBuf::from("/repo/older"));
+        assert_eq!(stale[1].repo_path, PathBuf::from("/repo/stale"));
+        assert_eq!((stale[1].reviewed, stale[1].total), (1, 2));
+        assert_eq!(format_prompt(&stale), "tuicr:2");
+    }
+
+    #[test]
+    fn should_format_report_lines() {
+        // given
+        let stale = stale_sessions(&[session("/repo/app", 3, &[false])], Utc::now(), 2);
+
+        // when
+        let report = format_report(&stale, 2);
+
+        // then
+        assert_eq!(
+            report,
+            "1 unfinished review older than 2 days:\n  /repo/app (main) - 0/1 files reviewed, 0 comments, last touched 3 days ago\n"
+        );
+        assert_eq!(format_prompt(&[]), "");
+    }
+}
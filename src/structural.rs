@@ -0,0 +1,369 @@
+//! Key-level diff for JSON and YAML files.
+//!
+//! Both versions are flattened into `path → scalar` maps (`server.ports[0]`),
+//! so reordered keys and reflowed formatting drop out and only real value
+//! changes remain. JSON goes through serde_json; YAML uses a small line-based
+//! reader covering block mappings, sequences and `|`/`>` scalars, which is
+//! enough for typical config files but not for anchors or flow collections.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuredFormat {
+    Json,
+    Yaml,
+}
+
+impl StructuredFormat {
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(StructuredFormat::Json),
+            "yaml" | "yml" => Some(StructuredFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructuralChange {
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+    Changed {
+        path: String,
+        old: String,
+        new: String,
+    },
+}
+
+/// Compare two versions of a document key by key. An empty version counts as
+/// an empty document, so added and deleted files list every key.
+pub fn structural_diff(
+    format: StructuredFormat,
+    old: &str,
+    new: &str,
+) -> Result<Vec<StructuralChange>, String> {
+    let old = flatten(format, old).map_err(|e| format!("old version: {e}"))?;
+    let new = flatten(format, new).map_err(|e| format!("new version: {e}"))?;
+
+    let mut changes = Vec::new();
+    for (path, old_value) in &old {
+        match new.get(path) {
+            None => changes.push(StructuralChange::Removed {
+                path: path.clone(),
+                value: old_value.clone(),
+            }),
+            Some(new_value) if new_value != old_value => changes.push(StructuralChange::Changed {
+                path: path.clone(),
+                old: old_value.clone(),
+                new: new_value.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (path, value) in &new {
+        if !old.contains_key(path) {
+            changes.push(StructuralChange::Added {
+                path: path.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    changes.sort_by(|a, b| change_path(a).cmp(change_path(b)));
+    Ok(changes)
+}
+
+fn change_path(change: &StructuralChange) -> &str {
+    match change {
+        StructuralChange::Added { path, .. }
+        | StructuralChange::Removed { path, .. }
+        | StructuralChange::Changed { path, .. } => path,
+    }
+}
+
+pub fn flatten(format: StructuredFormat, text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut out = BTreeMap::new();
+    if text.trim().is_empty() {
+        return Ok(out);
+    }
+    match format {
+        StructuredFormat::Json => {
+            let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+            flatten_json(&value, String::new(), &mut out);
+        }
+        StructuredFormat::Yaml => flatten_yaml(text, &mut out),
+    }
+    Ok(out)
+}
+
+fn flatten_json(value: &serde_json::Value, path: String, out: &mut BTreeMap<String, String>) {
+    match value {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten_json(child, join_key(&path, key), out);
+            }
+        }
+        serde_json::Value::Array(items) if !items.is_empty() => {
+            for (idx, child) in items.iter().enumerate() {
+                flatten_json(child, format!("{path}[{idx}]"), out);
+            }
+        }
+        leaf => {
+            out.insert(root_or(path), leaf.to_string());
+        }
+    }
+}
+
+fn join_key(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+fn root_or(path: String) -> String {
+    if path.is_empty() {
+        "$".to_string()
+    } else {
+        path
+    }
+}
+
+/// A mapping or sequence being filled; lines indented at least `indent` belong to it
+struct YamlFrame {
+    indent: usize,
+    path: String,
+    next_index: usize,
+    /// Opened by `key:` rather than by a `- ` item
+    keyed: bool,
+}
+
+fn flatten_yaml(text: &str, out: &mut BTreeMap<String, String>) {
+    let mut stack: Vec<YamlFrame> = vec![YamlFrame {
+        indent: 0,
+        path: String::new(),
+        next_index: 0,
+        keyed: false,
+    }];
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let raw = lines[i];
+        i += 1;
+        let content = strip_yaml_comment(raw);
+        let trimmed = content.trim();
+        if trimmed.is_empty() || trimmed == "---" || trimmed == "..." {
+            continue;
+        }
+        let mut indent = raw.len() - raw.trim_start().len();
+        let mut rest = trimmed;
+
+        // Close frames we have dedented out of. A `- ` item may sit at the same
+        // indent as the key that owns the sequence.
+        let is_item = rest == "-" || rest.starts_with("- ");
+        while let Some(top) = stack.last()
+            && stack.len() > 1
+            && indent < top.indent
+            && !(is_item && top.keyed && top.indent == indent + 1)
+        {
+            stack.pop();
+        }
+
+        // "- item": a sequence entry of the innermost frame
+        let mut parent_path = stack[stack.len() - 1].path.clone();
+        while let Some(item) = rest
+            .strip_prefix("- ")
+            .or(if rest == "-" { Some("") } else { None })
+        {
+            let frame = stack.last_mut().expect("root frame is never popped");
+            let item_path = format!("{}[{}]", frame.path, frame.next_index);
+            frame.next_index += 1;
+            let item_indent = indent + (rest.len() - item.len());
+            stack.push(YamlFrame {
+                indent: item_indent,
+                path: item_path.clone(),
+                next_index: 0,
+                keyed: false,
+            });
+            parent_path = item_path;
+            indent = item_indent;
+            rest = item.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+        }
+        if rest.is_empty() {
+            continue;
+        }
+
+        match split_yaml_key(rest) {
+            Some((key, value)) => {
+                let path = join_key(&parent_path, key);
+                if value.is_empty() {
+                    // Nested block; children are indented further
+                    stack.push(YamlFrame {
+                        indent: indent + 1,
+                        path,
+                        next_index: 0,
+                        keyed: true,
+                    });
+                } else if value.starts_with(['|', '>']) {
+                    let (block, consumed) = yaml_block_scalar(&lines[i..], indent);
+                    i += consumed;
+                    out.insert(path, block);
+                } else {
+                    out.insert(path, value.to_string());
+                }
+            }
+            None => {
+                out.insert(root_or(parent_path), rest.to_string());
+            }
+        }
+    }
+}
+
+/// Split `key: value`, ignoring colons inside quoted keys
+fn split_yaml_key(text: &str) -> Option<(&str, &str)> {
+    let (key, value) = if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'')
+    {
+        let end = text[1..].find(quote)? + 1;
+        let after = text[end + 1..].strip_prefix(':')?;
+        (&text[1..end], after)
+    } else {
+        let pos = text
+            .find(": ")
+            .or_else(|| text.ends_with(':').then(|| text.len() - 1))?;
+        (&text[..pos], &text[pos + 1..])
+    };
+    Some((key.trim(), value.trim()))
+}
+
+fn strip_yaml_comment(line: &str) -> &str {
+    if line.trim_start().starts_with('#') {
+        return "";
+    }
+    match line.find(" #") {
+        Some(pos) if !line[..pos].contains(['"', '\'']) => &line[..pos],
+        _ => line,
+    }
+}
+
+/// Collect the lines of a `|` or `>` scalar indented deeper than `indent`
+fn yaml_block_scalar(lines: &[&str], indent: usize) -> (String, usize) {
+    let mut body = Vec::new();
+    for line in lines {
+        let line_indent = line.len() - line.trim_start().len();
+        if !line.trim().is_empty() && line_indent <= indent {
+            break;
+        }
+        body.push(line.trim());
+    }
+    let consumed = body.len();
+    while body.last().is_some_and(|l| l.is_empty()) {
+        body.pop();
+    }
+    (body.join("\n"), consumed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_ignore_reordered_and_reflowed_json() {
+        // given the same object with keys reordered and reformatted
+        let old = r#"{"a": 1, "b": {"c": [1, 2]}}"#;
+        let new = "{\n  \"b\": {\"c\": [1, 2]},\n  \"a\": 1\n}";
+
+        // when diffed structurally
+        let changes = structural_diff(StructuredFormat::Json, old, new).unwrap();
+
+        // then nothing changed
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn should_report_key_level_json_changes() {
+        // given a document with one changed, one removed and one added key
+        let old = r#"{"name": "app", "port": 80, "debug": true}"#;
+        let new = r#"{"name": "app", "port": 8080, "tags": ["web"]}"#;
+
+        // when diffed structurally
+        let changes = structural_diff(StructuredFormat::Json, old, new).unwrap();
+
+        // then each change is reported by path, sorted
+        assert_eq!(
+            changes,
+            vec![
+                StructuralChange::Removed {
+                    path: "debug".to_string(),
+                    value: "true".to_string()
+                },
+                StructuralChange::Changed {
+                    path: "port".to_string(),
+                    old: "80".to_string(),
+                    new: "8080".to_string()
+                },
+                StructuralChange::Added {
+                    path: "tags[0]".to_string(),
+                    value: "\"web\"".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_flatten_yaml_mappings_and_sequences() {
+        // given a small config file
+        let text = "server:\n  host: localhost # dev\n  ports:\n    - 80\n    - 443\njobs:\n  - name: build\n    run: |\n      cargo build\n      cargo test\n  - lint\n";
+
+        // when flattened
+        let mut out = BTreeMap::new();
+        flatten_yaml(text, &mut out);
+
+        // then nested keys and list items get dotted/indexed paths
+        assert_eq!(
+            out.get("server.host").map(String::as_str),
+            Some("localhost")
+        );
+        assert_eq!(out.get("server.ports[1]").map(String::as_str), Some("443"));
+        assert_eq!(out.get("jobs[0].name").map(String::as_str), Some("build"));
+        assert_eq!(out.get("jobs[1]").map(String::as_str), Some("lint"));
+        assert_eq!(
+            out.get("jobs[0].run").map(String::as_str),
+            Some("cargo build\ncargo test")
+        );
+    }
+
+    #[test]
+    fn should_list_every_key_for_added_file() {
+        // given a new YAML file
+        let changes = structural_diff(StructuredFormat::Yaml, "", "a: 1\nb: 2\n").unwrap();
+
+        // then both keys are added
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(changes[0], StructuralChange::Added { .. }));
+    }
+
+    #[test]
+    fn should_detect_format_from_extension() {
+        assert_eq!(
+            StructuredFormat::for_path(Path::new("package.json")),
+            Some(StructuredFormat::Json)
+        );
+        assert_eq!(
+            StructuredFormat::for_path(Path::new(".github/ci.YML")),
+            Some(StructuredFormat::Yaml)
+        );
+        assert_eq!(StructuredFormat::for_path(Path::new("main.rs")), None);
+    }
+}
@@ -0,0 +1,304 @@
+//! Which changed source files come with changed tests.
+//!
+//! Test files are recognized by directory (`tests/`, `__tests__/`, `spec/`)
+//! or by name (`test_foo.py`, `foo_test.go`, `foo.spec.ts`, `FooTest.java`).
+//! A source file counts as tested when a changed test file names the same
+//! subject, when a configured `test_mappings` rule points at a changed test
+//! file, or when its own hunks touch inline `#[test]` functions.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::TestMapping;
+use crate::model::{DiffFile, FileStatus, LineOrigin};
+
+/// Extensions of files expected to have tests
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "go", "js", "jsx", "ts", "tsx", "java", "kt", "rb", "c", "cc", "cpp", "h", "hpp",
+    "cs", "swift", "php", "scala", "ex", "exs",
+];
+const TEST_DIRS: &[&str] = &["test", "tests", "__tests__", "spec", "specs"];
+const TEST_PREFIXES: &[&str] = &["test_"];
+const TEST_SUFFIXES: &[&str] = &["_test", ".test", "-test", "_spec", ".spec", "-spec"];
+/// Case-sensitive suffixes of CamelCase test classes
+const CLASS_SUFFIXES: &[&str] = &["Tests", "Test", "Spec"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCoverage {
+    pub file_idx: usize,
+    pub source: PathBuf,
+    /// Changed test files mapped to the source; the source itself when it
+    /// changes inline tests. Empty when no tests changed.
+    pub tests: Vec<PathBuf>,
+}
+
+/// Whether the path looks like a test file
+pub fn is_test_path(path: &Path) -> bool {
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components().any(|c| {
+            c.as_os_str()
+                .to_str()
+                .is_some_and(|c| TEST_DIRS.contains(&c))
+        })
+    });
+    in_test_dir || test_subject(path).is_some()
+}
+
+/// The subject a test file is named after (`test_parser.py` → `parser`),
+/// or None when the name has no test affix
+fn test_subject(path: &Path) -> Option<String> {
+    let stem = file_stem(path)?;
+    let stripped = TEST_PREFIXES
+        .iter()
+        .find_map(|prefix| stem.strip_prefix(prefix))
+        .or_else(|| {
+            TEST_SUFFIXES
+                .iter()
+                .find_map(|suffix| stem.strip_suffix(suffix))
+        })
+        .or_else(|| {
+            CLASS_SUFFIXES
+                .iter()
+                .find_map(|suffix| stem.strip_suffix(suffix))
+        })?;
+    (!stripped.is_empty()).then(|| stripped.to_lowercase())
+}
+
+/// File name up to the last dot, so `parser.test.ts` yields `parser.test`
+/// and `parser.ts` yields `parser`
+fn file_stem(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    Some(name.rsplit_once('.').map_or(name, |(stem, _)| stem))
+}
+
+fn is_source_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+        && !is_test_path(path)
+}
+
+/// Expand `{stem}`, `{name}` and `{dir}` in a mapping template for `source`
+fn expand_template(template: &str, source: &Path) -> String {
+    let stem = file_stem(source).unwrap_or_default();
+    let name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let dir = source.parent().and_then(|d| d.to_str()).unwrap_or_default();
+    template
+        .replace("{stem}", stem)
+        .replace("{name}", name)
+        .replace("{dir}", dir)
+}
+
+/// Whether any hunk of the file adds or removes an inline test
+fn touches_inline_tests(file: &DiffFile) -> bool {
+    file.hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.iter())
+        .any(|line| {
+            line.origin != LineOrigin::Context
+                && (line.content.contains("#[test]") || line.content.contains("#[cfg(test)]"))
+        })
+}
+
+/// Changed test files for every changed (not deleted) source file, in file order.
+/// Configured rules whose `source` glob matches a file replace the name heuristic.
+pub fn test_coverage(files: &[DiffFile], rules: &[TestMapping]) -> Vec<TestCoverage> {
+    let tests: Vec<&PathBuf> = files
+        .iter()
+        .map(|file| file.display_path())
+        .filter(|path| is_test_path(path))
+        .collect();
+
+    let mut coverage = Vec::new();
+    for (file_idx, file) in files.iter().enumerate() {
+        let source = file.display_path();
+        if file.status == FileStatus::Deleted || file.is_binary || !is_source_path(source) {
+            continue;
+        }
+
+        let matching_rules: Vec<&TestMapping> = rules
+            .iter()
+            .filter(|rule| crate::glob::matches_path(&rule.source, source))
+            .collect();
+        let mut mapped: Vec<PathBuf> = if matching_rules.is_empty() {
+            let stem = file_stem(source).map(str::to_lowercase);
+            tests
+                .iter()
+                .filter(|test| {
+                    // `tests/parser.rs` is named after its subject without an affix
+                    let subject =
+                        test_subject(test).or_else(|| file_stem(test).map(str::to_lowercase));
+                    subject == stem
+                })
+                .map(|test| (*test).clone())
+                .collect()
+        } else {
+            tests
+                .iter()
+                .filter(|test| {
+                    matching_rules.iter().any(|rule| {
+                        rule.tests.iter().any(|template| {
+                            crate::glob::matches_path(&expand_template(template, source), test)
+                        })
+                    })
+                })
+                .map(|test| (*test).clone())
+                .collect()
+        };
+        if touches_inline_tests(file) {
+            mapped.insert(0, source.clone());
+        }
+
+        coverage.push(TestCoverage {
+            file_idx,
+            source: source.clone(),
+            tests: mapped,
+        });
+    }
+    coverage
+}
+
+/// Changed files on the other side of `files[file_idx]`: the tests mapped to
+/// a source file, or the source files a test file is mapped to
+pub fn alternate_files(files: &[DiffFile], file_idx: usize, rules: &[TestMapping]) -> Vec<usize> {
+    let Some(path) = files.get(file_idx).map(|file| file.display_path()) else {
+        return Vec::new();
+    };
+    let coverage = test_coverage(files, rules);
+
+    if let Some(entry) = coverage.iter().find(|c| c.file_idx == file_idx) {
+        return entry
+            .tests
+            .iter()
+            .filter(|test| *test != path)
+            .filter_map(|test| files.iter().position(|f| f.display_path() == test))
+            .collect();
+    }
+    coverage
+        .iter()
+        .filter(|c| c.tests.contains(path))
+        .map(|c| c.file_idx)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DiffHunk, DiffLine};
+
+    fn file(path: &str, status: FileStatus, added: &[&str]) -> DiffFile {
+        DiffFile {
+            old_path: Some(PathBuf::from(path)),
+            new_path: Some(PathBuf::from(path)),
+            status,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,1 +1,1 @@".to_string(),
+                lines: added
+                    .iter()
+                    .map(|content| DiffLine {
+                        origin: LineOrigin::Addition,
+                        content: content.to_string(),
+                        old_lineno: None,
+                        new_lineno: None,
+                        highlighted_spans: None,
+                    })
+                    .collect(),
+                old_start: 1,
+                old_count: 1,
+                new_start: 1,
+                new_count: 1,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    #[test]
+    fn should_recognize_test_files() {
+        assert!(is_test_path(Path::new("tests/parser.rs")));
+        assert!(is_test_path(Path::new("pkg/lexer_test.go")));
+        assert!(is_test_path(Path::new("web/src/app.spec.ts")));
+        assert!(is_test_path(Path::new("app/test_views.py")));
+        assert!(is_test_path(Path::new("src/main/ParserTest.java")));
+        assert!(!is_test_path(Path::new("src/contest.rs")));
+        assert!(!is_test_path(Path::new("src/parser.rs")));
+    }
+
+    #[test]
+    fn should_map_sources_to_tests_by_name() {
+        // given sources with and without a changed test
+        let files = vec![
+            file("pkg/lexer.go", FileStatus::Modified, &[]),
+            file("pkg/lexer_test.go", FileStatus::Modified, &[]),
+            file("pkg/parser.go", FileStatus::Modified, &[]),
+            file("README.md", FileStatus::Modified, &[]),
+            file("src/config.rs", FileStatus::Modified, &[]),
+            file("tests/config.rs", FileStatus::Added, &[]),
+        ];
+
+        // when
+        let coverage = test_coverage(&files, &[]);
+
+        // then
+        assert_eq!(coverage.len(), 3);
+        assert_eq!(coverage[0].tests, vec![PathBuf::from("pkg/lexer_test.go")]);
+        assert_eq!(coverage[1].source, PathBuf::from("pkg/parser.go"));
+        assert!(coverage[1].tests.is_empty());
+        assert_eq!(coverage[2].tests, vec![PathBuf::from("tests/config.rs")]);
+    }
+
+    #[test]
+    fn should_count_inline_rust_tests_and_skip_deletions() {
+        let files = vec![
+            file("src/app.rs", FileStatus::Modified, &["    #[test]"]),
+            file("src/old.rs", FileStatus::Deleted, &[]),
+        ];
+
+        let coverage = test_coverage(&files, &[]);
+
+        assert_eq!(coverage.len(), 1);
+        assert_eq!(coverage[0].tests, vec![PathBuf::from("src/app.rs")]);
+    }
+
+    #[test]
+    fn should_apply_configured_mappings() {
+        // given a rule mapping lib sources to a differently named spec directory
+        let rules = vec![TestMapping {
+            source: "lib/**/*.rb".to_string(),
+            tests: vec!["spec/**/{stem}_behaviour.rb".to_string()],
+        }];
+        let files = vec![
+            file("lib/billing/invoice.rb", FileStatus::Modified, &[]),
+            file("spec/billing/invoice_behaviour.rb", FileStatus::Added, &[]),
+        ];
+
+        // when
+        let coverage = test_coverage(&files, &rules);
+
+        // then
+        assert_eq!(
+            coverage[0].tests,
+            vec![PathBuf::from("spec/billing/invoice_behaviour.rb")]
+        );
+    }
+
+    #[test]
+    fn should_find_alternate_files_in_both_directions() {
+        // given a source with a test and an unrelated file
+        let files = vec![
+            file("src/parser.rs", FileStatus::Modified, &[]),
+            file("README.md", FileStatus::Modified, &[]),
+            file("tests/parser.rs", FileStatus::Modified, &[]),
+        ];
+
+        // then the source and the test point at each other
+        assert_eq!(alternate_files(&files, 0, &[]), vec![2]);
+        assert_eq!(alternate_files(&files, 2, &[]), vec![0]);
+        assert!(alternate_files(&files, 1, &[]).is_empty());
+    }
+}
@@ -1,89 +1,416 @@
 //! UTF-8 aware text editing utilities.
 //!
-//! Provides cursor movement and text manipulation functions that correctly
-//! handle multi-byte UTF-8 characters (CJK, emoji, etc.).
-
-/// Find the byte position of the previous character boundary.
-/// Returns 0 if already at the start.
-pub fn prev_char_boundary(buffer: &str, cursor: usize) -> usize {
-    if cursor == 0 {
-        return 0;
-    }
-    let cursor = cursor.min(buffer.len());
-    // Move back one byte at a time until we find a char boundary
-    let mut pos = cursor - 1;
-    while pos > 0 && !buffer.is_char_boundary(pos) {
+//! Provides cursor movement and text manipulation functions that work on
+//! grapheme clusters, so multi-byte characters (CJK, emoji) and characters
+//! built from several code points (accents, flags, ZWJ emoji) are never split.
+
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+use unicode_width::UnicodeWidthStr;
+
+/// Move a cursor that sits inside a character or grapheme cluster back to
+/// the start of it.
+fn grapheme_floor(buffer: &str, cursor: usize) -> usize {
+    let mut pos = cursor.min(buffer.len());
+    while !buffer.is_char_boundary(pos) {
         pos -= 1;
     }
-    pos
+    let mut graphemes = GraphemeCursor::new(pos, buffer.len(), true);
+    match graphemes.is_boundary(buffer, 0) {
+        Ok(false) => graphemes
+            .prev_boundary(buffer, 0)
+            .ok()
+            .flatten()
+            .unwrap_or(0),
+        _ => pos,
+    }
+}
+
+/// Find the byte position of the previous grapheme cluster boundary.
+/// Returns 0 if already at the start.
+pub fn prev_grapheme_boundary(buffer: &str, cursor: usize) -> usize {
+    let cursor = grapheme_floor(buffer, cursor);
+    GraphemeCursor::new(cursor, buffer.len(), true)
+        .prev_boundary(buffer, 0)
+        .ok()
+        .flatten()
+        .unwrap_or(0)
 }
 
-/// Find the byte position of the next character boundary.
+/// Find the byte position of the next grapheme cluster boundary.
 /// Returns buffer.len() if already at the end.
-pub fn next_char_boundary(buffer: &str, cursor: usize) -> usize {
-    if cursor >= buffer.len() {
-        return buffer.len();
-    }
-    // Move forward one byte at a time until we find a char boundary
-    let mut pos = cursor + 1;
-    while pos < buffer.len() && !buffer.is_char_boundary(pos) {
-        pos += 1;
-    }
-    pos
+pub fn next_grapheme_boundary(buffer: &str, cursor: usize) -> usize {
+    let cursor = grapheme_floor(buffer, cursor);
+    GraphemeCursor::new(cursor, buffer.len(), true)
+        .next_boundary(buffer, 0)
+        .ok()
+        .flatten()
+        .unwrap_or(buffer.len())
+}
+
+/// Whether a grapheme cluster is whitespace. A space carrying a combining
+/// mark renders as the mark, so it counts as part of a word.
+pub fn is_whitespace_grapheme(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+/// Insert a character at the cursor, returning the new cursor position.
+/// A cursor inside a character or grapheme cluster is moved back to its
+/// start first, so composed input from an IME can never split one.
+pub fn insert_char(buffer: &mut String, cursor: usize, c: char) -> usize {
+    let pos = grapheme_floor(buffer, cursor);
+    buffer.insert(pos, c);
+    pos + c.len_utf8()
 }
 
-/// Delete the character before the cursor position, returning the new cursor position.
-/// Handles multi-byte UTF-8 characters correctly.
-pub fn delete_char_before(buffer: &mut String, cursor: usize) -> usize {
+/// Insert text at the cursor, returning the position after it. Like
+/// `insert_char`, a cursor inside a grapheme cluster moves to its start.
+pub fn insert_text(buffer: &mut String, cursor: usize, text: &str) -> usize {
+    let pos = grapheme_floor(buffer, cursor);
+    buffer.insert_str(pos, text);
+    pos + text.len()
+}
+
+/// Delete the grapheme cluster before the cursor position, returning the new
+/// cursor position. An accented letter or emoji sequence goes in one step.
+pub fn delete_grapheme_before(buffer: &mut String, cursor: usize) -> usize {
+    let cursor = grapheme_floor(buffer, cursor);
     if cursor == 0 {
         return 0;
     }
-    let prev = prev_char_boundary(buffer, cursor);
+    let prev = prev_grapheme_boundary(buffer, cursor);
     buffer.replace_range(prev..cursor, "");
     prev
 }
 
 /// Delete word before cursor, returning the new cursor position.
-/// Handles multi-byte UTF-8 characters correctly.
+/// Words are split on whitespace grapheme clusters.
 pub fn delete_word_before(buffer: &mut String, cursor: usize) -> usize {
-    if cursor == 0 {
-        return 0;
+    let cursor = grapheme_floor(buffer, cursor);
+    let pos = word_start_before(buffer, cursor);
+    buffer.replace_range(pos..cursor, "");
+    pos
+}
+
+/// Start of the word before the cursor, skipping whitespace in between.
+pub fn word_start_before(buffer: &str, cursor: usize) -> usize {
+    let cursor = grapheme_floor(buffer, cursor);
+    let mut graphemes = buffer[..cursor].grapheme_indices(true).rev().peekable();
+    let mut pos = cursor;
+    while let Some((idx, _)) = graphemes.next_if(|(_, g)| is_whitespace_grapheme(g)) {
+        pos = idx;
     }
+    while let Some((idx, _)) = graphemes.next_if(|(_, g)| !is_whitespace_grapheme(g)) {
+        pos = idx;
+    }
+    pos
+}
 
+/// End of the word after the cursor, skipping whitespace in between
+/// (where readline's `Alt-D` stops).
+pub fn word_end_after(buffer: &str, cursor: usize) -> usize {
+    let cursor = grapheme_floor(buffer, cursor);
+    let mut graphemes = buffer[cursor..].grapheme_indices(true).peekable();
     let mut pos = cursor;
+    while let Some((idx, g)) = graphemes.next_if(|(_, g)| is_whitespace_grapheme(g)) {
+        pos = cursor + idx + g.len();
+    }
+    while let Some((idx, g)) = graphemes.next_if(|(_, g)| !is_whitespace_grapheme(g)) {
+        pos = cursor + idx + g.len();
+    }
+    pos
+}
+
+/// Kind of character for word motions: a run of one kind is a word
+#[derive(PartialEq, Eq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(grapheme: &str) -> CharClass {
+    if is_whitespace_grapheme(grapheme) {
+        CharClass::Space
+    } else if grapheme.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// Start of the next word on the line, like vim's `w`; stays put on the
+/// last word.
+pub fn next_word_start(line: &str, cursor: usize) -> usize {
+    let cursor = grapheme_floor(line, cursor);
+    let mut graphemes = line[cursor..].grapheme_indices(true).peekable();
+    let Some((_, first)) = graphemes.next() else {
+        return cursor;
+    };
+    let class = char_class(first);
+    if class != CharClass::Space {
+        while graphemes.next_if(|(_, g)| char_class(g) == class).is_some() {}
+    }
+    while graphemes
+        .next_if(|(_, g)| char_class(g) == CharClass::Space)
+        .is_some()
+    {}
+    graphemes.next().map_or(cursor, |(idx, _)| cursor + idx)
+}
+
+/// Last character of the word under or after the cursor, like vim's `e`;
+/// moves on to the next word when already at the end of one.
+pub fn next_word_end(line: &str, cursor: usize) -> usize {
+    let cursor = grapheme_floor(line, cursor);
+    let mut graphemes = line[cursor..].grapheme_indices(true).skip(1).peekable();
+    while graphemes
+        .next_if(|(_, g)| char_class(g) == CharClass::Space)
+        .is_some()
+    {}
+    let Some((mut end, first)) = graphemes.next() else {
+        return cursor;
+    };
+    let class = char_class(first);
+    while let Some((idx, _)) = graphemes.next_if(|(_, g)| char_class(g) == class) {
+        end = idx;
+    }
+    cursor + end
+}
+
+/// Byte range of the word (or run of punctuation or spaces) the cursor is
+/// on; empty at the end of the line
+pub fn word_at(line: &str, cursor: usize) -> std::ops::Range<usize> {
+    let cursor = grapheme_floor(line, cursor);
+    let Some(grapheme) = line[cursor..].graphemes(true).next() else {
+        return cursor..cursor;
+    };
+    let class = char_class(grapheme);
+    let start = line[..cursor]
+        .grapheme_indices(true)
+        .rev()
+        .take_while(|(_, g)| char_class(g) == class)
+        .last()
+        .map_or(cursor, |(idx, _)| idx);
+    let end = line[cursor..]
+        .grapheme_indices(true)
+        .find(|(_, g)| char_class(g) != class)
+        .map_or(line.len(), |(idx, _)| cursor + idx);
+    start..end
+}
+
+/// Start of the word before the cursor, or of the one it is in, like vim's `b`
+pub fn prev_word_start(line: &str, cursor: usize) -> usize {
+    let cursor = grapheme_floor(line, cursor);
+    let mut graphemes = line[..cursor].grapheme_indices(true).rev().peekable();
+    while graphemes
+        .next_if(|(_, g)| char_class(g) == CharClass::Space)
+        .is_some()
+    {}
+    let Some((mut start, first)) = graphemes.next() else {
+        return cursor;
+    };
+    let class = char_class(first);
+    while let Some((idx, _)) = graphemes.next_if(|(_, g)| char_class(g) == class) {
+        start = idx;
+    }
+    start
+}
+
+/// Start of the line the cursor is on.
+pub fn line_start(buffer: &str, cursor: usize) -> usize {
+    let cursor = cursor.min(buffer.len());
+    match buffer[..cursor].rfind('\n') {
+        Some(pos) => pos + 1,
+        None => 0,
+    }
+}
 
-    // Skip whitespace backwards
-    while pos > 0 {
-        let prev = prev_char_boundary(buffer, pos);
-        if let Some(ch) = buffer[prev..pos].chars().next()
-            && !ch.is_whitespace()
-        {
-            break;
+/// End of the line the cursor is on, before its newline.
+pub fn line_end(buffer: &str, cursor: usize) -> usize {
+    let cursor = cursor.min(buffer.len());
+    match buffer[cursor..].find('\n') {
+        Some(pos) => cursor + pos,
+        None => buffer.len(),
+    }
+}
+
+/// Position on the line starting at `start` at display `column`, or the
+/// line's end when it is shorter. Wide characters are never split.
+pub fn position_at_column(buffer: &str, start: usize, column: usize) -> usize {
+    let end = line_end(buffer, start);
+    let mut width = 0;
+    for (idx, grapheme) in buffer[start..end].grapheme_indices(true) {
+        width += grapheme.width();
+        if width > column {
+            return start + idx;
         }
-        pos = prev;
     }
+    end
+}
+
+/// How the lines of a buffer wrap on screen: every `width` columns, with the
+/// first row of each line starting `indent` columns in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrapWidth {
+    pub width: usize,
+    pub indent: usize,
+}
+
+impl WrapWidth {
+    /// Screen row within its line and column within that row of display
+    /// column `column` of a line
+    fn row_and_column(self, column: usize) -> (usize, usize) {
+        let column = self.indent + column;
+        (column / self.width, column % self.width)
+    }
+
+    /// Display column of a line at `column` on screen row `row` of it
+    fn line_column(self, row: usize, column: usize) -> usize {
+        (row * self.width + column).saturating_sub(self.indent)
+    }
+}
 
-    // Skip non-whitespace backwards (the word itself)
-    while pos > 0 {
-        let prev = prev_char_boundary(buffer, pos);
-        if let Some(ch) = buffer[prev..pos].chars().next()
-            && ch.is_whitespace()
-        {
-            break;
+/// Move to the same screen column on the row above, or to the start of the
+/// buffer when already on the first row. Without `wrap` every line is one row.
+pub fn line_up(buffer: &str, cursor: usize, wrap: Option<WrapWidth>) -> usize {
+    let cursor = grapheme_floor(buffer, cursor);
+    let start = line_start(buffer, cursor);
+    let column = buffer[start..cursor].width();
+    let Some(wrap) = wrap.filter(|wrap| wrap.width > 0) else {
+        if start == 0 {
+            return 0;
         }
-        pos = prev;
+        return position_at_column(buffer, line_start(buffer, start - 1), column);
+    };
+
+    let (row, row_column) = wrap.row_and_column(column);
+    if row > 0 {
+        return position_at_column(buffer, start, wrap.line_column(row - 1, row_column));
+    }
+    if start == 0 {
+        return 0;
     }
+    let prev_start = line_start(buffer, start - 1);
+    let (last_row, _) = wrap.row_and_column(buffer[prev_start..start - 1].width());
+    position_at_column(buffer, prev_start, wrap.line_column(last_row, row_column))
+}
 
-    // Delete from pos to cursor
-    buffer.replace_range(pos..cursor, "");
-    pos
+/// Move to the same screen column on the row below, or to the end of the
+/// buffer when already on the last row. Without `wrap` every line is one row.
+pub fn line_down(buffer: &str, cursor: usize, wrap: Option<WrapWidth>) -> usize {
+    let cursor = grapheme_floor(buffer, cursor);
+    let start = line_start(buffer, cursor);
+    let end = line_end(buffer, cursor);
+    let column = buffer[start..cursor].width();
+    let Some(wrap) = wrap.filter(|wrap| wrap.width > 0) else {
+        if end == buffer.len() {
+            return end;
+        }
+        return position_at_column(buffer, end + 1, column);
+    };
+
+    let (row, row_column) = wrap.row_and_column(column);
+    let (last_row, _) = wrap.row_and_column(buffer[start..end].width());
+    if row < last_row {
+        return position_at_column(buffer, start, wrap.line_column(row + 1, row_column));
+    }
+    if end == buffer.len() {
+        return end;
+    }
+    position_at_column(buffer, end + 1, wrap.line_column(0, row_column))
+}
+
+/// How many kills the kill ring remembers
+const KILL_RING_SIZE: usize = 16;
+
+/// Text removed with `Ctrl-K`, `Ctrl-W` or `Alt-D`, newest last, for
+/// `Ctrl-Y` to yank back
+#[derive(Debug, Default)]
+pub struct KillRing {
+    entries: Vec<String>,
+}
+
+impl KillRing {
+    /// Remember killed text; empty kills are ignored
+    pub fn push(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        if self.entries.len() == KILL_RING_SIZE {
+            self.entries.remove(0);
+        }
+        self.entries.push(text);
+    }
+
+    /// The most recent kill
+    pub fn latest(&self) -> Option<&str> {
+        self.entries.last().map(String::as_str)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    // -- prev_char_boundary tests --
+    // -- word motion tests --
+
+    #[test]
+    fn should_move_to_next_word_start_across_punctuation() {
+        // given
+        let line = "let x = foo.bar(1);";
+
+        // then w stops at each word and at each run of punctuation
+        assert_eq!(next_word_start(line, 0), 4);
+        assert_eq!(next_word_start(line, 8), 11);
+        assert_eq!(next_word_start(line, 11), 12);
+        assert_eq!(next_word_start(line, 18), 18);
+    }
+
+    #[test]
+    fn should_move_to_word_end() {
+        // given
+        let line = "let value = 1";
+
+        // then e goes to the end of the word, then of the next one
+        assert_eq!(next_word_end(line, 0), 2);
+        assert_eq!(next_word_end(line, 2), 8);
+        assert_eq!(next_word_end(line, 12), 12);
+    }
+
+    #[test]
+    fn should_move_to_previous_word_start() {
+        // given
+        let line = "    retry(3);";
+
+        // then b goes to the start of the current or previous word
+        assert_eq!(prev_word_start(line, 12), 11);
+        assert_eq!(prev_word_start(line, 9), 4);
+        assert_eq!(prev_word_start(line, 7), 4);
+        assert_eq!(prev_word_start(line, 4), 4);
+    }
+
+    #[test]
+    fn should_find_word_under_cursor() {
+        // given
+        let line = "let x = foo_bar(1);";
+
+        // then
+        assert_eq!(word_at(line, 10), 8..15);
+        assert_eq!(word_at(line, 15), 15..16);
+        assert_eq!(word_at(line, 19), 19..19);
+    }
+
+    #[test]
+    fn should_step_over_wide_characters_in_word_motions() {
+        // given CJK words separated by spaces
+        let line = "日本 語";
+
+        // then offsets land on grapheme starts
+        assert_eq!(next_word_start(line, 0), 7);
+        assert_eq!(next_word_end(line, 0), 3);
+        assert_eq!(prev_word_start(line, 7), 0);
+    }
+
+    // -- prev_grapheme_boundary tests --
 
     #[test]
     fn should_return_zero_when_at_start() {
@@ -91,7 +418,7 @@ mod tests {
         let s = "hello";
 
         // when
-        let result = prev_char_boundary(s, 0);
+        let result = prev_grapheme_boundary(s, 0);
 
         // then
         assert_eq!(result, 0);
@@ -103,9 +430,9 @@ mod tests {
         let s = "hello";
 
         // when/then
-        assert_eq!(prev_char_boundary(s, 5), 4);
-        assert_eq!(prev_char_boundary(s, 3), 2);
-        assert_eq!(prev_char_boundary(s, 1), 0);
+        assert_eq!(prev_grapheme_boundary(s, 5), 4);
+        assert_eq!(prev_grapheme_boundary(s, 3), 2);
+        assert_eq!(prev_grapheme_boundary(s, 1), 0);
     }
 
     #[test]
@@ -115,8 +442,8 @@ mod tests {
         assert_eq!(s.len(), 6);
 
         // when/then
-        assert_eq!(prev_char_boundary(s, 6), 3); // End -> start of '아'
-        assert_eq!(prev_char_boundary(s, 3), 0); // Start of '아' -> start of '좋'
+        assert_eq!(prev_grapheme_boundary(s, 6), 3); // End -> start of '아'
+        assert_eq!(prev_grapheme_boundary(s, 3), 0); // Start of '아' -> start of '좋'
     }
 
     #[test]
@@ -126,7 +453,7 @@ mod tests {
         assert_eq!(s.len(), 4);
 
         // when/then
-        assert_eq!(prev_char_boundary(s, 4), 0);
+        assert_eq!(prev_grapheme_boundary(s, 4), 0);
     }
 
     #[test]
@@ -135,12 +462,12 @@ mod tests {
         let s = "a좋b"; // 1 + 3 + 1 = 5 bytes
 
         // when/then
-        assert_eq!(prev_char_boundary(s, 5), 4); // After 'b' -> start of 'b'
-        assert_eq!(prev_char_boundary(s, 4), 1); // After '좋' -> start of '좋'
-        assert_eq!(prev_char_boundary(s, 1), 0); // After 'a' -> start
+        assert_eq!(prev_grapheme_boundary(s, 5), 4); // After 'b' -> start of 'b'
+        assert_eq!(prev_grapheme_boundary(s, 4), 1); // After '좋' -> start of '좋'
+        assert_eq!(prev_grapheme_boundary(s, 1), 0); // After 'a' -> start
     }
 
-    // -- next_char_boundary tests --
+    // -- next_grapheme_boundary tests --
 
     #[test]
     fn should_return_len_when_at_end() {
@@ -148,7 +475,7 @@ mod tests {
         let s = "hello";
 
         // when
-        let result = next_char_boundary(s, 5);
+        let result = next_grapheme_boundary(s, 5);
 
         // then
         assert_eq!(result, 5);
@@ -160,9 +487,9 @@ mod tests {
         let s = "hello";
 
         // when/then
-        assert_eq!(next_char_boundary(s, 0), 1);
-        assert_eq!(next_char_boundary(s, 2), 3);
-        assert_eq!(next_char_boundary(s, 4), 5);
+        assert_eq!(next_grapheme_boundary(s, 0), 1);
+        assert_eq!(next_grapheme_boundary(s, 2), 3);
+        assert_eq!(next_grapheme_boundary(s, 4), 5);
     }
 
     #[test]
@@ -171,8 +498,8 @@ mod tests {
         let s = "좋아";
 
         // when/then
-        assert_eq!(next_char_boundary(s, 0), 3); // Start -> after '좋'
-        assert_eq!(next_char_boundary(s, 3), 6); // After '좋' -> after '아'
+        assert_eq!(next_grapheme_boundary(s, 0), 3); // Start -> after '좋'
+        assert_eq!(next_grapheme_boundary(s, 3), 6); // After '좋' -> after '아'
     }
 
     #[test]
@@ -181,10 +508,64 @@ mod tests {
         let s = "🦀";
 
         // when/then
-        assert_eq!(next_char_boundary(s, 0), 4);
+        assert_eq!(next_grapheme_boundary(s, 0), 4);
+    }
+
+    #[test]
+    fn should_step_over_combined_graphemes() {
+        // given - 'e' + combining acute, a ZWJ family, and a flag
+        let s = "e\u{301}👨‍👩‍👧🇫🇷";
+        let family_end = 3 + "👨‍👩‍👧".len();
+
+        // when/then
+        assert_eq!(next_grapheme_boundary(s, 0), 3);
+        assert_eq!(next_grapheme_boundary(s, 3), family_end);
+        assert_eq!(next_grapheme_boundary(s, family_end), s.len());
+        assert_eq!(prev_grapheme_boundary(s, s.len()), family_end);
+        assert_eq!(prev_grapheme_boundary(s, family_end), 3);
+        assert_eq!(prev_grapheme_boundary(s, 3), 0);
+    }
+
+    #[test]
+    fn should_snap_cursor_inside_grapheme_to_its_start() {
+        // given - byte 1 is between 'e' and its combining accent
+        let s = "e\u{301}x";
+
+        // when/then
+        assert_eq!(next_grapheme_boundary(s, 1), 3);
+        assert_eq!(prev_grapheme_boundary(s, 1), 0);
+    }
+
+    // -- insert_char tests --
+
+    #[test]
+    fn should_insert_multibyte_chars_in_sequence() {
+        // given
+        let mut s = String::from("ab");
+
+        // when - an IME commits '日本' between 'a' and 'b'
+        let cursor = insert_char(&mut s, 1, '日');
+        let cursor = insert_char(&mut s, cursor, '本');
+
+        // then
+        assert_eq!(s, "a日本b");
+        assert_eq!(cursor, 7);
+    }
+
+    #[test]
+    fn should_insert_before_char_when_cursor_is_mid_char() {
+        // given - byte 2 is inside '좋'
+        let mut s = String::from("a좋");
+
+        // when
+        let cursor = insert_char(&mut s, 2, 'x');
+
+        // then
+        assert_eq!(s, "ax좋");
+        assert_eq!(cursor, 2);
     }
 
-    // -- delete_char_before tests --
+    // -- delete_grapheme_before tests --
 
     #[test]
     fn should_delete_ascii_char() {
@@ -192,7 +573,7 @@ mod tests {
         let mut s = String::from("hello");
 
         // when
-        let cursor = delete_char_before(&mut s, 5);
+        let cursor = delete_grapheme_before(&mut s, 5);
 
         // then
         assert_eq!(s, "hell");
@@ -205,7 +586,7 @@ mod tests {
         let mut s = String::from("좋아");
 
         // when
-        let cursor = delete_char_before(&mut s, 6);
+        let cursor = delete_grapheme_before(&mut s, 6);
 
         // then
         assert_eq!(s, "좋");
@@ -218,7 +599,7 @@ mod tests {
         let mut s = String::from("좋아요");
 
         // when
-        let cursor = delete_char_before(&mut s, 6); // After '좋아'
+        let cursor = delete_grapheme_before(&mut s, 6); // After '좋아'
 
         // then
         assert_eq!(s, "좋요");
@@ -231,7 +612,7 @@ mod tests {
         let mut s = String::from("hello");
 
         // when
-        let cursor = delete_char_before(&mut s, 0);
+        let cursor = delete_grapheme_before(&mut s, 0);
 
         // then
         assert_eq!(s, "hello");
@@ -244,13 +625,43 @@ mod tests {
         let mut s = String::from("hi🦀");
 
         // when
-        let cursor = delete_char_before(&mut s, 6); // After emoji
+        let cursor = delete_grapheme_before(&mut s, 6); // After emoji
 
         // then
         assert_eq!(s, "hi");
         assert_eq!(cursor, 2);
     }
 
+    #[test]
+    fn should_delete_accented_letter_in_one_step() {
+        // given - "café" with a decomposed 'é'
+        let mut s = String::from("cafe\u{301}");
+
+        let end = s.len();
+
+        // when
+        let cursor = delete_grapheme_before(&mut s, end);
+
+        // then
+        assert_eq!(s, "caf");
+        assert_eq!(cursor, 3);
+    }
+
+    #[test]
+    fn should_delete_emoji_with_skin_tone_in_one_step() {
+        // given
+        let mut s = String::from("ok👍🏽");
+
+        let end = s.len();
+
+        // when
+        let cursor = delete_grapheme_before(&mut s, end);
+
+        // then
+        assert_eq!(s, "ok");
+        assert_eq!(cursor, 2);
+    }
+
     // -- delete_word_before tests --
 
     #[test]
@@ -305,6 +716,44 @@ mod tests {
         assert_eq!(cursor, 0);
     }
 
+    // -- word motion tests --
+
+    #[test]
+    fn should_find_word_bounds_around_cursor() {
+        // given
+        let s = "fix  the   bug";
+
+        // when/then - from inside "the"
+        assert_eq!(word_start_before(s, 7), 5);
+        assert_eq!(word_end_after(s, 7), 8);
+        // from the whitespace before "bug"
+        assert_eq!(word_start_before(s, 9), 5);
+        assert_eq!(word_end_after(s, 9), s.len());
+    }
+
+    // -- KillRing tests --
+
+    #[test]
+    fn should_yank_latest_kill_and_forget_oldest() {
+        // given
+        let mut ring = KillRing::default();
+        assert_eq!(ring.latest(), None);
+
+        // when
+        for i in 0..=KILL_RING_SIZE {
+            ring.push(format!("kill {i}"));
+        }
+        ring.push(String::new());
+
+        // then
+        assert_eq!(
+            ring.latest(),
+            Some(format!("kill {KILL_RING_SIZE}").as_str())
+        );
+        assert_eq!(ring.entries.len(), KILL_RING_SIZE);
+        assert_eq!(ring.entries[0], "kill 1");
+    }
+
     // -- Integration tests --
 
     #[test]
@@ -314,19 +763,19 @@ mod tests {
 
         // when - navigate right through all chars
         let mut cursor = 0;
-        cursor = next_char_boundary(s, cursor);
+        cursor = next_grapheme_boundary(s, cursor);
         assert_eq!(cursor, 3);
-        cursor = next_char_boundary(s, cursor);
+        cursor = next_grapheme_boundary(s, cursor);
         assert_eq!(cursor, 6);
-        cursor = next_char_boundary(s, cursor);
+        cursor = next_grapheme_boundary(s, cursor);
         assert_eq!(cursor, 9);
 
         // when - navigate left through all chars
-        cursor = prev_char_boundary(s, cursor);
+        cursor = prev_grapheme_boundary(s, cursor);
         assert_eq!(cursor, 6);
-        cursor = prev_char_boundary(s, cursor);
+        cursor = prev_grapheme_boundary(s, cursor);
         assert_eq!(cursor, 3);
-        cursor = prev_char_boundary(s, cursor);
+        cursor = prev_grapheme_boundary(s, cursor);
         assert_eq!(cursor, 0);
     }
 
@@ -347,11 +796,75 @@ mod tests {
         assert_eq!(cursor, 6);
 
         // when - delete both chars
-        cursor = delete_char_before(&mut s, cursor);
+        cursor = delete_grapheme_before(&mut s, cursor);
         assert_eq!(s, "좋");
 
-        cursor = delete_char_before(&mut s, cursor);
+        cursor = delete_grapheme_before(&mut s, cursor);
         assert_eq!(s, "");
         assert_eq!(cursor, 0);
     }
+
+    #[test]
+    fn should_insert_text_at_grapheme_start() {
+        // given - the cursor sits inside '좋' (bytes 0..3)
+        let mut s = "좋아".to_string();
+
+        // when
+        let cursor = insert_text(&mut s, 1, "a\nb");
+
+        // then
+        assert_eq!(s, "a\nb좋아");
+        assert_eq!(cursor, 3);
+    }
+
+    // -- line_up / line_down tests --
+
+    #[test]
+    fn should_keep_column_when_moving_between_lines() {
+        // given
+        let s = "hello\nhi\nworld";
+
+        // when/then - on the first line, up goes to the start
+        assert_eq!(line_up(s, 3, None), 0);
+        // from "hel|lo" down clamps to "hi|"
+        assert_eq!(line_down(s, 3, None), 8);
+        // from "hi|" down lands on "wo|rld"
+        assert_eq!(line_down(s, 8, None), 11);
+        assert_eq!(line_up(s, 11, None), 8);
+        // on the last line, down goes to the end
+        assert_eq!(line_down(s, 11, None), s.len());
+    }
+
+    #[test]
+    fn should_move_between_lines_by_display_width() {
+        // given - '좋' and '아' are two columns wide each
+        let s = "좋아\nabcd";
+
+        // when/then - "ab|cd" is column 2, after '좋'
+        assert_eq!(line_up(s, 9, None), 3);
+        // "좋|아" is column 2, which is "ab|cd"
+        assert_eq!(line_down(s, 3, None), 9);
+        // column 3 falls inside '아', so stay before it
+        assert_eq!(line_up(s, 10, None), 3);
+    }
+
+    #[test]
+    fn should_move_between_wrapped_rows_of_a_line() {
+        // given rows of 10 columns after a 4-column border
+        let wrap = Some(WrapWidth {
+            width: 10,
+            indent: 4,
+        });
+        let s = "abcdefghijklmnopqrst\nxy";
+
+        // when/then - "ab|c" sits at screen column 6, as does "abcdefghijkl|m"
+        assert_eq!(line_down(s, 2, wrap), 12);
+        assert_eq!(line_up(s, 12, wrap), 2);
+        // the last row is short, so down clamps to the end of the line
+        assert_eq!(line_down(s, 12, wrap), 20);
+        // from the last row, down goes to the next line and up comes back
+        assert_eq!(line_down(s, 20, wrap), 21);
+        assert_eq!(line_up(s, 21, wrap), 20);
+        assert_eq!(line_up(s, 20, wrap), 10);
+    }
 }
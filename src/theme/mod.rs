@@ -1,6 +1,7 @@
 //! Theme support for tuicr
 //!
-//! Provides dark and light themes with automatic terminal background detection.
+//! Provides dark and light themes with automatic terminal background detection,
+//! plus colorblind-safe variants of both.
 
 use std::sync::OnceLock;
 
@@ -26,6 +27,9 @@ pub struct Theme {
     pub diff_del_bg: Color,
     pub diff_context: Color,
     pub diff_hunk_header: Color,
+    /// Lines moved rather than added or deleted (both sides)
+    pub diff_moved: Color,
+    pub diff_moved_bg: Color,
     pub expanded_context_fg: Color,
 
     // Syntax highlighting diff backgrounds (for syntax-highlighted code)
@@ -40,10 +44,14 @@ pub struct Theme {
     pub file_modified: Color,
     pub file_deleted: Color,
     pub file_renamed: Color,
+    pub file_copied: Color,
+    pub file_conflicted: Color,
 
     // Review status colors
     pub reviewed: Color,
     pub pending: Color,
+    pub in_progress: Color,
+    pub second_look: Color,
 
     // Comment type colors
     pub comment_note: Color,
@@ -87,6 +95,8 @@ impl Theme {
             diff_del_bg: Color::Rgb(70, 0, 0),
             diff_context: Color::Rgb(200, 200, 200),
             diff_hunk_header: Color::Rgb(90, 200, 255),
+            diff_moved: Color::Rgb(190, 150, 255),
+            diff_moved_bg: Color::Rgb(40, 25, 70),
             expanded_context_fg: Color::Rgb(140, 140, 140),
 
             // Syntax highlighting diff backgrounds
@@ -101,10 +111,14 @@ impl Theme {
             file_modified: Color::Rgb(255, 210, 90),
             file_deleted: Color::Rgb(240, 90, 90),
             file_renamed: Color::Rgb(255, 140, 220),
+            file_copied: Color::Rgb(170, 150, 255),
+            file_conflicted: Color::Rgb(255, 120, 40),
 
             // Review status colors
             reviewed: Color::Rgb(80, 220, 120),
             pending: Color::Rgb(255, 210, 90),
+            in_progress: Color::Rgb(90, 200, 255),
+            second_look: Color::Rgb(255, 140, 40),
 
             // Comment type colors
             comment_note: Color::Rgb(90, 170, 255),
@@ -143,6 +157,8 @@ impl Theme {
             diff_del_bg: Color::Rgb(255, 240, 240), // Very light pink bg
             diff_context: Color::Rgb(0, 0, 0),      // Black for max readability
             diff_hunk_header: Color::Rgb(0, 60, 140),
+            diff_moved: Color::Rgb(90, 30, 150),
+            diff_moved_bg: Color::Rgb(240, 230, 255),
             expanded_context_fg: Color::Rgb(60, 60, 60),
 
             // Syntax highlighting diff backgrounds (lighter for light theme)
@@ -157,10 +173,14 @@ impl Theme {
             file_modified: Color::Rgb(140, 80, 0),
             file_deleted: Color::Rgb(160, 0, 0),
             file_renamed: Color::Rgb(100, 0, 100),
+            file_copied: Color::Rgb(60, 40, 160),
+            file_conflicted: Color::Rgb(190, 70, 0),
 
             // Review status colors
             reviewed: Color::Rgb(0, 100, 0),
             pending: Color::Rgb(140, 80, 0),
+            in_progress: Color::Rgb(0, 100, 160),
+            second_look: Color::Rgb(190, 70, 0),
 
             // Comment type colors
             comment_note: Color::Rgb(0, 60, 140),
@@ -181,12 +201,67 @@ impl Theme {
     }
 }
 
+impl Theme {
+    /// Dark theme for red-green color blindness (deuteranopia, protanopia):
+    /// additions are blue and deletions orange instead of green and red
+    pub fn dark_colorblind() -> Self {
+        Self {
+            diff_add: Color::Rgb(86, 180, 233),
+            diff_add_bg: Color::Rgb(0, 40, 75),
+            diff_del: Color::Rgb(230, 159, 0),
+            diff_del_bg: Color::Rgb(75, 45, 0),
+            syntax_add_bg: Color::Rgb(0, 28, 55),
+            syntax_del_bg: Color::Rgb(55, 32, 0),
+
+            file_added: Color::Rgb(86, 180, 233),
+            file_modified: Color::Rgb(240, 228, 66),
+            file_deleted: Color::Rgb(230, 159, 0),
+
+            reviewed: Color::Rgb(86, 180, 233),
+            pending: Color::Rgb(240, 228, 66),
+            in_progress: Color::Rgb(204, 121, 167),
+            second_look: Color::Rgb(230, 159, 0),
+
+            comment_issue: Color::Rgb(213, 94, 0),
+            comment_praise: Color::Rgb(204, 121, 167),
+
+            ..Self::dark()
+        }
+    }
+
+    /// Light counterpart of [`Theme::dark_colorblind`]
+    pub fn light_colorblind() -> Self {
+        Self {
+            diff_add: Color::Rgb(0, 80, 150),
+            diff_add_bg: Color::Rgb(220, 235, 255),
+            diff_del: Color::Rgb(150, 75, 0),
+            diff_del_bg: Color::Rgb(255, 238, 215),
+            syntax_add_bg: Color::Rgb(220, 235, 255),
+            syntax_del_bg: Color::Rgb(255, 235, 210),
+
+            file_added: Color::Rgb(0, 80, 150),
+            file_deleted: Color::Rgb(150, 75, 0),
+
+            reviewed: Color::Rgb(0, 80, 150),
+            in_progress: Color::Rgb(120, 50, 110),
+            second_look: Color::Rgb(170, 60, 0),
+
+            comment_issue: Color::Rgb(170, 60, 0),
+            comment_praise: Color::Rgb(120, 50, 110),
+
+            ..Self::light()
+        }
+    }
+}
+
 /// Theme selection from CLI argument
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ThemeArg {
     #[default]
     Dark,
     Light,
+    DarkColorblind,
+    LightColorblind,
 }
 
 /// CLI arguments parsed from command line
@@ -197,6 +272,37 @@ pub struct CliArgs {
     pub output_to_stdout: bool,
     /// Skip checking for updates on startup
     pub no_update_check: bool,
+    /// Read a unified diff from stdin instead of a repository (`tuicr -`)
+    pub read_stdin: bool,
+    /// Detect copied files in git diffs
+    pub find_copies: bool,
+    /// Open the worktree picker on startup
+    pub pick_worktree: bool,
+    /// Run as if started in this directory (`-C` / `--repo`)
+    pub repo_path: Option<std::path::PathBuf>,
+    /// Only review files matching these paths (`tuicr -- src/ crates/foo`)
+    pub pathspecs: Vec<String>,
+    /// Review this GitLab merge request (`--mr <IID>`)
+    pub merge_request: Option<u64>,
+    /// Static analysis report to attach to changed lines (`--import-findings`)
+    pub import_findings: Option<std::path::PathBuf>,
+    /// Saved session to reopen on its diff snapshot (`--snapshot`)
+    pub snapshot: Option<std::path::PathBuf>,
+    /// Start the UI even when CI names a pull/merge request target (`--no-ci`)
+    pub no_ci: bool,
+}
+
+/// Subcommands that print and exit without starting the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subcommand {
+    /// `tuicr status [--days N] [--prompt]`
+    Status { days: i64, prompt: bool },
+    /// `tuicr gc [--days N] [--dry-run]`
+    Gc {
+        days: i64,
+        dry_run: bool,
+        delete_missing: bool,
+    },
 }
 
 impl ThemeArg {
@@ -204,6 +310,11 @@ impl ThemeArg {
         match s.to_lowercase().as_str() {
             "dark" => Some(Self::Dark),
             "light" => Some(Self::Light),
+            // Both forms of red-green color blindness are served by one palette
+            "dark-colorblind" | "colorblind" | "deuteranopia" | "protanopia" => {
+                Some(Self::DarkColorblind)
+            }
+            "light-colorblind" => Some(Self::LightColorblind),
             _ => None,
         }
     }
@@ -214,6 +325,8 @@ pub fn resolve_theme(arg: ThemeArg) -> Theme {
     match arg {
         ThemeArg::Dark => Theme::dark(),
         ThemeArg::Light => Theme::light(),
+        ThemeArg::DarkColorblind => Theme::dark_colorblind(),
+        ThemeArg::LightColorblind => Theme::light_colorblind(),
     }
 }
 
@@ -239,13 +352,42 @@ fn print_help() -> ! {
     println!(
         "tuicr - Review AI-generated diffs like a GitHub pull request
 
-Usage: {name} [OPTIONS]
+Usage: {name} [OPTIONS] [-- <PATH>...]
+       git diff | {name} [OPTIONS] -
+       {name} status [--days <N>] [--prompt]
+       {name} gc [--days <N>] [--dry-run] [--delete-missing]
+
+Commands:
+  status             List unfinished reviews untouched for N days [default: 2];
+                     --prompt prints only a short count for shell prompts
+  gc                 Archive finished sessions older than N days [default: 3]
+                     and sessions of repositories that cannot be found,
+                     and show the data directory's disk usage;
+                     --delete-missing deletes the latter instead,
+                     --dry-run only reports
 
 Options:
   --theme <THEME>    Color theme to use [default: dark]
-                     Valid values: dark, light
+                     Valid values: dark, light, dark-colorblind,
+                     light-colorblind (deuteranopia, protanopia)
   --stdout           Output to stdout instead of clipboard when exporting
   --no-update-check  Skip checking for updates on startup
+  --find-copies      Detect copied files (git only)
+  --worktree         Pick which worktree to review on startup (git only)
+  --mr <IID>         Review a GitLab merge request of the origin remote (git only)
+  -C, --repo <PATH>  Review the repository at PATH instead of the current directory
+  --import-findings <FILE>
+                     Attach ESLint JSON, cargo clippy JSON or clang-tidy
+                     findings on changed lines as comments
+  --snapshot <SESSION>
+                     Reopen a saved session on the diff snapshot stored
+                     in it (see snapshot_diff), without saving changes
+  --no-ci            Start the UI in CI too; otherwise a pull/merge request
+                     build (GITHUB_BASE_REF, CI_MERGE_REQUEST_TARGET_BRANCH_NAME,
+                     ...) reviews against its target branch and prints the
+                     export without the UI
+  -                  Read a unified diff from stdin instead of a repository
+  -- <PATH>...       Only review files under these paths or matching these globs
   -h, --help         Print this help message
 
 Press ? in the application for keybinding help."
@@ -263,6 +405,12 @@ pub fn parse_cli_args() -> CliArgs {
     let mut cli_args = CliArgs::default();
 
     for i in 0..args.len() {
+        // Everything after -- is a pathspec, like git diff
+        if i > 0 && args[i] == "--" {
+            cli_args.pathspecs = args[i + 1..].to_vec();
+            break;
+        }
+
         // Handle --help / -h
         if args[i] == "--help" || args[i] == "-h" {
             print_help();
@@ -278,24 +426,100 @@ pub fn parse_cli_args() -> CliArgs {
             cli_args.no_update_check = true;
         }
 
+        // Handle --find-copies
+        if args[i] == "--find-copies" {
+            cli_args.find_copies = true;
+        }
+
+        // Handle --no-ci
+        if args[i] == "--no-ci" {
+            cli_args.no_ci = true;
+        }
+
+        // Handle -C / --repo path
+        if args[i] == "-C" || args[i] == "--repo" {
+            if let Some(value) = args.get(i + 1) {
+                cli_args.repo_path = Some(std::path::PathBuf::from(value));
+            } else {
+                eprintln!("Warning: {} requires a path", args[i]);
+            }
+        }
+        // Handle --repo=path
+        if let Some(value) = args[i].strip_prefix("--repo=") {
+            cli_args.repo_path = Some(std::path::PathBuf::from(value));
+        }
+
+        // Handle --worktree
+        if args[i] == "--worktree" {
+            cli_args.pick_worktree = true;
+        }
+
+        // Handle --mr IID / --mr=IID
+        let mr_value = if args[i] == "--mr" {
+            let value = args.get(i + 1).map(String::as_str);
+            if value.is_none() {
+                eprintln!("Warning: --mr requires a merge request number");
+            }
+            value
+        } else {
+            args[i].strip_prefix("--mr=")
+        };
+        if let Some(value) = mr_value {
+            match value.trim_start_matches('!').parse() {
+                Ok(iid) => cli_args.merge_request = Some(iid),
+                Err(_) => eprintln!("Warning: Invalid merge request number '{value}'"),
+            }
+        }
+
+        // Handle --import-findings file / --import-findings=file
+        if args[i] == "--import-findings" {
+            if let Some(value) = args.get(i + 1) {
+                cli_args.import_findings = Some(std::path::PathBuf::from(value));
+            } else {
+                eprintln!("Warning: --import-findings requires a report file");
+            }
+        }
+        if let Some(value) = args[i].strip_prefix("--import-findings=") {
+            cli_args.import_findings = Some(std::path::PathBuf::from(value));
+        }
+
+        // Handle --snapshot session / --snapshot=session
+        if args[i] == "--snapshot" {
+            if let Some(value) = args.get(i + 1) {
+                cli_args.snapshot = Some(std::path::PathBuf::from(value));
+            } else {
+                eprintln!("Warning: --snapshot requires a session file");
+            }
+        }
+        if let Some(value) = args[i].strip_prefix("--snapshot=") {
+            cli_args.snapshot = Some(std::path::PathBuf::from(value));
+        }
+
+        // Handle - (read diff from stdin)
+        if i > 0 && args[i] == "-" {
+            cli_args.read_stdin = true;
+        }
+
         // Handle --theme value
         if args[i] == "--theme" {
             if let Some(value) = args.get(i + 1) {
                 cli_args.theme = ThemeArg::from_str(value).unwrap_or_else(|| {
                     eprintln!(
-                        "Warning: Unknown theme '{value}', using dark. Valid options: dark, light"
+                        "Warning: Unknown theme '{value}', using dark. Valid options: dark, light, dark-colorblind, light-colorblind"
                     );
                     ThemeArg::Dark
                 });
             } else {
-                eprintln!("Warning: --theme requires a value (dark, light)");
+                eprintln!(
+                    "Warning: --theme requires a value (dark, light, dark-colorblind, light-colorblind)"
+                );
             }
         }
         // Handle --theme=value
         if let Some(value) = args[i].strip_prefix("--theme=") {
             cli_args.theme = ThemeArg::from_str(value).unwrap_or_else(|| {
                 eprintln!(
-                    "Warning: Unknown theme '{value}', using dark. Valid options: dark, light"
+                    "Warning: Unknown theme '{value}', using dark. Valid options: dark, light, dark-colorblind, light-colorblind"
                 );
                 ThemeArg::Dark
             });
@@ -304,3 +528,77 @@ pub fn parse_cli_args() -> CliArgs {
 
     cli_args
 }
+
+/// The subcommand named by the first argument, if any
+pub fn parse_subcommand() -> Option<Subcommand> {
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("status") => Some(parse_status_args(&args[2..])),
+        Some("gc") => Some(parse_gc_args(&args[2..])),
+        _ => None,
+    }
+}
+
+fn parse_status_args(args: &[String]) -> Subcommand {
+    let mut days = crate::status::DEFAULT_STALE_DAYS;
+    let mut prompt = false;
+
+    for i in 0..args.len() {
+        if args[i] == "--help" || args[i] == "-h" {
+            print_help();
+        }
+        if args[i] == "--prompt" {
+            prompt = true;
+        }
+
+        let value = if args[i] == "--days" {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            args[i].strip_prefix("--days=")
+        };
+        if let Some(value) = value {
+            match value.parse() {
+                Ok(n) => days = n,
+                Err(_) => eprintln!("Warning: Invalid day count '{value}', using {days}"),
+            }
+        }
+    }
+
+    Subcommand::Status { days, prompt }
+}
+
+fn parse_gc_args(args: &[String]) -> Subcommand {
+    let mut days = crate::gc::DEFAULT_ARCHIVE_DAYS;
+    let mut dry_run = false;
+    let mut delete_missing = false;
+
+    for i in 0..args.len() {
+        if args[i] == "--help" || args[i] == "-h" {
+            print_help();
+        }
+        if args[i] == "--dry-run" {
+            dry_run = true;
+        }
+        if args[i] == "--delete-missing" {
+            delete_missing = true;
+        }
+
+        let value = if args[i] == "--days" {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            args[i].strip_prefix("--days=")
+        };
+        if let Some(value) = value {
+            match value.parse() {
+                Ok(n) => days = n,
+                Err(_) => eprintln!("Warning: Invalid day count '{value}', using {days}"),
+            }
+        }
+    }
+
+    Subcommand::Gc {
+        days,
+        dry_run,
+        delete_missing,
+    }
+}
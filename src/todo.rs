@@ -0,0 +1,149 @@
+//! TODO markers introduced by the diff.
+//!
+//! Added lines are scanned for `TODO`, `FIXME` and `HACK` written in capitals
+//! as whole words, so `todo!()` or `hackathon` do not count. Markers on
+//! removed lines are debt being paid off and are left out.
+
+use std::path::PathBuf;
+
+use crate::model::{DiffFile, LineOrigin};
+
+const MARKERS: &[&str] = &["TODO", "FIXME", "HACK"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoMarker {
+    pub marker: &'static str,
+    /// Line text from the marker on, e.g. `TODO(ana): retry on timeout`
+    pub text: String,
+    pub path: PathBuf,
+    /// Line number in the new file
+    pub line: u32,
+    /// Location of the added line in the diff
+    pub file_idx: usize,
+    pub hunk_idx: usize,
+    pub line_idx: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The first marker on the line and its byte offset
+fn find_marker(content: &str) -> Option<(&'static str, usize)> {
+    MARKERS
+        .iter()
+        .filter_map(|marker| {
+            content
+                .match_indices(marker)
+                .find(|(pos, _)| {
+                    let before = content[..*pos].chars().next_back();
+                    let after = content[pos + marker.len()..].chars().next();
+                    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+                })
+                .map(|(pos, _)| (*marker, pos))
+        })
+        .min_by_key(|(_, pos)| *pos)
+}
+
+/// Markers on added lines, in diff order
+pub fn find_todos(files: &[DiffFile]) -> Vec<TodoMarker> {
+    let mut todos = Vec::new();
+    for (file_idx, file) in files.iter().enumerate() {
+        if file.is_commit_message() {
+            continue;
+        }
+        for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+            for (line_idx, line) in hunk.lines.iter().enumerate() {
+                if line.origin != LineOrigin::Addition {
+                    continue;
+                }
+                let (Some((marker, pos)), Some(lineno)) =
+                    (find_marker(&line.content), line.new_lineno)
+                else {
+                    continue;
+                };
+                todos.push(TodoMarker {
+                    marker,
+                    text: line.content[pos..].trim_end().to_string(),
+                    path: file.display_path().clone(),
+                    line: lineno,
+                    file_idx,
+                    hunk_idx,
+                    line_idx,
+                });
+            }
+        }
+    }
+    todos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{DiffHunk, DiffLine, FileStatus};
+
+    fn file(path: &str, lines: &[(LineOrigin, &str)]) -> DiffFile {
+        DiffFile {
+            old_path: Some(PathBuf::from(path)),
+            new_path: Some(PathBuf::from(path)),
+            status: FileStatus::Modified,
+            hunks: vec![DiffHunk {
+                header: "@@ -1,1 +1,1 @@".to_string(),
+                lines: lines
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (origin, content))| DiffLine {
+                        origin: *origin,
+                        content: content.to_string(),
+                        old_lineno: None,
+                        new_lineno: (*origin != LineOrigin::Deletion).then_some(i as u32 + 1),
+                        highlighted_spans: None,
+                    })
+                    .collect(),
+                old_start: 1,
+                old_count: 1,
+                new_start: 1,
+                new_count: 1,
+            }],
+            is_binary: false,
+            special: None,
+            similarity: None,
+            binary_detail: None,
+            mode_change: None,
+        }
+    }
+
+    #[test]
+    fn should_find_markers_on_added_lines() {
+        // given
+        let files = vec![file(
+            "src/net.rs",
+            &[
+                (LineOrigin::Deletion, "// TODO: old debt"),
+                (LineOrigin::Addition, "    // TODO(ana): retry on timeout"),
+                (LineOrigin::Context, "    connect();"),
+                (LineOrigin::Addition, "    let x = 1; // FIXME overflow"),
+            ],
+        )];
+
+        // when
+        let todos = find_todos(&files);
+
+        // then
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].marker, "TODO");
+        assert_eq!(todos[0].text, "TODO(ana): retry on timeout");
+        assert_eq!(todos[0].line, 2);
+        assert_eq!(todos[1].marker, "FIXME");
+        assert_eq!(todos[1].line_idx, 3);
+    }
+
+    #[test]
+    fn should_only_match_whole_uppercase_words() {
+        assert_eq!(find_marker("todo!()"), None);
+        assert_eq!(find_marker("let TODOS = 3;"), None);
+        assert_eq!(find_marker("hackathon HACKATHON"), None);
+        assert_eq!(find_marker("# HACK: pin version"), Some(("HACK", 2)));
+        assert_eq!(find_marker("FIXME and TODO"), Some(("FIXME", 0)));
+    }
+}
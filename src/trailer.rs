@@ -0,0 +1,139 @@
+//! Commit message trailers summing up a review (`:trailer`), for pasting
+//! into the merge commit.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+/// What the trailer block is built from
+#[derive(Debug)]
+pub struct TrailerInfo {
+    /// `Name <email>` of the reviewer
+    pub reviewer: Option<String>,
+    pub reviewed_at: DateTime<Utc>,
+    /// Short ids of the reviewed commits, empty for the working tree
+    pub commits: Vec<String>,
+    /// Issue references such as `#123` or `PROJ-42`
+    pub issue_refs: Vec<String>,
+    /// Comments not marked resolved
+    pub unresolved: usize,
+}
+
+pub fn format_trailer(info: &TrailerInfo) -> String {
+    let mut lines = Vec::new();
+    if let Some(reviewer) = &info.reviewer {
+        lines.push(format!("Reviewed-by: {reviewer}"));
+    }
+    let date = info.reviewed_at.format("%Y-%m-%d");
+    lines.push(match (info.commits.first(), info.commits.last()) {
+        (Some(first), Some(last)) if first != last => {
+            format!("Reviewed-on: {date} ({first}..{last})")
+        }
+        (Some(commit), _) => format!("Reviewed-on: {date} ({commit})"),
+        _ => format!("Reviewed-on: {date}"),
+    });
+    if !info.issue_refs.is_empty() {
+        lines.push(format!("Refs: {}", info.issue_refs.join(", ")));
+    }
+    lines.push(format!("Unresolved-comments: {}", info.unresolved));
+    lines.join("\n") + "\n"
+}
+
+/// `#123` and `PROJ-42` style references in `texts`, in order of appearance
+pub fn issue_refs<'a>(texts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut refs: Vec<String> = Vec::new();
+    for text in texts {
+        for word in text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '#' || c == '-')) {
+            // `PROJ-42` may be part of a longer word like a branch name
+            let parts: Vec<&str> = word.split('-').collect();
+            let candidates = parts
+                .iter()
+                .map(|part| part.to_string())
+                .chain(parts.windows(2).map(|pair| pair.join("-")));
+            for candidate in candidates {
+                if is_issue_ref(&candidate) && !refs.contains(&candidate) {
+                    refs.push(candidate);
+                }
+            }
+        }
+    }
+    refs
+}
+
+fn is_issue_ref(word: &str) -> bool {
+    if let Some(number) = word.strip_prefix('#') {
+        return !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+    }
+    let Some((project, number)) = word.split_once('-') else {
+        return false;
+    };
+    project.len() >= 2
+        && project.chars().all(|c| c.is_ascii_uppercase())
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// `user.name <user.email>` from the repository's git config
+pub fn reviewer(root: &Path) -> Option<String> {
+    let config = git_config(root)?;
+    let name = config.get_string("user.name").ok()?;
+    Some(match config.get_string("user.email") {
+        Ok(email) => format!("{name} <{email}>"),
+        Err(_) => name,
+    })
+}
+
+/// `user.name` from the repository's git config, the default author of comments
+pub fn user_name(root: &Path) -> Option<String> {
+    git_config(root)?.get_string("user.name").ok()
+}
+
+fn git_config(root: &Path) -> Option<git2::Config> {
+    git2::Repository::open(root)
+        .and_then(|repo| repo.config())
+        .or_else(|_| git2::Config::open_default())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_find_issue_refs_once_in_order() {
+        // when
+        let refs = issue_refs([
+            "feature/PROJ-42-retry",
+            "Fix retry backoff (#17)\n\nCloses #17, see PROJ-42 and #3",
+        ]);
+
+        // then
+        assert_eq!(refs, vec!["PROJ-42", "#17", "#3"]);
+    }
+
+    #[test]
+    fn should_ignore_words_that_only_look_like_refs() {
+        assert!(issue_refs(["utf-8 #tag x-1 A-1 PROJ-"]).is_empty());
+    }
+
+    #[test]
+    fn should_format_trailer_block() {
+        // given
+        let info = TrailerInfo {
+            reviewer: Some("Dana Reyes <dana@example.com>".to_string()),
+            reviewed_at: "2026-03-02T10:00:00Z".parse().unwrap(),
+            commits: vec!["3f9c2a1".to_string(), "8b1d4e0".to_string()],
+            issue_refs: vec!["#17".to_string()],
+            unresolved: 2,
+        };
+
+        // when
+        let trailer = format_trailer(&info);
+
+        // then
+        assert_eq!(
+            trailer,
+            "Reviewed-by: Dana Reyes <dana@example.com>\nReviewed-on: 2026-03-02 (3f9c2a1..8b1d4e0)\nRefs: #17\nUnresolved-comments: 2\n"
+        );
+    }
+}
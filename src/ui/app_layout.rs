@@ -1,21 +1,44 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::Style,
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        Wrap,
+    },
 };
-use unicode_width::UnicodeWidthStr;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::app::{App, DiffViewMode, FileTreeItem, FocusedPanel, GapId, InputMode};
-use crate::model::{LineOrigin, LineRange, LineSide};
+use crate::app::{
+    AnchorPreviewLine, App, ConfirmAction, DiffViewMode, FileTreeItem, FocusedPanel,
+    GENERATED_PLACEHOLDER, GapId, InputMode,
+};
+use crate::config::FileListWidth;
+use crate::hyperlink::Hyperlink;
+use crate::model::{
+    ConflictLine, ConflictSide, DiffFile, FileStatus, LineOrigin, LineRange, LineSide, ReviewState,
+};
 use crate::theme::Theme;
-use crate::ui::{comment_panel, help_popup, status_bar, styles};
+use crate::ui::{
+    attachment_popup, comment_list_popup, comment_panel, comments_pane, export_preview_popup,
+    function_popup, guide, help_popup, impact_popup, migration_popup, public_api_popup,
+    stale_popup, status_bar, structural_popup, styles, test_map_popup, todo_popup, worktree_popup,
+};
 use crate::vcs::git::calculate_gap;
 
 pub fn render(frame: &mut Frame, app: &mut App) {
+    app.hyperlinks.clear();
+
     // Special handling for commit selection mode
-    if app.input_mode == InputMode::CommitSelect {
+    if matches!(
+        app.input_mode,
+        InputMode::CommitSelect | InputMode::CommitFilter
+    ) {
         render_commit_select(frame, app);
         return;
     }
@@ -36,16 +59,118 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     render_main_content(frame, app, chunks[1]);
     status_bar::render_status_bar(frame, app, chunks[2]);
 
+    // Links are written over the finished frame, so they would cover a popup
+    if !matches!(
+        app.input_mode,
+        InputMode::Normal
+            | InputMode::Comment
+            | InputMode::Command
+            | InputMode::Search
+            | InputMode::VisualSelect
+    ) {
+        app.hyperlinks.clear();
+    }
+
     // Render help popup on top if in help mode
     if app.input_mode == InputMode::Help {
         help_popup::render_help(frame, app);
     }
 
+    if app.input_mode == InputMode::FunctionDiff {
+        function_popup::render_function_diff(frame, app);
+    }
+
+    if app.input_mode == InputMode::StructuralDiff {
+        structural_popup::render_structural_diff(frame, app);
+    }
+
+    if app.input_mode == InputMode::Migration {
+        migration_popup::render_migration(frame, app);
+    }
+
+    if app.input_mode == InputMode::PublicApi {
+        public_api_popup::render_public_api(frame, app);
+    }
+
+    if app.input_mode == InputMode::Impact {
+        impact_popup::render_impact(frame, app);
+    }
+
+    if app.input_mode == InputMode::TestMap {
+        test_map_popup::render_test_map(frame, app);
+    }
+
+    if app.input_mode == InputMode::Todos {
+        todo_popup::render_todos(frame, app);
+    }
+
+    if app.input_mode == InputMode::CommentList {
+        comment_list_popup::render_comment_list(frame, app);
+    }
+
+    if app.input_mode == InputMode::StaleComments {
+        stale_popup::render_stale_comments(frame, app);
+    }
+
+    if app.input_mode == InputMode::ExportPreview {
+        export_preview_popup::render_export_preview(frame, app);
+    }
+
+    if app.input_mode == InputMode::Attachments {
+        attachment_popup::render_attachments(frame, app);
+    }
+
+    if app.input_mode == InputMode::WorktreeSelect {
+        worktree_popup::render_worktree_select(frame, app);
+    }
+
     // Comment input is now rendered inline in the diff view
 
     // Render confirm dialog if in confirm mode
     if app.input_mode == InputMode::Confirm {
-        comment_panel::render_confirm_dialog(frame, app, "Copy review to clipboard?");
+        let message = if let Some(ConfirmAction::ApplySuggestion(id)) = &app.pending_confirm {
+            let target = app
+                .session
+                .files
+                .iter()
+                .find(|(_, review)| {
+                    review
+                        .line_comments
+                        .values()
+                        .flatten()
+                        .any(|comment| &comment.id == id)
+                })
+                .map(|(path, _)| path.display().to_string())
+                .unwrap_or_default();
+            format!("Apply the suggested change to {target}?")
+        } else if let Some(ConfirmAction::SeedFromSession { summary, .. }) = &app.pending_confirm {
+            format!(
+                "Continue from the earlier review of {} ({summary})?",
+                app.vcs_info.branch_name.as_deref().unwrap_or("this branch")
+            )
+        } else if let Some(ConfirmAction::RecoverDamagedSession { path, summary }) =
+            &app.pending_confirm
+        {
+            format!(
+                "The saved review {} is damaged ({summary}). Recover them?",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            )
+        } else if let Some(ConfirmAction::ClearFileComments(path)) = &app.pending_confirm {
+            let count = app
+                .session
+                .files
+                .get(path)
+                .map_or(0, |review| review.comment_count());
+            format!("Delete all {count} comment(s) on {}?", path.display())
+        } else if app.is_review_complete() {
+            "Copy review to clipboard?".to_string()
+        } else {
+            format!(
+                "Review incomplete ({}). Copy review to clipboard?",
+                app.progress_summary()
+            )
+        };
+        comment_panel::render_confirm_dialog(frame, app, &message);
     }
 
     // Position terminal cursor for IME when in Comment mode
@@ -66,6 +191,9 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     }
 }
 
+/// Narrower terminals give the whole width to the commit list
+const COMMIT_PREVIEW_MIN_WIDTH: u16 = 100;
+
 fn render_commit_select(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
 
@@ -84,14 +212,22 @@ fn render_commit_select(frame: &mut Frame, app: &mut App) {
         .block(Block::default());
     frame.render_widget(header, chunks[0]);
 
-    // Commit list
+    // Commit list, with a preview of the highlighted commit when there is room
+    let (list_area, preview_area) = if chunks[1].width >= COMMIT_PREVIEW_MIN_WIDTH {
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(chunks[1]);
+        (list_area, Some(preview_area))
+    } else {
+        (chunks[1], None)
+    };
     let block = Block::default()
         .title(" Recent Commits ")
         .borders(Borders::ALL)
         .border_style(styles::border_style(&app.theme, true));
 
-    let inner = block.inner(chunks[1]);
-    frame.render_widget(block, chunks[1]);
+    let inner = block.inner(list_area);
+    frame.render_widget(block, list_area);
 
     // Update viewport height for scroll calculations
     app.commit_list_viewport_height = inner.height as usize;
@@ -100,17 +236,17 @@ fn render_commit_select(frame: &mut Frame, app: &mut App) {
     let range = app.commit_selection_range;
 
     // Determine commits to show
-    let total_commits = app.commit_list.len();
-    let visible_count = app.visible_commit_count.min(total_commits);
+    let rows = app.commit_rows();
+    let visible_count = rows.len();
 
-    let mut items: Vec<Line> = app
-        .commit_list
+    // Each row with the column its SHA starts at
+    let (mut items, sha_columns): (Vec<Line>, Vec<u16>) = rows
         .iter()
-        .take(visible_count)
         .enumerate()
-        .map(|(i, commit)| {
+        .map(|(row, &i)| {
+            let commit = &app.commit_list[i];
             let is_selected = app.is_commit_selected(i);
-            let is_cursor = i == app.commit_list_cursor;
+            let is_cursor = row == app.commit_list_cursor;
 
             // Range boundary indicators
             let range_marker = match range {
@@ -144,12 +280,15 @@ fn render_commit_select(frame: &mut Frame, app: &mut App) {
                 Style::default().fg(app.theme.fg_secondary)
             };
 
-            // Format: > ┌ [x] abc1234  Commit message (author, date)
+            // Format: > ┌ [x] abc1234  Commit message (author, date) +N -M, K files
             let time_str = commit.time.format("%Y-%m-%d").to_string();
-            Line::from(vec![
+            let mut spans = vec![
                 Span::styled(format!("{pointer} "), style),
                 Span::styled(format!("{range_marker} "), range_style),
                 Span::styled(format!("{checkbox} "), checkbox_style),
+            ];
+            let sha_column = spans.iter().map(Span::width).sum::<usize>() as u16;
+            spans.extend([
                 Span::styled(
                     format!("{} ", commit.short_id),
                     styles::hash_style(&app.theme),
@@ -159,9 +298,27 @@ fn render_commit_select(frame: &mut Frame, app: &mut App) {
                     format!(" ({}, {})", commit.author, time_str),
                     Style::default().fg(app.theme.fg_secondary),
                 ),
-            ])
+            ]);
+            // Stats arrive from a background worker; backends without line
+            // counts report no files and show nothing
+            if let Some(files) = app.commit_stats.get(&commit.id)
+                && !files.is_empty()
+            {
+                let additions: usize = files.iter().map(|file| file.additions).sum();
+                let deletions: usize = files.iter().map(|file| file.deletions).sum();
+                let noun = if files.len() == 1 { "file" } else { "files" };
+                spans.extend([
+                    Span::styled(format!(" +{additions}"), styles::diff_add_style(&app.theme)),
+                    Span::styled(format!(" -{deletions}"), styles::diff_del_style(&app.theme)),
+                    Span::styled(
+                        format!(", {} {noun}", files.len()),
+                        Style::default().fg(app.theme.fg_secondary),
+                    ),
+                ]);
+            }
+            (Line::from(spans), sha_column)
         })
-        .collect();
+        .unzip();
 
     // Show an expand row when commits are collapsed
     if app.can_show_more_commits() {
@@ -189,9 +346,63 @@ fn render_commit_select(frame: &mut Frame, app: &mut App) {
     let list = Paragraph::new(visible_items);
     frame.render_widget(list, inner);
 
+    // SHAs link to the commit on the forge
+    if let Some(targets) = &app.link_targets {
+        for (row, (&i, &column)) in rows
+            .iter()
+            .zip(&sha_columns)
+            .skip(app.commit_list_scroll_offset)
+            .take(inner.height as usize)
+            .enumerate()
+        {
+            let commit = &app.commit_list[i];
+            if let Some(url) = targets.commit(&commit.id) {
+                let sha = Rect::new(
+                    inner.x + column,
+                    inner.y + row as u16,
+                    commit.short_id.width() as u16,
+                    1,
+                );
+                app.hyperlinks.push(Hyperlink {
+                    area: sha.intersection(inner),
+                    url,
+                });
+            }
+        }
+    }
+
+    if let Some(area) = preview_area {
+        render_commit_preview(frame, app, area);
+    }
+
     // Footer with mode, hints, and right-aligned message
     let theme = &app.theme;
-    let mode_span = Span::styled(" SELECT ", styles::mode_style(theme));
+    if app.input_mode == InputMode::CommitFilter {
+        let prompt = Line::from(vec![
+            Span::styled(
+                format!("/{}", app.commit_filter),
+                Style::default().fg(theme.fg_primary),
+            ),
+            Span::styled(
+                format!("  ({visible_count} matching)"),
+                Style::default().fg(theme.fg_secondary),
+            ),
+        ]);
+        let footer = Paragraph::new(prompt).style(styles::status_bar_style(theme));
+        frame.render_widget(footer, chunks[2]);
+        let width = u16::try_from(1 + app.commit_filter.width()).unwrap_or(u16::MAX);
+        frame.set_cursor_position(ratatui::layout::Position {
+            x: chunks[2].x + width.min(chunks[2].width.saturating_sub(1)),
+            y: chunks[2].y,
+        });
+        return;
+    }
+    let mode_label = if app.commit_anchor.is_some() {
+        " VISUAL "
+    } else {
+        " SELECT "
+    };
+    let mode_span = Span::styled(mode_label, styles::mode_style(theme));
 
     let selected_count = match app.commit_selection_range {
         Some((start, end)) => end - start + 1,
@@ -202,7 +413,14 @@ fn render_commit_select(frame: &mut Frame, app: &mut App) {
     } else {
         String::new()
     };
-    let hints = format!(" j/k:navigate  Space:select range  Enter:confirm  q:quit{selection_info}");
+    let filter_info = if app.commit_filter.trim().is_empty() {
+        String::new()
+    } else {
+        format!(" [/{}]", app.commit_filter.trim())
+    };
+    let hints = format!(
+        " j/k:navigate  Space:select range  v:anchor range  Enter:confirm  /:filter  q:quit{selection_info}{filter_info}"
+    );
     let hints_span = Span::styled(hints, Style::default().fg(theme.fg_secondary));
 
     let left_spans = vec![mode_span, hints_span];
@@ -221,49 +439,162 @@ fn render_commit_select(frame: &mut Frame, app: &mut App) {
     frame.render_widget(footer, chunks[2]);
 }
 
+/// Full message and changed files of the commit under the picker cursor
+fn render_commit_preview(frame: &mut Frame, app: &mut App, area: Rect) {
+    let index = app.load_cursor_commit_preview();
+    let theme = &app.theme;
+    let block = Block::default()
+        .title(" Preview ")
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, false));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(commit) = index.map(|i| &app.commit_list[i]) else {
+        return;
+    };
+    let Some(preview) = app.commit_previews.get(&commit.id) else {
+        return;
+    };
+
+    let secondary = Style::default().fg(theme.fg_secondary);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(commit.short_id.clone(), styles::hash_style(theme)),
+            Span::styled(
+                format!(
+                    "  {}, {}",
+                    commit.author,
+                    commit.time.format("%Y-%m-%d %H:%M")
+                ),
+                secondary,
+            ),
+        ]),
+        Line::from(""),
+    ];
+    lines.extend(
+        preview
+            .message
+            .trim_end()
+            .lines()
+            .map(|line| Line::from(line.to_string())),
+    );
+
+    // Backends without line counts leave the file list empty
+    if !preview.files.is_empty() {
+        let additions: usize = preview.files.iter().map(|file| file.additions).sum();
+        let deletions: usize = preview.files.iter().map(|file| file.deletions).sum();
+        let noun = if preview.files.len() == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} {noun} changed, ", preview.files.len()),
+                secondary,
+            ),
+            Span::styled(format!("+{additions}"), styles::diff_add_style(theme)),
+            Span::styled(" ", secondary),
+            Span::styled(format!("-{deletions}"), styles::diff_del_style(theme)),
+        ]));
+        for file in &preview.files {
+            let added = format!("+{}", file.additions);
+            let deleted = format!("-{}", file.deletions);
+            lines.push(Line::from(vec![
+                Span::styled(format!("{added:>6}"), styles::diff_add_style(theme)),
+                Span::styled(format!("{deleted:>6} "), styles::diff_del_style(theme)),
+                Span::raw(file.path.display().to_string()),
+            ]));
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
 fn truncate_str(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.width() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+        format!("{}...", take_width(s, max_len.saturating_sub(3)))
+    }
+}
+
+/// The longest prefix of `s` that fits in `width` terminal columns
+fn take_width(s: &str, width: usize) -> String {
+    let mut taken = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width > width {
+            break;
+        }
+        taken.push(c);
+        used += char_width;
     }
+    taken
 }
 
 fn render_main_content(frame: &mut Frame, app: &mut App, area: Rect) {
     if app.show_file_list {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(20), // File list
-                Constraint::Percentage(80), // Diff view
-            ])
+            .constraints(match app.config.file_list_width {
+                FileListWidth::Columns(columns) => [
+                    Constraint::Length(columns.min(area.width / 2)), // File list
+                    Constraint::Min(0),                              // Diff view
+                ],
+                FileListWidth::Percent(percent) => [
+                    Constraint::Percentage(percent),       // File list
+                    Constraint::Percentage(100 - percent), // Diff view
+                ],
+            })
             .split(area);
 
-        app.file_list_area = Some(chunks[0]);
         app.diff_area = Some(chunks[1]);
 
-        render_file_list(frame, app, chunks[0]);
+        // The comments pane takes the lower part of the left column
+        if app.has_comments_pane() {
+            let [files, comments] =
+                Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(chunks[0]);
+            app.file_list_area = Some(files);
+            render_file_list(frame, app, files);
+            comments_pane::render_comments_pane(frame, app, comments);
+        } else {
+            if app.focused_panel == FocusedPanel::Comments {
+                app.focused_panel = FocusedPanel::Diff;
+            }
+            app.file_list_area = Some(chunks[0]);
+            render_file_list(frame, app, chunks[0]);
+        }
         render_diff_view(frame, app, chunks[1]);
     } else {
         app.file_list_area = None;
         app.diff_area = Some(area);
+        if app.focused_panel == FocusedPanel::Comments {
+            app.focused_panel = FocusedPanel::Diff;
+        }
 
         render_diff_view(frame, app, area);
     }
 }
 
 fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
-    use ratatui::style::Modifier;
     use std::path::Path;
 
     let focused = app.focused_panel == FocusedPanel::FileList;
 
-    let block = Block::default()
-        .title(" Files ")
-        .borders(Borders::ALL)
-        .border_style(styles::border_style(&app.theme, focused));
-
-    let inner = block.inner(area);
+    let plan_label = if app.plan_mode {
+        " (plan: J/K to reorder)"
+    } else if app.has_review_plan() {
+        " (plan)"
+    } else {
+        ""
+    };
+    // The bordered block's inner area; its title depends on the scroll state
+    let inner = area.inner(Margin::new(1, 1));
     let visible_items = app.build_visible_items();
 
     let max_content_width = visible_items
@@ -278,13 +609,10 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
             }
             FileTreeItem::File { file_idx, depth } => {
                 let file = &app.diff_files[*file_idx];
-                let filename = file
-                    .display_path()
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("?");
+                let filename = file_list_name(app, file);
                 depth * 2 + 3 + 3 + filename.width()
             }
+            FileTreeItem::VendoredGroup { count, .. } => 2 + vendored_group_label(*count).width(),
         })
         .max()
         .unwrap_or(0);
@@ -311,8 +639,12 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
                     // Save current offset before changing selection
                     let current_offset = app.file_list_state.list_state.offset();
                     app.file_list_state.select(tree_idx);
-                    // Restore offset to prevent auto-scrolling
-                    *app.file_list_state.list_state.offset_mut() = current_offset;
+                    // Restore offset to prevent auto-scrolling, unless that
+                    // would leave the selection off screen
+                    let shown = current_offset..current_offset + inner.height as usize;
+                    if shown.contains(&tree_idx) {
+                        *app.file_list_state.list_state.offset_mut() = current_offset;
+                    }
                 }
                 break;
             }
@@ -321,6 +653,18 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
 
     let selected_idx = app.file_list_state.selected();
 
+    // A long list shows where the selection is, as the top rows scroll away
+    let overflows = visible_items.len() > inner.height as usize;
+    let position = if overflows {
+        format!(" {}/{}", selected_idx + 1, visible_items.len())
+    } else {
+        String::new()
+    };
+    let block = Block::default()
+        .title(format!(" Files{plan_label}{position} "))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(&app.theme, focused));
+
     let items: Vec<ListItem> = visible_items
         .iter()
         .enumerate()
@@ -357,34 +701,69 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
                 FileTreeItem::File { file_idx, depth } => {
                     let file = &app.diff_files[*file_idx];
                     let path = file.display_path();
-                    let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("?");
-                    let status = file.status.as_char();
-                    let is_reviewed = app.session.is_file_reviewed(path);
-                    let review_mark = if is_reviewed { "✓" } else { " " };
+                    let filename = file_list_name(app, file);
+                    let status = file.status_char();
+                    let state = app.session.file_state(path);
+                    let excluded = app.session.is_file_excluded(path);
 
                     let indent = "  ".repeat(*depth);
 
-                    let style = if is_selected {
-                        styles::selected_style(&app.theme).add_modifier(Modifier::UNDERLINED)
-                    } else {
-                        Style::default()
+                    let style = match (is_selected, excluded) {
+                        (true, _) => {
+                            styles::selected_style(&app.theme).add_modifier(Modifier::UNDERLINED)
+                        }
+                        (false, true) => styles::dim_style(&app.theme),
+                        (false, false) => Style::default(),
                     };
 
-                    let line = Line::from(vec![
+                    let mut spans = vec![
                         Span::styled(indent, Style::default()),
-                        Span::styled(
-                            format!("[{review_mark}]"),
-                            if is_reviewed {
-                                styles::reviewed_style(&app.theme)
-                            } else {
-                                styles::pending_style(&app.theme)
-                            },
-                        ),
+                        if excluded {
+                            Span::styled("[-]", styles::dim_style(&app.theme))
+                        } else {
+                            Span::styled(
+                                format!("[{}]", state.mark()),
+                                styles::review_state_style(&app.theme, state),
+                            )
+                        },
                         Span::styled(
                             format!(" {status} "),
                             styles::file_status_style(&app.theme, status),
                         ),
-                        Span::styled(filename.to_string(), style),
+                        Span::styled(filename, style),
+                    ];
+                    if app.migration_paths.contains(path) {
+                        if app.file_has_warnings(*file_idx) {
+                            spans.push(Span::styled(
+                                " [migration ⚠]",
+                                styles::pending_style(&app.theme),
+                            ));
+                        } else {
+                            spans.push(Span::styled(" [migration]", styles::dim_style(&app.theme)));
+                        }
+                    }
+                    if app.untested_paths.contains(path) {
+                        spans.push(Span::styled(
+                            " [no tests]",
+                            styles::pending_style(&app.theme),
+                        ));
+                    }
+                    let line = Line::from(spans);
+
+                    ListItem::new(apply_horizontal_scroll(line, scroll_x))
+                }
+                FileTreeItem::VendoredGroup { count, expanded } => {
+                    let icon = if *expanded { "▾" } else { "▸" };
+
+                    let style = if is_selected {
+                        styles::selected_style(&app.theme).add_modifier(Modifier::UNDERLINED)
+                    } else {
+                        styles::dim_style(&app.theme)
+                    };
+
+                    let line = Line::from(vec![
+                        Span::styled(format!("{icon} "), styles::dir_icon_style(&app.theme)),
+                        Span::styled(vendored_group_label(*count), style),
                     ]);
 
                     ListItem::new(apply_horizontal_scroll(line, scroll_x))
@@ -396,6 +775,97 @@ fn render_file_list(frame: &mut Frame, app: &mut App, area: Rect) {
     let list = List::new(items).block(block);
 
     frame.render_stateful_widget(list, area, &mut app.file_list_state.list_state);
+
+    // Each file's row links to it on disk
+    if let Some(targets) = &app.link_targets {
+        let offset = app.file_list_state.list_state.offset();
+        for (row, item) in visible_items
+            .iter()
+            .skip(offset)
+            .take(inner.height as usize)
+            .enumerate()
+        {
+            if let FileTreeItem::File { file_idx, .. } = item {
+                let file = &app.diff_files[*file_idx];
+                if file.status != FileStatus::Deleted {
+                    app.hyperlinks.push(Hyperlink {
+                        area: Rect::new(inner.x, inner.y + row as u16, inner.width, 1),
+                        url: targets.file(file.display_path()),
+                    });
+                }
+            }
+        }
+    }
+
+    if overflows {
+        let height = inner.height as usize;
+        let mut scrollbar_state = ScrollbarState::new(visible_items.len().saturating_sub(height))
+            .position(app.file_list_state.list_state.offset())
+            .viewport_content_length(height);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin::new(0, 1)),
+            &mut scrollbar_state,
+        );
+    }
+}
+
+/// `(vendored) ` and `(excluded) ` after the file name in its header
+fn file_header_tags(app: &App, path: &PathBuf) -> String {
+    let mut tags = String::new();
+    if app.is_vendored(path) {
+        tags.push_str("(vendored) ");
+    }
+    if app.session.is_file_excluded(path) {
+        tags.push_str("(excluded) ");
+    }
+    tags
+}
+
+/// Review state mark in a file header; nothing for unreviewed files
+fn review_header_mark(state: ReviewState) -> String {
+    match state {
+        ReviewState::Unreviewed => String::new(),
+        state => format!("{} ", state.mark()),
+    }
+}
+
+/// Name shown for a file in the tree. Vendored files sit outside the
+/// directory tree, so they show their full path, as does every file once a
+/// review plan flattens the list. Renames and copies show where the file
+/// came from: `old.rs → new.rs (92%)`, with the full source path if it lives
+/// in another directory.
+fn file_list_name(app: &App, file: &DiffFile) -> String {
+    let path = file.display_path();
+    let name = if app.vendored_paths.contains(path) || app.has_review_plan() {
+        path.display().to_string()
+    } else {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("?")
+            .to_string()
+    };
+
+    match file.source_path() {
+        Some(old) => {
+            let old_name = if old.parent() == path.parent() {
+                old.file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| old.display().to_string())
+            } else {
+                old.display().to_string()
+            };
+            format!("{old_name} → {name}{}", file.similarity_suffix())
+        }
+        None => name,
+    }
+}
+
+fn vendored_group_label(count: usize) -> String {
+    let noun = if count == 1 { "file" } else { "files" };
+    format!("vendored changes ({count} {noun})")
 }
 
 fn render_diff_view(frame: &mut Frame, app: &mut App, area: Rect) {
@@ -432,27 +902,34 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
     for (file_idx, file) in app.diff_files.iter().enumerate() {
         let path = file.display_path();
-        let status = file.status.as_char();
-        let is_reviewed = app.session.is_file_reviewed(path);
+        let status = file.status_char();
+        let state = app.session.file_state(path);
 
         // File header
         let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
 
-        // Add checkmark if reviewed (using same character as file list)
-        let review_mark = if is_reviewed { "✓ " } else { "" };
+        // Add the review state mark (using same character as file list)
+        let review_mark = review_header_mark(state);
+        let vendored_tag = file_header_tags(app, path);
 
         lines.push(Line::from(vec![
             Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
             Span::styled(
-                format!("═══ {}{} [{}] ", review_mark, path.display(), status),
+                format!(
+                    "═══ {}{} [{}] {}",
+                    review_mark,
+                    file.header_label(),
+                    status,
+                    vendored_tag
+                ),
                 styles::file_header_style(&app.theme),
             ),
             Span::styled("═".repeat(40), styles::file_header_style(&app.theme)),
         ]));
         line_idx += 1;
 
-        // If file is reviewed, skip rendering the body (fold it away)
-        if is_reviewed {
+        // If file is reviewed or a collapsed vendored file, skip rendering the body
+        if app.is_file_folded(path) {
             continue;
         }
 
@@ -463,7 +940,11 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
         // Show file-level comments right after the header
         if let Some(review) = app.session.files.get(path) {
-            for comment in &review.file_comments {
+            for comment in review
+                .file_comments
+                .iter()
+                .filter(|comment| app.is_comment_shown(comment))
+            {
                 // Skip rendering this comment if it's being edited
                 let is_being_edited =
                     app.editing_comment_id.as_ref() == Some(&comment.id) && is_file_comment_mode;
@@ -473,11 +954,15 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                     let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
                         &app.theme,
                         app.comment_type,
+                        app.comment_severity,
+                        app.comment_required_action,
                         &app.comment_buffer,
                         app.comment_cursor,
                         None,
-                        true,
+                        app.comment_input_action(),
+                        &app.comment_anchor_preview(),
                         app.supports_keyboard_enhancement,
+                        app.config.comment_limits,
                     );
                     // Track cursor position: logical line = current line_idx + cursor offset within input
                     comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
@@ -497,12 +982,8 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                         line_idx += 1;
                     }
                 } else {
-                    let comment_lines = comment_panel::format_comment_lines(
-                        &app.theme,
-                        comment.comment_type,
-                        &comment.content,
-                        None,
-                    );
+                    let comment_lines =
+                        comment_panel::format_comment_lines(&app.theme, comment, None);
                     for mut comment_line in comment_lines {
                         let indicator = cursor_indicator(line_idx, current_line_idx);
                         comment_line.spans.insert(
@@ -524,11 +1005,15 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
                 &app.theme,
                 app.comment_type,
+                app.comment_severity,
+                app.comment_required_action,
                 &app.comment_buffer,
                 app.comment_cursor,
                 None,
-                false,
+                app.comment_input_action(),
+                &app.comment_anchor_preview(),
                 app.supports_keyboard_enhancement,
+                app.config.comment_limits,
             );
             // Track cursor position
             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
@@ -545,7 +1030,33 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             }
         }
 
-        if file.is_binary {
+        if app.is_generated_collapsed(path) {
+            let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(GENERATED_PLACEHOLDER, styles::dim_style(&app.theme)),
+            ]));
+            line_idx += 1;
+        } else if let Some(special) = &file.special
+            && file.hunks.is_empty()
+        {
+            let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(
+                    special.summary(),
+                    styles::diff_hunk_header_style(&app.theme),
+                ),
+            ]));
+            line_idx += 1;
+        } else if let Some(detail) = &file.binary_detail {
+            let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(detail.summary(), styles::diff_hunk_header_style(&app.theme)),
+            ]));
+            line_idx += 1;
+        } else if file.is_binary {
             let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
@@ -556,7 +1067,14 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
-                Span::styled("(no changes)", styles::dim_style(&app.theme)),
+                Span::styled(
+                    if file.mode_change.is_some() {
+                        "(mode change only)"
+                    } else {
+                        "(no changes)"
+                    },
+                    styles::dim_style(&app.theme),
+                ),
             ]));
             line_idx += 1;
         } else {
@@ -632,22 +1150,61 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
                 // Hunk header
                 let indicator = cursor_indicator_spaced(line_idx, current_line_idx);
-                lines.push(Line::from(vec![
+                let mut header_spans = vec![
                     Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
                     Span::styled(
                         hunk.header.to_string(),
                         styles::diff_hunk_header_style(&app.theme),
                     ),
-                ]));
+                ];
+                push_hunk_warnings(app, file_idx, hunk_idx, &mut header_spans);
+                if app.config.guide {
+                    header_spans.push(Span::styled(
+                        guide::hunk_explanation(hunk, hunk_idx),
+                        styles::dim_style(&app.theme),
+                    ));
+                }
+                lines.push(Line::from(header_spans));
                 line_idx += 1;
 
                 // Diff lines
-                for diff_line in &hunk.lines {
-                    let (prefix, base_style) = match diff_line.origin {
+                let hunk_conflicts = app
+                    .conflict_lines
+                    .get(&file_idx)
+                    .and_then(|tags| tags.get(hunk_idx));
+                for (diff_line_idx, diff_line) in hunk.lines.iter().enumerate() {
+                    let (mut prefix, mut base_style) = match diff_line.origin {
                         LineOrigin::Addition => ("+", styles::diff_add_style(&app.theme)),
                         LineOrigin::Deletion => ("-", styles::diff_del_style(&app.theme)),
                         LineOrigin::Context => (" ", styles::diff_context_style(&app.theme)),
                     };
+                    let is_moved = app.is_moved_line(file_idx, hunk_idx, diff_line_idx);
+                    if is_moved {
+                        base_style = styles::diff_moved_style(&app.theme);
+                    }
+
+                    // Conflicted files: bold markers, and a gutter showing which side a line is on
+                    let conflict =
+                        hunk_conflicts.and_then(|tags| tags.get(diff_line_idx).copied().flatten());
+                    let is_conflict_marker = conflict == Some(ConflictLine::Marker);
+                    match conflict {
+                        Some(ConflictLine::Marker) => {
+                            base_style = styles::conflict_marker_style(&app.theme);
+                        }
+                        Some(ConflictLine::Section(side)) => {
+                            prefix = match side {
+                                ConflictSide::Ours => "<",
+                                ConflictSide::Base => "|",
+                                ConflictSide::Theirs => ">",
+                            };
+                        }
+                        None => {}
+                    }
+                    // A bar beside the sign keeps changed lines apparent without color
+                    let change_bar = match (diff_line.origin, conflict) {
+                        (LineOrigin::Addition | LineOrigin::Deletion, None) => "▌",
+                        _ => " ",
+                    };
 
                     // Check if this line is in visual selection
                     let is_in_visual_selection = {
@@ -700,17 +1257,26 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                     let mut line_spans = vec![
                         Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
                         Span::styled(line_num_str, line_num_style),
-                        Span::styled(format!("{prefix} "), style),
+                        Span::styled(format!("{prefix}{change_bar}"), style),
                     ];
 
                     // Add content spans
-                    if let Some(ref highlighted) = diff_line.highlighted_spans {
+                    if let Some(highlighted) = diff_line
+                        .highlighted_spans
+                        .as_ref()
+                        .filter(|_| !is_conflict_marker)
+                    {
                         // Use syntax-highlighted spans
                         for (span_style, span_text) in highlighted {
+                            let span_style = if is_moved {
+                                span_style.bg(app.theme.diff_moved_bg)
+                            } else {
+                                *span_style
+                            };
                             let final_style = if is_in_visual_selection {
                                 span_style.patch(styles::visual_selection_style(&app.theme))
                             } else {
-                                *span_style
+                                span_style
                             };
                             line_spans.push(Span::styled(span_text.clone(), final_style));
                         }
@@ -718,6 +1284,35 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                         // Fall back to default diff styling
                         line_spans.push(Span::styled(diff_line.content.clone(), style));
                     }
+                    // Underline the part of the line a comment points at
+                    let commented_columns = [
+                        (diff_line.old_lineno, LineSide::Old),
+                        (diff_line.new_lineno, LineSide::New),
+                    ]
+                    .into_iter()
+                    .filter_map(|(lineno, side)| Some((line_comments.get(&lineno?)?, side)))
+                    .flat_map(|(comments, side)| {
+                        comments
+                            .iter()
+                            .filter(move |c| c.side.unwrap_or(LineSide::New) == side)
+                    })
+                    .filter(|comment| app.is_comment_shown(comment))
+                    .filter_map(|comment| comment.columns);
+                    for columns in commented_columns {
+                        let content_spans = line_spans.split_off(3);
+                        line_spans.extend(style_columns(
+                            content_spans,
+                            columns.start.saturating_sub(1) as usize,
+                            columns.end.saturating_sub(1) as usize,
+                            Modifier::UNDERLINED,
+                        ));
+                    }
+                    if line_idx == current_line_idx
+                        && let Some(column) = app.diff_state.cursor_col
+                    {
+                        let content_spans = line_spans.split_off(3);
+                        line_spans.extend(mark_column(content_spans, column));
+                    }
 
                     lines.push(Line::from(line_spans));
                     line_idx += 1;
@@ -733,7 +1328,9 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
                         if let Some(comments) = line_comments.get(&old_ln) {
                             for comment in comments {
-                                if comment.side == Some(LineSide::Old) {
+                                if comment.side == Some(LineSide::Old)
+                                    && app.is_comment_shown(comment)
+                                {
                                     // Skip if this comment is being edited
                                     let is_being_edited = is_line_comment_mode
                                         && app.editing_comment_id.as_ref() == Some(&comment.id);
@@ -747,11 +1344,15 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                             comment_panel::format_comment_input_lines(
                                                 &app.theme,
                                                 app.comment_type,
+                                                app.comment_severity,
+                                                app.comment_required_action,
                                                 &app.comment_buffer,
                                                 app.comment_cursor,
                                                 line_range,
-                                                true,
+                                                app.comment_input_action(),
+                                                &app.comment_anchor_preview(),
                                                 app.supports_keyboard_enhancement,
+                                                app.config.comment_limits,
                                             );
                                         comment_cursor_logical_line =
                                             Some(line_idx + cursor_info.line_offset);
@@ -777,10 +1378,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                             .line_range
                                             .or_else(|| Some(LineRange::single(old_ln)));
                                         let comment_lines = comment_panel::format_comment_lines(
-                                            &app.theme,
-                                            comment.comment_type,
-                                            &comment.content,
-                                            line_range,
+                                            &app.theme, comment, line_range,
                                         );
                                         for mut comment_line in comment_lines {
                                             let is_current = line_idx == current_line_idx;
@@ -812,11 +1410,15 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 comment_panel::format_comment_input_lines(
                                     &app.theme,
                                     app.comment_type,
+                                    app.comment_severity,
+                                    app.comment_required_action,
                                     &app.comment_buffer,
                                     app.comment_cursor,
                                     line_range,
-                                    false,
+                                    app.comment_input_action(),
+                                    &app.comment_anchor_preview(),
                                     app.supports_keyboard_enhancement,
+                                    app.config.comment_limits,
                                 );
                             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
                             comment_cursor_column = 1 + cursor_info.column;
@@ -846,7 +1448,9 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
                         if let Some(comments) = line_comments.get(&new_ln) {
                             for comment in comments {
-                                if comment.side != Some(LineSide::Old) {
+                                if comment.side != Some(LineSide::Old)
+                                    && app.is_comment_shown(comment)
+                                {
                                     // Skip if this comment is being edited
                                     let is_being_edited = is_line_comment_mode
                                         && app.editing_comment_id.as_ref() == Some(&comment.id);
@@ -860,11 +1464,15 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                             comment_panel::format_comment_input_lines(
                                                 &app.theme,
                                                 app.comment_type,
+                                                app.comment_severity,
+                                                app.comment_required_action,
                                                 &app.comment_buffer,
                                                 app.comment_cursor,
                                                 line_range,
-                                                true,
+                                                app.comment_input_action(),
+                                                &app.comment_anchor_preview(),
                                                 app.supports_keyboard_enhancement,
+                                                app.config.comment_limits,
                                             );
                                         comment_cursor_logical_line =
                                             Some(line_idx + cursor_info.line_offset);
@@ -890,10 +1498,7 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                             .line_range
                                             .or_else(|| Some(LineRange::single(new_ln)));
                                         let comment_lines = comment_panel::format_comment_lines(
-                                            &app.theme,
-                                            comment.comment_type,
-                                            &comment.content,
-                                            line_range,
+                                            &app.theme, comment, line_range,
                                         );
                                         for mut comment_line in comment_lines {
                                             let indicator =
@@ -925,11 +1530,15 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                                 comment_panel::format_comment_input_lines(
                                     &app.theme,
                                     app.comment_type,
+                                    app.comment_severity,
+                                    app.comment_required_action,
                                     &app.comment_buffer,
                                     app.comment_cursor,
                                     line_range,
-                                    false,
+                                    app.comment_input_action(),
+                                    &app.comment_anchor_preview(),
                                     app.supports_keyboard_enhancement,
+                                    app.config.comment_limits,
                                 );
                             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
                             comment_cursor_column = 1 + cursor_info.column;
@@ -961,6 +1570,11 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         line_idx += 1;
     }
 
+    if let Some((line, column)) = render_general_section(app, &mut lines, &mut line_idx) {
+        comment_cursor_logical_line = Some(line);
+        comment_cursor_column = column;
+    }
+
     let visible_lines_unscrolled: Vec<Line> = lines
         .into_iter()
         .skip(app.diff_state.scroll_offset)
@@ -1069,11 +1683,12 @@ fn render_unified_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 visual_row = logical_offset as u16;
             }
 
-            // Account for diff area position (inner starts at diff block's inner area)
-            let screen_col = inner.x + comment_cursor_column;
-            let screen_row_abs = inner.y + visual_row;
-
-            app.comment_cursor_screen_pos = Some((screen_col, screen_row_abs));
+            app.comment_cursor_screen_pos = Some(comment_cursor_position(
+                inner,
+                app.diff_state.wrap_lines,
+                visual_row,
+                comment_cursor_column,
+            ));
         }
     }
 }
@@ -1087,11 +1702,97 @@ struct SideBySideContext<'a> {
     comment_input_mode: bool,
     comment_line: Option<(u32, LineSide)>,
     comment_type: crate::model::CommentType,
+    comment_severity: Option<crate::model::Severity>,
+    comment_required_action: Option<crate::model::RequiredAction>,
     comment_buffer: &'a str,
     comment_cursor: usize,
     comment_line_range: Option<LineRange>,
     editing_comment_id: Option<&'a str>,
+    comment_action: &'static str,
+    comment_anchor_preview: &'a [AnchorPreviewLine],
     supports_keyboard_enhancement: bool,
+    comment_limits: crate::config::CommentLimits,
+    hidden_comment_types: &'a HashSet<crate::model::CommentType>,
+}
+
+/// Render the "General" section after the last file: directory comments,
+/// then session comments, plus the inline input while one is being written.
+/// Returns the logical line and column of the input cursor, if any.
+fn render_general_section(
+    app: &App,
+    lines: &mut Vec<Line<'_>>,
+    line_idx: &mut usize,
+) -> Option<(usize, u16)> {
+    if app.diff_files.is_empty() {
+        return None;
+    }
+
+    let theme = &app.theme;
+    let current_line_idx = app.diff_state.cursor_line;
+    let indicator = cursor_indicator_spaced(*line_idx, current_line_idx);
+    lines.push(Line::from(vec![
+        Span::styled(indicator, styles::current_line_indicator_style(theme)),
+        Span::styled("═══ General ", styles::file_header_style(theme)),
+        Span::styled("═".repeat(40), styles::file_header_style(theme)),
+    ]));
+    *line_idx += 1;
+
+    let composing = app.input_mode == InputMode::Comment && app.comment_general_target.is_some();
+    let mut cursor = None;
+    let mut input_box = |line_idx: usize| {
+        let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
+            theme,
+            app.comment_type,
+            app.comment_severity,
+            app.comment_required_action,
+            &app.comment_buffer,
+            app.comment_cursor,
+            None,
+            app.comment_input_action(),
+            &app.comment_anchor_preview(),
+            app.supports_keyboard_enhancement,
+            app.config.comment_limits,
+        );
+        cursor = Some((line_idx + cursor_info.line_offset, 1 + cursor_info.column));
+        input_lines
+    };
+
+    let mut section_lines = Vec::new();
+    for (dir, comment) in app.session.general_comments() {
+        if !app.is_comment_shown(comment) {
+            continue;
+        }
+        if composing && app.editing_comment_id.as_ref() == Some(&comment.id) {
+            section_lines.extend(input_box(*line_idx + section_lines.len()));
+            continue;
+        }
+
+        let mut comment_lines = comment_panel::format_comment_lines(theme, comment, None);
+        if let Some(dir) = dir
+            && let Some(top) = comment_lines.first_mut()
+        {
+            top.spans.insert(
+                2,
+                Span::styled(format!("{}/ ", dir.display()), styles::dim_style(theme)),
+            );
+        }
+        section_lines.extend(comment_lines);
+    }
+    if composing && app.editing_comment_id.is_none() {
+        section_lines.extend(input_box(*line_idx + section_lines.len()));
+    }
+
+    for mut line in section_lines {
+        let indicator = cursor_indicator(*line_idx, current_line_idx);
+        line.spans.insert(
+            0,
+            Span::styled(indicator, styles::current_line_indicator_style(theme)),
+        );
+        lines.push(line);
+        *line_idx += 1;
+    }
+
+    cursor
 }
 
 /// Get cursor indicator (single character for inline content)
@@ -1112,6 +1813,16 @@ fn cursor_indicator_spaced(line_idx: usize, current_line_idx: usize) -> &'static
     }
 }
 
+/// Append indentation and migration warnings for a hunk to its header line
+fn push_hunk_warnings(app: &App, file_idx: usize, hunk_idx: usize, spans: &mut Vec<Span>) {
+    if let Some(warnings) = app.hunk_warnings.get(&(file_idx, hunk_idx)) {
+        spans.push(Span::styled(
+            format!("  ⚠ {}", warnings.join("; ")),
+            styles::pending_style(&app.theme),
+        ));
+    }
+}
+
 fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
     let focused = app.focused_panel == FocusedPanel::Diff;
 
@@ -1134,6 +1845,7 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // Determine if we're in line comment mode (not file-level)
     let comment_input_mode = app.input_mode == InputMode::Comment && !app.comment_is_file_level;
+    let anchor_preview = app.comment_anchor_preview();
 
     let ctx = SideBySideContext {
         theme: &app.theme,
@@ -1142,11 +1854,17 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         comment_input_mode,
         comment_line: app.comment_line,
         comment_type: app.comment_type,
+        comment_severity: app.comment_severity,
+        comment_required_action: app.comment_required_action,
         comment_buffer: &app.comment_buffer,
         comment_cursor: app.comment_cursor,
         comment_line_range: app.comment_line_range.map(|(r, _)| r),
         editing_comment_id: app.editing_comment_id.as_deref(),
+        comment_action: app.comment_input_action(),
+        comment_anchor_preview: &anchor_preview,
         supports_keyboard_enhancement: app.supports_keyboard_enhancement,
+        comment_limits: app.config.comment_limits,
+        hidden_comment_types: &app.hidden_comment_types,
     };
 
     // Build all diff lines for side-by-side view
@@ -1159,26 +1877,33 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
     for (file_idx, file) in app.diff_files.iter().enumerate() {
         let path = file.display_path();
-        let status = file.status.as_char();
-        let is_reviewed = app.session.is_file_reviewed(path);
+        let status = file.status_char();
+        let state = app.session.file_state(path);
 
         // File header
         let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
 
-        let review_mark = if is_reviewed { "✓ " } else { "" };
+        let review_mark = review_header_mark(state);
+        let vendored_tag = file_header_tags(app, path);
 
         lines.push(Line::from(vec![
             Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
             Span::styled(
-                format!("═══ {}{} [{}] ", review_mark, path.display(), status),
+                format!(
+                    "═══ {}{} [{}] {}",
+                    review_mark,
+                    file.header_label(),
+                    status,
+                    vendored_tag
+                ),
                 styles::file_header_style(&app.theme),
             ),
             Span::styled("═".repeat(40), styles::file_header_style(&app.theme)),
         ]));
         line_idx += 1;
 
-        // If file is reviewed, skip rendering the body
-        if is_reviewed {
+        // If file is reviewed or a collapsed vendored file, skip rendering the body
+        if app.is_file_folded(path) {
             continue;
         }
 
@@ -1189,7 +1914,11 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
         // Show file-level comments
         if let Some(review) = app.session.files.get(path) {
-            for comment in &review.file_comments {
+            for comment in review
+                .file_comments
+                .iter()
+                .filter(|comment| app.is_comment_shown(comment))
+            {
                 // Skip rendering this comment if it's being edited
                 let is_being_edited =
                     app.editing_comment_id.as_ref() == Some(&comment.id) && is_file_comment_mode;
@@ -1199,11 +1928,15 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                     let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
                         &app.theme,
                         app.comment_type,
+                        app.comment_severity,
+                        app.comment_required_action,
                         &app.comment_buffer,
                         app.comment_cursor,
                         None,
-                        true,
+                        app.comment_input_action(),
+                        &app.comment_anchor_preview(),
                         app.supports_keyboard_enhancement,
+                        app.config.comment_limits,
                     );
                     comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
                     comment_cursor_column = 1 + cursor_info.column;
@@ -1221,12 +1954,8 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                         line_idx += 1;
                     }
                 } else {
-                    let comment_lines = comment_panel::format_comment_lines(
-                        &app.theme,
-                        comment.comment_type,
-                        &comment.content,
-                        None,
-                    );
+                    let comment_lines =
+                        comment_panel::format_comment_lines(&app.theme, comment, None);
                     for mut comment_line in comment_lines {
                         let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
                         comment_line.spans.insert(
@@ -1248,11 +1977,15 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
                 &app.theme,
                 app.comment_type,
+                app.comment_severity,
+                app.comment_required_action,
                 &app.comment_buffer,
                 app.comment_cursor,
                 None,
-                false,
+                app.comment_input_action(),
+                &app.comment_anchor_preview(),
                 app.supports_keyboard_enhancement,
+                app.config.comment_limits,
             );
             comment_cursor_logical_line = Some(line_idx + cursor_info.line_offset);
             comment_cursor_column = 1 + cursor_info.column;
@@ -1268,7 +2001,33 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             }
         }
 
-        if file.is_binary {
+        if app.is_generated_collapsed(path) {
+            let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(GENERATED_PLACEHOLDER, styles::dim_style(&app.theme)),
+            ]));
+            line_idx += 1;
+        } else if let Some(special) = &file.special
+            && file.hunks.is_empty()
+        {
+            let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(
+                    special.summary(),
+                    styles::diff_hunk_header_style(&app.theme),
+                ),
+            ]));
+            line_idx += 1;
+        } else if let Some(detail) = &file.binary_detail {
+            let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
+            lines.push(Line::from(vec![
+                Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
+                Span::styled(detail.summary(), styles::diff_hunk_header_style(&app.theme)),
+            ]));
+            line_idx += 1;
+        } else if file.is_binary {
             let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
@@ -1279,7 +2038,14 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
             let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
             lines.push(Line::from(vec![
                 Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
-                Span::styled("(no changes)", styles::dim_style(&app.theme)),
+                Span::styled(
+                    if file.mode_change.is_some() {
+                        "(mode change only)"
+                    } else {
+                        "(no changes)"
+                    },
+                    styles::dim_style(&app.theme),
+                ),
             ]));
             line_idx += 1;
         } else {
@@ -1365,18 +2131,31 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
 
                 // Hunk header
                 let indicator = cursor_indicator_spaced(line_idx, ctx.current_line_idx);
-                lines.push(Line::from(vec![
+                let mut header_spans = vec![
                     Span::styled(indicator, styles::current_line_indicator_style(&app.theme)),
                     Span::styled(
                         hunk.header.to_string(),
                         styles::diff_hunk_header_style(&app.theme),
                     ),
-                ]));
+                ];
+                push_hunk_warnings(app, file_idx, hunk_idx, &mut header_spans);
+                if app.config.guide {
+                    header_spans.push(Span::styled(
+                        guide::hunk_explanation(hunk, hunk_idx),
+                        styles::dim_style(&app.theme),
+                    ));
+                }
+                lines.push(Line::from(header_spans));
                 line_idx += 1;
 
                 // Process diff lines in side-by-side format
+                let moved = app
+                    .moved_lines
+                    .get(&(file_idx, hunk_idx))
+                    .map_or(&[][..], Vec::as_slice);
                 let (new_line_idx, cursor_info) = render_hunk_lines_side_by_side(
                     &hunk.lines,
+                    moved,
                     &line_comments,
                     &ctx,
                     line_idx,
@@ -1399,6 +2178,11 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
         line_idx += 1;
     }
 
+    if let Some((line, column)) = render_general_section(app, &mut lines, &mut line_idx) {
+        comment_cursor_logical_line = Some(line);
+        comment_cursor_column = column;
+    }
+
     let visible_lines_unscrolled: Vec<Line> = lines
         .into_iter()
         .skip(app.diff_state.scroll_offset)
@@ -1502,18 +2286,36 @@ fn render_side_by_side_diff(frame: &mut Frame, app: &mut App, area: Rect) {
                 visual_row = logical_offset as u16;
             }
 
-            let screen_col = inner.x + comment_cursor_column;
-            let screen_row_abs = inner.y + visual_row;
-
-            app.comment_cursor_screen_pos = Some((screen_col, screen_row_abs));
+            app.comment_cursor_screen_pos = Some(comment_cursor_position(
+                inner,
+                app.diff_state.wrap_lines,
+                visual_row,
+                comment_cursor_column,
+            ));
         }
     }
 }
 
+/// Screen position of the comment cursor inside the diff area. A cursor
+/// past the right edge moves to its wrapped row, or stops at the edge when
+/// lines don't wrap, so an IME never composes off-screen
+fn comment_cursor_position(inner: Rect, wrap_lines: bool, row: u16, column: u16) -> (u16, u16) {
+    let (row, column) = if wrap_lines && inner.width > 0 {
+        (row + column / inner.width, column % inner.width)
+    } else {
+        (row, column.min(inner.width.saturating_sub(1)))
+    };
+    (
+        inner.x + column,
+        inner.y + row.min(inner.height.saturating_sub(1)),
+    )
+}
+
 /// Process and render all diff lines in a hunk for side-by-side view
 /// Returns (new_line_idx, Option<(cursor_logical_line, cursor_column)>)
 fn render_hunk_lines_side_by_side(
     hunk_lines: &[crate::model::DiffLine],
+    moved: &[bool],
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
     ctx: &SideBySideContext,
     mut line_idx: usize,
@@ -1544,6 +2346,7 @@ fn render_hunk_lines_side_by_side(
                 let (new_line_idx, lines_processed, cursor_info) =
                     render_deletion_addition_pair_side_by_side(
                         hunk_lines,
+                        moved,
                         i,
                         line_comments,
                         ctx,
@@ -1559,6 +2362,7 @@ fn render_hunk_lines_side_by_side(
             LineOrigin::Addition => {
                 let (new_line_idx, cursor_info) = render_standalone_addition_side_by_side(
                     diff_line,
+                    moved.get(i).copied().unwrap_or(false),
                     line_comments,
                     ctx,
                     line_idx,
@@ -1654,6 +2458,7 @@ fn render_context_line_side_by_side(
 /// Returns (line_idx, skip_count, Option<(cursor_logical_line, cursor_column)>)
 fn render_deletion_addition_pair_side_by_side(
     hunk_lines: &[crate::model::DiffLine],
+    moved: &[bool],
     start_idx: usize,
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
     ctx: &SideBySideContext,
@@ -1690,7 +2495,8 @@ fn render_deletion_addition_pair_side_by_side(
         // Left side (deletion)
         if offset < del_count {
             let del_line = &hunk_lines[start_idx + offset];
-            add_deletion_spans(ctx.theme, &mut spans, del_line, ctx.content_width);
+            let is_moved = moved.get(start_idx + offset).copied().unwrap_or(false);
+            add_deletion_spans(ctx.theme, &mut spans, del_line, is_moved, ctx.content_width);
         } else {
             add_empty_column_spans(&mut spans, ctx.content_width);
         }
@@ -1700,7 +2506,8 @@ fn render_deletion_addition_pair_side_by_side(
         // Right side (addition)
         if offset < add_count {
             let add_line = &hunk_lines[add_start + offset];
-            add_addition_spans(ctx.theme, &mut spans, add_line, ctx.content_width);
+            let is_moved = moved.get(add_start + offset).copied().unwrap_or(false);
+            add_addition_spans(ctx.theme, &mut spans, add_line, is_moved, ctx.content_width);
         } else {
             add_empty_column_spans(&mut spans, ctx.content_width);
         }
@@ -1754,6 +2561,7 @@ fn render_deletion_addition_pair_side_by_side(
 /// Returns (new_line_idx, Option<(cursor_logical_line, cursor_column)>)
 fn render_standalone_addition_side_by_side(
     diff_line: &crate::model::DiffLine,
+    is_moved: bool,
     line_comments: &std::collections::HashMap<u32, Vec<crate::model::Comment>>,
     ctx: &SideBySideContext,
     mut line_idx: usize,
@@ -1767,7 +2575,13 @@ fn render_standalone_addition_side_by_side(
     )];
     add_empty_column_spans(&mut spans, ctx.content_width);
     spans.push(Span::styled(" │ ", styles::dim_style(ctx.theme)));
-    add_addition_spans(ctx.theme, &mut spans, diff_line, ctx.content_width);
+    add_addition_spans(
+        ctx.theme,
+        &mut spans,
+        diff_line,
+        is_moved,
+        ctx.content_width,
+    );
 
     lines.push(Line::from(spans));
     line_idx += 1;
@@ -1789,8 +2603,14 @@ fn add_deletion_spans(
     theme: &Theme,
     spans: &mut Vec<Span>,
     diff_line: &crate::model::DiffLine,
+    is_moved: bool,
     content_width: usize,
 ) {
+    let line_style = if is_moved {
+        styles::diff_moved_style(theme)
+    } else {
+        styles::diff_del_style(theme)
+    };
     let line_num = diff_line
         .old_lineno
         .map(|n| format!("{n:>4}"))
@@ -1800,17 +2620,24 @@ fn add_deletion_spans(
         format!("{line_num} "),
         styles::dim_style(theme),
     ));
-    spans.push(Span::styled("-".to_string(), styles::diff_del_style(theme)));
+    spans.push(Span::styled("-".to_string(), line_style));
 
     // Use syntax highlighting if available
     if let Some(ref highlighted) = diff_line.highlighted_spans {
-        let content_spans =
-            truncate_or_pad_spans(highlighted, content_width, styles::diff_del_style(theme));
-        spans.extend(content_spans);
+        let content_spans = truncate_or_pad_spans(highlighted, content_width, line_style);
+        if is_moved {
+            spans.extend(
+                content_spans
+                    .into_iter()
+                    .map(|span| span.patch_style(Style::default().bg(theme.diff_moved_bg))),
+            );
+        } else {
+            spans.extend(content_spans);
+        }
     } else {
         // Fall back to plain text
         let content = truncate_or_pad(&diff_line.content, content_width);
-        spans.push(Span::styled(content, styles::diff_del_style(theme)));
+        spans.push(Span::styled(content, line_style));
     }
 }
 
@@ -1819,8 +2646,14 @@ fn add_addition_spans(
     theme: &Theme,
     spans: &mut Vec<Span>,
     diff_line: &crate::model::DiffLine,
+    is_moved: bool,
     content_width: usize,
 ) {
+    let line_style = if is_moved {
+        styles::diff_moved_style(theme)
+    } else {
+        styles::diff_add_style(theme)
+    };
     let line_num = diff_line
         .new_lineno
         .map(|n| format!("{n:>4}"))
@@ -1830,17 +2663,24 @@ fn add_addition_spans(
         format!("{line_num} "),
         styles::dim_style(theme),
     ));
-    spans.push(Span::styled("+".to_string(), styles::diff_add_style(theme)));
+    spans.push(Span::styled("+".to_string(), line_style));
 
     // Use syntax highlighting if available
     if let Some(ref highlighted) = diff_line.highlighted_spans {
-        let content_spans =
-            truncate_or_pad_spans(highlighted, content_width, styles::diff_add_style(theme));
-        spans.extend(content_spans);
+        let content_spans = truncate_or_pad_spans(highlighted, content_width, line_style);
+        if is_moved {
+            spans.extend(
+                content_spans
+                    .into_iter()
+                    .map(|span| span.patch_style(Style::default().bg(theme.diff_moved_bg))),
+            );
+        } else {
+            spans.extend(content_spans);
+        }
     } else {
         // Fall back to plain text
         let content = truncate_or_pad(&diff_line.content, content_width);
-        spans.push(Span::styled(content, styles::diff_add_style(theme)));
+        spans.push(Span::styled(content, line_style));
     }
 }
 
@@ -1870,6 +2710,9 @@ fn add_comments_to_line(
     if let Some(comments) = line_comments.get(&line_num) {
         for comment in comments {
             let comment_side = comment.side.unwrap_or(LineSide::New);
+            if ctx.hidden_comment_types.contains(&comment.comment_type) {
+                continue;
+            }
             if (side == LineSide::Old && comment_side == LineSide::Old)
                 || (side == LineSide::New && comment_side != LineSide::Old)
             {
@@ -1885,11 +2728,15 @@ fn add_comments_to_line(
                     let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
                         ctx.theme,
                         ctx.comment_type,
+                        ctx.comment_severity,
+                        ctx.comment_required_action,
                         ctx.comment_buffer,
                         ctx.comment_cursor,
                         line_range,
-                        true,
+                        ctx.comment_action,
+                        ctx.comment_anchor_preview,
                         ctx.supports_keyboard_enhancement,
+                        ctx.comment_limits,
                     );
                     cursor_info_out =
                         Some((line_idx + cursor_info.line_offset, 1 + cursor_info.column));
@@ -1910,12 +2757,8 @@ fn add_comments_to_line(
                     let line_range = comment
                         .line_range
                         .or_else(|| Some(LineRange::single(line_num)));
-                    let comment_lines = comment_panel::format_comment_lines(
-                        ctx.theme,
-                        comment.comment_type,
-                        &comment.content,
-                        line_range,
-                    );
+                    let comment_lines =
+                        comment_panel::format_comment_lines(ctx.theme, comment, line_range);
                     for mut comment_line in comment_lines {
                         let indicator = cursor_indicator(line_idx, ctx.current_line_idx);
                         comment_line.spans.insert(
@@ -1941,11 +2784,15 @@ fn add_comments_to_line(
         let (input_lines, cursor_info) = comment_panel::format_comment_input_lines(
             ctx.theme,
             ctx.comment_type,
+            ctx.comment_severity,
+            ctx.comment_required_action,
             ctx.comment_buffer,
             ctx.comment_cursor,
             line_range,
-            false,
+            ctx.comment_action,
+            ctx.comment_anchor_preview,
             ctx.supports_keyboard_enhancement,
+            ctx.comment_limits,
         );
         cursor_info_out = Some((line_idx + cursor_info.line_offset, 1 + cursor_info.column));
 
@@ -1963,13 +2810,18 @@ fn add_comments_to_line(
     (line_idx, cursor_info_out)
 }
 
-/// Truncate or pad a string to a specific width
+/// Truncate or pad a string to a specific display width, so wide characters
+/// (CJK, emoji) do not push the columns after them out of line
 fn truncate_or_pad(s: &str, width: usize) -> String {
-    let char_count = s.chars().count();
-    if char_count > width {
-        s.chars().take(width.saturating_sub(3)).collect::<String>() + "..."
+    let text_width = s.width();
+    if text_width > width {
+        let room = width.saturating_sub(3);
+        let truncated = take_width(s, room);
+        // A wide character that does not fit leaves a column to fill
+        let gap = room - truncated.width();
+        format!("{truncated}{}...", " ".repeat(gap))
     } else {
-        format!("{s:width$}")
+        format!("{s}{}", " ".repeat(width - text_width))
     }
 }
 
@@ -1999,20 +2851,11 @@ fn truncate_or_pad_spans(
                 result.push(Span::styled(text.clone(), *style));
                 remaining -= text_width;
             } else {
-                // Truncate this span character by character to fit remaining width
-                let mut truncated = String::new();
-                let mut current_width = 0;
-                for c in text.chars() {
-                    let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
-                    if current_width + char_width > remaining {
-                        break;
-                    }
-                    truncated.push(c);
-                    current_width += char_width;
-                }
-                if !truncated.is_empty() {
-                    result.push(Span::styled(truncated, *style));
-                }
+                // Truncate this span to fit, padding the column a wide
+                // character could not use
+                let mut truncated = take_width(text, remaining);
+                truncated.push_str(&" ".repeat(remaining - truncated.width()));
+                result.push(Span::styled(truncated, *style));
                 remaining = 0;
             }
         }
@@ -2040,6 +2883,46 @@ fn truncate_or_pad_spans(
     }
 }
 
+/// Show the column cursor by reversing the character at display `column`
+/// of a line's content spans, or the last one when the line is shorter
+fn mark_column(spans: Vec<Span<'static>>, column: usize) -> Vec<Span<'static>> {
+    let total: usize = spans.iter().map(|span| span.content.width()).sum();
+    let column = column.min(total.saturating_sub(1));
+    style_columns(spans, column, column, Modifier::REVERSED)
+}
+
+/// Add `modifier` to the characters starting at display columns
+/// `first..=last` (0-based) of a line's content spans
+fn style_columns(
+    spans: Vec<Span<'static>>,
+    first: usize,
+    last: usize,
+    modifier: Modifier,
+) -> Vec<Span<'static>> {
+    let mut styled = Vec::with_capacity(spans.len() + 2);
+    let mut column = 0;
+    for span in spans {
+        let mut pieces: Vec<(bool, String)> = Vec::new();
+        for grapheme in span.content.graphemes(true) {
+            let inside = (first..=last).contains(&column);
+            column += grapheme.width();
+            match pieces.last_mut() {
+                Some((was_inside, text)) if *was_inside == inside => text.push_str(grapheme),
+                _ => pieces.push((inside, grapheme.to_string())),
+            }
+        }
+        for (inside, text) in pieces {
+            let style = if inside {
+                span.style.add_modifier(modifier)
+            } else {
+                span.style
+            };
+            styled.push(Span::styled(text, style));
+        }
+    }
+    styled
+}
+
 /// Apply horizontal scroll to a line while preserving the first span (cursor indicator)
 fn apply_horizontal_scroll(line: Line, scroll_x: usize) -> Line {
     if scroll_x == 0 || line.spans.is_empty() {
@@ -2051,26 +2934,117 @@ fn apply_horizontal_scroll(line: Line, scroll_x: usize) -> Line {
     // Preserve the first span (indicator)
     let indicator = spans.remove(0);
 
-    // Skip scroll_x characters from the remaining spans
-    let mut chars_to_skip = scroll_x;
+    // Skip scroll_x display columns from the remaining spans
+    let mut columns_to_skip = scroll_x;
     let mut new_spans = vec![indicator];
 
     for span in spans {
-        let content = span.content.to_string();
-        let char_count = content.chars().count();
-        if chars_to_skip >= char_count {
-            chars_to_skip -= char_count;
-            // Skip this span entirely
-        } else if chars_to_skip > 0 {
-            // Partially skip this span
-            let new_content: String = content.chars().skip(chars_to_skip).collect();
-            chars_to_skip = 0;
-            new_spans.push(Span::styled(new_content, span.style));
-        } else {
-            // Keep this span as-is
-            new_spans.push(Span::styled(content, span.style));
+        if columns_to_skip == 0 {
+            new_spans.push(span);
+            continue;
+        }
+        let mut kept = String::new();
+        for c in span.content.chars() {
+            if columns_to_skip == 0 {
+                kept.push(c);
+                continue;
+            }
+            let char_width = c.width().unwrap_or(0);
+            if char_width > columns_to_skip {
+                // A wide character cut by the left edge becomes blanks
+                kept.push_str(&" ".repeat(char_width - columns_to_skip));
+                columns_to_skip = 0;
+            } else {
+                columns_to_skip -= char_width;
+            }
+        }
+        if !kept.is_empty() {
+            new_spans.push(Span::styled(kept, span.style));
         }
     }
 
     Line::from(new_spans)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_reverse_character_at_column_across_spans() {
+        // given highlighted content split into two spans
+        let spans = vec![Span::raw("let "), Span::raw("x = 1;")];
+
+        // when column 5 (the space after `x`) is marked
+        let marked = mark_column(spans, 5);
+
+        // then only that character is reversed, and the text is unchanged
+        let text: Vec<&str> = marked.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, vec!["let ", "x", " ", "= 1;"]);
+        assert!(marked[2].style.add_modifier.contains(Modifier::REVERSED));
+        assert!(!marked[1].style.add_modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn should_mark_last_character_when_column_is_past_the_end() {
+        let marked = mark_column(vec![Span::raw("ab")], 9);
+        let text: Vec<&str> = marked.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, vec!["a", "b"]);
+        assert!(marked[1].style.add_modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn should_pad_wide_characters_by_display_width() {
+        // given a line with two CJK characters (4 columns)
+        let padded = truncate_or_pad("a日本", 8);
+
+        // then padding fills display columns, not characters
+        assert_eq!(padded, "a日本   ");
+        assert_eq!(padded.width(), 8);
+    }
+
+    #[test]
+    fn should_truncate_emoji_without_overflowing() {
+        // given an emoji that would straddle the cut
+        let truncated = truncate_or_pad("ab🎉cdefgh", 6);
+
+        // then the half-column is filled and the width is exact
+        assert_eq!(truncated, "ab ...");
+        assert_eq!(truncated.width(), 6);
+        assert_eq!(truncate_str("日本語のコミット", 7), "日本...");
+    }
+
+    #[test]
+    fn should_keep_comment_cursor_inside_diff_area() {
+        // given a 10x5 diff area at (2, 1) and a cursor 23 columns in
+        let inner = Rect::new(2, 1, 10, 5);
+
+        // when lines wrap and when they don't
+        let wrapped = comment_cursor_position(inner, true, 1, 23);
+        let clipped = comment_cursor_position(inner, false, 1, 23);
+
+        // then the cursor moves down to its wrapped row or stops at the edge
+        assert_eq!(wrapped, (5, 4));
+        assert_eq!(clipped, (11, 2));
+    }
+
+    #[test]
+    fn should_scroll_by_display_columns() {
+        // given an indicator and a line starting with a wide character
+        let line = Line::from(vec![Span::raw(">"), Span::raw("日本x"), Span::raw("yz")]);
+
+        // when scrolled by one and by three columns
+        let by_one = apply_horizontal_scroll(line.clone(), 1);
+        let by_three = apply_horizontal_scroll(line, 3);
+
+        // then a split character becomes a blank and later spans line up
+        let text = |line: &Line| {
+            line.spans
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect::<String>()
+        };
+        assert_eq!(text(&by_one), "> 本xyz");
+        assert_eq!(text(&by_three), "> xyz");
+    }
+}
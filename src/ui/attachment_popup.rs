@@ -0,0 +1,71 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::ui::styles;
+
+pub fn render_attachments(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let attachments = &app.session.attachments;
+    let root = &app.vcs_info.root_path;
+    let Some(view) = app.attachment_view.as_mut() else {
+        return;
+    };
+    let area = centered_rect(70, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " Attachments ({}) - Space to remove, Esc to close ",
+            attachments.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let height = (inner.height as usize).max(1);
+    if view.cursor < view.scroll_offset {
+        view.scroll_offset = view.cursor;
+    } else if view.cursor >= view.scroll_offset + height {
+        view.scroll_offset = view.cursor + 1 - height;
+    }
+
+    let lines: Vec<Line> = attachments
+        .iter()
+        .enumerate()
+        .skip(view.scroll_offset)
+        .take(height)
+        .map(|(i, path)| {
+            let style = if i == view.cursor {
+                styles::selected_style(theme)
+            } else {
+                Style::default()
+            };
+            let mut spans = vec![
+                Span::styled(if i == view.cursor { "> " } else { "  " }, style),
+                Span::styled(path.display().to_string(), style),
+            ];
+            // Relative paths live in the repository; absolute ones join as-is
+            if !root.join(path).exists() {
+                spans.push(Span::styled("  (missing)", styles::dim_style(theme)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
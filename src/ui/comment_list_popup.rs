@@ -0,0 +1,77 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, CommentListEntry};
+use crate::theme::Theme;
+use crate::ui::styles;
+
+pub fn render_comment_list(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.comment_list.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " Comments ({}) - Enter to jump, i to edit, m to resolve, Esc to close ",
+            view.entries.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let height = (inner.height as usize).max(1);
+    if view.cursor < view.scroll_offset {
+        view.scroll_offset = view.cursor;
+    } else if view.cursor >= view.scroll_offset + height {
+        view.scroll_offset = view.cursor + 1 - height;
+    }
+
+    let lines: Vec<Line> = view
+        .entries
+        .iter()
+        .enumerate()
+        .skip(view.scroll_offset)
+        .take(height)
+        .map(|(i, entry)| entry_line(theme, entry, i == view.cursor))
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// One comment as a row: type, location and first line, dimmed once resolved
+pub fn entry_line(theme: &Theme, entry: &CommentListEntry, selected: bool) -> Line<'static> {
+    let style = if selected {
+        styles::selected_style(theme)
+    } else if entry.resolved {
+        styles::dim_style(theme)
+    } else {
+        Style::default()
+    };
+    Line::from(vec![
+        Span::styled(if selected { "> " } else { "  " }, style),
+        Span::styled(
+            format!("[{}] ", entry.comment_type.as_str()),
+            styles::comment_type_style(theme, entry.comment_type),
+        ),
+        Span::styled(format!("{}  ", entry.location), styles::dim_style(theme)),
+        Span::styled(if entry.resolved { "✓ " } else { "" }, style),
+        Span::styled(entry.first_line.clone(), style),
+    ])
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
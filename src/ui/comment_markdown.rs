@@ -0,0 +1,159 @@
+//! Basic Markdown in displayed comments: inline code, bold, italics, bullet
+//! lists and fenced code blocks. Only the diff view renders it; the saved
+//! text and the export keep the raw Markdown.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Span;
+
+/// Spans for each line of `content`, one entry per line so the comment box
+/// keeps its height. `code` styles inline code and fenced blocks.
+pub fn render_lines(content: &str, base: Style, code: Style) -> Vec<Vec<Span<'static>>> {
+    let mut in_fence = false;
+    content
+        .split('\n')
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                return vec![Span::styled(
+                    line.to_string(),
+                    base.add_modifier(Modifier::DIM),
+                )];
+            }
+            if in_fence {
+                return vec![Span::styled(line.to_string(), code)];
+            }
+            let indent = line.len() - line.trim_start().len();
+            let (prefix, rest) = match line[indent..].split_at_checked(2) {
+                Some(("- " | "* " | "+ ", rest)) => (format!("{}• ", &line[..indent]), rest),
+                _ => (line[..indent].to_string(), &line[indent..]),
+            };
+            let mut spans = Vec::new();
+            if !prefix.is_empty() {
+                spans.push(Span::styled(prefix, base));
+            }
+            spans.extend(inline_spans(rest, base, code));
+            spans
+        })
+        .collect()
+}
+
+/// `` `code` ``, `**bold**`/`__bold__` and `*italic*`/`_italic_`; unmatched
+/// markers stay as text, and `_` inside a word (`snake_case`) is not emphasis
+fn inline_spans(text: &str, base: Style, code: Style) -> Vec<Span<'static>> {
+    let bold = base.add_modifier(Modifier::BOLD);
+    let italic = base.add_modifier(Modifier::ITALIC);
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    let mut prev: Option<char> = None;
+    while let Some(c) = rest.chars().next() {
+        let styled = match c {
+            '`' => rest[1..]
+                .find('`')
+                .map(|end| (&rest[1..=end], end + 2, code)),
+            '*' | '_' if prev.is_none_or(|p| !p.is_alphanumeric()) => {
+                let double = if c == '*' { "**" } else { "__" };
+                if let Some(inner) = rest.strip_prefix(double) {
+                    inner
+                        .find(double)
+                        .filter(|&end| end > 0)
+                        .map(|end| (&inner[..end], end + 4, bold))
+                } else {
+                    rest[1..]
+                        .find(c)
+                        .filter(|&end| end > 0 && !rest[1..].starts_with(' '))
+                        .map(|end| (&rest[1..=end], end + 2, italic))
+                }
+            }
+            _ => None,
+        };
+        match styled {
+            Some((inner, consumed, style)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut plain), base));
+                }
+                spans.push(Span::styled(inner.to_string(), style));
+                prev = rest[..consumed].chars().last();
+                rest = &rest[consumed..];
+            }
+            None => {
+                plain.push(c);
+                prev = Some(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() || spans.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    fn texts(spans: &[Span]) -> Vec<String> {
+        spans.iter().map(|s| s.content.to_string()).collect()
+    }
+
+    #[test]
+    fn should_style_inline_code_and_emphasis() {
+        // given
+        let code = Style::default().fg(Color::Cyan);
+
+        // when
+        let lines = render_lines(
+            "Use `Vec::new` here, **not** a *slice*",
+            Style::default(),
+            code,
+        );
+
+        // then
+        let spans = &lines[0];
+        assert_eq!(
+            texts(spans),
+            vec!["Use ", "Vec::new", " here, ", "not", " a ", "slice"]
+        );
+        assert_eq!(spans[1].style, code);
+        assert!(spans[3].style.add_modifier.contains(Modifier::BOLD));
+        assert!(spans[5].style.add_modifier.contains(Modifier::ITALIC));
+    }
+
+    #[test]
+    fn should_leave_identifiers_and_unmatched_markers_alone() {
+        // when
+        let lines = render_lines(
+            "rename my_var_name, 2 * 3 and a lone `tick",
+            Style::default(),
+            Style::default(),
+        );
+
+        // then
+        assert_eq!(
+            texts(&lines[0]),
+            vec!["rename my_var_name, 2 * 3 and a lone `tick"]
+        );
+    }
+
+    #[test]
+    fn should_render_bullets_and_fenced_code_line_for_line() {
+        // given
+        let code = Style::default().fg(Color::Cyan);
+
+        // when
+        let lines = render_lines(
+            "- first\n  * nested\n```\nlet x = *y;\n```",
+            Style::default(),
+            code,
+        );
+
+        // then
+        assert_eq!(lines.len(), 5);
+        assert_eq!(texts(&lines[0]), vec!["• ", "first"]);
+        assert_eq!(texts(&lines[1]), vec!["  • ", "nested"]);
+        assert_eq!(texts(&lines[3]), vec!["let x = *y;"]);
+        assert_eq!(lines[3][0].style, code);
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use ratatui::{
     Frame,
     layout::{Constraint, Flex, Layout, Rect},
@@ -5,20 +6,25 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
 };
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
-use crate::app::App;
-use crate::model::{CommentType, LineRange};
+use crate::app::{AnchorPreviewLine, App};
+use crate::config::CommentLimits;
+use crate::model::{Comment, CommentType, LineOrigin, LineRange, RequiredAction, Severity};
 use crate::theme::Theme;
-use crate::ui::styles;
+use crate::ui::{comment_markdown, styles};
+
+/// Left border in front of each line of the comment input
+pub const INPUT_BORDER: &str = "     │ ";
 
 /// Information about where the cursor should be positioned within comment input
 #[derive(Debug, Clone)]
 pub struct CommentCursorInfo {
     /// Which line within the formatted output contains the cursor (0-indexed, relative to content start)
     /// This is the line index within the Vec<Line> returned by format_comment_input_lines,
-    /// where 0 = header line, 1+ = content lines, last = footer line.
-    /// The cursor is only on content lines (1 to n-2 inclusive for n total lines).
+    /// where 0 = header line, then any anchor preview lines, then content lines,
+    /// last = footer line. The cursor is only on content lines.
     pub line_offset: usize,
     /// Column offset (display width) from start of line where cursor should be
     pub column: u16,
@@ -29,14 +35,19 @@ pub struct CommentCursorInfo {
 ///
 /// Returns a tuple of (lines, cursor_info) where cursor_info contains the position
 /// of the cursor within the formatted output for IME positioning.
+#[allow(clippy::too_many_arguments)]
 pub fn format_comment_input_lines(
     theme: &Theme,
     comment_type: CommentType,
+    severity: Option<Severity>,
+    required_action: Option<RequiredAction>,
     buffer: &str,
     cursor_pos: usize,
     line_range: Option<LineRange>,
-    is_editing: bool,
+    action: &str,
+    anchor_preview: &[AnchorPreviewLine],
     supports_keyboard_enhancement: bool,
+    limits: CommentLimits,
 ) -> (Vec<Line<'static>>, CommentCursorInfo) {
     let type_style = styles::comment_type_style(theme, comment_type);
     let border_style = styles::comment_border_style(theme, comment_type);
@@ -44,7 +55,6 @@ pub fn format_comment_input_lines(
         .fg(theme.cursor_color)
         .add_modifier(Modifier::UNDERLINED);
 
-    let action = if is_editing { "Edit" } else { "Add" };
     let line_info = match line_range {
         Some(range) if range.is_single() => format!("L{} ", range.start),
         Some(range) => format!("L{}-L{} ", range.start, range.end),
@@ -58,25 +68,56 @@ pub fn format_comment_input_lines(
     };
 
     let mut result = Vec::new();
-    // Track cursor position: line offset within result, column (display width)
-    // Default to first content line (index 1) with cursor at start of content (after border)
-    let border_prefix = "     │ ";
+    let border_prefix = INPUT_BORDER;
     let border_width = border_prefix.width() as u16;
-    let mut cursor_line_offset: usize = 1; // First content line (after header)
-    let mut cursor_column: u16 = border_width; // After the border prefix
 
     // Top border with type label and hints
     result.push(Line::from(vec![
         Span::styled("     ╭─ ", border_style),
         Span::styled(format!("{} ", action), styles::dim_style(theme)),
         Span::styled(format!("[{}] ", comment_type.as_str()), type_style),
+        severity_span(theme, severity),
+        required_action_span(theme, required_action),
         Span::styled(line_info, styles::dim_style(theme)),
         Span::styled(
-            format!("(Tab:type Enter:save {}:newline Esc:cancel)", newline_hint),
+            format!(
+                "(Tab:type S-Tab:severity C-t:action Enter:save {}:newline Esc:cancel)",
+                newline_hint
+            ),
             styles::dim_style(theme),
         ),
     ]));
 
+    // The commented diff lines, so the anchor can be checked while typing
+    for line in anchor_preview {
+        let marker = if line.anchored { "▶" } else { " " };
+        let (sign, content_style) = match line.origin {
+            LineOrigin::Addition => ("+", styles::diff_add_style(theme)),
+            LineOrigin::Deletion => ("-", styles::diff_del_style(theme)),
+            LineOrigin::Context => (" ", styles::dim_style(theme)),
+        };
+        result.push(Line::from(vec![
+            Span::styled(border_prefix, border_style),
+            Span::styled(
+                format!("{marker}{:>5} {sign} ", line.lineno),
+                styles::dim_style(theme),
+            ),
+            Span::styled(line.content.clone(), content_style),
+        ]));
+    }
+    if !anchor_preview.is_empty() {
+        result.push(Line::from(vec![Span::styled(
+            "     ├".to_string() + &"─".repeat(38),
+            border_style,
+        )]));
+    }
+
+    // Track cursor position: line offset within result, column (display width)
+    // Default to first content line with cursor at start of content (after border)
+    let first_content_line = result.len();
+    let mut cursor_line_offset = first_content_line;
+    let mut cursor_column: u16 = border_width; // After the border prefix
+
     // Content lines with cursor
     if buffer.is_empty() {
         // Show placeholder with cursor at start
@@ -85,7 +126,7 @@ pub fn format_comment_input_lines(
             Span::styled(" ", cursor_style),
             Span::styled("Type your comment...", styles::dim_style(theme)),
         ]));
-        // cursor_line_offset is already 1 (first content line)
+        // cursor_line_offset is already the first content line
         // cursor_column is already border_width (cursor at start of content)
     } else {
         // Split buffer into lines and render with cursor
@@ -109,8 +150,8 @@ pub fn format_comment_input_lines(
                 let (before_cursor, after_cursor) = text.split_at(cursor_pos_in_line);
 
                 // Track cursor position for IME
-                // line_offset: header (1) + current content line index
-                cursor_line_offset = 1 + line_idx;
+                // line_offset: header and preview + current content line index
+                cursor_line_offset = first_content_line + line_idx;
                 // column: border width + display width of text before cursor
                 cursor_column = border_width + before_cursor.width() as u16;
 
@@ -118,11 +159,11 @@ pub fn format_comment_input_lines(
                     line_spans.push(Span::raw(before_cursor.to_string()));
                     line_spans.push(Span::styled(" ", cursor_style));
                 } else {
-                    let mut chars = after_cursor.chars();
-                    let cursor_char = chars.next().unwrap();
-                    let remaining = chars.as_str();
+                    // Highlight the whole grapheme so an accent stays on its letter
+                    let cursor_grapheme = after_cursor.graphemes(true).next().unwrap_or_default();
+                    let remaining = &after_cursor[cursor_grapheme.len()..];
                     line_spans.push(Span::raw(before_cursor.to_string()));
-                    line_spans.push(Span::styled(cursor_char.to_string(), cursor_style));
+                    line_spans.push(Span::styled(cursor_grapheme.to_string(), cursor_style));
                     line_spans.push(Span::raw(remaining.to_string()));
                 }
             } else {
@@ -136,11 +177,8 @@ pub fn format_comment_input_lines(
         }
     }
 
-    // Bottom border
-    result.push(Line::from(vec![Span::styled(
-        "     ╰".to_string() + &"─".repeat(38),
-        border_style,
-    )]));
+    // Bottom border, with the length so far
+    result.push(length_footer(theme, buffer, limits, border_style));
 
     let cursor_info = CommentCursorInfo {
         line_offset: cursor_line_offset,
@@ -150,22 +188,120 @@ pub fn format_comment_input_lines(
     (result, cursor_info)
 }
 
-/// Format a comment as multiple lines with a box border (themed version)
+/// Bottom border of the comment input with a character and word count,
+/// flagging comments the forge would reject or that ramble on
+fn length_footer(
+    theme: &Theme,
+    buffer: &str,
+    limits: CommentLimits,
+    border_style: Style,
+) -> Line<'static> {
+    let chars = buffer.chars().count();
+    let words = buffer.split_whitespace().count();
+    let counter = format!(" {chars} chars · {words} words ");
+    let (note, note_style) = if limits.too_long(buffer) {
+        (
+            format!(
+                "over the {} character limit ",
+                limits.max_chars.unwrap_or_default()
+            ),
+            styles::comment_type_style(theme, CommentType::Issue).add_modifier(Modifier::BOLD),
+        )
+    } else if limits.wordy(buffer) {
+        (
+            "long, consider splitting it ".to_string(),
+            styles::pending_style(theme),
+        )
+    } else {
+        (String::new(), Style::default())
+    };
+    let fill = 36usize
+        .saturating_sub(counter.width() + note.width())
+        .max(2);
+    Line::from(vec![
+        Span::styled("     ╰─", border_style),
+        Span::styled(counter, styles::dim_style(theme)),
+        Span::styled(note, note_style),
+        Span::styled("─".repeat(fill), border_style),
+    ])
+}
+
+/// `[MAJOR] ` in the severity's color, empty without one
+fn severity_span(theme: &Theme, severity: Option<Severity>) -> Span<'static> {
+    match severity {
+        Some(severity) => Span::styled(
+            format!("[{}] ", severity.as_str()),
+            styles::severity_style(theme, severity),
+        ),
+        None => Span::raw(""),
+    }
+}
+
+/// `→ fix ` for the response a comment expects, empty without one
+fn required_action_span(theme: &Theme, action: Option<RequiredAction>) -> Span<'static> {
+    match action {
+        Some(action) => Span::styled(format!("→ {} ", action.as_str()), styles::dim_style(theme)),
+        None => Span::raw(""),
+    }
+}
+
+/// Format a comment as multiple lines with a box border (themed version).
+/// Resolved comments are dimmed.
 pub fn format_comment_lines(
     theme: &Theme,
-    comment_type: CommentType,
-    content: &str,
+    comment: &Comment,
     line_range: Option<LineRange>,
 ) -> Vec<Line<'static>> {
-    let type_style = styles::comment_type_style(theme, comment_type);
-    let border_style = styles::comment_border_style(theme, comment_type);
+    let comment_type = comment.comment_type;
+    let (type_style, border_style, text_style) = if comment.resolved {
+        let dim = styles::dim_style(theme);
+        (dim, dim, dim)
+    } else {
+        (
+            styles::comment_type_style(theme, comment_type),
+            styles::comment_border_style(theme, comment_type),
+            Style::default(),
+        )
+    };
 
-    let line_info = match line_range {
-        Some(range) if range.is_single() => format!("L{} ", range.start),
-        Some(range) => format!("L{}-L{} ", range.start, range.end),
-        None => String::new(),
+    let line_info = match (line_range, comment.columns) {
+        (Some(range), Some(columns)) if range.is_single() => {
+            format!("L{}:{}-{} ", range.start, columns.start, columns.end)
+        }
+        (Some(range), _) if range.is_single() => format!("L{} ", range.start),
+        (Some(range), _) => format!("L{}-L{} ", range.start, range.end),
+        (None, _) => String::new(),
+    };
+    let content_lines: Vec<&str> = comment.content.split('\n').collect();
+    let resolved_label = if comment.resolved {
+        "✓ resolved "
+    } else {
+        ""
+    };
+    let anchor_label = if comment.anchor_lost {
+        "⚠ code changed "
+    } else {
+        ""
+    };
+    let source_label = comment
+        .source
+        .as_ref()
+        .map(|tool| format!("⚙ {tool} "))
+        .unwrap_or_default();
+    let author_label = comment
+        .author
+        .as_ref()
+        .map(|author| format!("{author} · "))
+        .unwrap_or_default();
+    let now = Utc::now();
+    let age_label = match comment.updated_at {
+        Some(updated) => format!(
+            "{}, edited {} ",
+            relative_age(comment.created_at, now),
+            relative_age(updated, now)
+        ),
+        None => format!("{} ", relative_age(comment.created_at, now)),
     };
-    let content_lines: Vec<&str> = content.split('\n').collect();
 
     let mut result = Vec::new();
 
@@ -173,16 +309,64 @@ pub fn format_comment_lines(
     result.push(Line::from(vec![
         Span::styled("     ╭─ ", border_style),
         Span::styled(format!("[{}] ", comment_type.as_str()), type_style),
+        if comment.resolved {
+            Span::styled(
+                comment
+                    .severity
+                    .map(|severity| format!("[{}] ", severity.as_str()))
+                    .unwrap_or_default(),
+                type_style,
+            )
+        } else {
+            severity_span(theme, comment.severity)
+        },
+        required_action_span(theme, comment.action),
         Span::styled(line_info, styles::dim_style(theme)),
+        Span::styled(resolved_label, styles::dim_style(theme)),
+        Span::styled(anchor_label, styles::dim_style(theme)),
+        Span::styled(source_label, styles::dim_style(theme)),
+        Span::styled(author_label, styles::dim_style(theme)),
+        Span::styled(age_label, styles::dim_style(theme)),
         Span::styled("─".repeat(30), border_style),
     ]));
 
-    // Content lines
-    for line in &content_lines {
-        result.push(Line::from(vec![
-            Span::styled("     │ ", border_style),
-            Span::raw(line.to_string()),
-        ]));
+    // Content lines; suggested changes are code and stay as written
+    let code_style = if comment.resolved {
+        text_style
+    } else {
+        styles::inline_code_style(theme)
+    };
+    if comment_type == CommentType::CodeSuggestion {
+        for line in &content_lines {
+            result.push(Line::from(vec![
+                Span::styled("     │ ", border_style),
+                Span::styled(line.to_string(), text_style),
+            ]));
+        }
+    } else {
+        for spans in comment_markdown::render_lines(&comment.content, text_style, code_style) {
+            let mut line = vec![Span::styled("     │ ", border_style)];
+            line.extend(spans);
+            result.push(Line::from(line));
+        }
+    }
+
+    // Replies, indented under the comment they answer
+    for reply in &comment.replies {
+        let lines = comment_markdown::render_lines(&reply.content, text_style, code_style);
+        for (i, spans) in lines.into_iter().enumerate() {
+            let marker = match (i, &reply.author) {
+                (0, Some(author)) => format!("↳ {author}: "),
+                (0, None) => "↳ ".to_string(),
+                _ => "  ".to_string(),
+            };
+            let mut line = vec![
+                Span::styled("     │ ", border_style),
+                Span::styled(marker, styles::dim_style(theme)),
+            ];
+            line.extend(spans);
+            result.push(Line::from(line));
+        }
     }
 
     // Bottom border
@@ -224,6 +408,22 @@ pub fn render_confirm_dialog(frame: &mut Frame, app: &App, message: &str) {
     frame.render_widget(paragraph, inner);
 }
 
+/// How long ago `then` was, e.g. "3m ago"; older than a week shows the date
+fn relative_age(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(then);
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else if elapsed.num_days() < 7 {
+        format!("{}d ago", elapsed.num_days())
+    } else {
+        then.format("%Y-%m-%d").to_string()
+    }
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
     let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
@@ -241,14 +441,110 @@ mod tests {
         Theme::default()
     }
 
+    #[test]
+    fn should_describe_comment_ages_relative_to_now() {
+        // given a fixed "now"
+        let now: DateTime<Utc> = "2024-05-10T12:00:00Z".parse().unwrap();
+        let ago = |text: &str| relative_age(text.parse().unwrap(), now);
+
+        // then recent times are relative and old ones show the date
+        assert_eq!(ago("2024-05-10T11:59:30Z"), "just now");
+        assert_eq!(ago("2024-05-10T11:57:00Z"), "3m ago");
+        assert_eq!(ago("2024-05-10T07:00:00Z"), "5h ago");
+        assert_eq!(ago("2024-05-08T12:00:00Z"), "2d ago");
+        assert_eq!(ago("2024-04-01T09:00:00Z"), "2024-04-01");
+    }
+
+    #[test]
+    fn should_show_severity_after_type_in_comment_header() {
+        // given
+        let theme = test_theme();
+        let mut comment = Comment::new("typo".to_string(), CommentType::Issue, None);
+        comment.severity = Some(Severity::Nit);
+
+        // when
+        let lines = format_comment_lines(&theme, &comment, None);
+
+        // then
+        let header: String = lines[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert!(header.starts_with("     ╭─ [ISSUE] [NIT] "));
+        assert_eq!(
+            lines[0].spans[2].style,
+            styles::severity_style(&theme, Severity::Nit)
+        );
+    }
+
+    #[test]
+    fn should_show_required_action_in_comment_header() {
+        // given
+        let theme = test_theme();
+        let mut comment = Comment::new("why?".to_string(), CommentType::Note, None);
+        comment.action = Some(RequiredAction::Answer);
+
+        // when
+        let lines = format_comment_lines(&theme, &comment, None);
+
+        // then
+        let header: String = lines[0].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert!(header.starts_with("     ╭─ [NOTE] → answer "));
+    }
+
+    #[test]
+    fn should_count_length_in_the_input_footer_and_flag_limits() {
+        // given
+        let theme = test_theme();
+        let limits = CommentLimits {
+            max_chars: Some(10),
+            soft_words: Some(2),
+        };
+        let footer = |buffer: &str| -> String {
+            let (lines, _) = format_comment_input_lines(
+                &theme,
+                CommentType::Note,
+                None,
+                None,
+                buffer,
+                0,
+                None,
+                "Add",
+                &[],
+                false,
+                limits,
+            );
+            lines
+                .last()
+                .unwrap()
+                .spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect()
+        };
+
+        // then
+        assert!(footer("a b").contains(" 3 chars · 2 words "));
+        assert!(footer("a b c").contains("long, consider splitting it"));
+        assert!(footer("eleven char").contains("over the 10 character limit"));
+    }
+
     #[test]
     fn should_return_cursor_at_start_for_empty_buffer() {
         // given
         let theme = test_theme();
 
         // when
-        let (lines, cursor_info) =
-            format_comment_input_lines(&theme, CommentType::Note, "", 0, None, false, false);
+        let (lines, cursor_info) = format_comment_input_lines(
+            &theme,
+            CommentType::Note,
+            None,
+            None,
+            "",
+            0,
+            None,
+            "Add",
+            &[],
+            false,
+            CommentLimits::default(),
+        );
 
         // then
         assert_eq!(lines.len(), 3); // header + content + footer
@@ -256,6 +552,49 @@ mod tests {
         assert_eq!(cursor_info.column, 7); // "     │ " = 7 chars
     }
 
+    #[test]
+    fn should_show_anchor_preview_between_header_and_content() {
+        // given
+        let theme = test_theme();
+        let preview = vec![
+            AnchorPreviewLine {
+                lineno: 41,
+                origin: LineOrigin::Context,
+                content: "fn main() {".to_string(),
+                anchored: false,
+            },
+            AnchorPreviewLine {
+                lineno: 42,
+                origin: LineOrigin::Addition,
+                content: "    let x = 1;".to_string(),
+                anchored: true,
+            },
+        ];
+
+        // when
+        let (lines, cursor_info) = format_comment_input_lines(
+            &theme,
+            CommentType::Note,
+            None,
+            None,
+            "ok",
+            2,
+            Some(LineRange::single(42)),
+            "Add",
+            &preview,
+            false,
+            CommentLimits::default(),
+        );
+
+        // then - header, two preview lines, separator, content, footer
+        assert_eq!(lines.len(), 6);
+        let anchored: String = lines[2].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert!(anchored.contains("▶   42 + "));
+        assert!(anchored.ends_with("let x = 1;"));
+        assert_eq!(cursor_info.line_offset, 4);
+        assert_eq!(cursor_info.column, 7 + 2);
+    }
+
     #[test]
     fn should_return_cursor_position_for_ascii_text() {
         // given
@@ -267,11 +606,15 @@ mod tests {
         let (_, cursor_info) = format_comment_input_lines(
             &theme,
             CommentType::Note,
+            None,
+            None,
             buffer,
             cursor_pos,
             None,
+            "Add",
+            &[],
             false,
-            false,
+            CommentLimits::default(),
         );
 
         // then
@@ -290,11 +633,15 @@ mod tests {
         let (_, cursor_info) = format_comment_input_lines(
             &theme,
             CommentType::Note,
+            None,
+            None,
             buffer,
             cursor_pos,
             None,
+            "Add",
+            &[],
             false,
-            false,
+            CommentLimits::default(),
         );
 
         // then
@@ -314,11 +661,15 @@ mod tests {
         let (_, cursor_info) = format_comment_input_lines(
             &theme,
             CommentType::Note,
+            None,
+            None,
             buffer,
             cursor_pos,
             None,
+            "Add",
+            &[],
             false,
-            false,
+            CommentLimits::default(),
         );
 
         // then
@@ -337,11 +688,15 @@ mod tests {
         let (lines, cursor_info) = format_comment_input_lines(
             &theme,
             CommentType::Note,
+            None,
+            None,
             buffer,
             cursor_pos,
             None,
+            "Add",
+            &[],
             false,
-            false,
+            CommentLimits::default(),
         );
 
         // then
@@ -361,11 +716,15 @@ mod tests {
         let (_, cursor_info) = format_comment_input_lines(
             &theme,
             CommentType::Note,
+            None,
+            None,
             buffer,
             cursor_pos,
             None,
+            "Add",
+            &[],
             false,
-            false,
+            CommentLimits::default(),
         );
 
         // then
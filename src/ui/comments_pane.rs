@@ -0,0 +1,51 @@
+//! The comments pane under the file list: every comment of the review,
+//! focused with Tab, to jump to, edit, resolve or delete them.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::app::{App, FocusedPanel};
+use crate::ui::{comment_list_popup, styles};
+
+pub fn render_comments_pane(frame: &mut Frame, app: &mut App, area: Rect) {
+    let focused = app.focused_panel == FocusedPanel::Comments;
+    let entries = app.comments_pane_entries();
+    let theme = &app.theme;
+    let pane = &mut app.comments_pane;
+    pane.cursor = pane.cursor.min(entries.len().saturating_sub(1));
+
+    let title = if focused {
+        format!(
+            " Comments ({}) - Enter:jump i:edit m:resolve d:delete ",
+            entries.len()
+        )
+    } else {
+        format!(" Comments ({}) ", entries.len())
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, focused));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let height = (inner.height as usize).max(1);
+    if pane.cursor < pane.scroll_offset {
+        pane.scroll_offset = pane.cursor;
+    } else if pane.cursor >= pane.scroll_offset + height {
+        pane.scroll_offset = pane.cursor + 1 - height;
+    }
+
+    let lines: Vec<Line> = entries
+        .iter()
+        .enumerate()
+        .skip(pane.scroll_offset)
+        .take(height)
+        .map(|(i, entry)| comment_list_popup::entry_line(theme, entry, focused && i == pane.cursor))
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
@@ -0,0 +1,50 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::ui::styles;
+
+pub fn render_export_preview(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(preview) = app.export_preview.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 80, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Export preview - y/Enter: copy, n/Esc: cancel ")
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    preview.viewport_height = inner.height as usize;
+    let max_offset = preview
+        .total_lines()
+        .saturating_sub(preview.viewport_height);
+    preview.scroll_offset = preview.scroll_offset.min(max_offset);
+
+    // Shown verbatim so what is on screen is exactly what gets copied
+    let lines: Vec<Line> = preview
+        .content
+        .lines()
+        .skip(preview.scroll_offset)
+        .take(preview.viewport_height)
+        .map(Line::raw)
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
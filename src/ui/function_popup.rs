@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::theme::Theme;
+use crate::ui::styles;
+
+pub fn render_function_diff(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.function_diff.as_mut() else {
+        return;
+    };
+    let area = centered_rect(90, 80, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " {} - old vs new (j/k to scroll) - Press Esc to close ",
+            view.name
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [old_area, new_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(inner);
+
+    // Each side has its own title line
+    view.viewport_height = inner.height.saturating_sub(1) as usize;
+    let max_offset = view.total_lines().saturating_sub(view.viewport_height);
+    view.scroll_offset = view.scroll_offset.min(max_offset);
+
+    // A line that does not appear anywhere on the other side is a change
+    let old_set: HashSet<&str> = view.old_lines.iter().map(String::as_str).collect();
+    let new_set: HashSet<&str> = view.new_lines.iter().map(String::as_str).collect();
+
+    let old_side = side_lines(
+        theme,
+        "old",
+        view.old_start,
+        &view.old_lines,
+        &new_set,
+        styles::diff_del_style(theme),
+        view.scroll_offset,
+        view.viewport_height,
+    );
+    let new_side = side_lines(
+        theme,
+        "new",
+        view.new_start,
+        &view.new_lines,
+        &old_set,
+        styles::diff_add_style(theme),
+        view.scroll_offset,
+        view.viewport_height,
+    );
+    frame.render_widget(Paragraph::new(old_side), old_area);
+    frame.render_widget(Paragraph::new(new_side), new_area);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn side_lines<'a>(
+    theme: &Theme,
+    label: &str,
+    start: usize,
+    lines: &'a [String],
+    other: &HashSet<&str>,
+    changed_style: Style,
+    scroll_offset: usize,
+    height: usize,
+) -> Vec<Line<'a>> {
+    let title = if lines.is_empty() {
+        format!(" {label}: (not present)")
+    } else {
+        format!(" {label}: L{}-L{}", start, start + lines.len() - 1)
+    };
+    let mut out = vec![Line::from(Span::styled(title, styles::header_style(theme)))];
+    out.extend(
+        lines
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(height)
+            .map(|(idx, content)| {
+                let style = if other.contains(content.as_str()) {
+                    styles::diff_context_style(theme)
+                } else {
+                    changed_style
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:>5} ", start + idx), styles::dim_style(theme)),
+                    Span::styled(content.as_str(), style),
+                ])
+            }),
+    );
+    out
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
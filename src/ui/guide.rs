@@ -0,0 +1,69 @@
+//! New-contributor mode: plain-language notes laid over the `@@` hunk
+//! headers, saying what the header means and which key to try next. The
+//! notes sit on the header row itself, so lines and the cursor stay put.
+
+use crate::model::DiffHunk;
+
+/// Review keys worth learning first, one per hunk in turn
+const TIPS: &[&str] = &[
+    "c comments on the cursor line",
+    "v selects lines for one comment on all of them",
+    "] and [ jump to the next and previous hunk",
+    "Enter here shows 10 more lines around the hunk",
+    "r marks the file reviewed",
+    "y copies the review to send it",
+    "? lists every key",
+];
+
+/// `← was lines 10-16, now 10-17; - removed, + added · tip: ...` for the
+/// `hunk_idx`th hunk of a file
+pub fn hunk_explanation(hunk: &DiffHunk, hunk_idx: usize) -> String {
+    format!(
+        "  ← was {}, now {}; - removed, + added · tip: {}",
+        line_span(hunk.old_start, hunk.old_count),
+        line_span(hunk.new_start, hunk.new_count),
+        TIPS[hunk_idx % TIPS.len()]
+    )
+}
+
+/// `line 4`, `lines 4-9`, or `nothing` for a side the hunk doesn't touch
+fn line_span(start: u32, count: u32) -> String {
+    match count {
+        0 => "nothing".to_string(),
+        1 => format!("line {start}"),
+        _ => format!("lines {start}-{}", start + count - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(old_start: u32, old_count: u32, new_start: u32, new_count: u32) -> DiffHunk {
+        DiffHunk {
+            header: format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@"),
+            lines: Vec::new(),
+            old_start,
+            old_count,
+            new_start,
+            new_count,
+        }
+    }
+
+    #[test]
+    fn should_spell_out_the_hunk_header() {
+        // given
+        let changed = hunk(10, 7, 10, 8);
+        let added = hunk(0, 0, 1, 1);
+
+        // when
+        let changed = hunk_explanation(&changed, 0);
+        let added = hunk_explanation(&added, 1);
+
+        // then
+        assert!(changed.starts_with("  ← was lines 10-16, now lines 10-17; - removed, + added"));
+        assert!(changed.ends_with("tip: c comments on the cursor line"));
+        assert!(added.starts_with("  ← was nothing, now line 1;"));
+        assert!(added.contains("tip: v selects"));
+    }
+}
@@ -44,6 +44,20 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Half page down/up"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  w / b / e ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Column cursor to next word / word start / word end"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  zf / zv   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle free scroll / bring cursor into view"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Ctrl-f/b  ",
@@ -53,11 +67,18 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
         ]),
         Line::from(vec![
             Span::styled(
-                "  g/G       ",
+                "  gg/G      ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
             Span::raw("Go to first/last file"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  ga        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Jump between source and its test"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  {/}       ",
@@ -93,12 +114,33 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Expand/collapse hidden context"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  Enter     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Expand 10 lines around hunk (on @@ header)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  F         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Old vs new of function under cursor"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Tab       ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Toggle focus file list/diff"),
+            Span::raw("Cycle focus file list/diff/comments"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  d         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Delete selected comment (comments pane)"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -139,7 +181,7 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
                 "  o         ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Expand all directories"),
+            Span::raw("Expand all dirs / toggle generated file"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -148,6 +190,20 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Collapse all directories"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  J / K     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Reorder file in review plan (:plan)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  C         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Comment on selected directory"),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
             "Review Actions",
@@ -159,14 +215,21 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
                 "  r         ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Toggle file reviewed"),
+            Span::raw("Cycle file state: in progress, second look, approved"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  x         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Exclude file from the review (again to undo)"),
         ]),
         Line::from(vec![
             Span::styled(
                 "  c         ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Add line comment"),
+            Span::raw("Add line comment (on the word under the column cursor)"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -177,10 +240,52 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
         ]),
         Line::from(vec![
             Span::styled(
-                "  i         ",
+                "  >         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Comment quoting the current line"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  i / e     ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Edit comment at cursor"),
+            Span::raw("Edit comment at cursor (e on a diff line: word end)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  R         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Reply to comment at cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  m         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle resolved on comment at cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  A         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Apply suggested change at cursor to the file"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  L         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("List all comments (Enter jump, i edit, m resolve)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  1-4       ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Hide/show notes, suggestions, issues, praise"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -189,6 +294,20 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Delete comment at cursor"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  u         ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Undo comment deletion"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Y / p     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Copy comment at cursor / paste a copy on this line"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  y         ",
@@ -223,6 +342,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Create comment for selected range"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  y / Y     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Copy selected code / selected diff lines with +/-"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Esc/v/V   ",
@@ -241,7 +367,21 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
                 "  Tab       ",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
-            Span::raw("Toggle type: Note/Suggestion/Issue/Praise"),
+            Span::raw("Toggle type: Note/Suggestion/Suggested change/Issue/Praise"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  S-Tab     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle severity: none/Blocker/Major/Minor/Nit"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-T    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Toggle required action: unset/fix/answer/consider/none"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -264,6 +404,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Insert newline"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  Up/Down   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Move between rows of the comment"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Ctrl-A/E  ",
@@ -285,6 +432,48 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Line start/end (macOS)"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-W/Alt-D",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Cut word before/after cursor"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-K    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Cut to end of line"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-Y    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Paste last cut text"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-Q    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Quote the commented code"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-V    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Paste from the clipboard"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  Ctrl-R    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Restore the draft left here"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  Esc/Ctrl-C",
@@ -319,6 +508,34 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Copy review to clipboard"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :clip --preview",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" Preview the export before copying"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :clip undo",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" Restore what the last export replaced"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :trailer  ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Copy Reviewed-by/Refs trailers for the commit"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :handoff  ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Copy the files left and open comments for the next reviewer"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :set wrap ",
@@ -333,6 +550,27 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Toggle line wrap in diff view"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :set context N ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Context lines per hunk"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :set showall ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Include files from .tuicrignore (noshowall hides)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :guide    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Explain hunk headers and review keys in the diff"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :diff     ",
@@ -340,6 +578,76 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Toggle unified/side-by-side diff view"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :plan     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Reorder files into a review plan"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :struct   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Key-level diff of JSON/YAML, API summary of specs"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :api      ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Public API changes of Rust files (Enter jumps)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :impact   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Unchanged files calling changed signatures"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :tests    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Changed source files and their changed tests"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :todos    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("TODO/FIXME/HACK markers on added lines"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :stale    ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Comments whose line left the diff"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :show     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Show only these comment types (:show issues, :show all)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :attach   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Attach a file as evidence (no path: list)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :mig      ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Current migration next to its up/down pair"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :commits  ",
@@ -347,6 +655,20 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Select commits to review"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :base     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Review commits since the base branch (B)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "  :worktree ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Review another git worktree"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :clear    ",
@@ -368,6 +690,13 @@ pub fn render_help(frame: &mut Frame, app: &mut App) {
             ),
             Span::raw("Save and quit"),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  :done     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("Finish if review is complete, else jump to what's left"),
+        ]),
         Line::from(vec![
             Span::styled(
                 "  :version  ",
@@ -0,0 +1,71 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::ui::styles;
+
+pub fn render_impact(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.impact_view.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Unchanged files referencing changed signatures - Press Esc to close ")
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    view.viewport_height = inner.height as usize;
+    let max_offset = view.total_lines().saturating_sub(view.viewport_height);
+    view.scroll_offset = view.scroll_offset.min(max_offset);
+
+    let mut lines: Vec<Line> = Vec::new();
+    for impact in &view.impacts {
+        let signature = &impact.signature;
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", signature.name), styles::header_style(theme)),
+            Span::styled(
+                format!("({})  ", signature.path.display()),
+                styles::dim_style(theme),
+            ),
+            Span::styled(signature.old.as_str(), styles::diff_del_style(theme)),
+            Span::styled(" → ", styles::dim_style(theme)),
+            Span::styled(signature.new.as_str(), styles::diff_add_style(theme)),
+        ]));
+        for (path, count) in &impact.references {
+            let plural = if *count == 1 { "" } else { "es" };
+            lines.push(Line::from(vec![
+                Span::styled("  ⚠ ", styles::pending_style(theme)),
+                Span::styled(path.display().to_string(), Style::default()),
+                Span::styled(
+                    format!("  ({count} match{plural})"),
+                    styles::dim_style(theme),
+                ),
+            ]));
+        }
+    }
+    let lines: Vec<Line> = lines
+        .into_iter()
+        .skip(view.scroll_offset)
+        .take(view.viewport_height)
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
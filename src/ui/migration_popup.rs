@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::theme::Theme;
+use crate::ui::styles;
+
+pub fn render_migration(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.migration_view.as_mut() else {
+        return;
+    };
+    let area = centered_rect(90, 80, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Migration - up vs down (j/k to scroll) - Press Esc to close ")
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [up_area, down_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(inner);
+
+    // Each side has its own title line
+    view.viewport_height = inner.height.saturating_sub(1) as usize;
+    let max_offset = view.total_lines().saturating_sub(view.viewport_height);
+    view.scroll_offset = view.scroll_offset.min(max_offset);
+
+    let up_side = side_lines(
+        theme,
+        &view.up_path,
+        Some(&view.up_lines),
+        view.scroll_offset,
+        view.viewport_height,
+    );
+    let down_side = side_lines(
+        theme,
+        &view.down_path,
+        view.down_lines.as_ref(),
+        view.scroll_offset,
+        view.viewport_height,
+    );
+    frame.render_widget(Paragraph::new(up_side), up_area);
+    frame.render_widget(Paragraph::new(down_side), down_area);
+}
+
+fn side_lines<'a>(
+    theme: &Theme,
+    path: &Path,
+    lines: Option<&'a Vec<String>>,
+    scroll_offset: usize,
+    height: usize,
+) -> Vec<Line<'a>> {
+    let Some(lines) = lines else {
+        return vec![
+            Line::from(Span::styled(
+                format!(" {}", path.display()),
+                styles::header_style(theme),
+            )),
+            Line::from(Span::styled(
+                " (missing - this migration cannot be reverted)",
+                styles::pending_style(theme),
+            )),
+        ];
+    };
+
+    let mut out = vec![Line::from(Span::styled(
+        format!(" {}", path.display()),
+        styles::header_style(theme),
+    ))];
+    out.extend(
+        lines
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(height)
+            .map(|(idx, content)| {
+                Line::from(vec![
+                    Span::styled(format!("{:>5} ", idx + 1), styles::dim_style(theme)),
+                    Span::styled(content.as_str(), Style::default()),
+                ])
+            }),
+    );
+    out
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
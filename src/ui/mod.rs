@@ -1,9 +1,24 @@
 pub mod app_layout;
+pub mod attachment_popup;
+pub mod comment_list_popup;
+pub mod comment_markdown;
 pub mod comment_panel;
+pub mod comments_pane;
 pub mod diff_view;
+pub mod export_preview_popup;
 pub mod file_list;
+pub mod function_popup;
+pub mod guide;
 pub mod help_popup;
+pub mod impact_popup;
+pub mod migration_popup;
+pub mod public_api_popup;
+pub mod stale_popup;
 pub mod status_bar;
+pub mod structural_popup;
 pub mod styles;
+pub mod test_map_popup;
+pub mod todo_popup;
+pub mod worktree_popup;
 
 pub use app_layout::render;
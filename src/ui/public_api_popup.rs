@@ -0,0 +1,76 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::public_api::{ApiChangeKind, semver_impact};
+use crate::ui::styles;
+
+pub fn render_public_api(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let diff_files = &app.diff_files;
+    let Some(view) = app.public_api.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " Public API - semver: {} - Enter to jump, Esc to close ",
+            semver_impact(&view.changes)
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let height = (inner.height as usize).max(1);
+    if view.cursor < view.scroll_offset {
+        view.scroll_offset = view.cursor;
+    } else if view.cursor >= view.scroll_offset + height {
+        view.scroll_offset = view.cursor + 1 - height;
+    }
+
+    let lines: Vec<Line> = view
+        .changes
+        .iter()
+        .enumerate()
+        .skip(view.scroll_offset)
+        .take(height)
+        .map(|(i, change)| {
+            let (marker, marker_style) = match change.kind {
+                ApiChangeKind::Added => ("+ ", styles::diff_add_style(theme)),
+                ApiChangeKind::Removed => ("- ", styles::diff_del_style(theme)),
+                ApiChangeKind::Changed => ("~ ", Style::default().fg(theme.file_modified)),
+            };
+            let style = if i == view.cursor {
+                styles::selected_style(theme)
+            } else {
+                Style::default()
+            };
+            let path = diff_files[change.file_idx].display_path();
+            Line::from(vec![
+                Span::styled(if i == view.cursor { "> " } else { "  " }, style),
+                Span::styled(marker, marker_style),
+                Span::styled(format!("{}  ", change.item), style),
+                Span::styled(path.display().to_string(), styles::dim_style(theme)),
+                Span::styled(format!("  {}", change.signature), styles::dim_style(theme)),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
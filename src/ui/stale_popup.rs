@@ -0,0 +1,72 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::model::LineSide;
+use crate::ui::styles;
+
+pub fn render_stale_comments(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.stale_view.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " Stale comments ({}) - Enter to jump, Space to delete, Esc to close ",
+            view.comments.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let height = (inner.height as usize).max(1);
+    if view.cursor < view.scroll_offset {
+        view.scroll_offset = view.cursor;
+    } else if view.cursor >= view.scroll_offset + height {
+        view.scroll_offset = view.cursor + 1 - height;
+    }
+
+    let lines: Vec<Line> = view
+        .comments
+        .iter()
+        .enumerate()
+        .skip(view.scroll_offset)
+        .take(height)
+        .map(|(i, stale)| {
+            let style = if i == view.cursor {
+                styles::selected_style(theme)
+            } else {
+                Style::default()
+            };
+            let side = if stale.side == LineSide::Old { "~" } else { "" };
+            let first_line = stale.content.lines().next().unwrap_or_default();
+            Line::from(vec![
+                Span::styled(if i == view.cursor { "> " } else { "  " }, style),
+                Span::styled(
+                    format!("{}:{side}{}  ", stale.path.display(), stale.line),
+                    styles::dim_style(theme),
+                ),
+                Span::styled(first_line.to_string(), style),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
@@ -6,7 +6,9 @@ use ratatui::{
     widgets::{Block, Paragraph},
 };
 
-use crate::app::{App, DiffSource, InputMode, Message, MessageType};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{App, CommentGroup, DiffSource, FocusedPanel, InputMode, Message, MessageType};
 use crate::theme::Theme;
 use crate::ui::styles;
 
@@ -54,10 +56,16 @@ pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
     let branch = app.vcs_info.branch_name.as_deref().unwrap_or("detached");
 
     let title = " tuicr - Code Review ".to_string();
-    let vcs_info = format!("[{vcs_type}:{branch}] ");
+    let vcs_info = if let Some(snapshot) = app.session.snapshot.as_ref().filter(|_| app.read_only) {
+        format!("[snapshot {}] ", snapshot.taken_at.format("%Y-%m-%d"))
+    } else if app.is_stdin_diff() {
+        format!("[{vcs_type}] ")
+    } else {
+        format!("[{vcs_type}:{branch}] ")
+    };
 
     // Show diff source info
-    let source_info = match &app.diff_source {
+    let mut source_info = match &app.diff_source {
         DiffSource::WorkingTree => String::new(),
         DiffSource::CommitRange(commits) => {
             if commits.len() == 1 {
@@ -67,15 +75,50 @@ pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
             }
         }
     };
+    if !app.session.pathspecs.is_empty() {
+        source_info.push_str(&format!("[-- {}] ", app.session.pathspecs.join(" ")));
+    }
 
-    let progress = format!("{}/{} reviewed ", app.reviewed_count(), app.file_count());
+    let progress = format!("{} ", app.progress_summary());
+    let conflicts = match app.conflicted_file_count() {
+        0 => String::new(),
+        1 => "[1 conflicted file] ".to_string(),
+        n => format!("[{n} conflicted files] "),
+    };
+    let untested = match app.untested_paths.len() {
+        0 => String::new(),
+        n => format!("[{n} without tests] "),
+    };
+    let ignored = match app.ignored_file_count {
+        0 => String::new(),
+        n => format!("[{n} ignored] "),
+    };
+    let hidden_groups: Vec<&str> = CommentGroup::ALL
+        .iter()
+        .filter(|group| {
+            group
+                .types()
+                .iter()
+                .all(|t| app.hidden_comment_types.contains(t))
+        })
+        .map(|group| group.name())
+        .collect();
+    let hidden = if hidden_groups.is_empty() {
+        String::new()
+    } else {
+        format!("[hiding {}] ", hidden_groups.join(", "))
+    };
 
     let title_span = Span::styled(title, styles::header_style(theme));
     let vcs_span = Span::styled(vcs_info, Style::default().fg(theme.fg_secondary));
     let source_span = Span::styled(source_info, Style::default().fg(theme.diff_hunk_header));
+    let conflicts_span = Span::styled(conflicts, styles::conflict_marker_style(theme));
+    let untested_span = Span::styled(untested, styles::pending_style(theme));
+    let ignored_span = Span::styled(ignored, Style::default().fg(theme.fg_secondary));
+    let hidden_span = Span::styled(hidden, Style::default().fg(theme.fg_secondary));
     let progress_span = Span::styled(
         progress,
-        if app.reviewed_count() == app.file_count() {
+        if app.is_review_complete() {
             styles::reviewed_style(theme)
         } else {
             styles::pending_style(theme)
@@ -116,7 +159,16 @@ pub fn render_header(frame: &mut Frame, app: &App, area: Rect) {
         (Span::raw(""), 0)
     };
 
-    let left_spans = vec![title_span, vcs_span, source_span, progress_span];
+    let left_spans = vec![
+        title_span,
+        vcs_span,
+        source_span,
+        conflicts_span,
+        untested_span,
+        ignored_span,
+        hidden_span,
+        progress_span,
+    ];
     let left_width: usize = left_spans.iter().map(|s| s.content.len()).sum();
     let total_width = area.width as usize;
     let padding_width = total_width.saturating_sub(left_width + update_width);
@@ -152,19 +204,41 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
             &app.search_buffer
         };
         let command_text = format!("{prefix}{buffer}");
+        // Put the terminal cursor after the typed text so IMEs compose there
+        let width = u16::try_from(command_text.width()).unwrap_or(u16::MAX);
+        frame.set_cursor_position(ratatui::layout::Position {
+            x: area.x + width.min(area.width.saturating_sub(1)),
+            y: area.y,
+        });
         vec![Span::styled(
             command_text,
             Style::default().fg(theme.fg_primary),
         )]
     } else {
         let mode_str = match app.input_mode {
-            InputMode::Normal => " NORMAL ".to_string(),
+            // A chord prefix waiting for its second key shows after the mode
+            InputMode::Normal => match app.chords.pending() {
+                Some(prefix) => format!(" NORMAL {prefix}- "),
+                None => " NORMAL ".to_string(),
+            },
             InputMode::Command => " COMMAND ".to_string(),
             InputMode::Search => " SEARCH ".to_string(),
             InputMode::Comment => " COMMENT ".to_string(),
             InputMode::Help => " HELP ".to_string(),
+            InputMode::FunctionDiff => " FUNCTION ".to_string(),
+            InputMode::StructuralDiff => " STRUCTURE ".to_string(),
+            InputMode::Migration => " MIGRATION ".to_string(),
+            InputMode::PublicApi => " API ".to_string(),
+            InputMode::TestMap => " TESTS ".to_string(),
+            InputMode::Todos => " TODOS ".to_string(),
+            InputMode::StaleComments => " STALE ".to_string(),
+            InputMode::CommentList => " COMMENTS ".to_string(),
+            InputMode::Attachments => " ATTACHMENTS ".to_string(),
+            InputMode::Impact => " IMPACT ".to_string(),
+            InputMode::ExportPreview => " PREVIEW ".to_string(),
             InputMode::Confirm => " CONFIRM ".to_string(),
-            InputMode::CommitSelect => " SELECT ".to_string(),
+            InputMode::CommitSelect | InputMode::CommitFilter => " SELECT ".to_string(),
+            InputMode::WorktreeSelect => " WORKTREE ".to_string(),
             InputMode::VisualSelect => {
                 if let Some((range, _)) = app.get_visual_selection() {
                     if range.is_single() {
@@ -181,18 +255,36 @@ pub fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         let mode_span = Span::styled(mode_str, styles::mode_style(theme));
 
         let hints = match app.input_mode {
+            InputMode::Normal if app.focused_panel == FocusedPanel::Comments => {
+                " j/k:select  Enter:jump  i:edit  m:resolve  d:delete  Tab:focus  u:undo "
+            }
             InputMode::Normal => {
-                " j/k:scroll  {/}:file  r:reviewed  c:comment  V:visual  /:search  ?:help  :q:quit "
+                " j/k:scroll  {/}:file  r:state  c:comment  V:visual  /:search  ?:help  :q:quit "
             }
             InputMode::Command => " Enter:execute  Esc:cancel ",
             InputMode::Search => " Enter:search  Esc:cancel ",
+            InputMode::CommitFilter => " Enter:keep filter  Esc:clear ",
             InputMode::Comment => " Ctrl-S:save  Esc:cancel ",
-            InputMode::Help => " q/?/Esc:close ",
+            InputMode::Help
+            | InputMode::FunctionDiff
+            | InputMode::StructuralDiff
+            | InputMode::Migration
+            | InputMode::Impact => " q/?/Esc:close ",
             InputMode::Confirm => " y:yes  n:no ",
+            InputMode::ExportPreview => " j/k:scroll  y/Enter:copy  n/Esc:cancel ",
             InputMode::CommitSelect => {
-                " j/k:navigate  Space:select  Enter:confirm  Esc:back  q:quit "
+                " j/k:navigate  Space:select  v:range  Enter:confirm  /:filter  Esc:back  q:quit "
+            }
+            InputMode::WorktreeSelect => " j/k:navigate  Enter:review  q/Esc:cancel ",
+            InputMode::PublicApi => " j/k:navigate  Enter:jump  q/Esc:close ",
+            InputMode::TestMap => " j/k:navigate  Enter:jump  q/Esc:close ",
+            InputMode::Todos => " j/k:navigate  Enter:jump  Space:add note  q/Esc:close ",
+            InputMode::CommentList => " j/k:navigate  Enter:jump  i:edit  m:resolve  q/Esc:close ",
+            InputMode::StaleComments => {
+                " j/k:navigate  Enter:jump to file  Space:delete  q/Esc:close "
             }
-            InputMode::VisualSelect => " j/k:extend  c/Enter:comment  Esc/V:cancel ",
+            InputMode::Attachments => " j/k:navigate  Space:remove  q/Esc:close ",
+            InputMode::VisualSelect => " j/k:extend  c/Enter:comment  y:copy  Esc/V:cancel ",
         };
         let hints_span = Span::styled(hints, Style::default().fg(theme.fg_secondary));
 
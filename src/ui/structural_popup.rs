@@ -0,0 +1,104 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::api_spec::{SpecChange, SpecChangeKind};
+use crate::app::App;
+use crate::structural::StructuralChange;
+use crate::theme::Theme;
+use crate::ui::styles;
+
+pub fn render_structural_diff(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.structural_diff.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let count = view.summary.len() + view.changes.len();
+    let plural = if count == 1 { "" } else { "s" };
+    let block = Block::default()
+        .title(format!(
+            " Structure of {} ({count} change{plural}) - Press Esc to close ",
+            view.path.display()
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    view.viewport_height = inner.height as usize;
+    let max_offset = view.line_count().saturating_sub(view.viewport_height);
+    view.scroll_offset = view.scroll_offset.min(max_offset);
+
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let mut rows: Vec<Line> = Vec::with_capacity(view.line_count());
+    if !view.summary.is_empty() {
+        rows.push(Line::styled("API changes", heading));
+        rows.extend(view.summary.iter().map(|change| spec_line(theme, change)));
+        if !view.changes.is_empty() {
+            rows.push(Line::default());
+            rows.push(Line::styled("Key-level changes", heading));
+        }
+    }
+    rows.extend(view.changes.iter().map(|change| match change {
+        StructuralChange::Added { path, value } => Line::from(vec![
+            Span::styled("+ ", styles::diff_add_style(theme)),
+            Span::styled(path.as_str(), styles::diff_add_style(theme)),
+            Span::styled(format!(": {value}"), styles::diff_context_style(theme)),
+        ]),
+        StructuralChange::Removed { path, value } => Line::from(vec![
+            Span::styled("- ", styles::diff_del_style(theme)),
+            Span::styled(path.as_str(), styles::diff_del_style(theme)),
+            Span::styled(format!(": {value}"), styles::diff_context_style(theme)),
+        ]),
+        StructuralChange::Changed { path, old, new } => Line::from(vec![
+            Span::styled("~ ", Style::default().fg(theme.file_modified)),
+            Span::styled(path.as_str(), Style::default().fg(theme.file_modified)),
+            Span::raw(": "),
+            Span::styled(old.as_str(), styles::diff_del_style(theme)),
+            Span::styled(" → ", styles::dim_style(theme)),
+            Span::styled(new.as_str(), styles::diff_add_style(theme)),
+        ]),
+    }));
+
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .skip(view.scroll_offset)
+        .take(view.viewport_height)
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn spec_line<'a>(theme: &Theme, change: &'a SpecChange) -> Line<'a> {
+    let (marker, style) = match change.kind {
+        SpecChangeKind::Added => ("+ ", styles::diff_add_style(theme)),
+        SpecChangeKind::Removed => ("- ", styles::diff_del_style(theme)),
+        SpecChangeKind::Changed => ("~ ", Style::default().fg(theme.file_modified)),
+    };
+    let mut spans = vec![
+        Span::styled(marker, style),
+        Span::styled(change.item.as_str(), style),
+    ];
+    if let Some(detail) = &change.detail {
+        spans.push(Span::styled(
+            format!(": {detail}"),
+            styles::dim_style(theme),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
@@ -1,5 +1,6 @@
 use ratatui::style::{Modifier, Style};
 
+use crate::model::ReviewState;
 use crate::theme::Theme;
 
 pub fn header_style(theme: &Theme) -> Style {
@@ -24,6 +25,13 @@ pub fn diff_del_style(theme: &Theme) -> Style {
     Style::default().fg(theme.diff_del).bg(theme.diff_del_bg)
 }
 
+/// Deleted or added line that belongs to a block moved elsewhere in the diff
+pub fn diff_moved_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(theme.diff_moved)
+        .bg(theme.diff_moved_bg)
+}
+
 pub fn diff_context_style(theme: &Theme) -> Style {
     Style::default().fg(theme.diff_context)
 }
@@ -52,6 +60,16 @@ pub fn pending_style(theme: &Theme) -> Style {
     Style::default().fg(theme.pending)
 }
 
+/// Color of a file's review state in the file list
+pub fn review_state_style(theme: &Theme, state: ReviewState) -> Style {
+    match state {
+        ReviewState::Unreviewed => pending_style(theme),
+        ReviewState::InProgress => Style::default().fg(theme.in_progress),
+        ReviewState::NeedsSecondLook => Style::default().fg(theme.second_look),
+        ReviewState::Approved => reviewed_style(theme),
+    }
+}
+
 pub fn border_style(theme: &Theme, focused: bool) -> Style {
     if focused {
         Style::default().fg(theme.border_focused)
@@ -79,11 +97,21 @@ pub fn file_status_style(theme: &Theme, status: char) -> Style {
         'M' => theme.file_modified,
         'D' => theme.file_deleted,
         'R' => theme.file_renamed,
+        'C' => theme.file_copied,
+        'U' => theme.file_conflicted,
+        '@' => theme.file_renamed,
         _ => theme.fg_secondary,
     };
     Style::default().fg(color)
 }
 
+/// Style for `<<<<<<<`, `|||||||`, `=======` and `>>>>>>>` lines in conflicted files
+pub fn conflict_marker_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(theme.file_conflicted)
+        .add_modifier(Modifier::BOLD)
+}
+
 pub fn current_line_indicator_style(theme: &Theme) -> Style {
     Style::default().fg(theme.border_focused)
 }
@@ -100,18 +128,39 @@ pub fn comment_type_style(theme: &Theme, comment_type: crate::model::CommentType
     use crate::model::CommentType;
     let color = match comment_type {
         CommentType::Note => theme.comment_note,
-        CommentType::Suggestion => theme.comment_suggestion,
+        CommentType::Suggestion | CommentType::CodeSuggestion => theme.comment_suggestion,
         CommentType::Issue => theme.comment_issue,
         CommentType::Praise => theme.comment_praise,
     };
     Style::default().fg(color).add_modifier(Modifier::BOLD)
 }
 
+pub fn severity_style(theme: &Theme, severity: crate::model::Severity) -> Style {
+    use crate::model::Severity;
+    match severity {
+        Severity::Blocker => Style::default()
+            .fg(theme.comment_issue)
+            .add_modifier(Modifier::BOLD),
+        Severity::Major => Style::default()
+            .fg(theme.second_look)
+            .add_modifier(Modifier::BOLD),
+        Severity::Minor => Style::default().fg(theme.pending),
+        Severity::Nit => Style::default().fg(theme.fg_dim),
+    }
+}
+
+/// `code` spans and fenced blocks in comment text
+pub fn inline_code_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(theme.fg_secondary)
+        .bg(theme.bg_highlight)
+}
+
 pub fn comment_border_style(theme: &Theme, comment_type: crate::model::CommentType) -> Style {
     use crate::model::CommentType;
     let color = match comment_type {
         CommentType::Note => theme.comment_note,
-        CommentType::Suggestion => theme.comment_suggestion,
+        CommentType::Suggestion | CommentType::CodeSuggestion => theme.comment_suggestion,
         CommentType::Issue => theme.comment_issue,
         CommentType::Praise => theme.comment_praise,
     };
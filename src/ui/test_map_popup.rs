@@ -0,0 +1,92 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::ui::styles;
+
+pub fn render_test_map(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.test_map.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let untested = view.coverage.iter().filter(|c| c.tests.is_empty()).count();
+    let block = Block::default()
+        .title(format!(
+            " Tests - {untested} of {} source files without test changes - Enter to jump, Esc to close ",
+            view.coverage.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let height = (inner.height as usize).max(1);
+    if view.cursor < view.scroll_offset {
+        view.scroll_offset = view.cursor;
+    } else if view.cursor >= view.scroll_offset + height {
+        view.scroll_offset = view.cursor + 1 - height;
+    }
+
+    let lines: Vec<Line> = view
+        .coverage
+        .iter()
+        .enumerate()
+        .skip(view.scroll_offset)
+        .take(height)
+        .map(|(i, coverage)| {
+            let style = if i == view.cursor {
+                styles::selected_style(theme)
+            } else {
+                Style::default()
+            };
+            let (marker, tests, tests_style) = if coverage.tests.is_empty() {
+                (
+                    Span::styled("✗ ", styles::pending_style(theme)),
+                    "no test changes".to_string(),
+                    styles::pending_style(theme),
+                )
+            } else {
+                let tests: Vec<String> = coverage
+                    .tests
+                    .iter()
+                    .map(|path| {
+                        if *path == coverage.source {
+                            "inline tests".to_string()
+                        } else {
+                            path.display().to_string()
+                        }
+                    })
+                    .collect();
+                (
+                    Span::styled("✓ ", styles::reviewed_style(theme)),
+                    tests.join(", "),
+                    styles::dim_style(theme),
+                )
+            };
+            Line::from(vec![
+                Span::styled(if i == view.cursor { "> " } else { "  " }, style),
+                marker,
+                Span::styled(format!("{}  ", coverage.source.display()), style),
+                Span::styled(tests, tests_style),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
@@ -0,0 +1,69 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::ui::styles;
+
+pub fn render_todos(frame: &mut Frame, app: &mut App) {
+    let theme = &app.theme;
+    let Some(view) = app.todo_view.as_mut() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " New TODOs ({}) - Enter to jump, Space to add a note, Esc to close ",
+            view.todos.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let height = (inner.height as usize).max(1);
+    if view.cursor < view.scroll_offset {
+        view.scroll_offset = view.cursor;
+    } else if view.cursor >= view.scroll_offset + height {
+        view.scroll_offset = view.cursor + 1 - height;
+    }
+
+    let lines: Vec<Line> = view
+        .todos
+        .iter()
+        .enumerate()
+        .skip(view.scroll_offset)
+        .take(height)
+        .map(|(i, todo)| {
+            let style = if i == view.cursor {
+                styles::selected_style(theme)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::styled(if i == view.cursor { "> " } else { "  " }, style),
+                Span::styled(
+                    format!("{}:{}  ", todo.path.display(), todo.line),
+                    styles::dim_style(theme),
+                ),
+                Span::styled(todo.text.clone(), style),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
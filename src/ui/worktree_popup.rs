@@ -0,0 +1,62 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::App;
+use crate::ui::styles;
+
+pub fn render_worktree_select(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(70, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Worktrees - Enter to review, Esc to cancel ")
+        .borders(Borders::ALL)
+        .border_style(styles::border_style(theme, true));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Keep the cursor row in view when there are more worktrees than rows
+    let height = (inner.height as usize).max(1);
+    let skip = (app.worktree_cursor + 1).saturating_sub(height);
+
+    let lines: Vec<Line> = app
+        .worktree_list
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(height)
+        .map(|(i, worktree)| {
+            let is_cursor = i == app.worktree_cursor;
+            let style = if is_cursor {
+                styles::selected_style(theme)
+            } else {
+                Style::default()
+            };
+            let pointer = if is_cursor { "> " } else { "  " };
+            let marker = if worktree.is_current { "* " } else { "  " };
+            let branch = worktree.branch.as_deref().unwrap_or("(detached)");
+            Line::from(vec![
+                Span::styled(pointer, style),
+                Span::styled(marker, styles::reviewed_style(theme)),
+                Span::styled(format!("{branch}  "), styles::hash_style(theme)),
+                Span::styled(worktree.path.display().to_string(), style),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
@@ -6,8 +6,12 @@
 use std::path::PathBuf;
 
 use crate::error::{Result, TuicrError};
-use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
+use crate::model::{
+    BinaryDetail, DiffFile, DiffHunk, DiffLine, EntryKind, FileStatus, LineOrigin, SpecialChange,
+    mode_change,
+};
 use crate::syntax::SyntaxHighlighter;
+use crate::vcs::FileStat;
 
 /// Diff format variants for different VCS tools.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,7 +38,27 @@ pub fn parse_unified_diff(
 
     while let Some(line) = lines.next() {
         if line.starts_with(header_prefix) {
-            let (old_path, new_path, status) = parse_file_header(&mut lines, format);
+            let FileHeader {
+                mut old_path,
+                mut new_path,
+                status,
+                old_mode,
+                new_mode,
+                similarity,
+            } = parse_file_header(&mut lines, format);
+
+            // Mode-only changes have no ---/+++ lines; take the paths from the header
+            if old_path.is_none()
+                && new_path.is_none()
+                && let Some((old, new)) = parse_git_header_paths(line)
+            {
+                if status != FileStatus::Added {
+                    old_path = Some(old);
+                }
+                if status != FileStatus::Deleted {
+                    new_path = Some(new);
+                }
+            }
 
             // Check if binary - hg uses "Binary file", jj/git use just "Binary"
             if lines.peek().is_some_and(|l| l.contains("Binary")) {
@@ -45,6 +69,10 @@ pub fn parse_unified_diff(
                     status,
                     hunks: Vec::new(),
                     is_binary: true,
+                    special: None,
+                    similarity,
+                    binary_detail: None,
+                    mode_change: mode_change(old_mode, new_mode),
                 });
                 continue;
             }
@@ -67,12 +95,29 @@ pub fn parse_unified_diff(
                 }
             }
 
+            let special = SpecialChange::classify(
+                old_mode.and_then(EntryKind::from_mode),
+                new_mode.and_then(EntryKind::from_mode),
+                &hunks,
+            );
+            let binary_detail = match special {
+                Some(_) => None,
+                None => BinaryDetail::from_lfs_pointers(&hunks),
+            };
+            if special.is_some() || binary_detail.is_some() {
+                hunks.clear();
+            }
+
             files.push(DiffFile {
                 old_path,
                 new_path,
                 status,
                 hunks,
                 is_binary: false,
+                special,
+                similarity,
+                binary_detail,
+                mode_change: mode_change(old_mode, new_mode),
             });
         }
     }
@@ -84,16 +129,32 @@ pub fn parse_unified_diff(
     Ok(files)
 }
 
-fn parse_file_header<'a, I>(
-    lines: &mut std::iter::Peekable<I>,
-    format: DiffFormat,
-) -> (Option<PathBuf>, Option<PathBuf>, FileStatus)
+/// Metadata parsed from the lines between a `diff` header and the first hunk
+struct FileHeader {
+    old_path: Option<PathBuf>,
+    new_path: Option<PathBuf>,
+    status: FileStatus,
+    /// Octal git file modes, when the diff carries mode metadata
+    old_mode: Option<u32>,
+    new_mode: Option<u32>,
+    /// From "similarity index N%" on renames and copies
+    similarity: Option<u8>,
+}
+
+fn parse_mode(s: &str) -> Option<u32> {
+    u32::from_str_radix(s.trim(), 8).ok()
+}
+
+fn parse_file_header<'a, I>(lines: &mut std::iter::Peekable<I>, format: DiffFormat) -> FileHeader
 where
     I: Iterator<Item = &'a str>,
 {
     let mut old_path: Option<PathBuf> = None;
     let mut new_path: Option<PathBuf> = None;
     let mut status = FileStatus::Modified;
+    let mut old_mode: Option<u32> = None;
+    let mut new_mode: Option<u32> = None;
+    let mut similarity: Option<u8> = None;
 
     // Parse --- and +++ lines and metadata
     while let Some(line) = lines.peek() {
@@ -123,9 +184,27 @@ where
             break; // Done with file header
         } else if line.starts_with("new file") {
             status = FileStatus::Added;
+            new_mode = line.strip_prefix("new file mode ").and_then(parse_mode);
             lines.next();
         } else if line.starts_with("deleted file") {
             status = FileStatus::Deleted;
+            old_mode = line.strip_prefix("deleted file mode ").and_then(parse_mode);
+            lines.next();
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            old_mode = parse_mode(mode);
+            lines.next();
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            new_mode = parse_mode(mode);
+            lines.next();
+        } else if let Some(index) = line.strip_prefix("index ") {
+            // "index abc..def 100644" carries the mode when it is unchanged
+            if let Some(mode) = index.split_whitespace().nth(1).and_then(parse_mode) {
+                old_mode.get_or_insert(mode);
+                new_mode.get_or_insert(mode);
+            }
+            lines.next();
+        } else if let Some(score) = line.strip_prefix("similarity index ") {
+            similarity = score.trim_end_matches('%').parse().ok();
             lines.next();
         } else if let Some(path) = line.strip_prefix("rename from ") {
             status = FileStatus::Renamed;
@@ -169,7 +248,14 @@ where
         }
     }
 
-    (old_path, new_path, status)
+    FileHeader {
+        old_path,
+        new_path,
+        status,
+        old_mode,
+        new_mode,
+        similarity,
+    }
 }
 
 fn parse_hunk<'a, I>(
@@ -315,6 +401,42 @@ fn parse_range(s: &str) -> (u32, u32) {
     }
 }
 
+/// Added and deleted line counts per file of a git-style diff, without
+/// building hunks or highlighting
+pub fn file_stats(diff_text: &str) -> Vec<FileStat> {
+    let mut stats: Vec<FileStat> = Vec::new();
+    let mut in_hunk = false;
+    for line in diff_text.lines() {
+        if line.starts_with("diff --git ") {
+            let path = parse_git_header_paths(line)
+                .map(|(_, new)| new)
+                .unwrap_or_default();
+            stats.push(FileStat {
+                path,
+                additions: 0,
+                deletions: 0,
+            });
+            in_hunk = false;
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+        } else if in_hunk && let Some(stat) = stats.last_mut() {
+            if line.starts_with('+') {
+                stat.additions += 1;
+            } else if line.starts_with('-') {
+                stat.deletions += 1;
+            }
+        }
+    }
+    stats
+}
+
+/// Paths from a `diff --git a/<old> b/<new>` header line
+fn parse_git_header_paths(line: &str) -> Option<(PathBuf, PathBuf)> {
+    let content = line.strip_prefix("diff --git a/")?;
+    let (old, new) = content.split_once(" b/")?;
+    Some((PathBuf::from(old), PathBuf::from(new)))
+}
+
 /// Parse paths from a binary file line.
 /// Git format: "Binary files a/<old> and b/<new> differ"
 /// Hg format: "Binary file <path> has changed"
@@ -361,6 +483,45 @@ mod tests {
 
     // ============ Common tests ============
 
+    #[test]
+    fn should_count_lines_per_file() {
+        // given two files, one with a deleted line that looks like a header
+        let diff = "diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,2 +1,2 @@
+--- old
++new
++more
+diff --git a/b.txt b/b.txt
+new file mode 100644
+--- /dev/null
++++ b/b.txt
+@@ -0,0 +1 @@
++hello
+";
+
+        // when
+        let stats = file_stats(diff);
+
+        // then
+        assert_eq!(
+            stats,
+            vec![
+                FileStat {
+                    path: PathBuf::from("src/a.rs"),
+                    additions: 2,
+                    deletions: 1,
+                },
+                FileStat {
+                    path: PathBuf::from("b.txt"),
+                    additions: 1,
+                    deletions: 0,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn should_return_no_changes_for_empty_diff() {
         assert!(matches!(
@@ -801,6 +962,24 @@ rename to new.txt
         assert_eq!(files[0].hunks.len(), 1);
     }
 
+    #[test]
+    fn jj_should_parse_rename_similarity() {
+        let diff = r#"diff --git a/old.txt b/new.txt
+similarity index 92%
+rename from old.txt
+rename to new.txt
+--- a/old.txt
++++ b/new.txt
+@@ -1 +1 @@
+-old content
++new content
+"#;
+        let files =
+            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+        assert_eq!(files[0].status, FileStatus::Renamed);
+        assert_eq!(files[0].similarity, Some(92));
+    }
+
     #[test]
     fn jj_should_parse_copied_file_without_content_changes() {
         // Pure copy with no content changes
@@ -938,4 +1117,88 @@ diff --git a/b.txt b/b.txt
         assert_eq!(hunk.lines[4].old_lineno, Some(7));
         assert_eq!(hunk.lines[4].new_lineno, Some(8));
     }
+
+    #[test]
+    fn jj_should_summarize_symlink_retarget() {
+        let diff = r#"diff --git a/link b/link
+index 1234567..89abcde 120000
+--- a/link
++++ b/link
+@@ -1 +1 @@
+-../old/target
+\ No newline at end of file
++../new/target
+\ No newline at end of file
+"#;
+        let files =
+            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].hunks.is_empty());
+        assert_eq!(
+            files[0].special,
+            Some(SpecialChange::Symlink {
+                old_target: Some("../old/target".to_string()),
+                new_target: Some("../new/target".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn jj_should_summarize_file_replaced_by_symlink() {
+        let diff = r#"diff --git a/config b/config
+old mode 100644
+new mode 120000
+index 1234567..89abcde
+--- a/config
++++ b/config
+@@ -1,2 +1 @@
+-key = value
+-other = value
++config.d/default
+"#;
+        let files =
+            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+
+        assert_eq!(
+            files[0].special,
+            Some(SpecialChange::TypeChange {
+                old_kind: EntryKind::File,
+                new_kind: EntryKind::Symlink,
+            })
+        );
+    }
+
+    #[test]
+    fn jj_should_record_mode_only_change() {
+        let diff = r#"diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+"#;
+        let files =
+            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].hunks.is_empty());
+        assert_eq!(files[0].special, None);
+        assert_eq!(files[0].mode_change, Some((0o100644, 0o100755)));
+        assert_eq!(files[0].header_label(), "run.sh (mode 100644 → 100755)");
+    }
+
+    #[test]
+    fn jj_should_not_mark_regular_files_as_special() {
+        let diff = r#"diff --git a/file.txt b/file.txt
+index 1234567..89abcde 100644
+--- a/file.txt
++++ b/file.txt
+@@ -1 +1 @@
+-old
++new
+"#;
+        let files =
+            parse_unified_diff(diff, DiffFormat::GitStyle, &SyntaxHighlighter::default()).unwrap();
+
+        assert!(files[0].special.is_none());
+        assert_eq!(files[0].hunks.len(), 1);
+    }
 }
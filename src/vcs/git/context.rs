@@ -3,6 +3,7 @@ use std::path::Path;
 
 use crate::error::{Result, TuicrError};
 use crate::model::{DiffLine, FileStatus, LineOrigin};
+use crate::vcs::traits::FileVersions;
 
 /// Fetch context lines from a file for gap expansion.
 ///
@@ -61,6 +62,55 @@ fn fetch_blob_content(repo: &Repository, file_path: &Path) -> Result<String> {
     Ok(content.to_string())
 }
 
+/// Read both versions of a file. Without `commit_ids` they are HEAD's blob and
+/// the working tree file; with them (oldest first) the blobs in the oldest
+/// commit's parent and in the newest commit.
+pub fn file_versions(
+    repo: &Repository,
+    old_path: &Path,
+    new_path: &Path,
+    commit_ids: Option<&[String]>,
+) -> Result<FileVersions> {
+    let Some(commit_ids) = commit_ids else {
+        let old = match repo.head().and_then(|head| head.peel_to_tree()) {
+            Ok(tree) => tree_file(repo, &tree, old_path)?,
+            // An unborn branch has no HEAD version of anything
+            Err(_) => None,
+        };
+        let workdir = repo.workdir().ok_or(TuicrError::NotARepository)?;
+        let new = match std::fs::read_to_string(workdir.join(new_path)) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        return Ok(FileVersions { old, new });
+    };
+
+    let (Some(oldest), Some(newest)) = (commit_ids.first(), commit_ids.last()) else {
+        return Err(TuicrError::NoChanges);
+    };
+    let oldest = repo.find_commit(git2::Oid::from_str(oldest)?)?;
+    let old = match oldest.parent(0) {
+        Ok(parent) => tree_file(repo, &parent.tree()?, old_path)?,
+        // The root commit adds every file it contains
+        Err(_) => None,
+    };
+    let newest = repo.find_commit(git2::Oid::from_str(newest)?)?;
+    let new = tree_file(repo, &newest.tree()?, new_path)?;
+    Ok(FileVersions { old, new })
+}
+
+/// Content of the blob at `path` in `tree`, None if the tree has no such file
+fn tree_file(repo: &Repository, tree: &git2::Tree, path: &Path) -> Result<Option<String>> {
+    let Ok(entry) = tree.get_path(path) else {
+        return Ok(None);
+    };
+    let blob = repo.find_blob(entry.id())?;
+    let content = std::str::from_utf8(blob.content())
+        .map_err(|e| TuicrError::CorruptedSession(format!("Invalid UTF-8 in file: {e}")))?;
+    Ok(Some(content.to_string()))
+}
+
 /// Calculate the number of hidden lines (gap) before a hunk.
 ///
 /// Returns the count of lines between the end of the previous hunk
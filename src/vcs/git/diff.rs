@@ -1,12 +1,17 @@
-use git2::{Delta, Diff, DiffOptions, Repository};
+use git2::{Delta, Diff, DiffFindOptions, DiffOptions, FileMode, Repository};
 use std::path::PathBuf;
 
 use crate::error::{Result, TuicrError};
-use crate::model::{DiffFile, DiffHunk, DiffLine, FileStatus, LineOrigin};
+use crate::model::{
+    BinaryDetail, DiffFile, DiffHunk, DiffLine, EntryKind, FileStatus, LineOrigin, SpecialChange,
+    mode_change,
+};
 use crate::syntax::SyntaxHighlighter;
+use crate::vcs::traits::DiffSettings;
 
 pub fn get_working_tree_diff(
     repo: &Repository,
+    settings: DiffSettings,
     highlighter: &SyntaxHighlighter,
 ) -> Result<Vec<DiffFile>> {
     let head = repo.head()?.peel_to_tree()?;
@@ -15,10 +20,92 @@ pub fn get_working_tree_diff(
     opts.include_untracked(true);
     opts.show_untracked_content(true);
     opts.recurse_untracked_dirs(true);
+    opts.context_lines(settings.context_lines);
+    // Report file <-> symlink swaps as one entry instead of a delete + add pair
+    opts.include_typechange(true);
 
-    let diff = repo.diff_tree_to_workdir_with_index(Some(&head), Some(&mut opts))?;
+    let mut diff = repo.diff_tree_to_workdir_with_index(Some(&head), Some(&mut opts))?;
+    detect_similar(&mut diff, settings)?;
 
-    parse_diff(&diff, highlighter)
+    let conflicted = conflicted_paths(repo)?;
+    let mut files = match parse_diff(repo, &diff, highlighter) {
+        Err(TuicrError::NoChanges) if !conflicted.is_empty() => Vec::new(),
+        result => result?,
+    };
+    for path in conflicted {
+        let file = conflicted_file(repo, &head, &path, settings, highlighter)?;
+        files.retain(|f| f.display_path() != &path);
+        files.push(file);
+    }
+    if settings.expand_submodules {
+        expand_submodule_logs(repo, &mut files);
+    }
+    Ok(files)
+}
+
+/// Paths with unmerged index entries (a merge, rebase or cherry-pick stopped on conflicts)
+fn conflicted_paths(repo: &Repository) -> Result<Vec<PathBuf>> {
+    let index = repo.index()?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+/// Diff HEAD's version of a conflicted path against the working tree file, so
+/// the conflict markers show up as added lines next to "ours"
+fn conflicted_file(
+    repo: &Repository,
+    head: &git2::Tree,
+    path: &PathBuf,
+    settings: DiffSettings,
+    highlighter: &SyntaxHighlighter,
+) -> Result<DiffFile> {
+    let ours = match head.get_path(path) {
+        Ok(entry) => repo.find_blob(entry.id())?.content().to_vec(),
+        Err(_) => Vec::new(),
+    };
+    let workdir = repo.workdir().ok_or(TuicrError::NotARepository)?;
+    let merged = std::fs::read(workdir.join(path)).unwrap_or_default();
+
+    let mut opts = DiffOptions::new();
+    opts.context_lines(settings.context_lines);
+    let patch = git2::Patch::from_buffers(
+        &ours,
+        Some(path.as_path()),
+        &merged,
+        Some(path.as_path()),
+        Some(&mut opts),
+    )?;
+    let is_binary = patch.delta().old_file().is_binary() || patch.delta().new_file().is_binary();
+    let hunks = if is_binary {
+        Vec::new()
+    } else {
+        patch_hunks(&patch, Some(path), highlighter)?
+    };
+
+    Ok(DiffFile {
+        old_path: Some(path.clone()),
+        new_path: Some(path.clone()),
+        status: FileStatus::Conflicted,
+        hunks,
+        is_binary,
+        special: None,
+        similarity: None,
+        binary_detail: None,
+        mode_change: None,
+    })
 }
 
 /// Get the diff for a range of commits.
@@ -27,6 +114,7 @@ pub fn get_working_tree_diff(
 pub fn get_commit_range_diff(
     repo: &Repository,
     commit_ids: &[String],
+    settings: DiffSettings,
     highlighter: &SyntaxHighlighter,
 ) -> Result<Vec<DiffFile>> {
     if commit_ids.is_empty() {
@@ -50,12 +138,48 @@ pub fn get_commit_range_diff(
 
     let new_tree = newest_commit.tree()?;
 
-    let diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+    let mut opts = DiffOptions::new();
+    opts.include_typechange(true);
+    opts.context_lines(settings.context_lines);
+
+    let mut diff = repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(&mut opts))?;
+    detect_similar(&mut diff, settings)?;
+
+    let mut files = parse_diff(repo, &diff, highlighter)?;
+    if settings.expand_submodules {
+        expand_submodule_logs(repo, &mut files);
+    }
+    Ok(files)
+}
 
-    parse_diff(&diff, highlighter)
+/// Pair up deleted and added files that are similar enough to be renames,
+/// including untracked files that replace a deleted tracked file. With
+/// `find_copies`, added files are also matched against modified files.
+fn detect_similar(diff: &mut Diff, settings: DiffSettings) -> Result<()> {
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true);
+    find_opts.for_untracked(true);
+    find_opts.copies(settings.find_copies);
+    diff.find_similar(Some(&mut find_opts))?;
+    Ok(())
 }
 
-fn parse_diff(diff: &Diff, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+/// Read the "similarity index N%" line git2 writes into the patch header
+fn similarity_index(diff: &Diff, delta_idx: usize) -> Option<u8> {
+    let mut patch = git2::Patch::from_diff(diff, delta_idx).ok()??;
+    let buf = patch.to_buf().ok()?;
+    String::from_utf8_lossy(&buf)
+        .lines()
+        .take_while(|line| !line.starts_with("@@"))
+        .find_map(|line| line.strip_prefix("similarity index "))
+        .and_then(|score| score.trim_end_matches('%').parse().ok())
+}
+
+fn parse_diff(
+    repo: &Repository,
+    diff: &Diff,
+    highlighter: &SyntaxHighlighter,
+) -> Result<Vec<DiffFile>> {
     let mut files: Vec<DiffFile> = Vec::new();
 
     for (delta_idx, delta) in diff.deltas().enumerate() {
@@ -65,20 +189,49 @@ fn parse_diff(diff: &Diff, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFi
             Delta::Modified => FileStatus::Modified,
             Delta::Renamed => FileStatus::Renamed,
             Delta::Copied => FileStatus::Copied,
+            Delta::Conflicted => FileStatus::Conflicted,
             _ => FileStatus::Modified,
         };
 
         let old_path = delta.old_file().path().map(PathBuf::from);
         let new_path = delta.new_file().path().map(PathBuf::from);
-        let is_binary = delta.old_file().is_binary() || delta.new_file().is_binary();
 
         // Use new_path for highlighting (the current version of the file)
         let file_path = new_path.as_ref().or(old_path.as_ref());
 
-        let hunks = if is_binary {
-            Vec::new()
+        // Workdir content is only sniffed for binary data once the patch is
+        // loaded, so read the flags after parsing the hunks
+        let mut hunks = parse_hunks(diff, delta_idx, file_path, highlighter)?;
+        let is_binary = diff
+            .get_delta(delta_idx)
+            .is_some_and(|d| d.old_file().is_binary() || d.new_file().is_binary());
+        if is_binary {
+            hunks.clear();
+        }
+
+        let special = SpecialChange::classify(
+            entry_kind(delta.old_file().mode()),
+            entry_kind(delta.new_file().mode()),
+            &hunks,
+        );
+        let binary_detail = if is_binary {
+            Some(BinaryDetail {
+                old_size: blob_size(repo, &delta.old_file()),
+                new_size: blob_size(repo, &delta.new_file()),
+                ..BinaryDetail::default()
+            })
+        } else if special.is_none() {
+            BinaryDetail::from_lfs_pointers(&hunks)
         } else {
-            parse_hunks(diff, delta_idx, file_path, highlighter)?
+            None
+        };
+        if special.is_some() || binary_detail.is_some() {
+            hunks.clear();
+        }
+
+        let similarity = match status {
+            FileStatus::Renamed | FileStatus::Copied => similarity_index(diff, delta_idx),
+            _ => None,
         };
 
         files.push(DiffFile {
@@ -87,6 +240,13 @@ fn parse_diff(diff: &Diff, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFi
             status,
             hunks,
             is_binary,
+            special,
+            similarity,
+            binary_detail,
+            mode_change: mode_change(
+                Some(u32::from(delta.old_file().mode())),
+                Some(u32::from(delta.new_file().mode())),
+            ),
         });
     }
 
@@ -97,99 +257,210 @@ fn parse_diff(diff: &Diff, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFi
     Ok(files)
 }
 
+/// Size of one side of a binary delta. Working tree files are not loaded
+/// into the delta, so fall back to the object database and then the file.
+fn blob_size(repo: &Repository, file: &git2::DiffFile) -> Option<u64> {
+    if !file.exists() {
+        return None;
+    }
+    if file.size() > 0 {
+        return Some(file.size());
+    }
+    if !file.id().is_zero()
+        && let Ok(blob) = repo.find_blob(file.id())
+    {
+        return Some(blob.size() as u64);
+    }
+    let path = repo.workdir()?.join(file.path()?);
+    std::fs::metadata(path).ok().map(|meta| meta.len())
+}
+
+/// Most commits listed for a single submodule pointer change
+const MAX_SUBMODULE_LOG: usize = 50;
+
+/// Replace the summary of each moved submodule that is checked out with a
+/// hunk listing the commits between the old and new pointer
+fn expand_submodule_logs(repo: &Repository, files: &mut [DiffFile]) {
+    for file in files.iter_mut() {
+        let Some(
+            special @ SpecialChange::Submodule {
+                old_commit: Some(old),
+                new_commit: Some(new),
+                ..
+            },
+        ) = &file.special
+        else {
+            continue;
+        };
+        if old == new {
+            continue;
+        }
+
+        let Some(path) = file.new_path.as_ref().and_then(|p| p.to_str()) else {
+            continue;
+        };
+        let header = special.summary();
+        if let Some(hunk) = submodule_log_hunk(repo, path, old, new, header) {
+            file.hunks = vec![hunk];
+        }
+    }
+}
+
+fn submodule_log_hunk(
+    repo: &Repository,
+    path: &str,
+    old: &str,
+    new: &str,
+    summary: String,
+) -> Option<DiffHunk> {
+    let sub_repo = repo.find_submodule(path).ok()?.open().ok()?;
+
+    let mut walk = sub_repo.revwalk().ok()?;
+    walk.push(git2::Oid::from_str(new).ok()?).ok()?;
+    walk.hide(git2::Oid::from_str(old).ok()?).ok()?;
+
+    let lines: Vec<DiffLine> = walk
+        .filter_map(|oid| oid.ok())
+        .take(MAX_SUBMODULE_LOG)
+        .filter_map(|oid| {
+            let commit = sub_repo.find_commit(oid).ok()?;
+            let short_id = &oid.to_string()[..7];
+            Some(DiffLine {
+                origin: LineOrigin::Addition,
+                content: format!("{short_id} {}", commit.summary().unwrap_or("")),
+                old_lineno: None,
+                new_lineno: None,
+                highlighted_spans: None,
+            })
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let count = lines.len();
+    let plural = if count == 1 { "" } else { "s" };
+    Some(DiffHunk {
+        header: format!("{summary} ({count} commit{plural})"),
+        lines,
+        old_start: 0,
+        old_count: 0,
+        new_start: 0,
+        new_count: 0,
+    })
+}
+
+/// Map a git2 file mode to an entry kind (None for absent or unknown entries)
+fn entry_kind(mode: FileMode) -> Option<EntryKind> {
+    match mode {
+        FileMode::Blob => Some(EntryKind::File),
+        FileMode::BlobExecutable => Some(EntryKind::Executable),
+        FileMode::Link => Some(EntryKind::Symlink),
+        FileMode::Commit => Some(EntryKind::Submodule),
+        _ => None,
+    }
+}
+
 fn parse_hunks(
     diff: &Diff,
     delta_idx: usize,
     file_path: Option<&PathBuf>,
     highlighter: &SyntaxHighlighter,
 ) -> Result<Vec<DiffHunk>> {
-    let mut hunks: Vec<DiffHunk> = Vec::new();
-
-    let patch = git2::Patch::from_diff(diff, delta_idx)?;
+    match git2::Patch::from_diff(diff, delta_idx)? {
+        Some(patch) => patch_hunks(&patch, file_path, highlighter),
+        None => Ok(Vec::new()),
+    }
+}
 
-    if let Some(patch) = patch {
-        for hunk_idx in 0..patch.num_hunks() {
-            let (hunk, _) = patch.hunk(hunk_idx)?;
+fn patch_hunks(
+    patch: &git2::Patch,
+    file_path: Option<&PathBuf>,
+    highlighter: &SyntaxHighlighter,
+) -> Result<Vec<DiffHunk>> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
 
-            let header = String::from_utf8_lossy(hunk.header()).trim().to_string();
-            let old_start = hunk.old_start();
-            let old_count = hunk.old_lines();
-            let new_start = hunk.new_start();
-            let new_count = hunk.new_lines();
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, _) = patch.hunk(hunk_idx)?;
 
-            let mut lines: Vec<DiffLine> = Vec::new();
+        let header = String::from_utf8_lossy(hunk.header()).trim().to_string();
+        let old_start = hunk.old_start();
+        let old_count = hunk.old_lines();
+        let new_start = hunk.new_start();
+        let new_count = hunk.new_lines();
 
-            // First, collect all line content for syntax highlighting
-            let mut line_contents: Vec<String> = Vec::new();
-            let mut line_origins: Vec<LineOrigin> = Vec::new();
+        let mut lines: Vec<DiffLine> = Vec::new();
 
-            for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
-                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+        // First, collect all line content for syntax highlighting
+        let mut line_contents: Vec<String> = Vec::new();
+        let mut line_origins: Vec<LineOrigin> = Vec::new();
 
-                let origin = match line.origin() {
-                    '+' => LineOrigin::Addition,
-                    '-' => LineOrigin::Deletion,
-                    ' ' => LineOrigin::Context,
-                    _ => LineOrigin::Context,
-                };
+        for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
 
-                let content = String::from_utf8_lossy(line.content())
-                    .trim_end_matches('\n')
-                    .trim_end_matches('\r')
-                    .replace('\t', "    ")
-                    .to_string();
+            let origin = match line.origin() {
+                '+' => LineOrigin::Addition,
+                '-' => LineOrigin::Deletion,
+                ' ' => LineOrigin::Context,
+                _ => LineOrigin::Context,
+            };
 
-                line_contents.push(content);
-                line_origins.push(origin);
-            }
+            let content = String::from_utf8_lossy(line.content())
+                .trim_end_matches('\n')
+                .trim_end_matches('\r')
+                .replace('\t', "    ")
+                .to_string();
 
-            // Apply syntax highlighting if we have a file path
-            let highlight_sequences =
-                SyntaxHighlighter::split_diff_lines_for_highlighting(&line_contents, &line_origins);
-            let (old_highlighted_lines, new_highlighted_lines) = if let Some(path) = file_path {
-                (
-                    highlighter.highlight_file_lines(path, &highlight_sequences.old_lines),
-                    highlighter.highlight_file_lines(path, &highlight_sequences.new_lines),
-                )
-            } else {
-                (None, None)
-            };
+            line_contents.push(content);
+            line_origins.push(origin);
+        }
 
-            // Now create DiffLines with syntax highlighting applied
-            for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
-                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
-                let old_lineno = line.old_lineno();
-                let new_lineno = line.new_lineno();
-                let content = line_contents[line_idx].clone();
-                let origin = line_origins[line_idx];
-
-                // Get highlighted spans and apply diff background
-                let highlighted_spans = highlighter.highlighted_line_for_diff_with_background(
-                    old_highlighted_lines.as_deref(),
-                    new_highlighted_lines.as_deref(),
-                    highlight_sequences.old_line_indices[line_idx],
-                    highlight_sequences.new_line_indices[line_idx],
-                    origin,
-                );
-
-                lines.push(DiffLine {
-                    origin,
-                    content,
-                    old_lineno,
-                    new_lineno,
-                    highlighted_spans,
-                });
-            }
+        // Apply syntax highlighting if we have a file path
+        let highlight_sequences =
+            SyntaxHighlighter::split_diff_lines_for_highlighting(&line_contents, &line_origins);
+        let (old_highlighted_lines, new_highlighted_lines) = if let Some(path) = file_path {
+            (
+                highlighter.highlight_file_lines(path, &highlight_sequences.old_lines),
+                highlighter.highlight_file_lines(path, &highlight_sequences.new_lines),
+            )
+        } else {
+            (None, None)
+        };
 
-            hunks.push(DiffHunk {
-                header,
-                lines,
-                old_start,
-                old_count,
-                new_start,
-                new_count,
+        // Now create DiffLines with syntax highlighting applied
+        for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let old_lineno = line.old_lineno();
+            let new_lineno = line.new_lineno();
+            let content = line_contents[line_idx].clone();
+            let origin = line_origins[line_idx];
+
+            // Get highlighted spans and apply diff background
+            let highlighted_spans = highlighter.highlighted_line_for_diff_with_background(
+                old_highlighted_lines.as_deref(),
+                new_highlighted_lines.as_deref(),
+                highlight_sequences.old_line_indices[line_idx],
+                highlight_sequences.new_line_indices[line_idx],
+                origin,
+            );
+
+            lines.push(DiffLine {
+                origin,
+                content,
+                old_lineno,
+                new_lineno,
+                highlighted_spans,
             });
         }
+
+        hunks.push(DiffHunk {
+            header,
+            lines,
+            old_start,
+            old_count,
+            new_start,
+            new_count,
+        });
     }
 
     Ok(hunks)
@@ -210,9 +481,39 @@ mod tests {
         let highlighter = SyntaxHighlighter::default();
 
         // when
-        let result = parse_diff(&diff, &highlighter);
+        let result = parse_diff(&repo, &diff, &highlighter);
 
         // then
         assert!(matches!(result, Err(TuicrError::NoChanges)));
     }
+
+    #[test]
+    fn should_report_binary_sizes_before_and_after() {
+        // given a committed binary file that grows in the working tree
+        let temp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        let path = temp.path().join("image.bin");
+        std::fs::write(&path, [0u8; 100]).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("image.bin")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        std::fs::write(&path, [0u8; 300]).unwrap();
+
+        // when
+        let files = get_working_tree_diff(
+            &repo,
+            DiffSettings::default(),
+            &SyntaxHighlighter::default(),
+        )
+        .unwrap();
+
+        // then
+        let detail = files[0].binary_detail.as_ref().unwrap();
+        assert_eq!(detail.old_size, Some(100));
+        assert_eq!(detail.new_size, Some(300));
+        assert!(!detail.lfs);
+    }
 }
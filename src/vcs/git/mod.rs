@@ -9,23 +9,26 @@ use crate::error::{Result, TuicrError};
 use crate::model::{DiffFile, DiffLine, FileStatus};
 use crate::syntax::SyntaxHighlighter;
 
-use super::traits::{CommitInfo, VcsBackend, VcsInfo, VcsType};
+use super::traits::{
+    CommitInfo, CommitQuery, DiffSettings, FileStat, FileVersions, VcsBackend, VcsInfo, VcsType,
+    WorktreeInfo,
+};
 
 // Re-export commonly used functions
-pub use context::{calculate_gap, fetch_context_lines};
+pub use context::{calculate_gap, fetch_context_lines, file_versions};
 pub use diff::{get_commit_range_diff, get_working_tree_diff};
 
 /// Git backend implementation using git2 library
 pub struct GitBackend {
     repo: Repository,
     info: VcsInfo,
+    settings: DiffSettings,
 }
 
 impl GitBackend {
-    /// Discover a git repository from the current directory
-    pub fn discover() -> Result<Self> {
-        let cwd = std::env::current_dir().map_err(|_| TuicrError::NotARepository)?;
-        let repo = Repository::discover(&cwd).map_err(|_| TuicrError::NotARepository)?;
+    /// Discover the git repository containing `path`
+    pub fn discover_at(path: &Path) -> Result<Self> {
+        let repo = Repository::discover(path).map_err(|_| TuicrError::NotARepository)?;
 
         let root_path = repo
             .workdir()
@@ -39,13 +42,7 @@ impl GitBackend {
             .map(|c| c.id().to_string())
             .unwrap_or_else(|| "HEAD".to_string());
 
-        let branch_name = repo.head().ok().and_then(|h| {
-            if h.is_branch() {
-                h.shorthand().map(|s| s.to_string())
-            } else {
-                None
-            }
-        });
+        let branch_name = repository::head_branch_name(&repo);
 
         let info = VcsInfo {
             root_path,
@@ -54,7 +51,11 @@ impl GitBackend {
             vcs_type: VcsType::Git,
         };
 
-        Ok(Self { repo, info })
+        Ok(Self {
+            repo,
+            info,
+            settings: DiffSettings::default(),
+        })
     }
 }
 
@@ -63,8 +64,12 @@ impl VcsBackend for GitBackend {
         &self.info
     }
 
+    fn set_diff_settings(&mut self, settings: DiffSettings) {
+        self.settings = settings;
+    }
+
     fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
-        get_working_tree_diff(&self.repo, highlighter)
+        get_working_tree_diff(&self.repo, self.settings, highlighter)
     }
 
     fn fetch_context_lines(
@@ -77,8 +82,13 @@ impl VcsBackend for GitBackend {
         fetch_context_lines(&self.repo, file_path, file_status, start_line, end_line)
     }
 
-    fn get_recent_commits(&self, offset: usize, limit: usize) -> Result<Vec<CommitInfo>> {
-        let git_commits = repository::get_recent_commits(&self.repo, offset, limit)?;
+    fn get_recent_commits(
+        &self,
+        offset: usize,
+        limit: usize,
+        query: &CommitQuery,
+    ) -> Result<Vec<CommitInfo>> {
+        let git_commits = repository::get_recent_commits(&self.repo, offset, limit, query)?;
         Ok(git_commits
             .into_iter()
             .map(|c| CommitInfo {
@@ -91,11 +101,36 @@ impl VcsBackend for GitBackend {
             .collect())
     }
 
+    fn get_commit_message(&self, commit_id: &str) -> Result<Option<String>> {
+        repository::get_commit_message(&self.repo, commit_id).map(Some)
+    }
+
+    fn get_commit_stats(&self, commit_id: &str) -> Result<Vec<FileStat>> {
+        repository::get_commit_stats(&self.repo, commit_id)
+    }
+
+    fn get_commits_since_base(&self, base: Option<&str>) -> Result<(String, Vec<String>)> {
+        repository::commits_since_base(&self.repo, base)
+    }
+
     fn get_commit_range_diff(
         &self,
         commit_ids: &[String],
         highlighter: &SyntaxHighlighter,
     ) -> Result<Vec<DiffFile>> {
-        get_commit_range_diff(&self.repo, commit_ids, highlighter)
+        get_commit_range_diff(&self.repo, commit_ids, self.settings, highlighter)
+    }
+
+    fn get_file_versions(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        commit_ids: Option<&[String]>,
+    ) -> Result<FileVersions> {
+        file_versions(&self.repo, old_path, new_path, commit_ids)
+    }
+
+    fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        repository::list_worktrees(&self.repo)
     }
 }
@@ -1,7 +1,9 @@
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
 use git2::Repository;
+use std::path::{Path, PathBuf};
 
-use crate::error::Result;
+use crate::error::{Result, TuicrError};
+use crate::vcs::{CommitQuery, FileStat, WorktreeInfo};
 
 #[derive(Debug, Clone)]
 pub struct CommitInfo {
@@ -12,19 +14,52 @@ pub struct CommitInfo {
     pub time: DateTime<Utc>,
 }
 
+/// Commits reachable from HEAD that match `query`, newest first.
+/// `offset` skips matching commits, so paging works on the filtered history.
 pub fn get_recent_commits(
     repo: &Repository,
     offset: usize,
     limit: usize,
+    query: &CommitQuery,
 ) -> Result<Vec<CommitInfo>> {
     let mut revwalk = repo.revwalk()?;
     revwalk.push_head()?;
+    // Time order lets the walk stop at the first commit before `since`
+    let since = query
+        .since
+        .map(|day| day.and_time(NaiveTime::MIN).and_utc().timestamp());
+    if since.is_some() {
+        revwalk.set_sorting(git2::Sort::TIME)?;
+    }
+    let author = query.author.as_deref().map(str::to_lowercase);
 
     let mut commits = Vec::new();
-    for oid in revwalk.skip(offset).take(limit) {
+    let mut skipped = 0;
+    for oid in revwalk {
+        if commits.len() >= limit {
+            break;
+        }
         let oid = oid?;
         let commit = repo.find_commit(oid)?;
 
+        if let Some(since) = since
+            && commit.time().seconds() < since
+        {
+            break;
+        }
+        if let Some(needle) = &author {
+            let signature = commit.author();
+            let name = signature.name().unwrap_or_default().to_lowercase();
+            let email = signature.email().unwrap_or_default().to_lowercase();
+            if !name.contains(needle) && !email.contains(needle) {
+                continue;
+            }
+        }
+        if skipped < offset {
+            skipped += 1;
+            continue;
+        }
+
         let id = oid.to_string();
         let short_id = id[..7.min(id.len())].to_string();
         let summary = commit.summary().unwrap_or("(no message)").to_string();
@@ -45,3 +80,331 @@ pub fn get_recent_commits(
 
     Ok(commits)
 }
+
+/// Refs tried, in order, when `origin/HEAD` is not set
+const BASE_CANDIDATES: &[&str] = &[
+    "refs/remotes/origin/main",
+    "refs/remotes/origin/master",
+    "refs/heads/main",
+    "refs/heads/master",
+];
+
+/// The branch feature work merges into: the target of `origin/HEAD`, else the
+/// first of origin/main, origin/master, main and master that exists.
+/// Returns its short name and commit.
+fn default_base(repo: &Repository) -> Option<(String, git2::Oid)> {
+    if let Ok(reference) = repo.find_reference("refs/remotes/origin/HEAD")
+        && let Ok(resolved) = reference.resolve()
+        && let Some(oid) = resolved.target()
+    {
+        let name = resolved.shorthand().unwrap_or("origin/HEAD").to_string();
+        return Some((name, oid));
+    }
+    BASE_CANDIDATES.iter().find_map(|name| {
+        let reference = repo.find_reference(name).ok()?;
+        Some((reference.shorthand()?.to_string(), reference.target()?))
+    })
+}
+
+/// `branch` on origin, else the local branch of that name
+fn named_base(repo: &Repository, branch: &str) -> Option<(String, git2::Oid)> {
+    [
+        format!("refs/remotes/origin/{branch}"),
+        format!("refs/heads/{branch}"),
+    ]
+    .iter()
+    .find_map(|name| {
+        let reference = repo.find_reference(name).ok()?;
+        Some((reference.shorthand()?.to_string(), reference.target()?))
+    })
+}
+
+/// Commits on HEAD's first-parent line since it forked from `base`, or the
+/// default base branch when None, oldest first, along with the base branch
+/// name
+pub fn commits_since_base(repo: &Repository, base: Option<&str>) -> Result<(String, Vec<String>)> {
+    let (base_name, base_oid) = match base {
+        Some(branch) => named_base(repo, branch).ok_or_else(|| {
+            TuicrError::VcsCommand(format!(
+                "Base branch {branch} not found locally or on origin (is it fetched?)"
+            ))
+        })?,
+        None => default_base(repo).ok_or_else(|| {
+            TuicrError::VcsCommand("No origin/HEAD, main or master branch found".into())
+        })?,
+    };
+    let head = repo.head()?.peel_to_commit()?.id();
+    let merge_base = repo.merge_base(base_oid, head)?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head)?;
+    revwalk.hide(merge_base)?;
+    revwalk.simplify_first_parent()?;
+    let mut ids = Vec::new();
+    for oid in revwalk {
+        ids.push(oid?.to_string());
+    }
+    ids.reverse();
+    Ok((base_name, ids))
+}
+
+/// Full message of a commit
+pub fn get_commit_message(repo: &Repository, commit_id: &str) -> Result<String> {
+    let commit = repo.find_commit(git2::Oid::from_str(commit_id)?)?;
+    Ok(String::from_utf8_lossy(commit.message_bytes()).into_owned())
+}
+
+/// Files changed by a commit against its first parent, with line counts
+pub fn get_commit_stats(repo: &Repository, commit_id: &str) -> Result<Vec<FileStat>> {
+    let commit = repo.find_commit(git2::Oid::from_str(commit_id)?)?;
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+    let mut stats = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+            continue;
+        };
+        let (additions, deletions) = match git2::Patch::from_diff(&diff, idx)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            }
+            // Binary files have no lines to count
+            None => (0, 0),
+        };
+        stats.push(FileStat {
+            path: path.to_path_buf(),
+            additions,
+            deletions,
+        });
+    }
+    Ok(stats)
+}
+
+/// Short name of the checked-out branch, or `None` for a detached HEAD
+pub fn head_branch_name(repo: &Repository) -> Option<String> {
+    repo.head().ok().and_then(|h| {
+        if h.is_branch() {
+            h.shorthand().map(|s| s.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// List the main worktree followed by every linked worktree.
+///
+/// Works from inside a linked worktree too, since linked worktrees share the
+/// main repository's common directory. Pruned or missing worktrees are skipped.
+pub fn list_worktrees(repo: &Repository) -> Result<Vec<WorktreeInfo>> {
+    let main_repo = Repository::open(repo.commondir())?;
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    if let Some(workdir) = main_repo.workdir() {
+        paths.push(workdir.to_path_buf());
+    }
+    for name in main_repo.worktrees()?.iter().flatten() {
+        let Ok(worktree) = main_repo.find_worktree(name) else {
+            continue;
+        };
+        if worktree.validate().is_ok() {
+            paths.push(worktree.path().to_path_buf());
+        }
+    }
+
+    let current = repo.workdir().map(canonical);
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let branch = Repository::open(&path)
+                .ok()
+                .and_then(|wt| head_branch_name(&wt));
+            let is_current = current.as_deref() == Some(canonical(&path).as_path());
+            WorktreeInfo {
+                path,
+                branch,
+                is_current,
+            }
+        })
+        .collect())
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_file(repo: &Repository, name: &str) {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(name), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+    }
+
+    fn commit_on_head(repo: &Repository, name: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join(name), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &[&parent])
+            .unwrap()
+    }
+
+    #[test]
+    fn should_filter_recent_commits_by_author() {
+        // given commits by two authors
+        let temp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        commit_file(&repo, "a.txt");
+        commit_on_head(&repo, "b.txt");
+        let workdir = repo.workdir().unwrap();
+        std::fs::write(workdir.join("c.txt"), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("c.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let alice = git2::Signature::now("Alice Liddell", "alice@example.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &alice, &alice, "c.txt", &tree, &[&parent])
+            .unwrap();
+        let query = CommitQuery {
+            author: Some("ALICE".to_string()),
+            since: None,
+        };
+
+        // when
+        let commits = get_recent_commits(&repo, 0, 10, &query).unwrap();
+        let others = get_recent_commits(&repo, 1, 10, &CommitQuery::default()).unwrap();
+
+        // then only Alice's commit matches, and offsets count matching commits
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].summary, "c.txt");
+        assert_eq!(others.len(), 2);
+        assert!(get_recent_commits(&repo, 1, 10, &query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_count_lines_changed_by_commit() {
+        // given a root commit and a commit adding a one-line file
+        let temp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        commit_file(&repo, "a.txt");
+        let second = commit_on_head(&repo, "b.txt");
+
+        // when
+        let stats = get_commit_stats(&repo, &second.to_string()).unwrap();
+
+        // then
+        assert_eq!(
+            stats,
+            vec![FileStat {
+                path: PathBuf::from("b.txt"),
+                additions: 1,
+                deletions: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn should_stop_recent_commits_before_since_day() {
+        // given a commit made today
+        let temp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        commit_file(&repo, "a.txt");
+        let tomorrow = Utc::now().date_naive().succ_opt().unwrap();
+        let today = Utc::now().date_naive();
+
+        // when / then
+        let since = |day| CommitQuery {
+            author: None,
+            since: Some(day),
+        };
+        assert_eq!(
+            get_recent_commits(&repo, 0, 10, &since(today))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            get_recent_commits(&repo, 0, 10, &since(tomorrow))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn should_find_commits_since_default_branch() {
+        // given main with one commit and a feature branch two commits ahead
+        let temp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        commit_file(&repo, "a.txt");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("main", &head, true).unwrap();
+        repo.branch("feature", &head, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        let first = commit_on_head(&repo, "b.txt");
+        let second = commit_on_head(&repo, "c.txt");
+
+        // when
+        let (base, ids) = commits_since_base(&repo, None).unwrap();
+        let (named, named_ids) = commits_since_base(&repo, Some("main")).unwrap();
+        let missing = commits_since_base(&repo, Some("develop"));
+
+        // then
+        assert_eq!(base, "main");
+        assert_eq!(ids, vec![first.to_string(), second.to_string()]);
+        assert_eq!(named, "main");
+        assert_eq!(named_ids, ids);
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn should_list_main_and_linked_worktrees() {
+        // given a repository with one linked worktree
+        let temp = tempfile::tempdir().unwrap();
+        let main_path = temp.path().join("main");
+        let repo = Repository::init(&main_path).unwrap();
+        commit_file(&repo, "a.txt");
+        let linked_path = temp.path().join("feature");
+        repo.worktree("feature", &linked_path, None).unwrap();
+
+        // when listing from inside the linked worktree
+        let linked = Repository::open(&linked_path).unwrap();
+        let worktrees = list_worktrees(&linked).unwrap();
+
+        // then both are listed, main first, and the linked one is current
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(canonical(&worktrees[0].path), canonical(&main_path));
+        assert!(!worktrees[0].is_current);
+        assert_eq!(canonical(&worktrees[1].path), canonical(&linked_path));
+        assert!(worktrees[1].is_current);
+        assert_eq!(worktrees[1].branch.as_deref(), Some("feature"));
+    }
+
+    #[test]
+    fn should_list_only_main_worktree_without_linked_ones() {
+        // given a plain repository
+        let temp = tempfile::tempdir().unwrap();
+        let repo = Repository::init(temp.path()).unwrap();
+        commit_file(&repo, "a.txt");
+
+        // when listing / then only the main worktree is present
+        let worktrees = list_worktrees(&repo).unwrap();
+        assert_eq!(worktrees.len(), 1);
+        assert!(worktrees[0].is_current);
+    }
+}
@@ -0,0 +1,164 @@
+//! Reader for the repository's top-level `.gitattributes`.
+//!
+//! Parsed as plain text so the same rules apply to every backend (jj and
+//! stdin included). Only boolean attributes are tracked, which covers the
+//! linguist markers we care about (`linguist-vendored`, `linguist-generated`)
+//! and `-diff`.
+
+use std::path::Path;
+
+use crate::glob;
+
+/// State of a boolean attribute as set by a single `.gitattributes` line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttrState {
+    Set,
+    Unset,
+    Unspecified,
+}
+
+#[derive(Debug, Clone)]
+struct AttrRule {
+    pattern: String,
+    attrs: Vec<(String, AttrState)>,
+}
+
+/// Parsed `.gitattributes` rules; later lines take precedence over earlier ones
+#[derive(Debug, Clone, Default)]
+pub struct GitAttributes {
+    rules: Vec<AttrRule>,
+}
+
+impl GitAttributes {
+    /// Load `.gitattributes` from the repository root. A missing file yields no rules.
+    pub fn load(root: &Path) -> Self {
+        std::fs::read_to_string(root.join(".gitattributes"))
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut tokens = line.split_whitespace();
+                let pattern = tokens.next()?.to_string();
+                let attrs = tokens.map(parse_attr).collect();
+                Some(AttrRule { pattern, attrs })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Whether `attr` is set for `path` (e.g. `linguist-vendored` or `linguist-vendored=true`)
+    pub fn is_set(&self, path: &Path, attr: &str) -> bool {
+        self.state(path, attr) == AttrState::Set
+    }
+
+    /// Whether `attr` is explicitly unset for `path` (e.g. `-diff`)
+    pub fn is_unset(&self, path: &Path, attr: &str) -> bool {
+        self.state(path, attr) == AttrState::Unset
+    }
+
+    fn state(&self, path: &Path, attr: &str) -> AttrState {
+        let mut state = AttrState::Unspecified;
+        for rule in &self.rules {
+            if !glob::matches_attributes_path(&rule.pattern, path) {
+                continue;
+            }
+            for (name, rule_state) in &rule.attrs {
+                if name == attr {
+                    state = *rule_state;
+                }
+            }
+        }
+        state
+    }
+}
+
+fn parse_attr(token: &str) -> (String, AttrState) {
+    if let Some(name) = token.strip_prefix('-') {
+        (name.to_string(), AttrState::Unset)
+    } else if let Some(name) = token.strip_prefix('!') {
+        (name.to_string(), AttrState::Unspecified)
+    } else if let Some((name, value)) = token.split_once('=') {
+        let state = match value {
+            "false" | "0" => AttrState::Unset,
+            _ => AttrState::Set,
+        };
+        (name.to_string(), state)
+    } else {
+        (token.to_string(), AttrState::Set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_set_attribute() {
+        // given
+        let attrs =
+            GitAttributes::parse("vendor/** linguist-vendored\n*.pb.go linguist-generated=true\n");
+
+        // then
+        assert!(attrs.is_set(Path::new("vendor/lib/a.go"), "linguist-vendored"));
+        assert!(attrs.is_set(Path::new("api/v1/user.pb.go"), "linguist-generated"));
+        assert!(!attrs.is_set(Path::new("src/main.go"), "linguist-vendored"));
+    }
+
+    #[test]
+    fn should_ignore_directory_patterns_like_git() {
+        // given a trailing-slash pattern, which git applies to no file
+        let attrs = GitAttributes::parse("vendor/ linguist-vendored\n");
+
+        // then
+        assert!(!attrs.is_set(Path::new("vendor/lib/a.go"), "linguist-vendored"));
+    }
+
+    #[test]
+    fn should_detect_unset_attribute() {
+        // given
+        let attrs = GitAttributes::parse(
+            "*.lock -diff
+Cargo.lock diff
+",
+        );
+
+        // then
+        assert!(attrs.is_unset(Path::new("yarn.lock"), "diff"));
+        assert!(!attrs.is_unset(Path::new("Cargo.lock"), "diff"));
+        assert!(!attrs.is_set(Path::new("yarn.lock"), "diff"));
+    }
+
+    #[test]
+    fn should_let_later_rules_override_earlier_ones() {
+        // given
+        let attrs = GitAttributes::parse(
+            "vendor/** linguist-vendored\nvendor/ours/** -linguist-vendored\n",
+        );
+
+        // then
+        assert!(attrs.is_set(Path::new("vendor/theirs/a.go"), "linguist-vendored"));
+        assert!(!attrs.is_set(Path::new("vendor/ours/a.go"), "linguist-vendored"));
+    }
+
+    #[test]
+    fn should_treat_false_value_as_unset() {
+        let attrs = GitAttributes::parse("*.js linguist-vendored=false\n");
+
+        assert!(!attrs.is_set(Path::new("app.js"), "linguist-vendored"));
+    }
+
+    #[test]
+    fn should_ignore_comments_and_blank_lines() {
+        let attrs =
+            GitAttributes::parse("# vendored deps\n\n   \nthird_party/** linguist-vendored\n");
+
+        assert!(attrs.is_set(Path::new("third_party/x.c"), "linguist-vendored"));
+    }
+}
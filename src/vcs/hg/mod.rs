@@ -1,3 +1,5 @@
+use std::borrow::Borrow;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -7,20 +9,24 @@ use crate::error::{Result, TuicrError};
 use crate::model::{DiffFile, DiffLine, FileStatus, LineOrigin};
 use crate::syntax::SyntaxHighlighter;
 use crate::vcs::diff_parser::{self, DiffFormat};
-use crate::vcs::traits::{CommitInfo, VcsBackend, VcsInfo, VcsType};
+use crate::vcs::traits::{
+    CommitInfo, CommitQuery, DiffSettings, FileStat, FileVersions, VcsBackend, VcsInfo, VcsType,
+};
 
 /// Mercurial backend implementation using hg CLI commands
 pub struct HgBackend {
     info: VcsInfo,
+    settings: DiffSettings,
 }
 
 impl HgBackend {
-    /// Discover a Mercurial repository from the current directory
-    pub fn discover() -> Result<Self> {
+    /// Discover the Mercurial repository containing `path`
+    pub fn discover_at(path: &Path) -> Result<Self> {
         // Use `hg root` to find the repository root
         // This handles being called from subdirectories
         let root_output = Command::new("hg")
             .args(["root"])
+            .current_dir(path)
             .output()
             .map_err(|e| TuicrError::VcsCommand(format!("Failed to run hg: {}", e)))?;
 
@@ -54,7 +60,10 @@ impl HgBackend {
             vcs_type: VcsType::Mercurial,
         };
 
-        Ok(Self { info })
+        Ok(Self {
+            info,
+            settings: DiffSettings::default(),
+        })
     }
 }
 
@@ -63,9 +72,13 @@ impl VcsBackend for HgBackend {
         &self.info
     }
 
+    fn set_diff_settings(&mut self, settings: DiffSettings) {
+        self.settings = settings;
+    }
+
     fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
         // Get unified diff output from hg
-        let diff_output = run_hg_command(&self.info.root_path, &["diff"])?;
+        let diff_output = run_hg_command(&self.info.root_path, &diff_args(&[], self.settings))?;
 
         if diff_output.trim().is_empty() {
             return Err(TuicrError::NoChanges);
@@ -119,7 +132,12 @@ impl VcsBackend for HgBackend {
         Ok(result)
     }
 
-    fn get_recent_commits(&self, offset: usize, limit: usize) -> Result<Vec<CommitInfo>> {
+    fn get_recent_commits(
+        &self,
+        offset: usize,
+        limit: usize,
+        query: &CommitQuery,
+    ) -> Result<Vec<CommitInfo>> {
         // Use hg log with a template to get structured output
         // Template fields separated by \x00, records separated by \x01
         //
@@ -128,16 +146,16 @@ impl VcsBackend for HgBackend {
         let fetch_count = offset + limit;
         let template =
             "{node}\\x00{node|short}\\x00{desc|firstline}\\x00{author|user}\\x00{date|hgdate}\\x01";
-        let output = run_hg_command(
-            &self.info.root_path,
-            &[
-                "log",
-                "-l",
-                &fetch_count.to_string(),
-                "--template",
-                template,
-            ],
-        )?;
+        let fetch_count = fetch_count.to_string();
+        let mut args = vec!["log", "-l", &fetch_count, "--template", template];
+        if let Some(author) = &query.author {
+            args.extend(["--user", author]);
+        }
+        let since = query.since.map(|day| format!(">{day}"));
+        if let Some(since) = &since {
+            args.extend(["--date", since]);
+        }
+        let output = run_hg_command(&self.info.root_path, &args)?;
 
         let mut commits = Vec::new();
         for record in output.split('\x01') {
@@ -176,6 +194,43 @@ impl VcsBackend for HgBackend {
         Ok(commits.into_iter().skip(offset).collect())
     }
 
+    fn get_commit_message(&self, commit_id: &str) -> Result<Option<String>> {
+        run_hg_command(
+            &self.info.root_path,
+            &["log", "-r", commit_id, "--template", "{desc}"],
+        )
+        .map(Some)
+    }
+
+    fn get_commit_stats(&self, commit_id: &str) -> Result<Vec<FileStat>> {
+        // Short hashes for Sapling, as in get_commit_range_diff
+        let short = &commit_id[..commit_id.len().min(12)];
+        let diff_output = run_hg_command(&self.info.root_path, &["diff", "--git", "-c", short])?;
+        Ok(diff_parser::file_stats(&diff_output))
+    }
+
+    fn get_file_versions(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        commit_ids: Option<&[String]>,
+    ) -> Result<FileVersions> {
+        let Some(commit_ids) = commit_ids else {
+            let new = std::fs::read_to_string(self.info.root_path.join(new_path)).ok();
+            return Ok(FileVersions {
+                old: self.cat_file(".", old_path),
+                new,
+            });
+        };
+        let (Some(oldest), Some(newest)) = (commit_ids.first(), commit_ids.last()) else {
+            return Err(TuicrError::NoChanges);
+        };
+        Ok(FileVersions {
+            old: self.cat_file(&self.parent_rev(short_hash(oldest)), old_path),
+            new: self.cat_file(short_hash(newest), new_path),
+        })
+    }
+
     fn get_commit_range_diff(
         &self,
         commit_ids: &[String],
@@ -191,54 +246,74 @@ impl VcsBackend for HgBackend {
         // - Sapling (Meta's hg fork) has issues with full 40-char hashes in certain operations
         // - We use 12-char short hashes which work with both standard Mercurial and Sapling
         // - The parents() revset is used to find the parent commit for diffing
-        let oldest = &commit_ids[0];
-        let oldest_short = if oldest.len() > 12 {
-            &oldest[..12]
-        } else {
-            oldest.as_str()
-        };
+        let oldest_short = short_hash(&commit_ids[0]);
+        let newest_short = short_hash(commit_ids.last().unwrap());
+        let from_rev = self.parent_rev(oldest_short);
 
-        let newest = commit_ids.last().unwrap();
-        let newest_short = if newest.len() > 12 {
-            &newest[..12]
-        } else {
-            newest.as_str()
-        };
+        let diff_output = run_hg_command(
+            &self.info.root_path,
+            &diff_args(&[&from_rev, newest_short], self.settings),
+        )?;
+
+        if diff_output.trim().is_empty() {
+            return Err(TuicrError::NoChanges);
+        }
+
+        diff_parser::parse_unified_diff(&diff_output, DiffFormat::Hg, highlighter)
+    }
+}
 
-        // First, get the parent commit of the oldest
-        // We use "log -r 'parents({oldest})'" to get the parent hash
+impl HgBackend {
+    /// The parent of `rev`, or `null` for the first commit
+    fn parent_rev(&self, rev: &str) -> String {
+        // We use "log -r 'parents({rev})'" to get the parent hash
         let parent_output = run_hg_command(
             &self.info.root_path,
             &[
                 "log",
                 "-r",
-                &format!("parents({})", oldest_short),
+                &format!("parents({})", rev),
                 "--template",
                 "{node|short}",
             ],
         );
 
-        // If there's no parent (first commit), diff from null
-        let from_rev = match parent_output {
+        match parent_output {
             Ok(parent) if !parent.trim().is_empty() => parent.trim().to_string(),
             _ => "null".to_string(),
-        };
+        }
+    }
 
-        let diff_output = run_hg_command(
+    /// A file's content at `rev`, None when the revision has no such file
+    fn cat_file(&self, rev: &str, path: &Path) -> Option<String> {
+        if rev == "null" {
+            return None;
+        }
+        run_hg_command(
             &self.info.root_path,
-            &["diff", "-r", &from_rev, "-r", newest_short],
-        )?;
+            &["cat", "-r", rev, &path.to_string_lossy()],
+        )
+        .ok()
+    }
+}
 
-        if diff_output.trim().is_empty() {
-            return Err(TuicrError::NoChanges);
-        }
+/// The 12-character prefix of a hash, which both Mercurial and Sapling accept
+fn short_hash(id: &str) -> &str {
+    &id[..id.len().min(12)]
+}
 
-        diff_parser::parse_unified_diff(&diff_output, DiffFormat::Hg, highlighter)
+/// `hg diff` arguments between `revs`, or for the working tree when empty
+fn diff_args(revs: &[&str], settings: DiffSettings) -> Vec<String> {
+    let mut args = vec!["diff".to_string()];
+    for rev in revs {
+        args.extend(["-r".to_string(), rev.to_string()]);
     }
+    args.extend(settings.context_args("-U"));
+    args
 }
 
 /// Run an hg command and return its stdout
-fn run_hg_command(root: &Path, args: &[&str]) -> Result<String> {
+fn run_hg_command<S: AsRef<OsStr> + Borrow<str>>(root: &Path, args: &[S]) -> Result<String> {
     let output = Command::new("hg")
         .current_dir(root)
         .args(args)
@@ -262,6 +337,22 @@ mod tests {
     use super::*;
     use std::fs;
 
+    #[test]
+    fn should_ask_hg_for_the_configured_context() {
+        // given
+        let settings = DiffSettings {
+            context_lines: 8,
+            ..DiffSettings::default()
+        };
+
+        // then
+        assert_eq!(diff_args(&[], settings), ["diff", "-U", "8"]);
+        assert_eq!(
+            diff_args(&["abc", "def"], settings),
+            ["diff", "-r", "abc", "-r", "def", "-U", "8"]
+        );
+    }
+
     /// Check if hg command is available
     fn hg_available() -> bool {
         Command::new("hg")
@@ -271,23 +362,6 @@ mod tests {
             .unwrap_or(false)
     }
 
-    /// Discover a Mercurial repository from a specific directory
-    fn discover_in(path: &Path) -> Result<HgBackend> {
-        let root_output = Command::new("hg")
-            .args(["root"])
-            .current_dir(path)
-            .output()
-            .map_err(|e| TuicrError::VcsCommand(format!("Failed to run hg: {}", e)))?;
-
-        if !root_output.status.success() {
-            return Err(TuicrError::NotARepository);
-        }
-
-        let root_path = PathBuf::from(String::from_utf8_lossy(&root_output.stdout).trim());
-
-        HgBackend::from_path(root_path)
-    }
-
     /// Create a temporary hg repo for testing.
     /// Returns None if hg is not available.
     fn setup_test_repo() -> Option<tempfile::TempDir> {
@@ -335,8 +409,8 @@ mod tests {
             return;
         };
 
-        // Use discover_in to avoid set_current_dir race conditions
-        let backend = discover_in(temp.path()).expect("Failed to discover hg repo");
+        // Use an explicit path to avoid set_current_dir race conditions
+        let backend = HgBackend::discover_at(temp.path()).expect("Failed to discover hg repo");
         let info = backend.info();
 
         // Canonicalize temp path to handle macOS /var -> /private/var symlink
@@ -464,7 +538,7 @@ mod tests {
             HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
 
         let commits = backend
-            .get_recent_commits(0, 5)
+            .get_recent_commits(0, 5, &CommitQuery::default())
             .expect("Failed to get commits");
 
         assert_eq!(commits.len(), 3);
@@ -492,7 +566,7 @@ mod tests {
             HgBackend::from_path(temp.path().to_path_buf()).expect("Failed to create hg backend");
 
         let commits = backend
-            .get_recent_commits(0, 5)
+            .get_recent_commits(0, 5, &CommitQuery::default())
             .expect("Failed to get commits");
         assert_eq!(commits.len(), 3);
 
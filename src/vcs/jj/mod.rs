@@ -1,5 +1,7 @@
 //! Jujutsu (jj) backend implementation using CLI commands.
 
+use std::borrow::Borrow;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -9,20 +11,24 @@ use crate::error::{Result, TuicrError};
 use crate::model::{DiffFile, DiffLine, FileStatus, LineOrigin};
 use crate::syntax::SyntaxHighlighter;
 use crate::vcs::diff_parser::{self, DiffFormat};
-use crate::vcs::traits::{CommitInfo, VcsBackend, VcsInfo, VcsType};
+use crate::vcs::traits::{
+    CommitInfo, CommitQuery, DiffSettings, FileStat, FileVersions, VcsBackend, VcsInfo, VcsType,
+};
 
 /// Jujutsu backend implementation using jj CLI commands
 pub struct JjBackend {
     info: VcsInfo,
+    settings: DiffSettings,
 }
 
 impl JjBackend {
-    /// Discover a Jujutsu repository from the current directory
-    pub fn discover() -> Result<Self> {
+    /// Discover the Jujutsu repository containing `path`
+    pub fn discover_at(path: &Path) -> Result<Self> {
         // Use `jj root` to find the repository root
         // This handles being called from subdirectories
         let root_output = Command::new("jj")
             .args(["root"])
+            .current_dir(path)
             .output()
             .map_err(|e| TuicrError::VcsCommand(format!("Failed to run jj: {}", e)))?;
 
@@ -91,7 +97,10 @@ impl JjBackend {
             vcs_type: VcsType::Jujutsu,
         };
 
-        Ok(Self { info })
+        Ok(Self {
+            info,
+            settings: DiffSettings::default(),
+        })
     }
 }
 
@@ -100,9 +109,13 @@ impl VcsBackend for JjBackend {
         &self.info
     }
 
+    fn set_diff_settings(&mut self, settings: DiffSettings) {
+        self.settings = settings;
+    }
+
     fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
         // Get unified diff output from jj using --git format
-        let diff_output = run_jj_command(&self.info.root_path, &["diff", "--git"])?;
+        let diff_output = run_jj_command(&self.info.root_path, &diff_args(None, self.settings))?;
 
         if diff_output.trim().is_empty() {
             return Err(TuicrError::NoChanges);
@@ -156,7 +169,12 @@ impl VcsBackend for JjBackend {
         Ok(result)
     }
 
-    fn get_recent_commits(&self, offset: usize, limit: usize) -> Result<Vec<CommitInfo>> {
+    fn get_recent_commits(
+        &self,
+        offset: usize,
+        limit: usize,
+        query: &CommitQuery,
+    ) -> Result<Vec<CommitInfo>> {
         // Use jj log with a template to get structured output
         // Template fields separated by \x00, records separated by \x01
         // Note: jj uses change_id for identifying changes, commit_id for the underlying git commit
@@ -165,12 +183,20 @@ impl VcsBackend for JjBackend {
         // and skip the first `offset` in Rust code
         let fetch_count = offset + limit;
         let template = r#"commit_id ++ "\x00" ++ commit_id.short() ++ "\x00" ++ description.first_line() ++ "\x00" ++ author.email() ++ "\x00" ++ committer.timestamp() ++ "\x01""#;
+        let mut revset = "::@".to_string();
+        if let Some(author) = &query.author {
+            let author = author.replace('\\', "\\\\").replace('"', "\\\"");
+            revset.push_str(&format!(" & author(substring-i:\"{author}\")"));
+        }
+        if let Some(since) = query.since {
+            revset.push_str(&format!(" & committer_date(after:\"{since}\")"));
+        }
         let output = run_jj_command(
             &self.info.root_path,
             &[
                 "log",
                 "-r",
-                "::@",
+                &revset,
                 "--limit",
                 &fetch_count.to_string(),
                 "--no-graph",
@@ -213,6 +239,49 @@ impl VcsBackend for JjBackend {
         Ok(commits.into_iter().skip(offset).collect())
     }
 
+    fn get_commit_message(&self, commit_id: &str) -> Result<Option<String>> {
+        run_jj_command(
+            &self.info.root_path,
+            &["log", "-r", commit_id, "--no-graph", "-T", "description"],
+        )
+        .map(Some)
+    }
+
+    fn get_commit_stats(&self, commit_id: &str) -> Result<Vec<FileStat>> {
+        let diff_output = run_jj_command(
+            &self.info.root_path,
+            &["diff", "-r", commit_id, "--git", "--context", "0"],
+        )?;
+        Ok(diff_parser::file_stats(&diff_output))
+    }
+
+    fn get_file_versions(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        commit_ids: Option<&[String]>,
+    ) -> Result<FileVersions> {
+        // The working copy is itself the commit `@`, on top of `@-`
+        let (old_rev, new_rev) = match commit_ids {
+            None => ("@-".to_string(), "@".to_string()),
+            Some(ids) => match (ids.first(), ids.last()) {
+                (Some(oldest), Some(newest)) => (format!("{}-", oldest), newest.clone()),
+                _ => return Err(TuicrError::NoChanges),
+            },
+        };
+        let show = |rev: &str, path: &Path| {
+            run_jj_command(
+                &self.info.root_path,
+                &["file", "show", "-r", rev, &path.to_string_lossy()],
+            )
+            .ok()
+        };
+        Ok(FileVersions {
+            old: show(&old_rev, old_path),
+            new: show(&new_rev, new_path),
+        })
+    }
+
     fn get_commit_range_diff(
         &self,
         commit_ids: &[String],
@@ -230,14 +299,7 @@ impl VcsBackend for JjBackend {
         // In jj, we use {commit}- to get the parent(s)
         let diff_output = run_jj_command(
             &self.info.root_path,
-            &[
-                "diff",
-                "--from",
-                &format!("{}-", oldest),
-                "--to",
-                newest,
-                "--git",
-            ],
+            &diff_args(Some((&format!("{}-", oldest), newest)), self.settings),
         )?;
 
         if diff_output.trim().is_empty() {
@@ -248,8 +310,20 @@ impl VcsBackend for JjBackend {
     }
 }
 
+/// `jj diff` arguments in git format, between `(from, to)` or for the
+/// working copy
+fn diff_args(range: Option<(&str, &str)>, settings: DiffSettings) -> Vec<String> {
+    let mut args = vec!["diff".to_string()];
+    if let Some((from, to)) = range {
+        args.extend(["--from", from, "--to", to].map(str::to_string));
+    }
+    args.push("--git".to_string());
+    args.extend(settings.context_args("--context"));
+    args
+}
+
 /// Run a jj command and return its stdout
-fn run_jj_command(root: &Path, args: &[&str]) -> Result<String> {
+fn run_jj_command<S: AsRef<OsStr> + Borrow<str>>(root: &Path, args: &[S]) -> Result<String> {
     let output = Command::new("jj")
         .current_dir(root)
         .args(args)
@@ -273,6 +347,34 @@ mod tests {
     use super::*;
     use std::fs;
 
+    #[test]
+    fn should_ask_jj_for_the_configured_context() {
+        // given
+        let settings = DiffSettings {
+            context_lines: 0,
+            ..DiffSettings::default()
+        };
+
+        // then
+        assert_eq!(
+            diff_args(None, settings),
+            ["diff", "--git", "--context", "0"]
+        );
+        assert_eq!(
+            diff_args(Some(("abc-", "def")), settings),
+            [
+                "diff",
+                "--from",
+                "abc-",
+                "--to",
+                "def",
+                "--git",
+                "--context",
+                "0"
+            ]
+        );
+    }
+
     /// Check if jj command is available
     fn jj_available() -> bool {
         Command::new("jj")
@@ -282,23 +384,6 @@ mod tests {
             .unwrap_or(false)
     }
 
-    /// Discover a Jujutsu repository from a specific directory
-    fn discover_in(path: &Path) -> Result<JjBackend> {
-        let root_output = Command::new("jj")
-            .args(["root"])
-            .current_dir(path)
-            .output()
-            .map_err(|e| TuicrError::VcsCommand(format!("Failed to run jj: {}", e)))?;
-
-        if !root_output.status.success() {
-            return Err(TuicrError::NotARepository);
-        }
-
-        let root_path = PathBuf::from(String::from_utf8_lossy(&root_output.stdout).trim());
-
-        JjBackend::from_path(root_path)
-    }
-
     /// Create a temporary jj repo for testing.
     /// Returns None if jj is not available.
     fn setup_test_repo() -> Option<tempfile::TempDir> {
@@ -348,8 +433,8 @@ mod tests {
             return;
         };
 
-        // Use discover_in to avoid set_current_dir race conditions
-        let backend = discover_in(temp.path()).expect("Failed to discover jj repo");
+        // Use an explicit path to avoid set_current_dir race conditions
+        let backend = JjBackend::discover_at(temp.path()).expect("Failed to discover jj repo");
         let info = backend.info();
 
         // Canonicalize temp path to handle macOS /var -> /private/var symlink
@@ -475,7 +560,7 @@ mod tests {
             JjBackend::from_path(temp.path().to_path_buf()).expect("Failed to create jj backend");
 
         let commits = backend
-            .get_recent_commits(0, 5)
+            .get_recent_commits(0, 5, &CommitQuery::default())
             .expect("Failed to get commits");
 
         // jj creates a working copy commit on top, so we may have 4 commits
@@ -517,7 +602,7 @@ mod tests {
             JjBackend::from_path(temp.path().to_path_buf()).expect("Failed to create jj backend");
 
         let commits = backend
-            .get_recent_commits(0, 10)
+            .get_recent_commits(0, 10, &CommitQuery::default())
             .expect("Failed to get commits");
         assert!(commits.len() >= 3, "Expected at least 3 commits");
 
@@ -4,6 +4,7 @@
 //! - Git
 //! - Mercurial
 //! - Jujutsu
+//! - Unified diffs piped in on stdin (no repository)
 //!
 //! ## Detection Order
 //!
@@ -13,34 +14,51 @@
 
 mod diff_parser;
 pub mod git;
+mod gitattributes;
 mod hg;
 mod jj;
+mod stdin;
 mod traits;
+mod tuicrignore;
 
 pub use git::GitBackend;
+pub use gitattributes::GitAttributes;
 pub use hg::HgBackend;
 pub use jj::JjBackend;
-pub use traits::{CommitInfo, VcsBackend, VcsInfo};
+pub use stdin::StdinBackend;
+pub use traits::{
+    CommitInfo, CommitQuery, DiffSettings, FileStat, FileVersions, VcsBackend, VcsInfo, VcsType,
+    WorktreeInfo,
+};
+pub use tuicrignore::TuicrIgnore;
+
+use std::path::Path;
 
 use crate::error::{Result, TuicrError};
 
-/// Detect the VCS type and return the appropriate backend.
+/// Detect the VCS type of the current directory and return the appropriate backend.
+pub fn detect_vcs() -> Result<Box<dyn VcsBackend>> {
+    let cwd = std::env::current_dir().map_err(|_| TuicrError::NotARepository)?;
+    detect_vcs_at(&cwd)
+}
+
+/// Detect the VCS type of the repository containing `path`.
 ///
 /// Detection order: Jujutsu → Git → Mercurial.
 /// Jujutsu is tried first because jj repos are Git-backed.
-pub fn detect_vcs() -> Result<Box<dyn VcsBackend>> {
+pub fn detect_vcs_at(path: &Path) -> Result<Box<dyn VcsBackend>> {
     // Try jj first since jj repos are Git-backed
-    if let Ok(backend) = JjBackend::discover() {
+    if let Ok(backend) = JjBackend::discover_at(path) {
         return Ok(Box::new(backend));
     }
 
     // Try git
-    if let Ok(backend) = GitBackend::discover() {
+    if let Ok(backend) = GitBackend::discover_at(path) {
         return Ok(Box::new(backend));
     }
 
     // Try hg
-    if let Ok(backend) = HgBackend::discover() {
+    if let Ok(backend) = HgBackend::discover_at(path) {
         return Ok(Box::new(backend));
     }
 
@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, TuicrError};
+use crate::model::{DiffFile, DiffLine, FileStatus, LineOrigin};
+use crate::syntax::SyntaxHighlighter;
+use crate::vcs::diff_parser::{self, DiffFormat};
+use crate::vcs::traits::{VcsBackend, VcsInfo, VcsType};
+
+/// Backend for reviewing a unified diff piped in on stdin (`git diff | tuicr -`).
+///
+/// There is no repository behind this backend: the diff text is captured once
+/// at startup and re-parsed on demand. Context expansion reads from the current
+/// directory when the referenced file exists there.
+pub struct StdinBackend {
+    info: VcsInfo,
+    diff_text: String,
+}
+
+impl StdinBackend {
+    /// Create a backend from already-read diff text, rooted at the current directory
+    pub fn from_diff(diff_text: String) -> Result<Self> {
+        let root_path = std::env::current_dir()?;
+        Ok(Self::from_diff_at(diff_text, root_path))
+    }
+
+    /// Create a backend whose context expansion reads files under `root_path`
+    pub fn from_diff_at(diff_text: String, root_path: PathBuf) -> Self {
+        let info = VcsInfo {
+            root_path,
+            head_commit: "stdin".to_string(),
+            branch_name: None,
+            vcs_type: VcsType::Stdin,
+        };
+
+        Self { info, diff_text }
+    }
+}
+
+impl VcsBackend for StdinBackend {
+    fn info(&self) -> &VcsInfo {
+        &self.info
+    }
+
+    fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>> {
+        if self.diff_text.trim().is_empty() {
+            return Err(TuicrError::NoChanges);
+        }
+
+        diff_parser::parse_unified_diff(&self.diff_text, DiffFormat::GitStyle, highlighter)
+    }
+
+    fn fetch_context_lines(
+        &self,
+        file_path: &Path,
+        file_status: FileStatus,
+        start_line: u32,
+        end_line: u32,
+    ) -> Result<Vec<DiffLine>> {
+        if start_line > end_line || start_line == 0 {
+            return Ok(Vec::new());
+        }
+
+        // The pre-image of a deleted file is not available without a repository
+        if file_status == FileStatus::Deleted {
+            return Err(TuicrError::UnsupportedOperation(
+                "Context for deleted files is not available for stdin diffs".into(),
+            ));
+        }
+
+        let full_path = self.info.root_path.join(file_path);
+        let content = std::fs::read_to_string(&full_path)?;
+        let lines: Vec<&str> = content.lines().collect();
+
+        let mut result = Vec::new();
+        for line_num in start_line..=end_line {
+            let idx = (line_num - 1) as usize;
+            if idx < lines.len() {
+                result.push(DiffLine {
+                    origin: LineOrigin::Context,
+                    content: lines[idx].to_string(),
+                    old_lineno: Some(line_num),
+                    new_lineno: Some(line_num),
+                    highlighted_spans: None,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const SAMPLE_DIFF: &str = "\
+diff --git a/hello.txt b/hello.txt
+index 3b18e51..c0d0fb4 100644
+--- a/hello.txt
++++ b/hello.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+";
+
+    #[test]
+    fn parses_piped_diff() {
+        // given
+        let backend = StdinBackend::from_diff_at(SAMPLE_DIFF.to_string(), PathBuf::from("/tmp"));
+
+        // when
+        let files = backend
+            .get_working_tree_diff(&SyntaxHighlighter::default())
+            .expect("Failed to parse diff");
+
+        // then
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].display_path(), &PathBuf::from("hello.txt"));
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!(backend.info().vcs_type, VcsType::Stdin);
+    }
+
+    #[test]
+    fn empty_input_has_no_changes() {
+        // given
+        let backend = StdinBackend::from_diff_at("\n".to_string(), PathBuf::from("/tmp"));
+
+        // when
+        let result = backend.get_working_tree_diff(&SyntaxHighlighter::default());
+
+        // then
+        assert!(matches!(result, Err(TuicrError::NoChanges)));
+    }
+
+    #[test]
+    fn fetches_context_from_current_directory() {
+        // given
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        fs::write(temp.path().join("hello.txt"), "one\nTWO\nthree\nfour\n")
+            .expect("Failed to write file");
+        let backend =
+            StdinBackend::from_diff_at(SAMPLE_DIFF.to_string(), temp.path().to_path_buf());
+
+        // when
+        let lines = backend
+            .fetch_context_lines(Path::new("hello.txt"), FileStatus::Modified, 4, 4)
+            .expect("Failed to fetch context");
+
+        // then
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].content, "four");
+        assert_eq!(lines[0].new_lineno, Some(4));
+    }
+
+    #[test]
+    fn deleted_file_context_is_unsupported() {
+        // given
+        let backend = StdinBackend::from_diff_at(SAMPLE_DIFF.to_string(), PathBuf::from("/tmp"));
+
+        // when
+        let result = backend.fetch_context_lines(Path::new("hello.txt"), FileStatus::Deleted, 1, 2);
+
+        // then
+        assert!(matches!(result, Err(TuicrError::UnsupportedOperation(_))));
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::path::{Path, PathBuf};
 
 use crate::error::Result;
@@ -11,6 +11,8 @@ pub enum VcsType {
     Git,
     Mercurial,
     Jujutsu,
+    /// A unified diff read from stdin, with no repository behind it
+    Stdin,
 }
 
 impl std::fmt::Display for VcsType {
@@ -19,6 +21,7 @@ impl std::fmt::Display for VcsType {
             VcsType::Git => write!(f, "git"),
             VcsType::Mercurial => write!(f, "hg"),
             VcsType::Jujutsu => write!(f, "jj"),
+            VcsType::Stdin => write!(f, "stdin"),
         }
     }
 }
@@ -43,11 +46,81 @@ pub struct CommitInfo {
     pub time: DateTime<Utc>,
 }
 
+/// Lines added and deleted in one file of a commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStat {
+    pub path: PathBuf,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Narrows `get_recent_commits`, from `author:` and `since:` terms typed in
+/// the commit picker filter
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitQuery {
+    /// Case-insensitive substring of the author's name or email
+    pub author: Option<String>,
+    /// Only commits committed on or after this day (UTC)
+    pub since: Option<NaiveDate>,
+}
+
+/// A checkout of the repository for the worktree picker
+#[derive(Debug, Clone)]
+pub struct WorktreeInfo {
+    pub path: PathBuf,
+    pub branch: Option<String>,
+    /// The worktree currently being reviewed
+    pub is_current: bool,
+}
+
+/// Options that change how backends compute diffs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffSettings {
+    /// Unchanged lines shown around each hunk
+    pub context_lines: u32,
+    /// Detect files copied from other modified files (git `--find-copies`)
+    pub find_copies: bool,
+    /// Show the commit log of checked-out submodules whose pointer moved
+    pub expand_submodules: bool,
+}
+
+impl DiffSettings {
+    pub const DEFAULT_CONTEXT_LINES: u32 = 3;
+
+    /// `flag` and the context line count, for diff commands run as a subprocess
+    pub fn context_args(&self, flag: &str) -> [String; 2] {
+        [flag.to_string(), self.context_lines.to_string()]
+    }
+}
+
+impl Default for DiffSettings {
+    fn default() -> Self {
+        Self {
+            context_lines: Self::DEFAULT_CONTEXT_LINES,
+            find_copies: false,
+            expand_submodules: false,
+        }
+    }
+}
+
+/// The two versions of a file that a diff compares
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileVersions {
+    /// Content before the change, None when the file did not exist
+    pub old: Option<String>,
+    /// Content after the change, None when the file was deleted
+    pub new: Option<String>,
+}
+
 /// Trait for VCS backend implementations
 pub trait VcsBackend: Send {
     /// Get repository information
     fn info(&self) -> &VcsInfo;
 
+    /// Update the settings used by subsequent diff calls.
+    /// Backends ignore settings they cannot honor (default).
+    fn set_diff_settings(&mut self, _settings: DiffSettings) {}
+
     /// Get the working tree diff (uncommitted changes)
     fn get_working_tree_diff(&self, highlighter: &SyntaxHighlighter) -> Result<Vec<DiffFile>>;
 
@@ -61,12 +134,40 @@ pub trait VcsBackend: Send {
         end_line: u32,
     ) -> Result<Vec<DiffLine>>;
 
-    /// Get recent commits for commit selection UI.
+    /// Get recent commits matching `query` for commit selection UI;
+    /// `offset` counts matching commits.
     /// Returns empty vec if not supported (default).
-    fn get_recent_commits(&self, _offset: usize, _limit: usize) -> Result<Vec<CommitInfo>> {
+    fn get_recent_commits(
+        &self,
+        _offset: usize,
+        _limit: usize,
+        _query: &CommitQuery,
+    ) -> Result<Vec<CommitInfo>> {
         Ok(Vec::new())
     }
 
+    /// Full message of a commit, shown as a reviewable entry when reviewing commits.
+    /// Returns None if not supported (default).
+    fn get_commit_message(&self, _commit_id: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Files a commit changes against its first parent, with line counts.
+    /// Returns empty vec if not supported (default).
+    fn get_commit_stats(&self, _commit_id: &str) -> Result<Vec<FileStat>> {
+        Ok(Vec::new())
+    }
+
+    /// Commits on the current branch since it forked from `base`, or from the
+    /// default base branch (`origin/HEAD`, else main or master) when None,
+    /// oldest first, with the base's name.
+    /// Returns error if not supported (default).
+    fn get_commits_since_base(&self, _base: Option<&str>) -> Result<(String, Vec<String>)> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Base branch detection not supported for this VCS".into(),
+        ))
+    }
+
     /// Get diff for a commit range.
     /// Returns error if not supported (default).
     fn get_commit_range_diff(
@@ -78,6 +179,26 @@ pub trait VcsBackend: Send {
             "Commit range diff not supported for this VCS".into(),
         ))
     }
+
+    /// Both versions of a file in the reviewed diff: HEAD and the working tree
+    /// when `commit_ids` is None, else the oldest commit's parent and the newest commit.
+    /// Returns error if not supported (default).
+    fn get_file_versions(
+        &self,
+        _old_path: &Path,
+        _new_path: &Path,
+        _commit_ids: Option<&[String]>,
+    ) -> Result<FileVersions> {
+        Err(crate::error::TuicrError::UnsupportedOperation(
+            "Reading file versions not supported for this VCS".into(),
+        ))
+    }
+
+    /// List the repository's worktrees, main worktree first.
+    /// Returns empty vec if not supported (default).
+    fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        Ok(Vec::new())
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +220,11 @@ mod tests {
         assert_eq!(format!("{}", VcsType::Jujutsu), "jj");
     }
 
+    #[test]
+    fn vcs_type_display_stdin() {
+        assert_eq!(format!("{}", VcsType::Stdin), "stdin");
+    }
+
     #[test]
     fn vcs_type_equality() {
         assert_eq!(VcsType::Git, VcsType::Git);
@@ -108,6 +234,22 @@ mod tests {
         assert_ne!(VcsType::Git, VcsType::Jujutsu);
     }
 
+    #[test]
+    fn should_pass_context_lines_after_the_flag() {
+        // given
+        let settings = DiffSettings {
+            context_lines: 10,
+            ..DiffSettings::default()
+        };
+
+        // then
+        assert_eq!(settings.context_args("-U"), ["-U", "10"]);
+        assert_eq!(
+            DiffSettings::default().context_args("--context"),
+            ["--context", "3"]
+        );
+    }
+
     #[test]
     fn vcs_info_clone() {
         let info = VcsInfo {
@@ -0,0 +1,106 @@
+//! Reader for the repository's top-level `.tuicrignore`.
+//!
+//! Uses gitignore syntax: one glob per line, `#` comments, `!` to re-include
+//! a path excluded by an earlier line, and a pattern naming a directory
+//! excludes everything below it. Matching files are left out of the review
+//! unless `:set showall` is on.
+
+use std::path::Path;
+
+use crate::glob;
+
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    pattern: String,
+    negated: bool,
+}
+
+/// Parsed `.tuicrignore` rules; later lines take precedence over earlier ones
+#[derive(Debug, Clone, Default)]
+pub struct TuicrIgnore {
+    rules: Vec<IgnoreRule>,
+}
+
+impl TuicrIgnore {
+    /// Load `.tuicrignore` from the repository root. A missing file yields no rules.
+    pub fn load(root: &Path) -> Self {
+        std::fs::read_to_string(root.join(".tuicrignore"))
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| match line.strip_prefix('!') {
+                Some(pattern) => IgnoreRule {
+                    pattern: pattern.to_string(),
+                    negated: true,
+                },
+                None => IgnoreRule {
+                    pattern: line.trim_start_matches('\\').to_string(),
+                    negated: false,
+                },
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether `path` is excluded, either directly or through one of its directories
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if path
+                .ancestors()
+                .filter(|p| !p.as_os_str().is_empty())
+                .any(|p| glob::matches_path(&rule.pattern, p))
+            {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_ignore_matching_files_and_directories() {
+        // given
+        let ignore = TuicrIgnore::parse("# noise\n*.lock\n__snapshots__\nvendor/\n");
+
+        // then
+        assert!(ignore.is_ignored(Path::new("Cargo.lock")));
+        assert!(ignore.is_ignored(Path::new("web/yarn.lock")));
+        assert!(ignore.is_ignored(Path::new("src/__snapshots__/app.snap")));
+        assert!(ignore.is_ignored(Path::new("vendor/lib/a.go")));
+        assert!(!ignore.is_ignored(Path::new("src/lock.rs")));
+    }
+
+    #[test]
+    fn should_reinclude_negated_paths() {
+        // given
+        let ignore = TuicrIgnore::parse("*.lock\n!Cargo.lock\n");
+
+        // then
+        assert!(ignore.is_ignored(Path::new("yarn.lock")));
+        assert!(!ignore.is_ignored(Path::new("Cargo.lock")));
+    }
+
+    #[test]
+    fn should_ignore_nothing_without_rules() {
+        let ignore = TuicrIgnore::parse("\n# only comments\n");
+
+        assert!(ignore.is_empty());
+        assert!(!ignore.is_ignored(Path::new("src/main.rs")));
+    }
+}